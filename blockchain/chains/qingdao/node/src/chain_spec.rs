@@ -61,7 +61,7 @@ use serde::{Deserialize, Serialize};
 use hex_literal::hex;
 use sp_core::{crypto::UncheckedInto, bytes::from_hex};
 
-use setheum_primitives::{AccountPublic, Balance, Nonce, currency::TokenInfo, TradingPair};
+use setheum_primitives::{AccountPublic, Balance, Nonce, currency::TokenInfo, TokenSymbol, TradingPair};
 use setheum_runtime::BABE_GENESIS_EPOCH_CONFIG;
 
 // The URL for the telemetry server.
@@ -549,56 +549,60 @@ fn dev_genesis(
 				])
 			.collect(),
 		},
-		// serp_treasury: SerpTreasuryConfig {
-		// 	stable_currency_inflation_rate: vec![
-		// 		(SETR, 100_000_000_000_000_000_000), 	// (currency_id, inflation rate of a setcurrency)
-		// 		(SETUSD, 10_000_000_000_000_000_000),	// (currency_id, inflation rate of a setcurrency)
-		// 	],
-		// 	stable_currency_cashdrop: vec![
-		// 		(SETR,  initial_balance), 	// (currency_id, cashdrop pool balance of a setcurrency)
-		// 		(SETUSD,  initial_balance),  // (currency_id, cashdrop pool balance of a setcurrency)
-		// 	],
-		// },
-		// cdp_treasury: CdpTreasuryConfig {
-		// 	expected_collateral_auction_size: vec![
-		// 		(SEE, 500 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
-		// 		(SERP, 300 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
-		// 		(DNAR, 100 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
-		// 		(HELP, 100 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
-		// 	],
-		// },
-		// cdp_engine: CdpEngineConfig {
-		// 	collaterals_params: vec![
-		// 		(
-		// 			SEE,
-		// 			Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
-		// 			Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
-		// 			Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
-		// 			25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
-		// 		),
-		// 		(
-		// 			SERP,
-		// 			Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
-		// 			Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
-		// 			Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
-		// 			25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
-		// 		),
-		// 		(
-		// 			DNAR,
-		// 			Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
-		// 			Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
-		// 			Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
-		// 			25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
-		// 		),
-		// 		(
-		// 			HELP,
-		// 			Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
-		// 			Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
-		// 			Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
-		// 			25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
-		// 		),
-		// 	],
-		// },
+		serp_treasury: SerpTreasuryConfig {
+			stable_currency_inflation_rate: vec![
+				(SETR, 100_000_000_000_000_000_000), 	// (currency_id, inflation rate of a setcurrency)
+				(SETUSD, 10_000_000_000_000_000_000),	// (currency_id, inflation rate of a setcurrency)
+			],
+			stable_currency_cashdrop: vec![
+				(SETR,  initial_balance), 	// (currency_id, cashdrop pool balance of a setcurrency)
+				(SETUSD,  initial_balance),  // (currency_id, cashdrop pool balance of a setcurrency)
+			],
+		},
+		cdp_treasury: CdpTreasuryConfig {
+			expected_collateral_auction_size: vec![
+				(SEE, 500 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
+				(SERP, 300 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
+				(DNAR, 100 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
+				(HELP, 100 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
+			],
+		},
+		cdp_engine: CdpEngineConfig {
+			collaterals_params: vec![
+				(
+					SEE,
+					Some(FixedU128::saturating_from_rational(1, 100_000_000)), // stability fee rate per block
+					Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
+					Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
+					Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
+					25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
+				),
+				(
+					SERP,
+					Some(FixedU128::saturating_from_rational(1, 100_000_000)), // stability fee rate per block
+					Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
+					Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
+					Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
+					25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
+				),
+				(
+					DNAR,
+					Some(FixedU128::saturating_from_rational(1, 100_000_000)), // stability fee rate per block
+					Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
+					Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
+					Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
+					25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
+				),
+				(
+					HELP,
+					Some(FixedU128::saturating_from_rational(1, 100_000_000)), // stability fee rate per block
+					Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
+					Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
+					Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
+					25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
+				),
+			],
+		},
 		// dex: DexConfig {
 		// 	initial_listing_trading_pairs: vec![],
 		// 	initial_enabled_trading_pairs: EnabledTradingPairs::get(),
@@ -796,56 +800,60 @@ fn testnet_genesis(
 				(team.clone(), SETUSD, 258, 1, 5_112_000, setusd_team_vesting),
 			]
 		},
-		// serp_treasury: SerpTreasuryConfig {
-		// 	stable_currency_inflation_rate: vec![
-		// 		(SETR, 0), 	// (currency_id, inflation rate of a setcurrency) to be set on-chain;
-		// 		(SETUSD, 0),	// (currency_id, inflation rate of a setcurrency) to be set on-chain;
-		// 	],
-		// 	stable_currency_cashdrop: vec![
-		// 		(SETR,  setr_cashdrop_alloc), 	// (currency_id, cashdrop pool balance of a setcurrency)
-		// 		(SETUSD,  setusd_cashdrop_alloc),  // (currency_id, cashdrop pool balance of a setcurrency)
-		// 	],
-		// },
-		// cdp_treasury: CdpTreasuryConfig {
-		// 	expected_collateral_auction_size: vec![
-		// 		(SEE, 500 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
-		// 		(SERP, 300 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
-		// 		(DNAR, 100 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
-		// 		(HELP, 100 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
-		// 	],
-		// },
-		// cdp_engine: CdpEngineConfig {
-		// 	collaterals_params: vec![
-		// 		(
-		// 			SEE,
-		// 			Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
-		// 			Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
-		// 			Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
-		// 			25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
-		// 		),
-		// 		(
-		// 			SERP,
-		// 			Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
-		// 			Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
-		// 			Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
-		// 			25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
-		// 		),
-		// 		(
-		// 			DNAR,
-		// 			Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
-		// 			Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
-		// 			Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
-		// 			25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
-		// 		),
-		// 		(
-		// 			HELP,
-		// 			Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
-		// 			Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
-		// 			Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
-		// 			25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
-		// 		),
-		// 	],
-		// },
+		serp_treasury: SerpTreasuryConfig {
+			stable_currency_inflation_rate: vec![
+				(SETR, 0), 	// (currency_id, inflation rate of a setcurrency) to be set on-chain;
+				(SETUSD, 0),	// (currency_id, inflation rate of a setcurrency) to be set on-chain;
+			],
+			stable_currency_cashdrop: vec![
+				(SETR,  setr_cashdrop_alloc), 	// (currency_id, cashdrop pool balance of a setcurrency)
+				(SETUSD,  setusd_cashdrop_alloc),  // (currency_id, cashdrop pool balance of a setcurrency)
+			],
+		},
+		cdp_treasury: CdpTreasuryConfig {
+			expected_collateral_auction_size: vec![
+				(SEE, 500 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
+				(SERP, 300 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
+				(DNAR, 100 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
+				(HELP, 100 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
+			],
+		},
+		cdp_engine: CdpEngineConfig {
+			collaterals_params: vec![
+				(
+					SEE,
+					Some(FixedU128::saturating_from_rational(1, 100_000_000)), // stability fee rate per block
+					Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
+					Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
+					Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
+					25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
+				),
+				(
+					SERP,
+					Some(FixedU128::saturating_from_rational(1, 100_000_000)), // stability fee rate per block
+					Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
+					Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
+					Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
+					25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
+				),
+				(
+					DNAR,
+					Some(FixedU128::saturating_from_rational(1, 100_000_000)), // stability fee rate per block
+					Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
+					Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
+					Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
+					25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
+				),
+				(
+					HELP,
+					Some(FixedU128::saturating_from_rational(1, 100_000_000)), // stability fee rate per block
+					Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
+					Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
+					Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
+					25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
+				),
+			],
+		},
 		// dex: DexConfig {
 		// 	initial_listing_trading_pairs: vec![],
 		// 	initial_enabled_trading_pairs: EnabledTradingPairs::get(),
@@ -1090,56 +1098,60 @@ fn mainnet_genesis(
 				(advisors_n_partners.clone(), SETUSD, 313, 1, 10_224_000, setusd_advisors_n_partners_vesting),
 			]
 		},
-		// serp_treasury: SerpTreasuryConfig {
-		// 	stable_currency_inflation_rate: vec![
-		// 		(SETR, 0), 	// (currency_id, inflation rate of a setcurrency) to be set on-chain;
-		// 		(SETUSD, 0),	// (currency_id, inflation rate of a setcurrency) to be set on-chain;
-		// 	],
-		// 	stable_currency_cashdrop: vec![
-		// 		(SETR,  setr_cashdrop_alloc), 	// (currency_id, cashdrop pool balance of a setcurrency)
-		// 		(SETUSD,  setusd_cashdrop_alloc),  // (currency_id, cashdrop pool balance of a setcurrency)
-		// 	],
-		// },
-		// cdp_treasury: CdpTreasuryConfig {
-		// 	expected_collateral_auction_size: vec![
-		// 		(SEE, 500 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
-		// 		(SERP, 300 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
-		// 		(DNAR, 100 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
-		// 		(HELP, 100 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
-		// 	],
-		// },
-		// cdp_engine: CdpEngineConfig {
-		// 	collaterals_params: vec![
-		// 		(
-		// 			SEE,
-		// 			Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
-		// 			Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
-		// 			Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
-		// 			25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
-		// 		),
-		// 		(
-		// 			SERP,
-		// 			Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
-		// 			Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
-		// 			Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
-		// 			25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
-		// 		),
-		// 		(
-		// 			DNAR,
-		// 			Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
-		// 			Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
-		// 			Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
-		// 			25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
-		// 		),
-		// 		(
-		// 			HELP,
-		// 			Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
-		// 			Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
-		// 			Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
-		// 			25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
-		// 		),
-		// 	],
-		// },
+		serp_treasury: SerpTreasuryConfig {
+			stable_currency_inflation_rate: vec![
+				(SETR, 0), 	// (currency_id, inflation rate of a setcurrency) to be set on-chain;
+				(SETUSD, 0),	// (currency_id, inflation rate of a setcurrency) to be set on-chain;
+			],
+			stable_currency_cashdrop: vec![
+				(SETR,  setr_cashdrop_alloc), 	// (currency_id, cashdrop pool balance of a setcurrency)
+				(SETUSD,  setusd_cashdrop_alloc),  // (currency_id, cashdrop pool balance of a setcurrency)
+			],
+		},
+		cdp_treasury: CdpTreasuryConfig {
+			expected_collateral_auction_size: vec![
+				(SEE, 500 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
+				(SERP, 300 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
+				(DNAR, 100 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
+				(HELP, 100 * 1_000_000_000_000_000_000), 		// (currency_id, max size of a collateral auction)
+			],
+		},
+		cdp_engine: CdpEngineConfig {
+			collaterals_params: vec![
+				(
+					SEE,
+					Some(FixedU128::saturating_from_rational(1, 100_000_000)), // stability fee rate per block
+					Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
+					Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
+					Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
+					25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
+				),
+				(
+					SERP,
+					Some(FixedU128::saturating_from_rational(1, 100_000_000)), // stability fee rate per block
+					Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
+					Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
+					Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
+					25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
+				),
+				(
+					DNAR,
+					Some(FixedU128::saturating_from_rational(1, 100_000_000)), // stability fee rate per block
+					Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
+					Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
+					Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
+					25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
+				),
+				(
+					HELP,
+					Some(FixedU128::saturating_from_rational(1, 100_000_000)), // stability fee rate per block
+					Some(FixedU128::saturating_from_rational(105, 100)), // liquidation ratio
+					Some(FixedU128::saturating_from_rational(5, 100)),   // liquidation penalty rate
+					Some(FixedU128::saturating_from_rational(110, 100)), // required liquidation ratio
+					25_800_000 * 1_000_000_000_000_000_000,              // maximum debit value in SETUSD (cap)
+				),
+			],
+		},
 		// dex: DexConfig {
 		// 	initial_listing_trading_pairs: vec![],
 		// 	initial_enabled_trading_pairs: EnabledTradingPairs::get(),
@@ -1155,9 +1167,9 @@ pub fn setheum_properties() -> Properties {
 	let mut properties = Map::new();
 	let mut token_symbol: Vec<String> = vec![];
 	let mut token_decimals: Vec<u32> = vec![];
-	[SEE, SERP, DNAR, HELP, SETR, SETUSD].iter().for_each(|token| {
-		token_symbol.push(token.symbol().unwrap().to_string());
-		token_decimals.push(token.decimals().unwrap() as u32);
+	TokenSymbol::get_info().iter().for_each(|(symbol, decimals)| {
+		token_symbol.push(symbol.to_string());
+		token_decimals.push(*decimals);
 	});
 	properties.insert("tokenSymbol".into(), token_symbol.into());
 	properties.insert("tokenDecimals".into(), token_decimals.into());
@@ -18,9 +18,10 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use std::path::PathBuf;
 use structopt::StructOpt;
 use sc_cli::RunCmd;
-
+use crate::service::Sealing;
 
 #[derive(Debug, StructOpt)]
 pub struct Cli {
@@ -29,6 +30,22 @@ pub struct Cli {
 
 	#[structopt(flatten)]
 	pub run: RunCmd,
+
+	/// Replace BABE/GRANDPA with on-demand block authoring for development.
+	///
+	/// `instant` seals a block for every incoming transaction, `manual` waits for
+	/// `engine_createBlock` RPC calls and a number seals a block every given
+	/// milliseconds. Only meant to be used with a single-node development chain.
+	#[structopt(long = "sealing")]
+	pub sealing: Option<Sealing>,
+
+	/// Build the chain spec from a TOML or JSON genesis config file, in place of
+	/// the one selected with `--chain`.
+	///
+	/// The file describes the validators, endowed accounts, vesting schedules,
+	/// council and oracle operator members, EVM accounts and token properties.
+	#[structopt(long = "genesis-config", global = true, parse(from_os_str))]
+	pub genesis_config: Option<PathBuf>,
 }
 
 #[derive(Debug, StructOpt)]
@@ -36,12 +53,12 @@ pub enum Subcommand {
 	/// Key management cli utilities
 	Key(sc_cli::KeySubcommand),
 
-	// /// The custom inspect subcommmand for decoding blocks and extrinsics.
-	// #[structopt(
-	// 	name = "inspect",
-	// 	about = "Decode given block or extrinsic using current native runtime."
-	// )]
-	// Inspect(inspect::cli::InspectCmd),
+	/// The custom inspect subcommmand for decoding blocks and extrinsics.
+	#[structopt(
+		name = "inspect",
+		about = "Decode given block or extrinsic using current native runtime."
+	)]
+	Inspect(crate::inspect::InspectCmd),
 
 	/// Verify a signature for a message, provided on STDIN, with a given
 	/// (public or secret) key.
@@ -65,6 +82,9 @@ pub enum Subcommand {
 	/// Export the state of a given block into a chain spec.
 	ExportState(sc_cli::ExportStateCmd),
 
+	/// Turn the exported state of a live chain into a development chain spec.
+	ForkState(crate::fork_state::ForkStateCmd),
+
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
@@ -77,4 +97,12 @@ pub enum Subcommand {
 	/// The custom benchmark subcommmand benchmarking runtime pallets.
 	#[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
 	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
+
+	/// Try some command against runtime state.
+	#[cfg(feature = "try-runtime")]
+	TryRuntime(try_runtime_cli::TryRuntimeCmd),
+
+	/// Try some command against runtime state. Note: `try-runtime` feature must be enabled.
+	#[cfg(not(feature = "try-runtime"))]
+	TryRuntime,
 }
@@ -19,7 +19,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 
-use crate::{chain_spec, service};
+use crate::{chain_spec, genesis_config::GenesisConfigFile, service};
 use crate::cli::{Cli, Subcommand};
 use sc_cli::{SubstrateCli, RuntimeVersion, Role, ChainSpec};
 use sc_service::PartialComponents;
@@ -51,6 +51,10 @@ impl SubstrateCli for Cli {
 	}
 
 	fn load_spec(&self, id: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
+		if let Some(path) = &self.genesis_config {
+			return Ok(Box::new(GenesisConfigFile::from_file(path)?.chain_spec()?));
+		}
+
 		Ok(match id {
 			"dev" => Box::new(chain_spec::development_config()?),
 			"local" => Box::new(chain_spec::local_testnet_config()?),
@@ -75,6 +79,14 @@ pub fn run() -> sc_cli::Result<()> {
 
 	match &cli.subcommand {
 		Some(Subcommand::Key(cmd)) => cmd.run(&cli),
+		Some(Subcommand::Inspect(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				let PartialComponents { client, ..}
+					= service::new_partial(&config)?;
+				cmd.run(client)
+			})
+		},
 		Some(Subcommand::Sign(cmd)) => cmd.run(),
 		Some(Subcommand::Verify(cmd)) => cmd.run(),
 		Some(Subcommand::Vanity(cmd)) => cmd.run(),
@@ -106,6 +118,7 @@ pub fn run() -> sc_cli::Result<()> {
 				Ok((cmd.run(client, config.chain_spec), task_manager))
 			})
 		},
+		Some(Subcommand::ForkState(cmd)) => cmd.run(&cli),
 		Some(Subcommand::ImportBlocks(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
@@ -136,12 +149,32 @@ pub fn run() -> sc_cli::Result<()> {
 				You can enable it with `--features runtime-benchmarks`.".into())
 			}
 		},
+		#[cfg(feature = "try-runtime")]
+		Some(Subcommand::TryRuntime(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			// we don't need any of the components of new_partial, just a runtime, or a task
+			// manager to do `async_run`.
+			let registry = &runner.config().prometheus_config.as_ref().map(|cfg| &cfg.registry);
+			let task_manager = sc_service::TaskManager::new(runner.config().task_executor.clone(), *registry)
+				.map_err(|e| sc_cli::Error::Service(sc_service::Error::Prometheus(e)))?;
+
+			runner.async_run(|config| Ok((cmd.run::<Block, service::Executor>(config), task_manager)))
+		},
+		#[cfg(not(feature = "try-runtime"))]
+		Some(Subcommand::TryRuntime) => {
+			Err("TryRuntime wasn't enabled when building the node. \
+				You can enable it with `--features try-runtime`.".into())
+		},
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
+			let sealing = cli.sealing;
 			runner.run_node_until_exit(|config| async move {
 				match config.role {
+					Role::Light if sealing.is_some() => Err(sc_service::Error::Other(
+						"Light clients do not support the `--sealing` option.".into(),
+					)),
 					Role::Light => service::new_light(config),
-					_ => service::new_full(config),
+					_ => service::new_full(config, sealing),
 				}.map_err(sc_cli::Error::Service)
 			})
 		}
@@ -0,0 +1,309 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `fork-state` subcommand, turning the raw state of a live chain into a
+//! development chain spec.
+
+use std::{fs, io::Write, path::PathBuf};
+
+use sc_cli::{Result, SubstrateCli};
+use serde_json::{Map, Value};
+use sp_core::{bytes::to_hex, hashing::twox_128, storage::well_known_keys};
+use structopt::StructOpt;
+
+/// Pallets whose state is taken from the base chain spec instead of the forked
+/// chain by default, so that the fork runs with the dev validator set.
+/// `System.Account` is still copied unless `System` is passed to `--drop`.
+const DEFAULT_DROPPED_PALLETS: &[&str] = &[
+	"System",
+	"Authorship",
+	"Babe",
+	"Grandpa",
+	"Session",
+	"Historical",
+	"Offences",
+	"ImOnline",
+	"AuthorityDiscovery",
+];
+
+/// `Forcing::ForceNone`, so that the forked staking state never plans a new era.
+const FORCE_NONE: &str = "0x02";
+
+/// The `fork-state` command used to bootstrap a development chain from the state
+/// of a live chain.
+#[derive(Debug, StructOpt)]
+pub struct ForkStateCmd {
+	/// Raw chain spec holding the state to fork, e.g. the output of `export-state`.
+	#[structopt(parse(from_os_str))]
+	pub input: PathBuf,
+
+	/// Chain spec providing the validator set, sudo key and runtime of the fork.
+	#[structopt(long, default_value = "dev")]
+	pub base: String,
+
+	/// Runtime wasm blob to run the fork with, instead of the base chain spec's runtime.
+	#[structopt(long, parse(from_os_str))]
+	pub wasm: Option<PathBuf>,
+
+	/// Pallets to copy from the forked state even if they are dropped by default.
+	#[structopt(long, use_delimiter = true)]
+	pub keep: Vec<String>,
+
+	/// Pallets to leave out of the forked state, in addition to the default ones.
+	#[structopt(long, use_delimiter = true)]
+	pub drop: Vec<String>,
+
+	/// Write the chain spec to this file instead of stdout.
+	#[structopt(long, short, parse(from_os_str))]
+	pub output: Option<PathBuf>,
+}
+
+impl ForkStateCmd {
+	/// Run the fork-state command
+	pub fn run<C: SubstrateCli>(&self, cli: &C) -> Result<()> {
+		let forked: Value = serde_json::from_slice(&fs::read(&self.input)?)
+			.map_err(|e| format!("Invalid chain spec {}: {}", self.input.display(), e))?;
+		let forked_top = forked
+			.pointer("/genesis/raw/top")
+			.and_then(Value::as_object)
+			.ok_or("The state to fork must be a raw chain spec")?;
+
+		let base = cli.load_spec(&self.base)?;
+		let mut spec: Value =
+			serde_json::from_str(&base.as_json(true)?).map_err(|e| format!("Invalid base chain spec: {}", e))?;
+
+		for (field, suffix) in [("name", "-fork"), ("id", "-fork"), ("protocolId", "")] {
+			if let Some(value) = forked.get(field).and_then(Value::as_str) {
+				spec[field] = Value::String(format!("{}{}", value, suffix));
+			}
+		}
+		if let Some(properties) = forked.get("properties") {
+			spec["properties"] = properties.clone();
+		}
+
+		let top = spec
+			.pointer_mut("/genesis/raw/top")
+			.and_then(Value::as_object_mut)
+			.ok_or("The base chain spec must have a raw genesis")?;
+		self.merge_state(top, forked_top)?;
+
+		let json = serde_json::to_string_pretty(&spec).map_err(|e| format!("Failed to encode chain spec: {}", e))?;
+		match &self.output {
+			Some(path) => fs::write(path, json)?,
+			None => std::io::stdout().write_all(json.as_bytes())?,
+		}
+
+		Ok(())
+	}
+
+	/// Replace the state of the kept pallets of `top` with their forked state.
+	fn merge_state(&self, top: &mut Map<String, Value>, forked_top: &Map<String, Value>) -> Result<()> {
+		let dropped = DEFAULT_DROPPED_PALLETS
+			.iter()
+			.map(|pallet| pallet.to_string())
+			.chain(self.drop.iter().cloned())
+			.filter(|pallet| !self.keep.contains(pallet))
+			.map(|pallet| storage_prefix(&pallet, None))
+			.collect::<Vec<_>>();
+		let system_account = storage_prefix("System", Some("Account"));
+		let copy_system_account = !self.drop.iter().any(|pallet| pallet == "System");
+
+		// well known keys such as `:code` always come from the base chain spec.
+		let well_known = to_hex(b":", false);
+		let is_kept_pallet =
+			|key: &str| !key.starts_with(&well_known) && !dropped.iter().any(|prefix| key.starts_with(prefix));
+
+		// the base state of the kept pallets is replaced as a whole, while the dev
+		// accounts stay funded next to the forked ones.
+		let sudo_key = storage_prefix("Sudo", Some("Key"));
+		let dev_sudo = top.get(&sudo_key).cloned();
+		top.retain(|key, _| !is_kept_pallet(key));
+		for (key, value) in forked_top {
+			if is_kept_pallet(key) || (copy_system_account && key.starts_with(&system_account)) {
+				top.insert(key.clone(), value.clone());
+			}
+		}
+
+		if let Some(dev_sudo) = dev_sudo {
+			top.insert(sudo_key, dev_sudo);
+		}
+
+		// triggers `on_runtime_upgrade` on the first block of the fork.
+		top.remove(&storage_prefix("System", Some("LastRuntimeUpgrade")));
+
+		if is_kept_pallet(&storage_prefix("Staking", None)) {
+			top.insert(
+				storage_prefix("Staking", Some("ForceEra")),
+				Value::String(FORCE_NONE.into()),
+			);
+		}
+
+		if let Some(wasm) = &self.wasm {
+			let code = fs::read(wasm)?;
+			top.insert(
+				to_hex(well_known_keys::CODE, false),
+				Value::String(to_hex(&code, false)),
+			);
+		}
+
+		Ok(())
+	}
+}
+
+/// The hex encoded storage prefix of a pallet, or of one of its storage items.
+fn storage_prefix(pallet: &str, item: Option<&str>) -> String {
+	let mut key = twox_128(pallet.as_bytes()).to_vec();
+	if let Some(item) = item {
+		key.extend_from_slice(&twox_128(item.as_bytes()));
+	}
+	to_hex(&key, false)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn fork_state_cmd(keep: &[&str], drop: &[&str], wasm: Option<PathBuf>) -> ForkStateCmd {
+		ForkStateCmd {
+			input: PathBuf::new(),
+			base: "dev".into(),
+			wasm,
+			keep: keep.iter().map(|pallet| pallet.to_string()).collect(),
+			drop: drop.iter().map(|pallet| pallet.to_string()).collect(),
+			output: None,
+		}
+	}
+
+	fn key(pallet: &str, item: &str, suffix: &str) -> String {
+		format!("{}{}", storage_prefix(pallet, Some(item)), suffix)
+	}
+
+	fn state(entries: &[(String, &str)]) -> Map<String, Value> {
+		entries
+			.iter()
+			.map(|(key, value)| (key.clone(), Value::String(value.to_string())))
+			.collect()
+	}
+
+	fn code_key() -> String {
+		to_hex(well_known_keys::CODE, false)
+	}
+
+	fn base_state() -> Map<String, Value> {
+		state(&[
+			(key("Babe", "Authorities", ""), "0xba5e"),
+			(key("System", "Account", "01"), "0xde01"),
+			(key("System", "Number", ""), "0x01"),
+			(key("Tokens", "Accounts", "01"), "0xde02"),
+			(key("Sudo", "Key", ""), "0xde03"),
+			(code_key(), "0xc0de"),
+		])
+	}
+
+	fn forked_state() -> Map<String, Value> {
+		state(&[
+			(key("Babe", "Authorities", ""), "0xf0"),
+			(key("System", "Account", "02"), "0xf1"),
+			(key("System", "Number", ""), "0xf2"),
+			(key("System", "LastRuntimeUpgrade", ""), "0xf3"),
+			(key("Tokens", "Accounts", "02"), "0xf4"),
+			(key("Sudo", "Key", ""), "0xf5"),
+			(key("Democracy", "ReferendumCount", ""), "0xf6"),
+			(key("Staking", "ForceEra", ""), "0x00"),
+			(code_key(), "0xf7"),
+		])
+	}
+
+	fn merged(cmd: &ForkStateCmd) -> Map<String, Value> {
+		let mut top = base_state();
+		cmd.merge_state(&mut top, &forked_state()).unwrap();
+		top
+	}
+
+	fn get<'a>(top: &'a Map<String, Value>, key: &str) -> Option<&'a str> {
+		top.get(key).and_then(Value::as_str)
+	}
+
+	#[test]
+	fn merge_state_drops_consensus_pallets_by_default() {
+		let top = merged(&fork_state_cmd(&[], &[], None));
+
+		// consensus state comes from the base chain spec
+		assert_eq!(get(&top, &key("Babe", "Authorities", "")), Some("0xba5e"));
+		assert_eq!(get(&top, &key("System", "Number", "")), Some("0x01"));
+		assert_eq!(get(&top, &key("System", "LastRuntimeUpgrade", "")), None);
+
+		// both the dev and the forked accounts are funded
+		assert_eq!(get(&top, &key("System", "Account", "01")), Some("0xde01"));
+		assert_eq!(get(&top, &key("System", "Account", "02")), Some("0xf1"));
+
+		// the state of the other pallets is replaced as a whole
+		assert_eq!(get(&top, &key("Tokens", "Accounts", "01")), None);
+		assert_eq!(get(&top, &key("Tokens", "Accounts", "02")), Some("0xf4"));
+		assert_eq!(get(&top, &key("Democracy", "ReferendumCount", "")), Some("0xf6"));
+
+		// the runtime of the base chain spec is kept
+		assert_eq!(get(&top, &code_key()), Some("0xc0de"));
+	}
+
+	#[test]
+	fn merge_state_keeps_and_drops_pallets() {
+		let top = merged(&fork_state_cmd(&["Babe"], &["Democracy", "System"], None));
+
+		assert_eq!(get(&top, &key("Babe", "Authorities", "")), Some("0xf0"));
+		assert_eq!(get(&top, &key("Democracy", "ReferendumCount", "")), None);
+		assert_eq!(get(&top, &key("System", "Account", "01")), Some("0xde01"));
+		assert_eq!(get(&top, &key("System", "Account", "02")), None);
+		assert_eq!(get(&top, &key("Tokens", "Accounts", "02")), Some("0xf4"));
+	}
+
+	#[test]
+	fn merge_state_keeps_dev_sudo_key() {
+		let top = merged(&fork_state_cmd(&[], &[], None));
+		assert_eq!(get(&top, &key("Sudo", "Key", "")), Some("0xde03"));
+
+		// no sudo key to keep
+		let mut top = base_state();
+		top.remove(&key("Sudo", "Key", ""));
+		fork_state_cmd(&[], &[], None)
+			.merge_state(&mut top, &forked_state())
+			.unwrap();
+		assert_eq!(get(&top, &key("Sudo", "Key", "")), Some("0xf5"));
+	}
+
+	#[test]
+	fn merge_state_forces_no_new_era() {
+		let top = merged(&fork_state_cmd(&[], &[], None));
+		assert_eq!(get(&top, &key("Staking", "ForceEra", "")), Some(FORCE_NONE));
+
+		let top = merged(&fork_state_cmd(&[], &["Staking"], None));
+		assert_eq!(get(&top, &key("Staking", "ForceEra", "")), None);
+	}
+
+	#[test]
+	fn merge_state_replaces_code() {
+		let wasm = std::env::temp_dir().join(format!("setheum-fork-state-{}.wasm", std::process::id()));
+		fs::write(&wasm, [0x00, 0x61, 0x73, 0x6d]).unwrap();
+		let top = merged(&fork_state_cmd(&[], &[], Some(wasm.clone())));
+		fs::remove_file(&wasm).unwrap();
+
+		assert_eq!(get(&top, &code_key()), Some("0x0061736d"));
+	}
+}
@@ -0,0 +1,785 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Chain specs built from a TOML or JSON genesis config file, passed to the
+//! node with `--genesis-config`.
+
+use std::{
+	collections::{btree_map::Entry, BTreeMap, BTreeSet},
+	convert::TryFrom,
+	fs,
+	path::Path,
+};
+
+use sc_service::{ChainType, Properties};
+use serde::{de, Deserialize, Deserializer};
+use serde_json::map::Map;
+use setheum_primitives::{currency::TokenInfo, AccountId, Balance, BlockNumber, CurrencyId, Nonce, TokenSymbol};
+use setheum_runtime::{
+	get_all_module_accounts, opaque::SessionKeys, AuthorityDiscoveryConfig, AuthorityDiscoveryId, BabeConfig,
+	BalancesConfig, EVMConfig, FinancialCouncilMembershipConfig, GenesisConfig, GetNativeCurrencyId, ImOnlineId,
+	IndicesConfig, NativeTokenExistentialDeposit, OperatorMembershipSetheumConfig, OrmlNFTConfig, SS58Prefix,
+	SessionConfig, ShuraCouncilMembershipConfig, StakerStatus, StakingConfig, SudoConfig, SystemConfig,
+	TechnicalCommitteeMembershipConfig, TokensConfig, VestingConfig, BABE_GENESIS_EPOCH_CONFIG, WASM_BINARY,
+};
+use sp_consensus_babe::AuthorityId as BabeId;
+use sp_core::{
+	bytes::from_hex,
+	crypto::{Ss58Codec, UncheckedInto},
+	sr25519, Bytes, H160, H256,
+};
+use sp_finality_grandpa::AuthorityId as GrandpaId;
+
+use crate::chain_spec::{evm_genesis, get_account_id_from_seed, get_authority_keys_from_seed, ChainSpec};
+
+/// The genesis of a chain, as written in a genesis config file.
+///
+/// Accounts are SS58 addresses, `0x` prefixed public keys or dev seeds such as
+/// `//Alice`, currencies are token symbols and amounts are in the smallest unit
+/// of their currency.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct GenesisConfigFile {
+	/// Name of the chain.
+	pub name: String,
+	/// Id of the chain.
+	pub id: String,
+	/// Type of the chain, `Live` if omitted.
+	#[serde(default)]
+	pub chain_type: ChainType,
+	/// Network protocol id.
+	#[serde(default)]
+	pub protocol_id: Option<String>,
+	/// Sudo account, also the EVM treasury unless `evm.treasury` is set.
+	pub sudo: String,
+	/// Initial validators.
+	pub authorities: Vec<Authority>,
+	/// Initial balances.
+	#[serde(default)]
+	pub endowments: Vec<Endowment>,
+	/// Vesting schedules, at most one per account and currency.
+	#[serde(default)]
+	pub vesting: Vec<Vesting>,
+	/// Council and oracle operator members.
+	#[serde(default)]
+	pub membership: Membership,
+	/// EVM accounts and contracts.
+	#[serde(default)]
+	pub evm: Evm,
+	/// Token properties of the chain spec.
+	#[serde(default)]
+	pub properties: Option<TokenProperties>,
+}
+
+/// An initial validator, given either by a dev seed or by all of its keys.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Authority {
+	/// Dev seed the keys are derived from, e.g. `Alice`.
+	#[serde(default)]
+	pub seed: Option<String>,
+	#[serde(default)]
+	pub stash: Option<String>,
+	#[serde(default)]
+	pub controller: Option<String>,
+	/// Hex encoded session keys.
+	#[serde(default)]
+	pub grandpa: Option<String>,
+	#[serde(default)]
+	pub babe: Option<String>,
+	#[serde(default)]
+	pub im_online: Option<String>,
+	#[serde(default)]
+	pub authority_discovery: Option<String>,
+	/// Amount bonded by the stash, which is endowed with it on top of its endowments.
+	pub bond: Amount,
+}
+
+/// Balances of an account, keyed by token symbol.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Endowment {
+	pub account: String,
+	pub balances: BTreeMap<String, Amount>,
+}
+
+/// A vesting schedule, locking `period_count * per_period` of the endowed balance.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Vesting {
+	pub account: String,
+	pub currency: String,
+	pub start: BlockNumber,
+	pub period: BlockNumber,
+	pub period_count: u32,
+	pub per_period: Amount,
+}
+
+/// Members of the collective and oracle membership pallets, the sudo account
+/// if a list is empty.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Membership {
+	#[serde(default)]
+	pub shura_council: Vec<String>,
+	#[serde(default)]
+	pub financial_council: Vec<String>,
+	#[serde(default)]
+	pub technical_committee: Vec<String>,
+	#[serde(default)]
+	pub oracle_operators: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Evm {
+	/// Deploy the predeploy contracts, as the built-in chain specs do.
+	#[serde(default)]
+	pub predeploys: bool,
+	#[serde(default)]
+	pub treasury: Option<String>,
+	#[serde(default)]
+	pub accounts: Vec<EvmAccount>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EvmAccount {
+	pub address: H160,
+	#[serde(default)]
+	pub balance: Amount,
+	#[serde(default)]
+	pub nonce: Nonce,
+	#[serde(default)]
+	pub code: Bytes,
+	#[serde(default)]
+	pub storage: BTreeMap<H256, H256>,
+}
+
+/// The tokens listed in the `tokenSymbol` and `tokenDecimals` properties, all
+/// the tokens of `TokenSymbol` if omitted.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TokenProperties {
+	pub tokens: Vec<String>,
+}
+
+/// An amount, either an integer or a string so that it can exceed `u64`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Amount(pub Balance);
+
+impl<'de> Deserialize<'de> for Amount {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		#[derive(Deserialize)]
+		#[serde(untagged)]
+		enum Raw {
+			Number(u64),
+			String(String),
+		}
+
+		match Raw::deserialize(deserializer)? {
+			Raw::Number(amount) => Ok(Amount(amount.into())),
+			Raw::String(amount) => amount
+				.replace('_', "")
+				.parse()
+				.map(Amount)
+				.map_err(|_| de::Error::custom(format!("invalid amount: {}", amount))),
+		}
+	}
+}
+
+/// The validated content of a genesis config file.
+#[derive(Clone)]
+struct Genesis {
+	authorities: Vec<(
+		AccountId,
+		AccountId,
+		GrandpaId,
+		BabeId,
+		ImOnlineId,
+		AuthorityDiscoveryId,
+		Balance,
+	)>,
+	sudo: AccountId,
+	balances: Vec<(AccountId, Balance)>,
+	tokens: Vec<(AccountId, CurrencyId, Balance)>,
+	vesting: Vec<(AccountId, CurrencyId, BlockNumber, BlockNumber, u32, Balance)>,
+	shura_council: Vec<AccountId>,
+	financial_council: Vec<AccountId>,
+	technical_committee: Vec<AccountId>,
+	oracle_operators: Vec<AccountId>,
+	evm_accounts: BTreeMap<H160, module_evm::GenesisAccount<Balance, Nonce>>,
+	evm_treasury: AccountId,
+}
+
+impl GenesisConfigFile {
+	/// Read a `.toml` or `.json` genesis config file.
+	pub fn from_file(path: &Path) -> Result<Self, String> {
+		let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+		match path.extension().and_then(|extension| extension.to_str()) {
+			Some("toml") => toml::from_str(&content).map_err(|e| format!("Invalid {}: {}", path.display(), e)),
+			Some("json") => serde_json::from_str(&content).map_err(|e| format!("Invalid {}: {}", path.display(), e)),
+			_ => Err(format!("{} must be a .toml or .json file", path.display())),
+		}
+	}
+
+	/// Validate the genesis config and build its chain spec.
+	pub fn chain_spec(self) -> Result<ChainSpec, String> {
+		let wasm_binary = WASM_BINARY.ok_or_else(|| "WASM binary not available".to_string())?;
+		let genesis = self.validate()?;
+		let properties = self.properties()?;
+
+		Ok(ChainSpec::from_genesis(
+			&self.name,
+			&self.id,
+			self.chain_type.clone(),
+			move || genesis.build(wasm_binary),
+			vec![],
+			None,
+			self.protocol_id.as_deref(),
+			Some(properties),
+			Default::default(),
+		))
+	}
+
+	fn validate(&self) -> Result<Genesis, String> {
+		let sudo = parse_account(&self.sudo)?;
+		let existential_deposit = NativeTokenExistentialDeposit::get();
+
+		if self.authorities.is_empty() {
+			return Err("At least one authority is required".into());
+		}
+		let mut stashes = BTreeSet::new();
+		let authorities = self
+			.authorities
+			.iter()
+			.map(|authority| {
+				let keys = authority.keys()?;
+				if !stashes.insert(keys.0.clone()) {
+					return Err(format!("Duplicate authority stash {}", keys.0));
+				}
+				Ok((keys.0, keys.1, keys.2, keys.3, keys.4, keys.5, authority.bond.0))
+			})
+			.collect::<Result<Vec<_>, String>>()?;
+
+		// the stashes are endowed with their bond and the module accounts with the
+		// existential deposit, on top of the endowments of the file.
+		let mut accounts = BTreeSet::new();
+		let mut balances = BTreeMap::<(AccountId, CurrencyId), Balance>::new();
+		for endowment in &self.endowments {
+			let account = parse_account(&endowment.account)?;
+			if !accounts.insert(account.clone()) {
+				return Err(format!("Duplicate endowed account {}", account));
+			}
+			for (symbol, amount) in &endowment.balances {
+				add_balance(&mut balances, account.clone(), parse_currency(symbol)?, amount.0)?;
+			}
+		}
+		for (stash, _, _, _, _, _, bond) in &authorities {
+			add_balance(
+				&mut balances,
+				stash.clone(),
+				GetNativeCurrencyId::get(),
+				bond.saturating_add(existential_deposit),
+			)?;
+		}
+		for account in get_all_module_accounts() {
+			add_balance(&mut balances, account, GetNativeCurrencyId::get(), existential_deposit)?;
+		}
+
+		let mut schedules = BTreeSet::new();
+		let vesting = self
+			.vesting
+			.iter()
+			.map(|vesting| {
+				let account = parse_account(&vesting.account)?;
+				let currency_id = parse_currency(&vesting.currency)?;
+				if !schedules.insert((account.clone(), currency_id)) {
+					return Err(format!(
+						"Duplicate vesting schedule of {} for {}",
+						account, vesting.currency
+					));
+				}
+				let locked = vesting
+					.per_period
+					.0
+					.checked_mul(vesting.period_count.into())
+					.ok_or_else(|| format!("Vesting schedule of {} for {} overflows", account, vesting.currency))?;
+				let endowed = balances
+					.get(&(account.clone(), currency_id))
+					.copied()
+					.unwrap_or_default();
+				if locked > endowed {
+					return Err(format!(
+						"Vesting schedule of {} locks {} {} but only {} is endowed",
+						account, locked, vesting.currency, endowed
+					));
+				}
+				Ok((
+					account,
+					currency_id,
+					vesting.start,
+					vesting.period,
+					vesting.period_count,
+					vesting.per_period.0,
+				))
+			})
+			.collect::<Result<Vec<_>, String>>()?;
+
+		let members = |name: &str, members: &[String]| -> Result<Vec<AccountId>, String> {
+			if members.is_empty() {
+				return Ok(vec![sudo.clone()]);
+			}
+			let mut unique = BTreeSet::new();
+			members
+				.iter()
+				.map(|member| {
+					let member = parse_account(member)?;
+					if !unique.insert(member.clone()) {
+						return Err(format!("Duplicate member {} of {}", member, name));
+					}
+					Ok(member)
+				})
+				.collect()
+		};
+
+		let mut evm_accounts = if self.evm.predeploys {
+			evm_genesis()
+		} else {
+			BTreeMap::new()
+		};
+		for account in &self.evm.accounts {
+			let genesis_account = module_evm::GenesisAccount {
+				nonce: account.nonce,
+				balance: account.balance.0,
+				storage: account.storage.clone(),
+				code: account.code.0.clone(),
+			};
+			if evm_accounts.insert(account.address, genesis_account).is_some() {
+				return Err(format!("Duplicate EVM account {:?}", account.address));
+			}
+		}
+		let evm_treasury = match &self.evm.treasury {
+			Some(treasury) => parse_account(treasury)?,
+			None => sudo.clone(),
+		};
+
+		let native_currency_id = GetNativeCurrencyId::get();
+		let (native, tokens): (Vec<_>, Vec<_>) = balances
+			.into_iter()
+			.partition(|((_, currency_id), _)| *currency_id == native_currency_id);
+
+		Ok(Genesis {
+			shura_council: members("shuraCouncil", &self.membership.shura_council)?,
+			financial_council: members("financialCouncil", &self.membership.financial_council)?,
+			technical_committee: members("technicalCommittee", &self.membership.technical_committee)?,
+			oracle_operators: members("oracleOperators", &self.membership.oracle_operators)?,
+			authorities,
+			sudo,
+			balances: native
+				.into_iter()
+				.map(|((account, _), amount)| (account, amount))
+				.collect(),
+			tokens: tokens
+				.into_iter()
+				.map(|((account, currency_id), amount)| (account, currency_id, amount))
+				.collect(),
+			vesting,
+			evm_accounts,
+			evm_treasury,
+		})
+	}
+
+	fn properties(&self) -> Result<Properties, String> {
+		let tokens = match &self.properties {
+			Some(properties) => {
+				let mut unique = BTreeSet::new();
+				properties
+					.tokens
+					.iter()
+					.map(|symbol| {
+						let currency_id = parse_currency(symbol)?;
+						if !unique.insert(currency_id) {
+							return Err(format!("Duplicate token property {}", symbol));
+						}
+						Ok((
+							currency_id.symbol().unwrap_or_default().to_string(),
+							currency_id.decimals().unwrap_or_default() as u32,
+						))
+					})
+					.collect::<Result<Vec<_>, String>>()?
+			}
+			None => TokenSymbol::get_info()
+				.into_iter()
+				.map(|(symbol, decimals)| (symbol.to_string(), decimals))
+				.collect(),
+		};
+
+		let (token_symbol, token_decimals): (Vec<_>, Vec<_>) = tokens.into_iter().unzip();
+		let mut properties = Map::new();
+		properties.insert("tokenSymbol".into(), token_symbol.into());
+		properties.insert("tokenDecimals".into(), token_decimals.into());
+		properties.insert("ss58Format".into(), SS58Prefix::get().into());
+
+		Ok(properties)
+	}
+}
+
+impl Authority {
+	fn keys(
+		&self,
+	) -> Result<
+		(
+			AccountId,
+			AccountId,
+			GrandpaId,
+			BabeId,
+			ImOnlineId,
+			AuthorityDiscoveryId,
+		),
+		String,
+	> {
+		if let Some(seed) = &self.seed {
+			return Ok(get_authority_keys_from_seed(seed));
+		}
+
+		let field = |value: &Option<String>, name: &str| {
+			value
+				.as_deref()
+				.ok_or_else(|| format!("Authority without a seed is missing `{}`", name))
+		};
+		Ok((
+			parse_account(field(&self.stash, "stash")?)?,
+			parse_account(field(&self.controller, "controller")?)?,
+			parse_key(field(&self.grandpa, "grandpa")?)?,
+			parse_key(field(&self.babe, "babe")?)?,
+			parse_key(field(&self.im_online, "imOnline")?)?,
+			parse_key(field(&self.authority_discovery, "authorityDiscovery")?)?,
+		))
+	}
+}
+
+impl Genesis {
+	fn build(&self, wasm_binary: &[u8]) -> GenesisConfig {
+		GenesisConfig {
+			system: SystemConfig {
+				code: wasm_binary.to_vec(),
+				changes_trie_config: Default::default(),
+			},
+			indices: IndicesConfig { indices: vec![] },
+			balances: BalancesConfig {
+				balances: self.balances.clone(),
+			},
+			sudo: SudoConfig { key: self.sudo.clone() },
+			shura_council: Default::default(),
+			shura_council_membership: ShuraCouncilMembershipConfig {
+				members: self.shura_council.clone(),
+				phantom: Default::default(),
+			},
+			financial_council: Default::default(),
+			financial_council_membership: FinancialCouncilMembershipConfig {
+				members: self.financial_council.clone(),
+				phantom: Default::default(),
+			},
+			technical_committee: Default::default(),
+			technical_committee_membership: TechnicalCommitteeMembershipConfig {
+				members: self.technical_committee.clone(),
+				phantom: Default::default(),
+			},
+			operator_membership_setheum: OperatorMembershipSetheumConfig {
+				members: self.oracle_operators.clone(),
+				phantom: Default::default(),
+			},
+			session: SessionConfig {
+				keys: self
+					.authorities
+					.iter()
+					.map(|x| {
+						(
+							x.0.clone(),
+							x.0.clone(),
+							SessionKeys {
+								grandpa: x.2.clone(),
+								babe: x.3.clone(),
+								im_online: x.4.clone(),
+								authority_discovery: x.5.clone(),
+							},
+						)
+					})
+					.collect(),
+			},
+			staking: StakingConfig {
+				validator_count: self.authorities.len() as u32 + 2,
+				minimum_validator_count: 1,
+				stakers: self
+					.authorities
+					.iter()
+					.map(|x| (x.0.clone(), x.1.clone(), x.6, StakerStatus::Validator))
+					.collect(),
+				invulnerables: self.authorities.iter().map(|x| x.0.clone()).collect(),
+				slash_reward_fraction: sp_runtime::Perbill::from_percent(10),
+				..Default::default()
+			},
+			babe: BabeConfig {
+				authorities: Default::default(),
+				epoch_config: Some(BABE_GENESIS_EPOCH_CONFIG),
+			},
+			grandpa: Default::default(),
+			authority_discovery: AuthorityDiscoveryConfig { keys: vec![] },
+			im_online: Default::default(),
+			treasury: Default::default(),
+			tokens: TokensConfig {
+				balances: self.tokens.clone(),
+			},
+			evm: EVMConfig {
+				accounts: self.evm_accounts.clone(),
+				treasury: self.evm_treasury.clone(),
+			},
+			vesting: VestingConfig {
+				vesting: self.vesting.clone(),
+			},
+			serp_treasury: Default::default(),
+			cdp_treasury: Default::default(),
+			cdp_engine: Default::default(),
+			orml_nft: OrmlNFTConfig { tokens: vec![] },
+		}
+	}
+}
+
+fn add_balance(
+	balances: &mut BTreeMap<(AccountId, CurrencyId), Balance>,
+	account: AccountId,
+	currency_id: CurrencyId,
+	amount: Balance,
+) -> Result<(), String> {
+	match balances.entry((account, currency_id)) {
+		Entry::Vacant(entry) => {
+			entry.insert(amount);
+		}
+		Entry::Occupied(mut entry) => {
+			let balance = entry
+				.get()
+				.checked_add(amount)
+				.ok_or_else(|| format!("Balance of {} overflows", entry.key().0))?;
+			entry.insert(balance);
+		}
+	}
+	Ok(())
+}
+
+fn parse_account(input: &str) -> Result<AccountId, String> {
+	if let Some(seed) = input.strip_prefix("//") {
+		return Ok(get_account_id_from_seed::<sr25519::Public>(seed));
+	}
+	if input.starts_with("0x") {
+		let bytes = from_hex(input).map_err(|e| format!("Invalid account {}: {:?}", input, e))?;
+		let public = <[u8; 32]>::try_from(&bytes[..]).map_err(|_| format!("Invalid account length: {}", input))?;
+		return Ok(public.into());
+	}
+	AccountId::from_ss58check(input).map_err(|e| format!("Invalid account {}: {:?}", input, e))
+}
+
+fn parse_key<T>(input: &str) -> Result<T, String>
+where
+	[u8; 32]: UncheckedInto<T>,
+{
+	let bytes = from_hex(input).map_err(|e| format!("Invalid key {}: {:?}", input, e))?;
+	let public = <[u8; 32]>::try_from(&bytes[..]).map_err(|_| format!("Invalid key length: {}", input))?;
+	Ok(public.unchecked_into())
+}
+
+fn parse_currency(symbol: &str) -> Result<CurrencyId, String> {
+	CurrencyId::try_from(symbol.as_bytes().to_vec()).map_err(|_| format!("Unknown currency {}", symbol))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EXAMPLE: &str = include_str!("../../../../resources/genesis-config.example.toml");
+
+	const MINIMAL: &str = r#"
+		name = "Setheum Test"
+		id = "setheum_test"
+		sudo = "//Alice"
+
+		[[authorities]]
+		seed = "Alice"
+		bond = 1_000
+	"#;
+
+	fn validate(config: &str) -> Result<Genesis, String> {
+		toml::from_str::<GenesisConfigFile>(config)
+			.map_err(|e| e.to_string())?
+			.validate()
+	}
+
+	fn validate_err(config: &str) -> String {
+		match validate(config) {
+			Ok(_) => panic!("genesis config should be invalid"),
+			Err(e) => e,
+		}
+	}
+
+	#[test]
+	fn example_genesis_config_is_valid() {
+		let genesis = validate(EXAMPLE).unwrap();
+		let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+		let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+
+		assert_eq!(genesis.sudo, alice);
+		assert_eq!(genesis.authorities.len(), 2);
+		assert!(genesis.tokens.contains(&(
+			charlie.clone(),
+			CurrencyId::Token(TokenSymbol::KHA),
+			1_000_000_000_000_000_000_000
+		)));
+		assert!(genesis
+			.balances
+			.contains(&(charlie.clone(), 1_000_000_000_000_000_000_000)));
+		assert_eq!(
+			genesis.vesting,
+			vec![(
+				charlie.clone(),
+				CurrencyId::Token(TokenSymbol::SEE),
+				10,
+				1,
+				3600,
+				100_000_000_000_000_000
+			)]
+		);
+		assert_eq!(genesis.financial_council, vec![alice.clone()]);
+		assert_eq!(genesis.oracle_operators.len(), 3);
+		assert_eq!(genesis.evm_treasury, alice);
+		assert!(!genesis.evm_accounts.is_empty());
+	}
+
+	#[test]
+	fn minimal_genesis_config_defaults_to_sudo() {
+		let genesis = validate(MINIMAL).unwrap();
+		let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+
+		assert_eq!(genesis.shura_council, vec![alice.clone()]);
+		assert_eq!(genesis.technical_committee, vec![alice.clone()]);
+		assert!(genesis.vesting.is_empty());
+		assert!(genesis.evm_accounts.is_empty());
+	}
+
+	#[test]
+	fn unknown_currency_is_rejected() {
+		let config = format!(
+			r#"{}
+			[[endowments]]
+			account = "//Bob"
+			balances = {{ SEE = 100, FOO = 100 }}
+			"#,
+			MINIMAL
+		);
+		assert_eq!(validate_err(&config), "Unknown currency FOO");
+
+		let config = format!(
+			r#"{}
+			[[vesting]]
+			account = "//Bob"
+			currency = "FOO"
+			start = 0
+			period = 1
+			periodCount = 1
+			perPeriod = 1
+			"#,
+			MINIMAL
+		);
+		assert_eq!(validate_err(&config), "Unknown currency FOO");
+	}
+
+	#[test]
+	fn duplicate_accounts_are_rejected() {
+		let config = format!(
+			r#"{}
+			[[endowments]]
+			account = "//Bob"
+			balances = {{ SEE = 100 }}
+
+			[[endowments]]
+			account = "//Bob"
+			balances = {{ USSD = 100 }}
+			"#,
+			MINIMAL
+		);
+		assert!(validate_err(&config).starts_with("Duplicate endowed account"));
+
+		let config = format!(
+			r#"{}
+			[[authorities]]
+			seed = "Alice"
+			bond = 1_000
+			"#,
+			MINIMAL
+		);
+		assert!(validate_err(&config).starts_with("Duplicate authority stash"));
+
+		let config = format!(
+			r#"{}
+			[membership]
+			shuraCouncil = ["//Bob", "//Bob"]
+			"#,
+			MINIMAL
+		);
+		assert!(validate_err(&config).starts_with("Duplicate member"));
+	}
+
+	#[test]
+	fn vesting_over_endowment_is_rejected() {
+		let vesting = |per_period: u32| {
+			format!(
+				r#"{}
+				[[endowments]]
+				account = "//Bob"
+				balances = {{ SEE = 100 }}
+
+				[[vesting]]
+				account = "//Bob"
+				currency = "SEE"
+				start = 0
+				period = 1
+				periodCount = 10
+				perPeriod = {}
+				"#,
+				MINIMAL, per_period
+			)
+		};
+
+		assert!(validate(&vesting(10)).is_ok());
+		let error = validate_err(&vesting(11));
+		assert!(error.starts_with("Vesting schedule of"));
+		assert!(error.ends_with("locks 110 SEE but only 100 is endowed"));
+	}
+
+	#[test]
+	fn authorities_are_required() {
+		let config = r#"
+			name = "Setheum Test"
+			id = "setheum_test"
+			sudo = "//Alice"
+			authorities = []
+		"#;
+		assert_eq!(validate_err(config), "At least one authority is required");
+	}
+}
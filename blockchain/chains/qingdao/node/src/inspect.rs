@@ -0,0 +1,480 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `inspect` subcommand, decoding blocks and extrinsics of the local
+//! database with the native runtime types.
+
+use std::{convert::TryFrom, sync::Arc};
+
+use codec::{Compact, Decode, Encode};
+use frame_support::traits::{CallMetadata, GetCallMetadata};
+use frame_system::{EventRecord, Phase};
+use sc_cli::{CliConfiguration, ImportParams, Result, SharedParams};
+use sc_client_api::{BlockBackend, StorageProvider};
+use scale_info::{form::PortableForm, Field, MetaType, PortableRegistry, Registry, TypeDef, TypeDefPrimitive, Variant};
+use serde_json::{json, Map, Value};
+use setheum_runtime::{opaque::Block, Call, Event, Hash, Nonce, SignedExtra, UncheckedExtrinsic};
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+	bytes::{from_hex, to_hex},
+	hashing::{blake2_256, twox_128},
+	storage::StorageKey,
+	U256,
+};
+use sp_runtime::{
+	generic::{BlockId, Era},
+	traits::{Block as BlockT, Header as HeaderT},
+	MultiAddress,
+};
+use structopt::StructOpt;
+
+use crate::service::FullClient;
+
+/// The `inspect` command used to decode blocks and extrinsics.
+#[derive(Debug, StructOpt)]
+pub struct InspectCmd {
+	#[allow(missing_docs)]
+	#[structopt(subcommand)]
+	pub command: InspectSubCmd,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub import_params: ImportParams,
+}
+
+/// What to inspect.
+#[derive(Debug, StructOpt)]
+pub enum InspectSubCmd {
+	/// Decode a block with its extrinsics and events.
+	Block {
+		/// Number or hash of the block, the best block if omitted.
+		input: Option<String>,
+	},
+	/// Decode an extrinsic with its events.
+	Extrinsic {
+		/// `<block number or hash>:<index>`, the hash of an extrinsic, or a hex
+		/// encoded extrinsic.
+		input: String,
+
+		/// How many blocks below the best block are searched for an extrinsic hash.
+		#[structopt(long, default_value = "1000")]
+		search_depth: u32,
+	},
+}
+
+impl InspectCmd {
+	/// Run the inspect command
+	pub fn run(&self, client: Arc<FullClient>) -> Result<()> {
+		let json = match &self.command {
+			InspectSubCmd::Block { input } => {
+				let id = match input {
+					Some(input) => parse_block_id(input)?,
+					None => BlockId::Hash(client.info().best_hash),
+				};
+				block_json(&client, id)?
+			}
+			InspectSubCmd::Extrinsic { input, search_depth } => extrinsic_json(&client, input, *search_depth)?,
+		};
+
+		println!(
+			"{}",
+			serde_json::to_string_pretty(&json).map_err(|e| format!("Failed to encode output: {}", e))?
+		);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for InspectCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn import_params(&self) -> Option<&ImportParams> {
+		Some(&self.import_params)
+	}
+}
+
+/// Parse a block number or a hex encoded block hash.
+fn parse_block_id(input: &str) -> Result<BlockId<Block>> {
+	if input.starts_with("0x") {
+		Ok(BlockId::Hash(parse_hash(input)?))
+	} else {
+		let number = input.parse().map_err(|_| format!("Invalid block number: {}", input))?;
+		Ok(BlockId::Number(number))
+	}
+}
+
+fn parse_hash(input: &str) -> Result<Hash> {
+	let bytes = from_hex(input).map_err(|e| format!("Invalid hex {}: {:?}", input, e))?;
+	if bytes.len() != Hash::len_bytes() {
+		return Err(format!("Invalid hash length: {}", input).into());
+	}
+	Ok(Hash::from_slice(&bytes))
+}
+
+fn block_json(client: &FullClient, id: BlockId<Block>) -> Result<Value> {
+	let block = client
+		.block(&id)?
+		.ok_or_else(|| format!("Block {} not found", id))?
+		.block;
+	let header = block.header();
+	let hash = header.hash();
+	let events = block_events(client, hash);
+
+	let extrinsics = block
+		.extrinsics()
+		.iter()
+		.enumerate()
+		.map(|(index, ext)| {
+			let ext = UncheckedExtrinsic::decode(&mut &ext.encode()[..])
+				.map_err(|e| format!("Failed to decode extrinsic {}: {}", index, e))?;
+			Ok(decoded_extrinsic_json(&ext, index as u32, events.as_deref()))
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	Ok(json!({
+		"number": header.number(),
+		"hash": hash,
+		"parentHash": header.parent_hash(),
+		"stateRoot": header.state_root(),
+		"extrinsicsRoot": header.extrinsics_root(),
+		"digest": header.digest().logs().iter().map(|log| format!("{:?}", log)).collect::<Vec<_>>(),
+		"extrinsics": extrinsics,
+		// events of `on_initialize` and `on_finalize`.
+		"events": events.map(|events| {
+			events
+				.iter()
+				.filter(|record| !matches!(record.phase, Phase::ApplyExtrinsic(_)))
+				.map(event_json)
+				.collect::<Vec<_>>()
+		}),
+	}))
+}
+
+fn extrinsic_json(client: &FullClient, input: &str, search_depth: u32) -> Result<Value> {
+	// `<block>:<index>`
+	if let Some((block, index)) = input.split_once(':') {
+		let id = parse_block_id(block)?;
+		let index = index
+			.parse::<usize>()
+			.map_err(|_| format!("Invalid extrinsic index: {}", index))?;
+		let block = client
+			.block(&id)?
+			.ok_or_else(|| format!("Block {} not found", id))?
+			.block;
+		let ext = block
+			.extrinsics()
+			.get(index)
+			.ok_or_else(|| format!("Block {} has no extrinsic {}", id, index))?;
+		return in_block_json(client, block.header().hash(), &ext.encode(), index as u32);
+	}
+
+	let bytes = from_hex(input).map_err(|e| format!("Invalid hex {}: {:?}", input, e))?;
+	if bytes.len() != Hash::len_bytes() {
+		// a raw extrinsic that is not looked up in the database.
+		let ext = UncheckedExtrinsic::decode(&mut &bytes[..]).map_err(|e| format!("Invalid extrinsic: {}", e))?;
+		return Ok(json!({
+			"hash": Hash::from(blake2_256(&bytes)),
+			"extrinsic": signed_json(&ext),
+			"call": call_json(&ext.function),
+		}));
+	}
+
+	let ext_hash = Hash::from_slice(&bytes);
+	let best = client.info().best_number;
+	for number in (best.saturating_sub(search_depth)..=best).rev() {
+		let block = match client.block(&BlockId::Number(number))? {
+			Some(block) => block.block,
+			None => continue,
+		};
+		for (index, ext) in block.extrinsics().iter().enumerate() {
+			let encoded = ext.encode();
+			if Hash::from(blake2_256(&encoded)) == ext_hash {
+				return in_block_json(client, block.header().hash(), &encoded, index as u32);
+			}
+		}
+	}
+
+	Err(format!("Extrinsic {} not found in the last {} blocks", input, search_depth).into())
+}
+
+fn in_block_json(client: &FullClient, block_hash: Hash, encoded: &[u8], index: u32) -> Result<Value> {
+	let ext = UncheckedExtrinsic::decode(&mut &encoded[..]).map_err(|e| format!("Invalid extrinsic: {}", e))?;
+	let mut json = decoded_extrinsic_json(&ext, index, block_events(client, block_hash).as_deref());
+	json["block"] = json!(block_hash);
+	Ok(json)
+}
+
+fn decoded_extrinsic_json(ext: &UncheckedExtrinsic, index: u32, events: Option<&[EventRecord<Event, Hash>]>) -> Value {
+	json!({
+		"index": index,
+		"hash": Hash::from(blake2_256(&ext.encode())),
+		"extrinsic": signed_json(ext),
+		"call": call_json(&ext.function),
+		// `null` when the state of the block has been pruned.
+		"events": events.map(|events| {
+			events
+				.iter()
+				.filter(|record| record.phase == Phase::ApplyExtrinsic(index))
+				.map(event_json)
+				.collect::<Vec<_>>()
+		}),
+	})
+}
+
+/// The signer, signature and `SignedExtra` fields, `null` for unsigned extrinsics.
+fn signed_json(ext: &UncheckedExtrinsic) -> Value {
+	let (address, signature, extra) = match &ext.signature {
+		Some(signature) => signature,
+		None => return Value::Null,
+	};
+	let signer = match address {
+		MultiAddress::Id(account_id) => json!(account_id.to_string()),
+		address => json!(format!("{:?}", address)),
+	};
+
+	json!({
+		"signer": signer,
+		"signature": to_hex(&signature.encode(), false),
+		"signedExtra": signed_extra_json(extra),
+	})
+}
+
+fn signed_extra_json(extra: &SignedExtra) -> Value {
+	// the era and nonce are private to the extensions, read them from their encoding.
+	let era = extra.3.using_encoded(|mut encoded| Era::decode(&mut encoded)).ok();
+	let nonce = extra
+		.4
+		.using_encoded(|mut encoded| Compact::<Nonce>::decode(&mut encoded))
+		.ok()
+		.map(|nonce| nonce.0);
+
+	json!({
+		"CheckEra": era.map(|era| match era {
+			Era::Immortal => json!("immortal"),
+			Era::Mortal(period, phase) => json!({ "period": period, "phase": phase }),
+		}),
+		"CheckNonce": nonce,
+		"ChargeTransactionPayment": { "tip": extra.6 .0.to_string() },
+		// `SetEvmOrigin` carries no encoded data, it sets the signer as the origin of the EVM
+		// calls of the extrinsic.
+		"SetEvmOrigin": Value::Null,
+	})
+}
+
+thread_local! {
+	static CALL_DECODER: CallDecoder = CallDecoder::new();
+}
+
+/// The pallet and call names with the decoded arguments, the nested calls of
+/// batches, sudo, proxies and others forming a call tree.
+fn call_json(call: &Call) -> Value {
+	let encoded = call.encode();
+	let decoded = CALL_DECODER.with(|decoder| decoder.decode_call(&mut &encoded[..]));
+
+	decoded.unwrap_or_else(|error| {
+		let CallMetadata {
+			pallet_name,
+			function_name,
+		} = call.get_call_metadata();
+		json!({
+			"pallet": pallet_name,
+			"call": function_name,
+			"error": error,
+		})
+	})
+}
+
+/// Decodes SCALE encoded calls to JSON with the type information of the runtime.
+struct CallDecoder {
+	registry: PortableRegistry,
+	call_type: u32,
+}
+
+impl CallDecoder {
+	fn new() -> Self {
+		let mut registry = Registry::new();
+		let call_type = registry.register_type(&MetaType::new::<Call>()).id();
+		Self {
+			registry: registry.into(),
+			call_type,
+		}
+	}
+
+	/// `{ "pallet", "call", "args" }` of an encoded `Call`.
+	fn decode_call(&self, input: &mut &[u8]) -> std::result::Result<Value, String> {
+		let pallet = self.decode_variant(self.call_type, input)?;
+		// the only field of a pallet variant is the call enum of the pallet.
+		let pallet_call_type = match pallet.fields() {
+			[field] => field.ty().id(),
+			_ => return Err(format!("Invalid call variant {}", pallet.name())),
+		};
+		let call = self.decode_variant(pallet_call_type, input)?;
+
+		Ok(json!({
+			"pallet": pallet.name(),
+			"call": call.name(),
+			"args": self.decode_fields(call.fields(), input)?,
+		}))
+	}
+
+	fn decode(&self, type_id: u32, input: &mut &[u8]) -> std::result::Result<Value, String> {
+		if type_id == self.call_type {
+			return self.decode_call(input);
+		}
+
+		match self.resolve(type_id)? {
+			TypeDef::Composite(composite) => self.decode_fields(composite.fields(), input),
+			TypeDef::Variant(_) => {
+				let variant = self.decode_variant(type_id, input)?;
+				if variant.fields().is_empty() {
+					return Ok(json!(variant.name()));
+				}
+				let mut json = Map::new();
+				json.insert(variant.name().clone(), self.decode_fields(variant.fields(), input)?);
+				Ok(Value::Object(json))
+			}
+			TypeDef::Sequence(sequence) => {
+				let len = decode::<Compact<u32>>(input)?.0;
+				self.decode_elements(sequence.type_param().id(), len, input)
+			}
+			TypeDef::Array(array) => self.decode_elements(array.type_param().id(), array.len(), input),
+			TypeDef::Tuple(tuple) => tuple
+				.fields()
+				.iter()
+				.map(|field| self.decode(field.id(), input))
+				.collect::<std::result::Result<_, _>>()
+				.map(Value::Array),
+			TypeDef::Primitive(primitive) => decode_primitive(primitive, input),
+			TypeDef::Compact(_) => Ok(number_json(decode::<Compact<u128>>(input)?.0)),
+			TypeDef::BitSequence(_) => Err("Bit sequences are not supported".into()),
+		}
+	}
+
+	/// Named fields as an object, a single unnamed field as its value, other
+	/// unnamed fields as an array.
+	fn decode_fields(&self, fields: &[Field<PortableForm>], input: &mut &[u8]) -> std::result::Result<Value, String> {
+		if fields.iter().all(|field| field.name().is_some()) {
+			let mut json = Map::new();
+			for field in fields {
+				let name = field.name().cloned().unwrap_or_default();
+				json.insert(name, self.decode(field.ty().id(), input)?);
+			}
+			return Ok(Value::Object(json));
+		}
+
+		match fields {
+			[field] => self.decode(field.ty().id(), input),
+			_ => fields
+				.iter()
+				.map(|field| self.decode(field.ty().id(), input))
+				.collect::<std::result::Result<_, _>>()
+				.map(Value::Array),
+		}
+	}
+
+	/// Byte sequences and arrays are hex encoded.
+	fn decode_elements(&self, type_id: u32, len: u32, input: &mut &[u8]) -> std::result::Result<Value, String> {
+		if let TypeDef::Primitive(TypeDefPrimitive::U8) = self.resolve(type_id)? {
+			let len = len as usize;
+			if input.len() < len {
+				return Err("Not enough data to decode".into());
+			}
+			let (bytes, rest) = input.split_at(len);
+			*input = rest;
+			return Ok(json!(to_hex(bytes, false)));
+		}
+
+		(0..len)
+			.map(|_| self.decode(type_id, input))
+			.collect::<std::result::Result<_, _>>()
+			.map(Value::Array)
+	}
+
+	fn decode_variant(&self, type_id: u32, input: &mut &[u8]) -> std::result::Result<&Variant<PortableForm>, String> {
+		let variants = match self.resolve(type_id)? {
+			TypeDef::Variant(variants) => variants.variants(),
+			_ => return Err(format!("Type {} is not an enum", type_id)),
+		};
+		let index = decode::<u8>(input)?;
+		variants
+			.iter()
+			.find(|variant| variant.index() == index)
+			.ok_or_else(|| format!("Invalid variant index {} of type {}", index, type_id))
+	}
+
+	fn resolve(&self, type_id: u32) -> std::result::Result<&TypeDef<PortableForm>, String> {
+		self.registry
+			.resolve(type_id)
+			.map(|ty| ty.type_def())
+			.ok_or_else(|| format!("Unknown type {}", type_id))
+	}
+}
+
+fn decode<T: Decode>(input: &mut &[u8]) -> std::result::Result<T, String> {
+	T::decode(input).map_err(|e| e.to_string())
+}
+
+fn decode_primitive(primitive: &TypeDefPrimitive, input: &mut &[u8]) -> std::result::Result<Value, String> {
+	Ok(match primitive {
+		TypeDefPrimitive::Bool => json!(decode::<bool>(input)?),
+		TypeDefPrimitive::Char => json!(std::char::from_u32(decode::<u32>(input)?)),
+		TypeDefPrimitive::Str => json!(decode::<String>(input)?),
+		TypeDefPrimitive::U8 => json!(decode::<u8>(input)?),
+		TypeDefPrimitive::U16 => json!(decode::<u16>(input)?),
+		TypeDefPrimitive::U32 => json!(decode::<u32>(input)?),
+		TypeDefPrimitive::U64 => json!(decode::<u64>(input)?),
+		TypeDefPrimitive::U128 => number_json(decode::<u128>(input)?),
+		TypeDefPrimitive::U256 => json!(decode::<U256>(input)?.to_string()),
+		TypeDefPrimitive::I8 => json!(decode::<i8>(input)?),
+		TypeDefPrimitive::I16 => json!(decode::<i16>(input)?),
+		TypeDefPrimitive::I32 => json!(decode::<i32>(input)?),
+		TypeDefPrimitive::I64 => json!(decode::<i64>(input)?),
+		TypeDefPrimitive::I128 => json!(decode::<i128>(input)?.to_string()),
+		TypeDefPrimitive::I256 => json!(to_hex(&decode::<[u8; 32]>(input)?, false)),
+	})
+}
+
+/// Numbers beyond `u64` are strings, since JSON readers may not handle them.
+fn number_json(number: u128) -> Value {
+	match u64::try_from(number) {
+		Ok(number) => json!(number),
+		Err(_) => json!(number.to_string()),
+	}
+}
+
+fn event_json(record: &EventRecord<Event, Hash>) -> Value {
+	json!({
+		"phase": format!("{:?}", record.phase),
+		"event": format!("{:?}", record.event),
+		"topics": record.topics,
+	})
+}
+
+/// The events deposited by the block, `None` if its state is not available.
+fn block_events(client: &FullClient, hash: Hash) -> Option<Vec<EventRecord<Event, Hash>>> {
+	let key = StorageKey([twox_128(b"System"), twox_128(b"Events")].concat());
+	let events = client.storage(&BlockId::Hash(hash), &key).ok()??;
+	Decode::decode(&mut &events.0[..]).ok()
+}
@@ -26,6 +26,9 @@ mod chain_spec;
 mod service;
 mod cli;
 mod command;
+mod fork_state;
+mod genesis_config;
+mod inspect;
 mod rpc;
 
 fn main() -> sc_cli::Result<()> {
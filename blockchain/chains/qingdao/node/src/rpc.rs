@@ -45,6 +45,10 @@ use sc_finality_grandpa::{
 };
 pub use evm_rpc::{EVMApi, EVMApiServer, EVMRuntimeRPCApi};
 pub use sc_rpc::SubscriptionTaskExecutor;
+use sc_consensus_manual_seal::{
+	rpc::{ManualSeal, ManualSealApi},
+	EngineCommand,
+};
 
 /// Extra dependencies for BABE.
 pub struct BabeDeps {
@@ -80,6 +84,8 @@ pub struct FullDeps<C, P, SC, B> {
 	pub select_chain: SC,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
+	/// Manual seal command sink, set when the node runs with `--sealing`.
+	pub command_sink: Option<futures::channel::mpsc::Sender<EngineCommand<Hash>>>,
 	/// BABE specific dependencies.
 	pub babe: BabeDeps,
 	/// GRANDPA specific dependencies.
@@ -118,6 +124,7 @@ pub fn create_full<C, P, SC, B>(
 		pool,
 		select_chain,
 		deny_unsafe,
+		command_sink,
 		babe,
 		grandpa,
 	} = deps;
@@ -165,5 +172,10 @@ pub fn create_full<C, P, SC, B>(
 	)));
 	io.extend_with(EVMApiServer::to_delegate(EVMApi::new(client, deny_unsafe)));
 
+	if let Some(command_sink) = command_sink {
+		// `engine_createBlock` and `engine_finalizeBlock` for development sealing.
+		io.extend_with(ManualSealApi::to_delegate(ManualSeal::new(command_sink)));
+	}
+
 	Ok(io)
 }
\ No newline at end of file
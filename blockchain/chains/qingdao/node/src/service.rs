@@ -19,17 +19,23 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use std::sync::Arc;
+use std::str::FromStr;
 use std::time::Duration;
+use futures::{stream, StreamExt};
 use sc_client_api::{ExecutorProvider, RemoteBackend};
 use setheum_runtime::{self, opaque::Block, RuntimeApi};
 use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
 use sc_executor::native_executor_instance;
 pub use sc_executor::NativeExecutor;
 use sc_finality_grandpa::SharedVoterState;
-use sc_keystore::LocalKeystore;
+use setheum_remote_keystore::{RemoteKeystore, TOKEN_ENV};
 use sc_telemetry::{Telemetry, TelemetryWorker};
 
 use sc_finality_grandpa::FinalityProofProvider;
+use sc_consensus_manual_seal::{
+	consensus::babe::{BabeConsensusDataProvider, SlotTimestampProvider},
+	EngineCommand, ManualSealParams,
+};
 
 // Our native executor instance.
 native_executor_instance!(
@@ -39,8 +45,8 @@ native_executor_instance!(
 	frame_benchmarking::benchmarking::HostFunctions,
 );
 
-type FullClient = sc_service::TFullClient<Block, RuntimeApi, Executor>;
-type FullBackend = sc_service::TFullBackend<Block>;
+pub type FullClient = sc_service::TFullClient<Block, RuntimeApi, Executor>;
+pub type FullBackend = sc_service::TFullBackend<Block>;
 type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
 type PartialResult = Result<sc_service::PartialComponents<
 	FullClient,
@@ -60,11 +66,38 @@ type PartialResult = Result<sc_service::PartialComponents<
 	)
 >, ServiceError>;
 
-pub fn new_partial(config: &Configuration) -> PartialResult {
-	if config.keystore_remote.is_some() {
-		return Err(ServiceError::Other("Remote Keystores are not supported.".to_string()))
+/// Block authoring scheme used by a development node.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Sealing {
+	/// Author a block as soon as a transaction enters the pool.
+	Instant,
+	/// Author blocks only when requested through the `engine_createBlock` RPC.
+	Manual,
+	/// Author a block every given number of milliseconds.
+	Interval(u64),
+}
+
+impl FromStr for Sealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(match s {
+			"instant" => Self::Instant,
+			"manual" => Self::Manual,
+			s => {
+				let millis = s
+					.parse::<u64>()
+					.map_err(|_| "expected `instant`, `manual` or a block interval in milliseconds")?;
+				if millis == 0 {
+					return Err("the sealing interval must be greater than zero".into());
+				}
+				Self::Interval(millis)
+			}
+		})
 	}
+}
 
+pub fn new_partial(config: &Configuration) -> PartialResult {
 	let telemetry = config
 		.telemetry_endpoints
 		.clone()
@@ -142,15 +175,17 @@ pub fn new_partial(config: &Configuration) -> PartialResult {
 	})
 }
 
-fn remote_keystore(_url: &str) -> Result<Arc<LocalKeystore>, &'static str> {
-	// FIXME: here would the concrete keystore be built,
-	//        must return a concrete type (NOT `LocalKeystore`) that
-	//        implements `CryptoStore` and `SyncCryptoStore`
-	Err("Remote Keystore not supported.")
+/// Connects to the signing daemon at `url`, see `setheum-signer`. HTTP daemons
+/// require the token set in `SETHEUM_SIGNER_TOKEN`.
+fn remote_keystore(url: &str) -> Result<Arc<RemoteKeystore>, String> {
+	RemoteKeystore::connect(url, std::env::var(TOKEN_ENV).ok()).map(Arc::new)
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError> {
+///
+/// With `sealing` set, blocks are authored on demand instead of by BABE and
+/// GRANDPA does not run.
+pub fn new_full(mut config: Configuration, sealing: Option<Sealing>) -> Result<TaskManager, ServiceError> {
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -218,11 +253,22 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	let babe_config = babe_link.config().clone();
 	let shared_epoch_changes = babe_link.epoch_changes().clone();
 
+	// Development sealing lets `engine_createBlock` / `engine_finalizeBlock` drive
+	// block production in addition to the mode specific triggers.
+	let (command_sink, commands_stream) = match sealing {
+		Some(_) => {
+			let (sink, stream) = futures::channel::mpsc::channel(1024);
+			(Some(sink), Some(stream))
+		}
+		None => (None, None),
+	};
+
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
 		let keystore = keystore_container.sync_keystore();
 		let select_chain = select_chain.clone();
+		let command_sink = command_sink.clone();
 
 		Box::new(move |deny_unsafe, subscription_executor| {
 			let deps = crate::rpc::FullDeps {
@@ -230,6 +276,7 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 				pool: pool.clone(),
 				select_chain: select_chain.clone(),
 				deny_unsafe,
+				command_sink: command_sink.clone(),
 				babe: crate::rpc::BabeDeps {
 					babe_config: babe_config.clone(),
 					shared_epoch_changes: shared_epoch_changes.clone(),
@@ -265,6 +312,82 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		},
 	)?;
 
+	if let (Some(sealing), Some(commands_stream)) = (sealing, commands_stream) {
+		let proposer = sc_basic_authorship::ProposerFactory::new(
+			task_manager.spawn_handle(),
+			client.clone(),
+			transaction_pool.clone(),
+			prometheus_registry.as_ref(),
+			telemetry.as_ref().map(|x| x.handle())
+		);
+
+		// Claims BABE slots on behalf of the dev authorities, so that blocks keep
+		// passing through the regular BABE/GRANDPA block import pipeline.
+		let consensus_data_provider = BabeConsensusDataProvider::new(
+			client.clone(),
+			keystore_container.sync_keystore(),
+			babe_link.epoch_changes().clone(),
+			babe_link.config().authorities.clone(),
+		).map_err(|e| ServiceError::Other(format!("Failed to create BABE data provider: {:?}", e)))?;
+
+		// There is no GRANDPA voter in development mode, so automatically sealed
+		// blocks are finalized straight away.
+		let seal_new_block = |create_empty| EngineCommand::SealNewBlock {
+			create_empty,
+			finalize: true,
+			parent_hash: None,
+			sender: None,
+		};
+		let commands_stream = match sealing {
+			Sealing::Manual => commands_stream.boxed(),
+			Sealing::Instant => stream::select(
+				commands_stream,
+				transaction_pool
+					.pool()
+					.validated_pool()
+					.import_notification_stream()
+					.map(move |_| seal_new_block(false)),
+			).boxed(),
+			Sealing::Interval(millis) => stream::select(
+				commands_stream,
+				stream::unfold((), move |_| async move {
+					futures_timer::Delay::new(Duration::from_millis(millis)).await;
+					Some((seal_new_block(true), ()))
+				}),
+			).boxed(),
+		};
+
+		let inherent_client = client.clone();
+		let manual_seal = sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+			block_import,
+			env: proposer,
+			client,
+			pool: transaction_pool.pool().clone(),
+			commands_stream,
+			select_chain,
+			consensus_data_provider: Some(Box::new(consensus_data_provider)),
+			create_inherent_data_providers: move |_, ()| {
+				let client = inherent_client.clone();
+				async move {
+					// Advances the timestamp by one slot per block, regardless of
+					// how fast blocks are sealed.
+					let timestamp = SlotTimestampProvider::new(client)
+						.map_err(|e| format!("Failed to create timestamp provider: {:?}", e))?;
+
+					let slot = sp_consensus_babe::inherents::InherentDataProvider::new(timestamp.slot().into());
+
+					Ok((timestamp, slot))
+				}
+			},
+		});
+
+		task_manager
+			.spawn_essential_handle()
+			.spawn_blocking("manual-seal", manual_seal);
+
+		network_starter.start_network();
+		return Ok(task_manager);
+	}
 
 	if role.is_authority() {
 		let proposer = sc_basic_authorship::ProposerFactory::new(
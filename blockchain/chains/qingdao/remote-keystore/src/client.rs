@@ -0,0 +1,406 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! A `CryptoStore` forwarding every request to a signing daemon.
+
+use std::{
+	convert::TryFrom,
+	io::{self, BufRead, BufReader, Read, Write},
+	net::TcpStream,
+	os::unix::net::UnixStream,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
+
+use async_trait::async_trait;
+use jsonrpc_core::Output;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use sp_core::{
+	crypto::{CryptoTypePublicPair, KeyTypeId},
+	ecdsa, ed25519, sr25519, Bytes,
+};
+use sp_keystore::{
+	vrf::{VRFSignature, VRFTranscriptData},
+	CryptoStore, Error, SyncCryptoStore,
+};
+
+use crate::protocol::{
+	decode_raw, error_from_rpc, Crypto, Endpoint, KeyType, PublicPair, TranscriptData, VrfSignature,
+};
+
+const LOG_TARGET: &str = "remote-keystore";
+
+/// How long a request to the signing daemon may take, BABE must sign its claims
+/// well within a slot.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A keystore whose keys are held by a signing daemon, reached over a Unix socket
+/// or HTTP with one JSON-RPC request per call.
+#[derive(Clone)]
+pub struct RemoteKeystore {
+	client: Arc<Client>,
+}
+
+struct Client {
+	endpoint: Endpoint,
+	/// Sent as a bearer token to HTTP endpoints.
+	token: Option<String>,
+	next_id: AtomicU64,
+}
+
+impl RemoteKeystore {
+	/// Connect to the signing daemon at `uri`, `unix:<path>` or
+	/// `http://<host>:<port>`, failing if it does not respond. HTTP endpoints
+	/// require the `token` shared with the daemon.
+	pub fn connect(uri: &str, token: Option<String>) -> Result<Self, String> {
+		let endpoint: Endpoint = uri.parse()?;
+		if matches!(endpoint, Endpoint::Http(_)) && token.is_none() {
+			return Err(format!("A token is required to connect to {}", uri));
+		}
+		let keystore = Self {
+			client: Arc::new(Client {
+				endpoint,
+				token,
+				next_id: AtomicU64::new(1),
+			}),
+		};
+		keystore
+			.client
+			.call::<()>("keystore_ping", json!([]))
+			.map_err(|e| format!("Signing daemon at {} is not responding: {}", uri, e))?;
+		Ok(keystore)
+	}
+
+	fn public_keys<P: for<'a> TryFrom<&'a [u8]>>(&self, crypto: Crypto, id: KeyTypeId) -> Vec<P> {
+		self.client
+			.call::<Vec<Bytes>>("keystore_publicKeys", json!([crypto, KeyType(id)]))
+			.unwrap_or_default()
+			.iter()
+			.filter_map(|public| decode_raw(public))
+			.collect()
+	}
+
+	fn generate_new<P: for<'a> TryFrom<&'a [u8]>>(
+		&self,
+		crypto: Crypto,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<P, Error> {
+		let public = self
+			.client
+			.call::<Bytes>("keystore_generateNew", json!([crypto, KeyType(id), seed]))?;
+		decode_raw(&public).ok_or_else(|| Error::Other("Invalid public key".into()))
+	}
+
+	/// Run `f` on the blocking thread pool, requests to the daemon block on
+	/// their socket and must not stall the async executor.
+	async fn blocking<R, F>(&self, f: F) -> Result<R, Error>
+	where
+		R: Send + 'static,
+		F: FnOnce(&RemoteKeystore) -> R + Send + 'static,
+	{
+		let keystore = self.clone();
+		tokio::task::spawn_blocking(move || f(&keystore))
+			.await
+			.map_err(|e| Error::Other(format!("Keystore request failed: {}", e)))
+	}
+}
+
+impl Client {
+	fn call<R: DeserializeOwned>(&self, method: &str, params: Value) -> Result<R, Error> {
+		let request = json!({
+			"jsonrpc": "2.0",
+			"id": self.next_id.fetch_add(1, Ordering::Relaxed),
+			"method": method,
+			"params": params,
+		});
+		let response = self.send(request.to_string().as_bytes()).map_err(|e| {
+			log::warn!(target: LOG_TARGET, "{} to {} failed: {}", method, self.endpoint, e);
+			Error::Unavailable
+		})?;
+
+		match serde_json::from_slice(&response) {
+			Ok(Output::Success(success)) => serde_json::from_value(success.result)
+				.map_err(|e| Error::Other(format!("Invalid {} result: {}", method, e))),
+			Ok(Output::Failure(failure)) => Err(error_from_rpc(failure.error)),
+			Err(e) => Err(Error::Other(format!("Invalid {} response: {}", method, e))),
+		}
+	}
+
+	fn send(&self, body: &[u8]) -> io::Result<Vec<u8>> {
+		match &self.endpoint {
+			Endpoint::Unix(path) => {
+				let mut stream = UnixStream::connect(path)?;
+				stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+				stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+				stream.write_all(body)?;
+				stream.write_all(b"\n")?;
+
+				// responses are newline delimited.
+				let mut response = Vec::new();
+				BufReader::new(stream).read_until(b'\n', &mut response)?;
+				Ok(response)
+			}
+			Endpoint::Http(address) => {
+				let mut stream = TcpStream::connect(address)?;
+				stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+				stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+				write!(
+					stream,
+					"POST / HTTP/1.1\r\nHost: {}\r\nAuthorization: Bearer {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+					address,
+					self.token.as_deref().unwrap_or_default(),
+					body.len()
+				)?;
+				stream.write_all(body)?;
+
+				let mut response = Vec::new();
+				stream.read_to_end(&mut response)?;
+				http_body(response)
+			}
+		}
+	}
+}
+
+/// The body of a `200 OK` HTTP response.
+fn http_body(response: Vec<u8>) -> io::Result<Vec<u8>> {
+	let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+	let separator = response
+		.windows(4)
+		.position(|window| window == b"\r\n\r\n")
+		.ok_or_else(|| invalid("Incomplete HTTP response"))?;
+	let status = response
+		.split(|byte| *byte == b'\r')
+		.next()
+		.and_then(|line| std::str::from_utf8(line).ok())
+		.unwrap_or_default();
+	if status.split_whitespace().nth(1) != Some("200") {
+		return Err(invalid(&format!("Unexpected HTTP status: {}", status)));
+	}
+	Ok(response[separator + 4..].to_vec())
+}
+
+impl SyncCryptoStore for RemoteKeystore {
+	fn sr25519_public_keys(&self, id: KeyTypeId) -> Vec<sr25519::Public> {
+		self.public_keys(Crypto::Sr25519, id)
+	}
+
+	fn sr25519_generate_new(&self, id: KeyTypeId, seed: Option<&str>) -> Result<sr25519::Public, Error> {
+		self.generate_new(Crypto::Sr25519, id, seed)
+	}
+
+	fn ed25519_public_keys(&self, id: KeyTypeId) -> Vec<ed25519::Public> {
+		self.public_keys(Crypto::Ed25519, id)
+	}
+
+	fn ed25519_generate_new(&self, id: KeyTypeId, seed: Option<&str>) -> Result<ed25519::Public, Error> {
+		self.generate_new(Crypto::Ed25519, id, seed)
+	}
+
+	fn ecdsa_public_keys(&self, id: KeyTypeId) -> Vec<ecdsa::Public> {
+		self.public_keys(Crypto::Ecdsa, id)
+	}
+
+	fn ecdsa_generate_new(&self, id: KeyTypeId, seed: Option<&str>) -> Result<ecdsa::Public, Error> {
+		self.generate_new(Crypto::Ecdsa, id, seed)
+	}
+
+	fn insert_unknown(&self, key_type: KeyTypeId, suri: &str, public: &[u8]) -> Result<(), ()> {
+		match self.client.call::<bool>(
+			"keystore_insertUnknown",
+			json!([KeyType(key_type), suri, Bytes(public.to_vec())]),
+		) {
+			Ok(true) => Ok(()),
+			_ => Err(()),
+		}
+	}
+
+	fn supported_keys(
+		&self,
+		id: KeyTypeId,
+		keys: Vec<CryptoTypePublicPair>,
+	) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		let keys = keys.iter().map(PublicPair::try_from).collect::<Result<Vec<_>, _>>()?;
+		let supported = self
+			.client
+			.call::<Vec<PublicPair>>("keystore_supportedKeys", json!([KeyType(id), keys]))?;
+		Ok(supported.into_iter().map(Into::into).collect())
+	}
+
+	fn keys(&self, id: KeyTypeId) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		let keys = self
+			.client
+			.call::<Vec<PublicPair>>("keystore_keys", json!([KeyType(id)]))?;
+		Ok(keys.into_iter().map(Into::into).collect())
+	}
+
+	fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		let public_keys = public_keys
+			.iter()
+			.map(|(public, id)| (Bytes(public.clone()), KeyType(*id)))
+			.collect::<Vec<_>>();
+		self.client
+			.call::<bool>("keystore_hasKeys", json!([public_keys]))
+			.unwrap_or_default()
+	}
+
+	fn sign_with(&self, id: KeyTypeId, key: &CryptoTypePublicPair, msg: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+		let key = PublicPair::try_from(key)?;
+		let signature = self
+			.client
+			.call::<Option<Bytes>>("keystore_signWith", json!([KeyType(id), key, Bytes(msg.to_vec())]))?;
+		Ok(signature.map(|signature| signature.0))
+	}
+
+	fn sr25519_vrf_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		transcript_data: VRFTranscriptData,
+	) -> Result<Option<VRFSignature>, Error> {
+		let public: &[u8] = public.as_ref();
+		let signature = self.client.call::<Option<VrfSignature>>(
+			"keystore_sr25519VrfSign",
+			json!([
+				KeyType(key_type),
+				Bytes(public.to_vec()),
+				TranscriptData::from(&transcript_data)
+			]),
+		)?;
+		signature.map(VRFSignature::try_from).transpose()
+	}
+
+	fn ecdsa_sign_prehashed(
+		&self,
+		id: KeyTypeId,
+		public: &ecdsa::Public,
+		msg: &[u8; 32],
+	) -> Result<Option<ecdsa::Signature>, Error> {
+		let public: &[u8] = public.as_ref();
+		let signature = self.client.call::<Option<Bytes>>(
+			"keystore_ecdsaSignPrehashed",
+			json!([KeyType(id), Bytes(public.to_vec()), Bytes(msg.to_vec())]),
+		)?;
+		signature
+			.map(|signature| decode_raw(&signature).ok_or_else(|| Error::Other("Invalid ecdsa signature".into())))
+			.transpose()
+	}
+}
+
+// Requests to the daemon block, so the async keystore runs the sync one on the
+// blocking thread pool.
+#[async_trait]
+impl CryptoStore for RemoteKeystore {
+	async fn sr25519_public_keys(&self, id: KeyTypeId) -> Vec<sr25519::Public> {
+		self.blocking(move |keystore| SyncCryptoStore::sr25519_public_keys(keystore, id))
+			.await
+			.unwrap_or_default()
+	}
+
+	async fn sr25519_generate_new(&self, id: KeyTypeId, seed: Option<&str>) -> Result<sr25519::Public, Error> {
+		let seed = seed.map(ToOwned::to_owned);
+		self.blocking(move |keystore| SyncCryptoStore::sr25519_generate_new(keystore, id, seed.as_deref()))
+			.await?
+	}
+
+	async fn ed25519_public_keys(&self, id: KeyTypeId) -> Vec<ed25519::Public> {
+		self.blocking(move |keystore| SyncCryptoStore::ed25519_public_keys(keystore, id))
+			.await
+			.unwrap_or_default()
+	}
+
+	async fn ed25519_generate_new(&self, id: KeyTypeId, seed: Option<&str>) -> Result<ed25519::Public, Error> {
+		let seed = seed.map(ToOwned::to_owned);
+		self.blocking(move |keystore| SyncCryptoStore::ed25519_generate_new(keystore, id, seed.as_deref()))
+			.await?
+	}
+
+	async fn ecdsa_public_keys(&self, id: KeyTypeId) -> Vec<ecdsa::Public> {
+		self.blocking(move |keystore| SyncCryptoStore::ecdsa_public_keys(keystore, id))
+			.await
+			.unwrap_or_default()
+	}
+
+	async fn ecdsa_generate_new(&self, id: KeyTypeId, seed: Option<&str>) -> Result<ecdsa::Public, Error> {
+		let seed = seed.map(ToOwned::to_owned);
+		self.blocking(move |keystore| SyncCryptoStore::ecdsa_generate_new(keystore, id, seed.as_deref()))
+			.await?
+	}
+
+	async fn insert_unknown(&self, id: KeyTypeId, suri: &str, public: &[u8]) -> Result<(), ()> {
+		let (suri, public) = (suri.to_owned(), public.to_vec());
+		self.blocking(move |keystore| SyncCryptoStore::insert_unknown(keystore, id, &suri, &public))
+			.await
+			.unwrap_or(Err(()))
+	}
+
+	async fn supported_keys(
+		&self,
+		id: KeyTypeId,
+		keys: Vec<CryptoTypePublicPair>,
+	) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		self.blocking(move |keystore| SyncCryptoStore::supported_keys(keystore, id, keys))
+			.await?
+	}
+
+	async fn keys(&self, id: KeyTypeId) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		self.blocking(move |keystore| SyncCryptoStore::keys(keystore, id))
+			.await?
+	}
+
+	async fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		let public_keys = public_keys.to_vec();
+		self.blocking(move |keystore| SyncCryptoStore::has_keys(keystore, &public_keys))
+			.await
+			.unwrap_or_default()
+	}
+
+	async fn sign_with(&self, id: KeyTypeId, key: &CryptoTypePublicPair, msg: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+		let (key, msg) = (key.clone(), msg.to_vec());
+		self.blocking(move |keystore| SyncCryptoStore::sign_with(keystore, id, &key, &msg))
+			.await?
+	}
+
+	async fn sr25519_vrf_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		transcript_data: VRFTranscriptData,
+	) -> Result<Option<VRFSignature>, Error> {
+		let public = *public;
+		self.blocking(move |keystore| SyncCryptoStore::sr25519_vrf_sign(keystore, key_type, &public, transcript_data))
+			.await?
+	}
+
+	async fn ecdsa_sign_prehashed(
+		&self,
+		id: KeyTypeId,
+		public: &ecdsa::Public,
+		msg: &[u8; 32],
+	) -> Result<Option<ecdsa::Signature>, Error> {
+		let (public, msg) = (public.clone(), *msg);
+		self.blocking(move |keystore| SyncCryptoStore::ecdsa_sign_prehashed(keystore, id, &public, &msg))
+			.await?
+	}
+}
@@ -0,0 +1,44 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Remote Keystore
+//!
+//! Keeps the session keys of a validator outside of the node process.
+//! [`RemoteKeystore`] implements `CryptoStore` and `SyncCryptoStore` by forwarding
+//! every call to a signing daemon over a Unix socket or HTTP, and is used by the
+//! node when started with `--keystore-uri`. Over HTTP, the node and the daemon
+//! share a token read from [`TOKEN_ENV`].
+//!
+//! The `setheum-signer` binary is a reference daemon serving a `LocalKeystore`,
+//! see [`server`] for its JSON-RPC interface.
+
+mod client;
+mod protocol;
+pub mod server;
+
+#[cfg(test)]
+mod tests;
+
+pub use client::RemoteKeystore;
+pub use protocol::Endpoint;
+
+/// The environment variable holding the token shared by the node and the
+/// signing daemon over HTTP.
+pub const TOKEN_ENV: &str = "SETHEUM_SIGNER_TOKEN";
@@ -0,0 +1,83 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! `setheum-signer`, a reference signing daemon for `--keystore-uri`.
+
+use std::{env, path::PathBuf, sync::Arc};
+
+use sc_keystore::LocalKeystore;
+use setheum_remote_keystore::{server, Endpoint, TOKEN_ENV};
+use sp_core::crypto::{key_types, SecretString};
+use sp_keystore::{SyncCryptoStore, SyncCryptoStorePtr};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "setheum-signer")]
+struct Opt {
+	/// `unix:<path>` or `http://<host>:<port>` to listen on.
+	///
+	/// The directory of a unix socket is created private to the daemon user.
+	/// HTTP is only served on loopback addresses, to clients sending the token
+	/// set in `SETHEUM_SIGNER_TOKEN`.
+	#[structopt(long, default_value = "unix:/tmp/setheum-signer/signer.sock")]
+	listen: Endpoint,
+
+	/// Keystore directory, an in-memory keystore if omitted.
+	#[structopt(long, parse(from_os_str))]
+	keystore_path: Option<PathBuf>,
+
+	/// Password of the keystore.
+	#[structopt(long)]
+	password: Option<String>,
+
+	/// Generate the session keys of a dev seed, e.g. `Alice`, when starting.
+	#[structopt(long)]
+	dev_seed: Option<String>,
+}
+
+fn main() -> Result<(), String> {
+	let opt = Opt::from_args();
+
+	let keystore: SyncCryptoStorePtr = match &opt.keystore_path {
+		Some(path) => Arc::new(
+			LocalKeystore::open(path, opt.password.map(SecretString::new))
+				.map_err(|e| format!("Failed to open keystore {}: {}", path.display(), e))?,
+		),
+		None => Arc::new(LocalKeystore::in_memory()),
+	};
+
+	if let Some(seed) = &opt.dev_seed {
+		let suri = format!("//{}", seed);
+		let suri = Some(suri.as_str());
+		SyncCryptoStore::sr25519_generate_new(&*keystore, key_types::BABE, suri)
+			.and_then(|_| SyncCryptoStore::ed25519_generate_new(&*keystore, key_types::GRANDPA, suri))
+			.and_then(|_| SyncCryptoStore::sr25519_generate_new(&*keystore, key_types::IM_ONLINE, suri))
+			.and_then(|_| SyncCryptoStore::sr25519_generate_new(&*keystore, key_types::AUTHORITY_DISCOVERY, suri))
+			.map_err(|e| format!("Failed to generate the session keys of {}: {}", seed, e))?;
+	}
+
+	let token = env::var(TOKEN_ENV).ok();
+	let server = server::start(&opt.listen, keystore, token.as_deref())
+		.map_err(|e| format!("Failed to listen on {}: {}", opt.listen, e))?;
+	println!("Signing daemon listening on {}", server.endpoint());
+	server.wait();
+
+	Ok(())
+}
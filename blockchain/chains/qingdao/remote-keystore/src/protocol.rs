@@ -0,0 +1,255 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The JSON-RPC protocol spoken between [`RemoteKeystore`](crate::RemoteKeystore)
+//! and the signing daemon.
+
+use std::{convert::TryFrom, fmt, path::PathBuf, str::FromStr};
+
+use codec::{Decode, Encode};
+use jsonrpc_core::{Error as RpcError, ErrorCode};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::json;
+use sp_consensus_vrf::schnorrkel::{VRFOutput, VRFProof};
+use sp_core::{
+	crypto::{CryptoTypeId, CryptoTypePublicPair, KeyTypeId},
+	ecdsa, ed25519, sr25519, Bytes,
+};
+use sp_keystore::{
+	vrf::{VRFSignature, VRFTranscriptData, VRFTranscriptValue},
+	Error,
+};
+
+const KEY_NOT_SUPPORTED: i64 = 1;
+const VALIDATION_ERROR: i64 = 2;
+const UNAVAILABLE: i64 = 3;
+const OTHER: i64 = 4;
+
+/// Where the signing daemon listens, `unix:<path>` or `http://<host>:<port>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Endpoint {
+	Unix(PathBuf),
+	Http(String),
+}
+
+impl FromStr for Endpoint {
+	type Err = String;
+
+	fn from_str(uri: &str) -> Result<Self, Self::Err> {
+		if let Some(path) = uri.strip_prefix("unix://").or_else(|| uri.strip_prefix("unix:")) {
+			if !path.is_empty() {
+				return Ok(Endpoint::Unix(path.into()));
+			}
+		} else if let Some(address) = uri.strip_prefix("http://") {
+			let address = address.trim_end_matches('/');
+			if !address.is_empty() && !address.contains('/') {
+				return Ok(Endpoint::Http(address.into()));
+			}
+		}
+		Err(format!(
+			"Invalid keystore uri {}, expected `unix:<path>` or `http://<host>:<port>`",
+			uri
+		))
+	}
+}
+
+impl fmt::Display for Endpoint {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Endpoint::Unix(path) => write!(f, "unix:{}", path.display()),
+			Endpoint::Http(address) => write!(f, "http://{}", address),
+		}
+	}
+}
+
+/// The crypto scheme of a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Crypto {
+	Sr25519,
+	Ed25519,
+	Ecdsa,
+}
+
+impl From<Crypto> for CryptoTypeId {
+	fn from(crypto: Crypto) -> Self {
+		match crypto {
+			Crypto::Sr25519 => sr25519::CRYPTO_ID,
+			Crypto::Ed25519 => ed25519::CRYPTO_ID,
+			Crypto::Ecdsa => ecdsa::CRYPTO_ID,
+		}
+	}
+}
+
+impl TryFrom<CryptoTypeId> for Crypto {
+	type Error = Error;
+
+	fn try_from(id: CryptoTypeId) -> Result<Self, Error> {
+		match id {
+			sr25519::CRYPTO_ID => Ok(Crypto::Sr25519),
+			ed25519::CRYPTO_ID => Ok(Crypto::Ed25519),
+			ecdsa::CRYPTO_ID => Ok(Crypto::Ecdsa),
+			id => Err(Error::Other(format!("Unsupported crypto {:?}", id))),
+		}
+	}
+}
+
+/// A key type id, serialized as its four characters, e.g. `babe`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyType(pub KeyTypeId);
+
+impl Serialize for KeyType {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&String::from_utf8_lossy(&self.0 .0))
+	}
+}
+
+impl<'de> Deserialize<'de> for KeyType {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let id = String::deserialize(deserializer)?;
+		KeyTypeId::try_from(id.as_str())
+			.map(KeyType)
+			.map_err(|_| de::Error::custom(format!("invalid key type: {}", id)))
+	}
+}
+
+/// A public key with its crypto scheme.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicPair {
+	pub crypto: Crypto,
+	pub public: Bytes,
+}
+
+impl TryFrom<&CryptoTypePublicPair> for PublicPair {
+	type Error = Error;
+
+	fn try_from(pair: &CryptoTypePublicPair) -> Result<Self, Error> {
+		Ok(PublicPair {
+			crypto: Crypto::try_from(pair.0)?,
+			public: pair.1.clone().into(),
+		})
+	}
+}
+
+impl From<PublicPair> for CryptoTypePublicPair {
+	fn from(pair: PublicPair) -> Self {
+		CryptoTypePublicPair(pair.crypto.into(), pair.public.0)
+	}
+}
+
+/// The transcript signed by a VRF, whose label and item names are static
+/// strings on the node side.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TranscriptData {
+	pub label: Bytes,
+	pub items: Vec<(String, TranscriptValue)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptValue {
+	Bytes(Bytes),
+	U64(u64),
+}
+
+impl From<&VRFTranscriptData> for TranscriptData {
+	fn from(data: &VRFTranscriptData) -> Self {
+		TranscriptData {
+			label: data.label.to_vec().into(),
+			items: data
+				.items
+				.iter()
+				.map(|(name, value)| {
+					let value = match value {
+						VRFTranscriptValue::Bytes(bytes) => TranscriptValue::Bytes(bytes.clone().into()),
+						VRFTranscriptValue::U64(value) => TranscriptValue::U64(*value),
+					};
+					(name.to_string(), value)
+				})
+				.collect(),
+		}
+	}
+}
+
+/// A VRF output and proof, SCALE encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VrfSignature {
+	pub output: Bytes,
+	pub proof: Bytes,
+}
+
+impl From<VRFSignature> for VrfSignature {
+	fn from(signature: VRFSignature) -> Self {
+		VrfSignature {
+			output: VRFOutput(signature.output).encode().into(),
+			proof: VRFProof(signature.proof).encode().into(),
+		}
+	}
+}
+
+impl TryFrom<VrfSignature> for VRFSignature {
+	type Error = Error;
+
+	fn try_from(signature: VrfSignature) -> Result<Self, Error> {
+		let output = VRFOutput::decode(&mut &signature.output[..])
+			.map_err(|e| Error::Other(format!("Invalid VRF output: {}", e)))?;
+		let proof = VRFProof::decode(&mut &signature.proof[..])
+			.map_err(|e| Error::Other(format!("Invalid VRF proof: {}", e)))?;
+		Ok(VRFSignature {
+			output: output.0,
+			proof: proof.0,
+		})
+	}
+}
+
+/// Decode a public key or a signature of a fixed length.
+pub fn decode_raw<T: for<'a> TryFrom<&'a [u8]>>(bytes: &[u8]) -> Option<T> {
+	T::try_from(bytes).ok()
+}
+
+/// The JSON-RPC error of a keystore error, keeping its variant.
+pub fn error_to_rpc(error: Error) -> RpcError {
+	let (code, message, data) = match error {
+		Error::KeyNotSupported(id) => (KEY_NOT_SUPPORTED, error.to_string(), Some(json!(KeyType(id)))),
+		Error::ValidationError(message) => (VALIDATION_ERROR, message, None),
+		Error::Unavailable => (UNAVAILABLE, error.to_string(), None),
+		Error::Other(message) => (OTHER, message, None),
+	};
+	RpcError {
+		code: ErrorCode::ServerError(code),
+		message,
+		data,
+	}
+}
+
+/// The keystore error of a JSON-RPC error returned by the signing daemon.
+pub fn error_from_rpc(error: RpcError) -> Error {
+	match error.code {
+		ErrorCode::ServerError(KEY_NOT_SUPPORTED) => {
+			match error.data.and_then(|data| serde_json::from_value::<KeyType>(data).ok()) {
+				Some(KeyType(id)) => Error::KeyNotSupported(id),
+				None => Error::Other(error.message),
+			}
+		}
+		ErrorCode::ServerError(VALIDATION_ERROR) => Error::ValidationError(error.message),
+		ErrorCode::ServerError(UNAVAILABLE) => Error::Unavailable,
+		_ => Error::Other(error.message),
+	}
+}
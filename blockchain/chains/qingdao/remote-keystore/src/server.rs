@@ -0,0 +1,355 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The signing daemon, serving a local keystore to remote nodes.
+
+use std::{
+	convert::TryFrom,
+	fs, io,
+	net::{SocketAddr, ToSocketAddrs},
+	os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt},
+	path::Path,
+};
+
+use jsonrpc_core::{Error as RpcError, IoHandler, Result};
+use jsonrpc_derive::rpc;
+use jsonrpc_http_server::{
+	hyper::{header, Body, Request, StatusCode},
+	RequestMiddlewareAction, Response,
+};
+use sp_core::{
+	crypto::{CryptoTypePublicPair, KeyTypeId},
+	ecdsa, sr25519, Bytes,
+};
+use sp_keystore::{
+	vrf::{VRFTranscriptData, VRFTranscriptValue},
+	SyncCryptoStore, SyncCryptoStorePtr,
+};
+
+use crate::protocol::{
+	decode_raw, error_to_rpc, Crypto, Endpoint, KeyType, PublicPair, TranscriptData, TranscriptValue, VrfSignature,
+};
+
+pub use rpc_impl_SignerApi::gen_server::SignerApi as SignerApiServer;
+
+/// Keystore rpc interface of the signing daemon, mirroring `SyncCryptoStore`.
+#[rpc(server)]
+pub trait SignerApi {
+	/// Check that the daemon is reachable.
+	#[rpc(name = "keystore_ping")]
+	fn ping(&self) -> Result<()>;
+
+	/// All public keys of the given crypto and key type.
+	#[rpc(name = "keystore_publicKeys")]
+	fn public_keys(&self, crypto: Crypto, key_type: KeyType) -> Result<Vec<Bytes>>;
+
+	/// Generate a new key, from the seed if given, returning its public key.
+	#[rpc(name = "keystore_generateNew")]
+	fn generate_new(&self, crypto: Crypto, key_type: KeyType, seed: Option<String>) -> Result<Bytes>;
+
+	/// Insert a key of any crypto from its secret uri.
+	#[rpc(name = "keystore_insertUnknown")]
+	fn insert_unknown(&self, key_type: KeyType, suri: String, public: Bytes) -> Result<bool>;
+
+	/// The given keys that are held by the keystore.
+	#[rpc(name = "keystore_supportedKeys")]
+	fn supported_keys(&self, key_type: KeyType, keys: Vec<PublicPair>) -> Result<Vec<PublicPair>>;
+
+	/// All keys of the given key type.
+	#[rpc(name = "keystore_keys")]
+	fn keys(&self, key_type: KeyType) -> Result<Vec<PublicPair>>;
+
+	/// Whether all the given keys are held by the keystore.
+	#[rpc(name = "keystore_hasKeys")]
+	fn has_keys(&self, public_keys: Vec<(Bytes, KeyType)>) -> Result<bool>;
+
+	/// Sign a message, `null` if the key is not held by the keystore.
+	#[rpc(name = "keystore_signWith")]
+	fn sign_with(&self, key_type: KeyType, key: PublicPair, msg: Bytes) -> Result<Option<Bytes>>;
+
+	/// Sign a VRF transcript, `null` if the key is not held by the keystore.
+	#[rpc(name = "keystore_sr25519VrfSign")]
+	fn sr25519_vrf_sign(
+		&self,
+		key_type: KeyType,
+		public: Bytes,
+		transcript: TranscriptData,
+	) -> Result<Option<VrfSignature>>;
+
+	/// Sign a 32 bytes pre-hashed message, `null` if the key is not held by the keystore.
+	#[rpc(name = "keystore_ecdsaSignPrehashed")]
+	fn ecdsa_sign_prehashed(&self, key_type: KeyType, public: Bytes, msg: Bytes) -> Result<Option<Bytes>>;
+}
+
+/// The VRF transcript labels the daemon signs, BABE's only.
+pub const TRANSCRIPT_LABELS: &[&[u8]] = &[b"BABE"];
+
+/// The VRF transcript item names the daemon signs, those of BABE's slot claims.
+pub const TRANSCRIPT_ITEMS: &[&str] = &["slot number", "current epoch", "chain randomness"];
+
+/// Serves a keystore over the `SignerApi`.
+pub struct Signer {
+	keystore: SyncCryptoStorePtr,
+}
+
+impl Signer {
+	pub fn new(keystore: SyncCryptoStorePtr) -> Self {
+		Self { keystore }
+	}
+
+	/// The static label of a transcript, as `VRFTranscriptData` only takes static
+	/// strings, `None` if it is not an allowed one.
+	fn intern_label(&self, label: &[u8]) -> Option<&'static [u8]> {
+		TRANSCRIPT_LABELS.iter().find(|allowed| **allowed == label).copied()
+	}
+
+	/// The static name of a transcript item, `None` if it is not an allowed one.
+	fn intern_name(&self, name: &str) -> Option<&'static str> {
+		TRANSCRIPT_ITEMS.iter().find(|allowed| **allowed == name).copied()
+	}
+}
+
+impl SignerApi for Signer {
+	fn ping(&self) -> Result<()> {
+		Ok(())
+	}
+
+	fn public_keys(&self, crypto: Crypto, key_type: KeyType) -> Result<Vec<Bytes>> {
+		let keystore = &*self.keystore;
+		Ok(match crypto {
+			Crypto::Sr25519 => to_bytes(SyncCryptoStore::sr25519_public_keys(keystore, key_type.0)),
+			Crypto::Ed25519 => to_bytes(SyncCryptoStore::ed25519_public_keys(keystore, key_type.0)),
+			Crypto::Ecdsa => to_bytes(SyncCryptoStore::ecdsa_public_keys(keystore, key_type.0)),
+		})
+	}
+
+	fn generate_new(&self, crypto: Crypto, key_type: KeyType, seed: Option<String>) -> Result<Bytes> {
+		let keystore = &*self.keystore;
+		let seed = seed.as_deref();
+		match crypto {
+			Crypto::Sr25519 => SyncCryptoStore::sr25519_generate_new(keystore, key_type.0, seed).map(raw),
+			Crypto::Ed25519 => SyncCryptoStore::ed25519_generate_new(keystore, key_type.0, seed).map(raw),
+			Crypto::Ecdsa => SyncCryptoStore::ecdsa_generate_new(keystore, key_type.0, seed).map(raw),
+		}
+		.map_err(error_to_rpc)
+	}
+
+	fn insert_unknown(&self, key_type: KeyType, suri: String, public: Bytes) -> Result<bool> {
+		Ok(SyncCryptoStore::insert_unknown(&*self.keystore, key_type.0, &suri, &public).is_ok())
+	}
+
+	fn supported_keys(&self, key_type: KeyType, keys: Vec<PublicPair>) -> Result<Vec<PublicPair>> {
+		let keys = keys.into_iter().map(Into::into).collect();
+		let supported = SyncCryptoStore::supported_keys(&*self.keystore, key_type.0, keys).map_err(error_to_rpc)?;
+		to_public_pairs(&supported)
+	}
+
+	fn keys(&self, key_type: KeyType) -> Result<Vec<PublicPair>> {
+		let keys = SyncCryptoStore::keys(&*self.keystore, key_type.0).map_err(error_to_rpc)?;
+		to_public_pairs(&keys)
+	}
+
+	fn has_keys(&self, public_keys: Vec<(Bytes, KeyType)>) -> Result<bool> {
+		let public_keys = public_keys
+			.into_iter()
+			.map(|(public, key_type)| (public.0, key_type.0))
+			.collect::<Vec<(Vec<u8>, KeyTypeId)>>();
+		Ok(SyncCryptoStore::has_keys(&*self.keystore, &public_keys))
+	}
+
+	fn sign_with(&self, key_type: KeyType, key: PublicPair, msg: Bytes) -> Result<Option<Bytes>> {
+		let key = CryptoTypePublicPair::from(key);
+		SyncCryptoStore::sign_with(&*self.keystore, key_type.0, &key, &msg)
+			.map(|signature| signature.map(Into::into))
+			.map_err(error_to_rpc)
+	}
+
+	fn sr25519_vrf_sign(
+		&self,
+		key_type: KeyType,
+		public: Bytes,
+		transcript: TranscriptData,
+	) -> Result<Option<VrfSignature>> {
+		let public = decode_raw::<sr25519::Public>(&public).ok_or_else(|| invalid_params("public"))?;
+		let transcript = VRFTranscriptData {
+			label: self
+				.intern_label(&transcript.label)
+				.ok_or_else(|| invalid_params("transcript label"))?,
+			items: transcript
+				.items
+				.into_iter()
+				.map(|(name, value)| {
+					let name = self
+						.intern_name(&name)
+						.ok_or_else(|| invalid_params("transcript item"))?;
+					let value = match value {
+						TranscriptValue::Bytes(bytes) => VRFTranscriptValue::Bytes(bytes.0),
+						TranscriptValue::U64(value) => VRFTranscriptValue::U64(value),
+					};
+					Ok((name, value))
+				})
+				.collect::<Result<_>>()?,
+		};
+		SyncCryptoStore::sr25519_vrf_sign(&*self.keystore, key_type.0, &public, transcript)
+			.map(|signature| signature.map(Into::into))
+			.map_err(error_to_rpc)
+	}
+
+	fn ecdsa_sign_prehashed(&self, key_type: KeyType, public: Bytes, msg: Bytes) -> Result<Option<Bytes>> {
+		let public = decode_raw::<ecdsa::Public>(&public).ok_or_else(|| invalid_params("public"))?;
+		let msg = <[u8; 32]>::try_from(&msg[..]).map_err(|_| invalid_params("msg"))?;
+		SyncCryptoStore::ecdsa_sign_prehashed(&*self.keystore, key_type.0, &public, &msg)
+			.map(|signature| signature.map(raw))
+			.map_err(error_to_rpc)
+	}
+}
+
+/// A running signing daemon, stopped when dropped.
+pub struct Server {
+	endpoint: Endpoint,
+	inner: Inner,
+}
+
+enum Inner {
+	Http(jsonrpc_http_server::Server),
+	Unix(jsonrpc_ipc_server::Server),
+}
+
+impl Server {
+	/// The endpoint the daemon listens on, with the bound port for `http://<host>:0`.
+	pub fn endpoint(&self) -> &Endpoint {
+		&self.endpoint
+	}
+
+	/// Block until the daemon is stopped.
+	pub fn wait(self) {
+		match self.inner {
+			Inner::Http(server) => server.wait(),
+			Inner::Unix(server) => server.wait(),
+		}
+	}
+}
+
+/// Start serving `keystore` on `endpoint`.
+///
+/// A unix socket is only accessible by the daemon user, in a directory private
+/// to it, and a stale socket left there is replaced. HTTP endpoints must be bound
+/// to a loopback address and require clients to send `token` as a bearer token.
+pub fn start(endpoint: &Endpoint, keystore: SyncCryptoStorePtr, token: Option<&str>) -> io::Result<Server> {
+	let mut io = IoHandler::default();
+	io.extend_with(SignerApiServer::to_delegate(Signer::new(keystore)));
+
+	match endpoint {
+		Endpoint::Http(address) => {
+			let token = token.ok_or_else(|| invalid_input("A token is required to serve over HTTP".into()))?;
+			let address: SocketAddr = address
+				.to_socket_addrs()?
+				.next()
+				.ok_or_else(|| invalid_input(format!("Invalid address {}", address)))?;
+			if !address.ip().is_loopback() {
+				return Err(invalid_input(format!(
+					"Refusing to listen on non-loopback address {}",
+					address
+				)));
+			}
+			let authorization = format!("Bearer {}", token);
+			let server = jsonrpc_http_server::ServerBuilder::new(io)
+				.request_middleware(move |request: Request<Body>| -> RequestMiddlewareAction {
+					let authorized = request.headers().get(header::AUTHORIZATION).map_or(false, |value| {
+						constant_time_eq(value.as_bytes(), authorization.as_bytes())
+					});
+					if authorized {
+						request.into()
+					} else {
+						Response {
+							code: StatusCode::UNAUTHORIZED,
+							content_type: header::HeaderValue::from_static("text/plain; charset=utf-8"),
+							content: "Invalid token\n".into(),
+						}
+						.into()
+					}
+				})
+				.start_http(&address)?;
+			Ok(Server {
+				endpoint: Endpoint::Http(server.address().to_string()),
+				inner: Inner::Http(server),
+			})
+		}
+		Endpoint::Unix(path) => {
+			ensure_private_dir(path.parent().unwrap_or_else(|| Path::new("/")))?;
+			if fs::symlink_metadata(path).map_or(false, |metadata| metadata.file_type().is_socket()) {
+				fs::remove_file(path)?;
+			}
+			let server = jsonrpc_ipc_server::ServerBuilder::new(io).start(&path.to_string_lossy())?;
+			fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+			Ok(Server {
+				endpoint: endpoint.clone(),
+				inner: Inner::Unix(server),
+			})
+		}
+	}
+}
+
+/// Create the socket directory only accessible by the daemon user, or check that
+/// an existing one is not accessible by other users.
+fn ensure_private_dir(dir: &Path) -> io::Result<()> {
+	let dir = if dir.as_os_str().is_empty() {
+		Path::new(".")
+	} else {
+		dir
+	};
+	match fs::symlink_metadata(dir) {
+		Ok(metadata) if !metadata.is_dir() => Err(invalid_input(format!("{} is not a directory", dir.display()))),
+		Ok(metadata) if metadata.permissions().mode() & 0o077 != 0 => Err(io::Error::new(
+			io::ErrorKind::PermissionDenied,
+			format!("{} must only be accessible by its owner", dir.display()),
+		)),
+		Ok(_) => Ok(()),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => fs::DirBuilder::new().recursive(true).mode(0o700).create(dir),
+		Err(e) => Err(e),
+	}
+}
+
+/// Compare without returning early, not to leak the token through timings.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+	a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+fn invalid_input(message: String) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidInput, message)
+}
+
+fn raw<T: AsRef<[u8]>>(value: T) -> Bytes {
+	value.as_ref().to_vec().into()
+}
+
+fn to_bytes<T: AsRef<[u8]>>(keys: Vec<T>) -> Vec<Bytes> {
+	keys.into_iter().map(raw).collect()
+}
+
+fn to_public_pairs(keys: &[CryptoTypePublicPair]) -> Result<Vec<PublicPair>> {
+	keys.iter()
+		.map(|key| PublicPair::try_from(key).map_err(error_to_rpc))
+		.collect()
+}
+
+fn invalid_params(param: &str) -> RpcError {
+	RpcError::invalid_params(format!("Invalid {}", param))
+}
@@ -0,0 +1,293 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Round trips between `RemoteKeystore` and an in-process signing daemon.
+
+use std::{fs, os::unix::fs::PermissionsExt, sync::Arc};
+
+use sc_keystore::LocalKeystore;
+use sp_core::{
+	crypto::{key_types, CryptoTypePublicPair, Pair, Public},
+	ecdsa, ed25519, sr25519,
+};
+use sp_keystore::{
+	vrf::{VRFTranscriptData, VRFTranscriptValue},
+	CryptoStore, Error, SyncCryptoStore, SyncCryptoStorePtr,
+};
+
+use crate::{server, Endpoint, RemoteKeystore};
+
+const TOKEN: &str = "secret";
+
+/// A BABE slot claim transcript.
+fn transcript() -> VRFTranscriptData {
+	VRFTranscriptData {
+		label: b"BABE",
+		items: vec![
+			("slot number", VRFTranscriptValue::U64(1)),
+			("current epoch", VRFTranscriptValue::U64(0)),
+			("chain randomness", VRFTranscriptValue::Bytes([0; 32].to_vec())),
+		],
+	}
+}
+
+fn mode(path: &std::path::Path) -> u32 {
+	fs::metadata(path).unwrap().permissions().mode() & 0o777
+}
+
+/// Run the tests against both transports.
+fn with_daemons(test: impl Fn(&RemoteKeystore, &SyncCryptoStorePtr)) {
+	let dir = tempfile::tempdir().unwrap();
+	for endpoint in [
+		Endpoint::Unix(dir.path().join("signer.sock")),
+		Endpoint::Http("127.0.0.1:0".into()),
+	] {
+		let local: SyncCryptoStorePtr = Arc::new(LocalKeystore::in_memory());
+		let server = server::start(&endpoint, local.clone(), Some(TOKEN)).unwrap();
+		let remote = RemoteKeystore::connect(&server.endpoint().to_string(), Some(TOKEN.into())).unwrap();
+		test(&remote, &local);
+	}
+}
+
+#[test]
+fn parse_endpoints() {
+	assert_eq!(
+		"unix:/tmp/signer.sock".parse(),
+		Ok(Endpoint::Unix("/tmp/signer.sock".into()))
+	);
+	assert_eq!(
+		"unix:///tmp/signer.sock".parse(),
+		Ok(Endpoint::Unix("/tmp/signer.sock".into()))
+	);
+	assert_eq!(
+		"http://127.0.0.1:9955/".parse(),
+		Ok(Endpoint::Http("127.0.0.1:9955".into()))
+	);
+	assert!("ws://127.0.0.1:9955".parse::<Endpoint>().is_err());
+	assert!("http://127.0.0.1:9955/signer".parse::<Endpoint>().is_err());
+	assert!("unix:".parse::<Endpoint>().is_err());
+}
+
+#[test]
+fn connect_fails_without_daemon() {
+	let dir = tempfile::tempdir().unwrap();
+	assert!(RemoteKeystore::connect(&format!("unix:{}", dir.path().join("none.sock").display()), None).is_err());
+}
+
+#[test]
+fn unix_socket_is_private() {
+	let dir = tempfile::tempdir().unwrap();
+	let path = dir.path().join("signer").join("signer.sock");
+	let local: SyncCryptoStorePtr = Arc::new(LocalKeystore::in_memory());
+	let _server = server::start(&Endpoint::Unix(path.clone()), local.clone(), None).unwrap();
+	assert_eq!(mode(path.parent().unwrap()), 0o700);
+	assert_eq!(mode(&path), 0o600);
+
+	// a directory other users can access is refused.
+	let shared = dir.path().join("shared");
+	fs::create_dir(&shared).unwrap();
+	fs::set_permissions(&shared, fs::Permissions::from_mode(0o755)).unwrap();
+	assert!(server::start(&Endpoint::Unix(shared.join("signer.sock")), local, None).is_err());
+}
+
+#[test]
+fn http_requires_token_on_loopback() {
+	let local: SyncCryptoStorePtr = Arc::new(LocalKeystore::in_memory());
+	assert!(server::start(&Endpoint::Http("127.0.0.1:0".into()), local.clone(), None).is_err());
+	assert!(server::start(&Endpoint::Http("0.0.0.0:0".into()), local.clone(), Some(TOKEN)).is_err());
+
+	let server = server::start(&Endpoint::Http("127.0.0.1:0".into()), local, Some(TOKEN)).unwrap();
+	let uri = server.endpoint().to_string();
+	assert!(RemoteKeystore::connect(&uri, None).is_err());
+	assert!(RemoteKeystore::connect(&uri, Some("wrong".into())).is_err());
+	assert!(RemoteKeystore::connect(&uri, Some(TOKEN.into())).is_ok());
+}
+
+#[test]
+fn generates_keys_held_by_the_daemon() {
+	with_daemons(|remote, local| {
+		let sr25519 = SyncCryptoStore::sr25519_generate_new(remote, key_types::BABE, None).unwrap();
+		let ed25519 = SyncCryptoStore::ed25519_generate_new(remote, key_types::GRANDPA, Some("//Alice")).unwrap();
+		let ecdsa = SyncCryptoStore::ecdsa_generate_new(remote, key_types::ACCOUNT, None).unwrap();
+
+		assert_eq!(
+			SyncCryptoStore::sr25519_public_keys(&**local, key_types::BABE),
+			vec![sr25519]
+		);
+		assert_eq!(
+			SyncCryptoStore::sr25519_public_keys(remote, key_types::BABE),
+			vec![sr25519]
+		);
+		assert_eq!(
+			SyncCryptoStore::ed25519_public_keys(remote, key_types::GRANDPA),
+			vec![ed25519]
+		);
+		assert_eq!(ed25519, ed25519::Pair::from_string("//Alice", None).unwrap().public());
+		assert_eq!(
+			SyncCryptoStore::ecdsa_public_keys(remote, key_types::ACCOUNT),
+			vec![ecdsa]
+		);
+
+		assert!(SyncCryptoStore::has_keys(
+			remote,
+			&[
+				(sr25519.to_raw_vec(), key_types::BABE),
+				(ed25519.to_raw_vec(), key_types::GRANDPA)
+			]
+		));
+		assert!(!SyncCryptoStore::has_keys(
+			remote,
+			&[(sr25519.to_raw_vec(), key_types::GRANDPA)]
+		));
+		assert_eq!(
+			SyncCryptoStore::keys(remote, key_types::BABE).unwrap(),
+			vec![CryptoTypePublicPair::from(sr25519)]
+		);
+	});
+}
+
+#[test]
+fn signs_with_the_daemon_keys() {
+	with_daemons(|remote, _| {
+		let sr25519 = SyncCryptoStore::sr25519_generate_new(remote, key_types::BABE, None).unwrap();
+		let ed25519 = SyncCryptoStore::ed25519_generate_new(remote, key_types::GRANDPA, None).unwrap();
+		let ecdsa = SyncCryptoStore::ecdsa_generate_new(remote, key_types::ACCOUNT, None).unwrap();
+		let msg = b"setheum";
+
+		let signature = SyncCryptoStore::sign_with(remote, key_types::BABE, &sr25519.into(), msg)
+			.unwrap()
+			.unwrap();
+		let signature = sr25519::Signature::from_slice(&signature);
+		assert!(sr25519::Pair::verify(&signature, msg, &sr25519));
+
+		let signature = SyncCryptoStore::sign_with(remote, key_types::GRANDPA, &ed25519.into(), msg)
+			.unwrap()
+			.unwrap();
+		let signature = ed25519::Signature::from_slice(&signature);
+		assert!(ed25519::Pair::verify(&signature, msg, &ed25519));
+
+		let hash = sp_core::hashing::blake2_256(msg);
+		let signature = SyncCryptoStore::ecdsa_sign_prehashed(remote, key_types::ACCOUNT, &ecdsa, &hash)
+			.unwrap()
+			.unwrap();
+		assert!(ecdsa::Pair::verify_prehashed(&signature, &hash, &ecdsa));
+
+		// keys the daemon does not hold.
+		let unknown = sr25519::Pair::generate().0.public();
+		assert!(matches!(
+			SyncCryptoStore::sign_with(remote, key_types::BABE, &unknown.into(), msg),
+			Ok(None)
+		));
+	});
+}
+
+#[test]
+fn signs_vrf_transcripts() {
+	with_daemons(|remote, local| {
+		let public = SyncCryptoStore::sr25519_generate_new(remote, key_types::BABE, None).unwrap();
+
+		let signature = SyncCryptoStore::sr25519_vrf_sign(remote, key_types::BABE, &public, transcript())
+			.unwrap()
+			.unwrap();
+		let expected = SyncCryptoStore::sr25519_vrf_sign(&**local, key_types::BABE, &public, transcript())
+			.unwrap()
+			.unwrap();
+		// the output is deterministic while the proof is not.
+		assert_eq!(signature.output, expected.output);
+
+		let unknown = sr25519::Pair::generate().0.public();
+		assert!(
+			SyncCryptoStore::sr25519_vrf_sign(remote, key_types::BABE, &unknown, transcript())
+				.unwrap()
+				.is_none()
+		);
+	});
+}
+
+#[test]
+fn rejects_unknown_transcripts() {
+	with_daemons(|remote, _| {
+		let public = SyncCryptoStore::sr25519_generate_new(remote, key_types::BABE, None).unwrap();
+
+		let mut label = transcript();
+		label.label = &b"test"[..];
+		assert!(SyncCryptoStore::sr25519_vrf_sign(remote, key_types::BABE, &public, label).is_err());
+
+		let mut item = transcript();
+		item.items.push(("other", VRFTranscriptValue::U64(1)));
+		assert!(SyncCryptoStore::sr25519_vrf_sign(remote, key_types::BABE, &public, item).is_err());
+	});
+}
+
+#[tokio::test]
+async fn async_store_does_not_block() {
+	let dir = tempfile::tempdir().unwrap();
+	let endpoint = Endpoint::Unix(dir.path().join("signer.sock"));
+	let local: SyncCryptoStorePtr = Arc::new(LocalKeystore::in_memory());
+	let _server = server::start(&endpoint, local, None).unwrap();
+	let remote = RemoteKeystore::connect(&endpoint.to_string(), None).unwrap();
+
+	let public = CryptoStore::sr25519_generate_new(&remote, key_types::BABE, Some("//Alice"))
+		.await
+		.unwrap();
+	assert_eq!(
+		CryptoStore::sr25519_public_keys(&remote, key_types::BABE).await,
+		vec![public]
+	);
+
+	let msg = b"setheum";
+	let signature = CryptoStore::sign_with(&remote, key_types::BABE, &public.into(), msg)
+		.await
+		.unwrap()
+		.unwrap();
+	let signature = sr25519::Signature::from_slice(&signature);
+	assert!(sr25519::Pair::verify(&signature, msg, &public));
+	assert!(
+		CryptoStore::sr25519_vrf_sign(&remote, key_types::BABE, &public, transcript())
+			.await
+			.unwrap()
+			.is_some()
+	);
+}
+
+#[test]
+fn inserts_unknown_keys() {
+	with_daemons(|remote, _| {
+		let pair = sr25519::Pair::from_string("//Bob", None).unwrap();
+		assert_eq!(
+			SyncCryptoStore::insert_unknown(remote, key_types::IM_ONLINE, "//Bob", pair.public().as_ref()),
+			Ok(())
+		);
+		assert_eq!(
+			SyncCryptoStore::sr25519_public_keys(remote, key_types::IM_ONLINE),
+			vec![pair.public()]
+		);
+	});
+}
+
+#[test]
+fn keeps_keystore_errors() {
+	let error = crate::protocol::error_from_rpc(crate::protocol::error_to_rpc(Error::KeyNotSupported(key_types::BABE)));
+	assert!(matches!(error, Error::KeyNotSupported(id) if id == key_types::BABE));
+
+	let error =
+		crate::protocol::error_from_rpc(crate::protocol::error_to_rpc(Error::ValidationError("invalid".into())));
+	assert!(matches!(error, Error::ValidationError(message) if message == "invalid"));
+}
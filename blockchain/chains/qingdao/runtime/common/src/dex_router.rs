@@ -0,0 +1,209 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Route the swaps between the dex and the stable swap pools.
+
+use module_support::{DEXManager, SwapLimit};
+use primitives::{Balance, CurrencyId};
+use sp_core::H160;
+use sp_runtime::{DispatchError, DispatchResult};
+use sp_std::{marker::PhantomData, prelude::*};
+
+/// A `DEXManager` which swaps with `StableSwap` when its pools give a better
+/// price for the path, and with `Dex` otherwise. The liquidity is always
+/// managed by `Dex`.
+pub struct DexRouter<Dex, StableSwap>(PhantomData<(Dex, StableSwap)>);
+
+impl<Dex, StableSwap> DexRouter<Dex, StableSwap> {
+	/// Whether `path` should be swapped with the stable swap pools.
+	fn use_stable_swap<AccountId>(path: &[CurrencyId], limit: SwapLimit<Balance>) -> bool
+	where
+		Dex: DEXManager<AccountId, CurrencyId, Balance>,
+		StableSwap: DEXManager<AccountId, CurrencyId, Balance>,
+	{
+		is_better_swap(
+			limit,
+			StableSwap::get_swap_amount(path, limit),
+			Dex::get_swap_amount(path, limit),
+		)
+	}
+}
+
+/// Whether the swap amounts `a` are better than `b` under `limit`.
+fn is_better_swap(limit: SwapLimit<Balance>, a: Option<(Balance, Balance)>, b: Option<(Balance, Balance)>) -> bool {
+	match (a, b) {
+		(Some((supply_a, target_a)), Some((supply_b, target_b))) => match limit {
+			SwapLimit::ExactSupply(..) => target_a > target_b,
+			SwapLimit::ExactTarget(..) => supply_a < supply_b,
+		},
+		(Some(_), None) => true,
+		_ => false,
+	}
+}
+
+impl<AccountId, Dex, StableSwap> DEXManager<AccountId, CurrencyId, Balance> for DexRouter<Dex, StableSwap>
+where
+	Dex: DEXManager<AccountId, CurrencyId, Balance>,
+	StableSwap: DEXManager<AccountId, CurrencyId, Balance>,
+{
+	fn get_liquidity_pool(currency_id_a: CurrencyId, currency_id_b: CurrencyId) -> (Balance, Balance) {
+		Dex::get_liquidity_pool(currency_id_a, currency_id_b)
+	}
+
+	fn get_liquidity_token_address(currency_id_a: CurrencyId, currency_id_b: CurrencyId) -> Option<H160> {
+		Dex::get_liquidity_token_address(currency_id_a, currency_id_b)
+	}
+
+	fn get_swap_amount(path: &[CurrencyId], limit: SwapLimit<Balance>) -> Option<(Balance, Balance)> {
+		if Self::use_stable_swap::<AccountId>(path, limit) {
+			StableSwap::get_swap_amount(path, limit)
+		} else {
+			Dex::get_swap_amount(path, limit)
+		}
+	}
+
+	fn get_best_price_swap_path(
+		supply_currency_id: CurrencyId,
+		target_currency_id: CurrencyId,
+		limit: SwapLimit<Balance>,
+		alternative_path_joint_list: Vec<Vec<CurrencyId>>,
+	) -> Option<Vec<CurrencyId>> {
+		let dex_path = Dex::get_best_price_swap_path(
+			supply_currency_id,
+			target_currency_id,
+			limit,
+			alternative_path_joint_list.clone(),
+		);
+		let stable_path = StableSwap::get_best_price_swap_path(
+			supply_currency_id,
+			target_currency_id,
+			limit,
+			alternative_path_joint_list,
+		);
+
+		match (dex_path, stable_path) {
+			(Some(dex_path), Some(stable_path)) => {
+				if is_better_swap(
+					limit,
+					StableSwap::get_swap_amount(&stable_path, limit),
+					Dex::get_swap_amount(&dex_path, limit),
+				) {
+					Some(stable_path)
+				} else {
+					Some(dex_path)
+				}
+			}
+			(dex_path, stable_path) => dex_path.or(stable_path),
+		}
+	}
+
+	fn swap_with_specific_path(
+		who: &AccountId,
+		path: &[CurrencyId],
+		limit: SwapLimit<Balance>,
+	) -> sp_std::result::Result<(Balance, Balance), DispatchError> {
+		if Self::use_stable_swap::<AccountId>(path, limit) {
+			StableSwap::swap_with_specific_path(who, path, limit)
+		} else {
+			Dex::swap_with_specific_path(who, path, limit)
+		}
+	}
+
+	fn buyback_swap_with_specific_path(
+		who: &AccountId,
+		path: &[CurrencyId],
+		limit: SwapLimit<Balance>,
+	) -> sp_std::result::Result<(Balance, Balance), DispatchError> {
+		if Self::use_stable_swap::<AccountId>(path, limit) {
+			StableSwap::buyback_swap_with_specific_path(who, path, limit)
+		} else {
+			Dex::buyback_swap_with_specific_path(who, path, limit)
+		}
+	}
+
+	fn swap_with_exact_target(
+		who: &AccountId,
+		path: &[CurrencyId],
+		exact_target_amount: Balance,
+		max_supply_amount: Balance,
+	) -> DispatchResult {
+		let limit = SwapLimit::ExactTarget(max_supply_amount, exact_target_amount);
+		if Self::use_stable_swap::<AccountId>(path, limit) {
+			StableSwap::swap_with_exact_target(who, path, exact_target_amount, max_supply_amount)
+		} else {
+			Dex::swap_with_exact_target(who, path, exact_target_amount, max_supply_amount)
+		}
+	}
+
+	fn add_liquidity(
+		who: &AccountId,
+		currency_id_a: CurrencyId,
+		currency_id_b: CurrencyId,
+		max_amount_a: Balance,
+		max_amount_b: Balance,
+		min_share_increment: Balance,
+	) -> sp_std::result::Result<(Balance, Balance, Balance), DispatchError> {
+		Dex::add_liquidity(
+			who,
+			currency_id_a,
+			currency_id_b,
+			max_amount_a,
+			max_amount_b,
+			min_share_increment,
+		)
+	}
+
+	fn remove_liquidity(
+		who: &AccountId,
+		currency_id_a: CurrencyId,
+		currency_id_b: CurrencyId,
+		remove_share: Balance,
+		min_withdrawn_a: Balance,
+		min_withdrawn_b: Balance,
+	) -> sp_std::result::Result<(Balance, Balance), DispatchError> {
+		Dex::remove_liquidity(
+			who,
+			currency_id_a,
+			currency_id_b,
+			remove_share,
+			min_withdrawn_a,
+			min_withdrawn_b,
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn is_better_swap_works() {
+		let exact_supply = SwapLimit::ExactSupply(100, 0);
+		assert!(is_better_swap(exact_supply, Some((100, 99)), Some((100, 98))));
+		assert!(!is_better_swap(exact_supply, Some((100, 98)), Some((100, 98))));
+		assert!(is_better_swap(exact_supply, Some((100, 98)), None));
+		assert!(!is_better_swap(exact_supply, None, Some((100, 98))));
+		assert!(!is_better_swap(exact_supply, None, None));
+
+		let exact_target = SwapLimit::ExactTarget(110, 100);
+		assert!(is_better_swap(exact_target, Some((101, 100)), Some((102, 100))));
+		assert!(!is_better_swap(exact_target, Some((102, 100)), Some((102, 100))));
+	}
+}
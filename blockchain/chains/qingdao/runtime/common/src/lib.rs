@@ -47,15 +47,17 @@ use static_assertions::const_assert;
 
 pub mod precompile;
 pub use precompile::{
-	AllPrecompiles, DexPrecompile, MultiCurrencyPrecompile, NFTPrecompile, OraclePrecompile, ScheduleCallPrecompile,
-	StateRentPrecompile,
+	AllPrecompiles, DexPrecompile, IdleSchedulerPrecompile, IncentivesPrecompile, MultiCurrencyPrecompile, NFTPrecompile,
+	OraclePrecompile, ScheduleCallPrecompile, StateRentPrecompile,
 };
 pub use primitives::{
 	currency::{TokenInfo, SEE, SERP, DNAR, HELP, SETR, SETUSD},
 	AccountId,
 };
 
+mod dex_router;
 mod gas_to_weight_ratio;
+pub use dex_router::DexRouter;
 
 pub type TimeStampedPrice = orml_oracle::TimestampedValue<Price, primitives::Moment>;
 
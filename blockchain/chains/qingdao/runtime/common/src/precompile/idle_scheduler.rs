@@ -0,0 +1,115 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use super::input::{Input, InputT, Output};
+use super::input::{Input, InputT, Output};
+use super::schedule_call::dynamic_bytes_at;
+use crate::precompile::PrecompileOutput;
+use frame_support::log;
+use module_evm::{Context, ExitError, ExitSucceed, Precompile};
+use module_support::{AddressMapping as AddressMappingT, CurrencyIdMapping as CurrencyIdMappingT, SignedIdleScheduler};
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use sp_runtime::RuntimeDebug;
+use sp_std::{convert::TryFrom, fmt::Debug, marker::PhantomData, prelude::*, result};
+
+/// The `IdleScheduler` impl precompile.
+///
+///
+/// `input` data starts with `action`.
+///
+/// Actions:
+/// - Schedule task. Rest `input` bytes: `who`, `priority`, `task`, the SCALE encoded task.
+/// - Cancel task. Rest `input` bytes: `who`, `task_id`.
+pub struct IdleSchedulerPrecompile<AccountId, AddressMapping, CurrencyIdMapping, IdleScheduler>(
+	PhantomData<(AccountId, AddressMapping, CurrencyIdMapping, IdleScheduler)>,
+);
+
+#[primitives_proc_macro::generate_function_selector]
+#[derive(RuntimeDebug, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[repr(u32)]
+pub enum Action {
+	ScheduleTask = "scheduleTask(address,uint256,bytes)",
+	CancelTask = "cancelTask(address,uint256)",
+}
+
+impl<AccountId, AddressMapping, CurrencyIdMapping, IdleScheduler> Precompile
+	for IdleSchedulerPrecompile<AccountId, AddressMapping, CurrencyIdMapping, IdleScheduler>
+where
+	AccountId: Debug + Clone,
+	AddressMapping: AddressMappingT<AccountId>,
+	CurrencyIdMapping: CurrencyIdMappingT,
+	IdleScheduler: SignedIdleScheduler<AccountId>,
+{
+	fn execute(
+		input: &[u8],
+		_target_gas: Option<u64>,
+		_context: &Context,
+	) -> result::Result<PrecompileOutput, ExitError> {
+		let input = Input::<Action, AccountId, AddressMapping, CurrencyIdMapping>::new(input);
+
+		let action = input.action()?;
+
+		match action {
+			Action::ScheduleTask => {
+				let who = input.account_id_at(1)?;
+				let priority =
+					u8::try_from(input.u32_at(2)?).map_err(|_| ExitError::Other("invalid priority".into()))?;
+				let task = dynamic_bytes_at(&input, 3)?;
+				log::debug!(
+					target: "evm",
+					"idle_scheduler: schedule_task who: {:?}, priority: {:?}, task: {:?}",
+					who, priority, task
+				);
+
+				let task_id = IdleScheduler::schedule_signed(&who, task, priority).map_err(|e| {
+					let err_msg: &str = e.into();
+					ExitError::Other(err_msg.into())
+				})?;
+
+				Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					cost: 0,
+					output: Output::default().encode_u32(task_id),
+					logs: Default::default(),
+				})
+			}
+			Action::CancelTask => {
+				let who = input.account_id_at(1)?;
+				let task_id = input.u32_at(2)?;
+				log::debug!(
+					target: "evm",
+					"idle_scheduler: cancel_task who: {:?}, task_id: {:?}",
+					who, task_id
+				);
+
+				IdleScheduler::cancel_signed(&who, task_id).map_err(|e| {
+					let err_msg: &str = e.into();
+					ExitError::Other(err_msg.into())
+				})?;
+
+				Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					cost: 0,
+					output: vec![],
+					logs: Default::default(),
+				})
+			}
+		}
+	}
+}
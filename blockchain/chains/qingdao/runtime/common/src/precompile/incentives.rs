@@ -0,0 +1,198 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+use super::input::{Input, InputT, Output};
+use crate::precompile::PrecompileOutput;
+use frame_support::log;
+use module_evm::{Context, ExitError, ExitSucceed, Precompile};
+use module_support::{AddressMapping as AddressMappingT, CurrencyIdMapping as CurrencyIdMappingT, IncentivesManager};
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use primitives::{Balance, CurrencyId};
+use sp_runtime::RuntimeDebug;
+use sp_std::{fmt::Debug, marker::PhantomData, prelude::*, result};
+
+/// The `Incentives` impl precompile.
+///
+///
+/// `input` data starts with `action`.
+///
+/// Actions:
+/// - Get reward rate. Rest `input` bytes: `lp_currency_id`, `reward_currency_id`.
+/// - Get deposited shares. Rest `input` bytes: `lp_currency_id`, `who`.
+/// - Get pending rewards. Rest `input` bytes: `lp_currency_id`, `who`, `reward_currency_id`.
+/// - Deposit dex share. Rest `input` bytes: `who`, `lp_currency_id`, `amount`.
+/// - Withdraw dex share. Rest `input` bytes: `who`, `lp_currency_id`, `amount`.
+/// - Claim rewards. Rest `input` bytes: `who`, `lp_currency_id`.
+pub struct IncentivesPrecompile<AccountId, AddressMapping, CurrencyIdMapping, Incentives>(
+	PhantomData<(AccountId, AddressMapping, CurrencyIdMapping, Incentives)>,
+);
+
+#[primitives_proc_macro::generate_function_selector]
+#[derive(RuntimeDebug, Eq, PartialEq, TryFromPrimitive, IntoPrimitive)]
+#[repr(u32)]
+pub enum Action {
+	GetRewardRate = "getRewardRate(address,address)",
+	GetDepositedShares = "getDepositedShares(address,address)",
+	GetPendingRewards = "getPendingRewards(address,address,address)",
+	DepositDexShare = "depositDexShare(address,address,uint256)",
+	WithdrawDexShare = "withdrawDexShare(address,address,uint256)",
+	ClaimRewards = "claimRewards(address,address)",
+}
+
+impl<AccountId, AddressMapping, CurrencyIdMapping, Incentives> Precompile
+	for IncentivesPrecompile<AccountId, AddressMapping, CurrencyIdMapping, Incentives>
+where
+	AccountId: Debug + Clone,
+	AddressMapping: AddressMappingT<AccountId>,
+	CurrencyIdMapping: CurrencyIdMappingT,
+	Incentives: IncentivesManager<AccountId, Balance, CurrencyId>,
+{
+	fn execute(
+		input: &[u8],
+		_target_gas: Option<u64>,
+		_context: &Context,
+	) -> result::Result<PrecompileOutput, ExitError> {
+		let input = Input::<Action, AccountId, AddressMapping, CurrencyIdMapping>::new(input);
+
+		let action = input.action()?;
+
+		match action {
+			Action::GetRewardRate => {
+				let lp_currency_id = input.currency_id_at(1)?;
+				let reward_currency_id = input.currency_id_at(2)?;
+				log::debug!(
+					target: "evm",
+					"incentives: get_reward_rate lp_currency_id: {:?}, reward_currency_id: {:?}",
+					lp_currency_id, reward_currency_id
+				);
+
+				let value = Incentives::get_reward_rate(lp_currency_id, reward_currency_id);
+
+				Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					cost: 0,
+					output: Output::default().encode_u128(value),
+					logs: Default::default(),
+				})
+			}
+			Action::GetDepositedShares => {
+				let lp_currency_id = input.currency_id_at(1)?;
+				let who = input.account_id_at(2)?;
+				log::debug!(
+					target: "evm",
+					"incentives: get_deposited_shares lp_currency_id: {:?}, who: {:?}",
+					lp_currency_id, who
+				);
+
+				let value = Incentives::get_deposited_shares(lp_currency_id, &who);
+
+				Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					cost: 0,
+					output: Output::default().encode_u128(value),
+					logs: Default::default(),
+				})
+			}
+			Action::GetPendingRewards => {
+				let lp_currency_id = input.currency_id_at(1)?;
+				let who = input.account_id_at(2)?;
+				let reward_currency_id = input.currency_id_at(3)?;
+				log::debug!(
+					target: "evm",
+					"incentives: get_pending_rewards lp_currency_id: {:?}, who: {:?}, reward_currency_id: {:?}",
+					lp_currency_id, who, reward_currency_id
+				);
+
+				let value = Incentives::get_pending_rewards(lp_currency_id, &who, reward_currency_id);
+
+				Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					cost: 0,
+					output: Output::default().encode_u128(value),
+					logs: Default::default(),
+				})
+			}
+			Action::DepositDexShare => {
+				let who = input.account_id_at(1)?;
+				let lp_currency_id = input.currency_id_at(2)?;
+				let amount = input.balance_at(3)?;
+				log::debug!(
+					target: "evm",
+					"incentives: deposit_dex_share who: {:?}, lp_currency_id: {:?}, amount: {:?}",
+					who, lp_currency_id, amount
+				);
+
+				Incentives::deposit_dex_share(&who, lp_currency_id, amount).map_err(|e| {
+					let err_msg: &str = e.into();
+					ExitError::Other(err_msg.into())
+				})?;
+
+				Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					cost: 0,
+					output: vec![],
+					logs: Default::default(),
+				})
+			}
+			Action::WithdrawDexShare => {
+				let who = input.account_id_at(1)?;
+				let lp_currency_id = input.currency_id_at(2)?;
+				let amount = input.balance_at(3)?;
+				log::debug!(
+					target: "evm",
+					"incentives: withdraw_dex_share who: {:?}, lp_currency_id: {:?}, amount: {:?}",
+					who, lp_currency_id, amount
+				);
+
+				Incentives::withdraw_dex_share(&who, lp_currency_id, amount).map_err(|e| {
+					let err_msg: &str = e.into();
+					ExitError::Other(err_msg.into())
+				})?;
+
+				Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					cost: 0,
+					output: vec![],
+					logs: Default::default(),
+				})
+			}
+			Action::ClaimRewards => {
+				let who = input.account_id_at(1)?;
+				let lp_currency_id = input.currency_id_at(2)?;
+				log::debug!(
+					target: "evm",
+					"incentives: claim_rewards who: {:?}, lp_currency_id: {:?}",
+					who, lp_currency_id
+				);
+
+				Incentives::claim_rewards(&who, lp_currency_id).map_err(|e| {
+					let err_msg: &str = e.into();
+					ExitError::Other(err_msg.into())
+				})?;
+
+				Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					cost: 0,
+					output: vec![],
+					logs: Default::default(),
+				})
+			}
+		}
+	}
+}
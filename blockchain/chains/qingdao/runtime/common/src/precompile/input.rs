@@ -32,7 +32,7 @@ use ethabi::Token;
 use module_evm::ExitError;
 use module_support::{AddressMapping as AddressMappingT, CurrencyIdMapping as CurrencyIdMappingT};
 use primitives::{Amount, Balance, CurrencyId};
-use sp_core::{H160, U256};
+use sp_core::{H160, H256, U256};
 
 pub const FUNCTION_SELECTOR_LENGTH: usize = 4;
 pub const PER_PARAM_BYTES: usize = 32;
@@ -213,6 +213,11 @@ impl Output {
 		ethabi::encode(&[out])
 	}
 
+	pub fn encode_h256_u32_tuple(&self, b: &H256, c: u32) -> Vec<u8> {
+		let out = Token::Tuple(vec![Token::FixedBytes(b.as_bytes().to_vec()), Token::Uint(U256::from(c))]);
+		ethabi::encode(&[out])
+	}
+
 	pub fn encode_bytes(&self, b: &[u8]) -> Vec<u8> {
 		let out = Token::Bytes(b.to_vec());
 		ethabi::encode(&[out])
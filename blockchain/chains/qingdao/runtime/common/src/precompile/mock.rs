@@ -24,7 +24,7 @@ use crate::{AllPrecompiles, Ratio, BlockWeights, SystemContractsFilter, Weight};
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
 	assert_ok, ord_parameter_types, parameter_types,
-	traits::{GenesisBuild, InstanceFilter, OnFinalize, OnInitialize, SortedMembers},
+	traits::{GenesisBuild, InstanceFilter, Nothing, OnFinalize, OnInitialize, SortedMembers},
 	weights::IdentityFee,
 	PalletId, RuntimeDebug,
 };
@@ -135,6 +135,8 @@ impl orml_tokens::Config for Test {
 	type ExistentialDeposits = ExistentialDeposits;
 	type OnDust = ();
 	type MaxLocks = ();
+	type MaxReserves = MaxReserves;
+	type ReserveIdentifier = ReserveIdentifier;
 	type DustRemovalWhitelist = ();
 }
 
@@ -344,6 +346,7 @@ impl module_evm_bridge::Config for Test {
 impl module_evm_manager::Config for Test {
 	type Currency = Balances;
 	type EVMBridge = EVMBridge;
+	type AssetMetadataProvider = ();
 }
 
 parameter_types! {
@@ -495,6 +498,19 @@ impl module_dex::Config for Test {
 	type ListingOrigin = EnsureSignedBy<ListingOrigin, AccountId>;
 }
 
+parameter_types! {
+	pub const IncentivesPalletId: PalletId = PalletId(*b"set/inct");
+}
+
+impl module_incentives::Config for Test {
+	type Event = Event;
+	type Currency = Tokens;
+	type RewardCurrency = Currencies;
+	type UpdateOrigin = EnsureSignedBy<ListingOrigin, AccountId>;
+	type PalletId = IncentivesPalletId;
+	type WeightInfo = ();
+}
+
 pub type AdaptedBasicCurrency = module_currencies::BasicCurrencyAdapter<Test, Balances, Amount, BlockNumber>;
 
 pub type EvmCurrencyIdMapping = module_evm_manager::EvmCurrencyIdMapping<Test>;
@@ -522,9 +538,38 @@ pub type ScheduleCallPrecompile = crate::ScheduleCallPrecompile<
 	Test,
 >;
 pub type DexPrecompile = crate::DexPrecompile<AccountId, MockAddressMapping, EvmCurrencyIdMapping, DexModule>;
+pub type IncentivesPrecompile =
+	crate::IncentivesPrecompile<AccountId, MockAddressMapping, EvmCurrencyIdMapping, IncentivesModule>;
+pub type IdleSchedulerPrecompile =
+	crate::IdleSchedulerPrecompile<AccountId, MockAddressMapping, EvmCurrencyIdMapping, IdleScheduler>;
+
+parameter_types! {
+	pub const MinimumWeightRemainInBlock: Weight = 0;
+	pub const MaxTaskRetries: u32 = 3;
+	pub const TaskRetryBackoff: BlockNumber = 10;
+	pub const TaskDeposit: Balance = 10;
+	pub const MaxTasksPerAccount: u32 = 10;
+	pub const MaxTasks: u32 = 100;
+}
+
+impl idle_scheduler::Config for Test {
+	type Event = Event;
+	type WeightInfo = ();
+	type Task = module_evm::EvmTask<Test>;
+	type MinimumWeightRemainInBlock = MinimumWeightRemainInBlock;
+	type MaxTaskRetries = MaxTaskRetries;
+	type TaskRetryBackoff = TaskRetryBackoff;
+	type Currency = Balances;
+	type TaskDeposit = TaskDeposit;
+	type MaxTasksPerAccount = MaxTasksPerAccount;
+	type MaxTasks = MaxTasks;
+	type SignedTaskFilter = Nothing;
+	type GovernanceOrigin = EnsureRoot<AccountId>;
+}
 
 parameter_types! {
 	pub NetworkContractSource: H160 = alice_evm_addr();
+	pub const Hardfork: module_evm::EvmHardfork = module_evm::EvmHardfork::Istanbul;
 }
 
 ord_parameter_types! {
@@ -535,6 +580,7 @@ ord_parameter_types! {
 	pub const StorageDepositPerByte: u64 = 10;
 	pub const DeveloperDeposit: u64 = 1000;
 	pub const DeploymentFee: u64 = 200;
+	pub const DefaultUpgradeDelay: BlockNumber = 0;
 	pub const ChainId: u64 = 1;
 }
 
@@ -560,18 +606,25 @@ impl module_evm::Config for Test {
 		OraclePrecompile,
 		ScheduleCallPrecompile,
 		DexPrecompile,
+		IncentivesPrecompile,
+		IdleSchedulerPrecompile,
 	>;
 	type ChainId = ChainId;
+	type Hardfork = Hardfork;
 	type GasToWeight = GasToWeight;
 	type ChargeTransactionPayment = ChargeTransactionPayment;
 	type NetworkContractOrigin = EnsureSignedBy<NetworkContractAccount, AccountId>;
 	type NetworkContractSource = NetworkContractSource;
 	type DeveloperDeposit = DeveloperDeposit;
 	type DeploymentFee = DeploymentFee;
+	type DefaultUpgradeDelay = DefaultUpgradeDelay;
 	type TreasuryAccount = TreasuryAccount;
 	type FreeDeploymentOrigin = EnsureSignedBy<CouncilAccount, AccountId>;
+	type CallFilter = ();
 	type Runner = module_evm::runner::stack::Runner<Self>;
 	type FindAuthor = ();
+	type Task = module_evm::EvmTask<Test>;
+	type IdleScheduler = IdleScheduler;
 	type WeightInfo = ();
 }
 
@@ -689,6 +742,8 @@ frame_support::construct_runtime!(
 		Utility: pallet_utility::{Pallet, Call, Event},
 		Scheduler: pallet_scheduler::{Pallet, Call, Storage, Event<T>},
 		DexModule: module_dex::{Pallet, Storage, Call, Event<T>, Config<T>},
+		IncentivesModule: module_incentives::{Pallet, Storage, Call, Event<T>},
+		IdleScheduler: idle_scheduler::{Pallet, Call, Storage, Event<T>},
 		ModuleEVM: module_evm::{Pallet, Config<T>, Call, Storage, Event<T>},
 	}
 );
@@ -42,6 +42,8 @@ use sp_core::H160;
 use sp_std::marker::PhantomData;
 
 pub mod dex;
+pub mod idle_scheduler;
+pub mod incentives;
 pub mod input;
 pub mod multicurrency;
 pub mod nft;
@@ -50,6 +52,8 @@ pub mod schedule_call;
 pub mod state_rent;
 
 pub use dex::DexPrecompile;
+pub use idle_scheduler::IdleSchedulerPrecompile;
+pub use incentives::IncentivesPrecompile;
 pub use multicurrency::MultiCurrencyPrecompile;
 pub use nft::NFTPrecompile;
 pub use oracle::OraclePrecompile;
@@ -64,6 +68,8 @@ pub struct AllPrecompiles<
 	OraclePrecompile,
 	ScheduleCallPrecompile,
 	DexPrecompile,
+	IncentivesPrecompile,
+	IdleSchedulerPrecompile,
 >(
 	PhantomData<(
 		PrecompileCallerFilter,
@@ -73,6 +79,8 @@ pub struct AllPrecompiles<
 		OraclePrecompile,
 		ScheduleCallPrecompile,
 		DexPrecompile,
+		IncentivesPrecompile,
+		IdleSchedulerPrecompile,
 	)>,
 );
 
@@ -84,6 +92,8 @@ impl<
 		OraclePrecompile,
 		ScheduleCallPrecompile,
 		DexPrecompile,
+		IncentivesPrecompile,
+		IdleSchedulerPrecompile,
 	> PrecompileSet
 	for AllPrecompiles<
 		PrecompileCallerFilter,
@@ -93,6 +103,8 @@ impl<
 		OraclePrecompile,
 		ScheduleCallPrecompile,
 		DexPrecompile,
+		IncentivesPrecompile,
+		IdleSchedulerPrecompile,
 	> where
 	MultiCurrencyPrecompile: Precompile,
 	NFTPrecompile: Precompile,
@@ -101,6 +113,8 @@ impl<
 	ScheduleCallPrecompile: Precompile,
 	PrecompileCallerFilter: PrecompileCallerFilterT,
 	DexPrecompile: Precompile,
+	IncentivesPrecompile: Precompile,
+	IdleSchedulerPrecompile: Precompile,
 {
 	#[allow(clippy::type_complexity)]
 	fn execute(
@@ -136,6 +150,10 @@ impl<
 				Some(ScheduleCallPrecompile::execute(input, target_gas, context))
 			} else if address == H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 5) {
 				Some(DexPrecompile::execute(input, target_gas, context))
+			} else if address == H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 6) {
+				Some(IncentivesPrecompile::execute(input, target_gas, context))
+			} else if address == H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 7) {
+				Some(IdleSchedulerPrecompile::execute(input, target_gas, context))
 			} else {
 				None
 			};
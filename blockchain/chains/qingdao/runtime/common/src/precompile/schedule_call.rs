@@ -30,14 +30,14 @@ use frame_support::{
 		Currency, IsType, OriginTrait,
 	},
 };
-use module_evm::{Context, ExitError, ExitSucceed, Precompile};
+use module_evm::{Context, ExitError, ExitSucceed, Precompile, ScheduledCallCondition};
 use module_support::{AddressMapping as AddressMappingT, CurrencyIdMapping as CurrencyIdMappingT, TransactionPayment};
 use primitives::{Balance, BlockNumber};
 use sp_core::H160;
 use sp_runtime::RuntimeDebug;
 use sp_std::{fmt::Debug, marker::PhantomData, prelude::*, result};
 
-use super::input::{Input, InputT, Output};
+use super::input::{Input, InputT, Output, PER_PARAM_BYTES};
 use codec::{Decode, Encode};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use pallet_scheduler::TaskAddress;
@@ -46,6 +46,9 @@ parameter_types! {
 	pub storage EvmSchedulerNextID: u32 = 0u32;
 }
 
+/// The maximum runs of a recurring or conditional scheduled call.
+pub const MAX_SCHEDULED_CALL_RUNS: u32 = 1_000;
+
 #[derive(RuntimeDebug, PartialEq, Encode, Decode)]
 pub struct TaskInfo {
 	pub prefix: Vec<u8>,
@@ -63,6 +66,13 @@ pub struct TaskInfo {
 /// Actions:
 /// - ScheduleCall. Rest `input` bytes: `from`, `target`, `value`, `gas_limit`, `storage_limit`,
 ///   `min_delay`, `input_len`, `input_data`.
+/// - ScheduleRecurringCall. Rest `input` bytes: `from`, `target`, `value`, `gas_limit`,
+///   `storage_limit`, `min_delay`, `period`, `count`, `input_data`.
+/// - ScheduleConditionalCall. Rest `input` bytes: `from`, `target`, `value`, `gas_limit`,
+///   `storage_limit`, `min_delay`, `period`, `count`, `condition_target`, `condition_gas_limit`,
+///   `input_data`, `condition_data`.
+/// - CancelCall. Rest `input` bytes: `from`, `task_id`.
+/// - RescheduleCall. Rest `input` bytes: `from`, `min_delay`, `task_id`.
 pub struct ScheduleCallPrecompile<
 	AccountId,
 	AddressMapping,
@@ -94,6 +104,21 @@ pub enum Action {
 	Schedule = "scheduleCall(address,address,uint256,uint256,uint256,bytes)",
 	Cancel = "cancelCall(address,bytes)",
 	Reschedule = "rescheduleCall(address,uint256,bytes)",
+	ScheduleRecurring = "scheduleRecurringCall(address,address,uint256,uint256,uint256,uint256,uint256,uint256,bytes)",
+	ScheduleConditional = "scheduleConditionalCall(address,address,uint256,uint256,uint256,uint256,uint256,uint256,address,uint256,bytes,bytes)",
+}
+
+/// Read the solidity abi encoded `bytes` whose offset is at param `index`.
+pub(crate) fn dynamic_bytes_at<I: InputT<Error = ExitError>>(input: &I, index: usize) -> result::Result<Vec<u8>, ExitError> {
+	let offset = input.u32_at(index)? as usize;
+	ensure!(
+		offset % PER_PARAM_BYTES == 0,
+		ExitError::Other("invalid bytes offset".into())
+	);
+	// the offset is counted from the first param, and the bytes length is stored there
+	let len_index = offset / PER_PARAM_BYTES + 1;
+	let len = input.u32_at(len_index)?;
+	input.bytes_at(len_index + 1, len as usize)
 }
 
 type PalletBalanceOf<T> =
@@ -138,7 +163,7 @@ impl<
 	fn execute(
 		input: &[u8],
 		_target_gas: Option<u64>,
-		_context: &Context,
+		context: &Context,
 	) -> result::Result<PrecompileOutput, ExitError> {
 		let input = Input::<Action, AccountId, AddressMapping, CurrencyIdMapping>::new(input);
 
@@ -249,13 +274,18 @@ impl<
 					.map_err(|_| ExitError::Other("Decode task_id failed".into()))?;
 				ensure!(task_info.sender == from, ExitError::Other("NoPermission".into()));
 
-				Scheduler::cancel_named(task_id).map_err(|_| ExitError::Other("Cancel schedule failed".into()))?;
+				Scheduler::cancel_named(task_id.clone())
+					.map_err(|_| ExitError::Other("Cancel schedule failed".into()))?;
+
+				// recurring and conditional calls reserved `fee` for each of the remaining runs
+				let _remaining_runs = module_evm::ScheduledCallRemainingRuns::<Runtime>::take(&task_id).unwrap_or(1);
 
 				#[cfg(not(feature = "with-ethereum-compatibility"))]
 				{
 					// unreserve the transaction fee for gas_limit
 					let from_account = AddressMapping::get_account_id(&from);
-					ChargeTransactionPayment::unreserve_fee(&from_account, task_info.fee.into());
+					let fee = task_info.fee.saturating_mul(_remaining_runs.into());
+					ChargeTransactionPayment::unreserve_fee(&from_account, fee.into());
 				}
 
 				Ok(PrecompileOutput {
@@ -296,6 +326,126 @@ impl<
 					logs: Default::default(),
 				})
 			}
+			Action::ScheduleRecurring | Action::ScheduleConditional => {
+				let from = input.evm_address_at(1)?;
+				let target = input.evm_address_at(2)?;
+
+				let value = input.balance_at(3)?;
+				let gas_limit = input.u64_at(4)?;
+				let storage_limit = input.u32_at(5)?;
+				let min_delay = input.u32_at(6)?;
+				let period = input.u32_at(7)?;
+				let count = input.u32_at(8)?;
+
+				let (prefix, input_data, condition) = if action == Action::ScheduleRecurring {
+					(b"ScheduleRecurringCall".to_vec(), dynamic_bytes_at(&input, 9)?, None)
+				} else {
+					let condition_target = input.evm_address_at(9)?;
+					let condition_gas_limit = input.u64_at(10)?;
+					let input_data = dynamic_bytes_at(&input, 11)?;
+					let condition_data = dynamic_bytes_at(&input, 12)?;
+					(
+						b"ScheduleConditionalCall".to_vec(),
+						input_data,
+						Some(ScheduledCallCondition {
+							target: condition_target,
+							input: condition_data,
+							gas_limit: condition_gas_limit,
+						}),
+					)
+				};
+
+				log::debug!(
+					target: "evm",
+					"schedule recurring call: from: {:?}, target: {:?}, value: {:?}, gas_limit: {:?}, storage_limit: {:?}, min_delay: {:?}, period: {:?}, count: {:?}, input_data: {:?}, condition: {:?}",
+					from,
+					target,
+					value,
+					gas_limit,
+					storage_limit,
+					min_delay,
+					period,
+					count,
+					input_data,
+					condition,
+				);
+
+				ensure!(
+					count > 0 && count <= MAX_SCHEDULED_CALL_RUNS,
+					ExitError::Other("Invalid count".into())
+				);
+				ensure!(count == 1 || period > 0, ExitError::Other("Invalid period".into()));
+
+				let mut _fee: Balance = Default::default();
+				#[cfg(not(feature = "with-ethereum-compatibility"))]
+				{
+					// reserve the transaction fee of every run up front, the condition gas is paid
+					// by the scheduled call as well
+					use sp_runtime::traits::Convert;
+					let from_account = AddressMapping::get_account_id(&from);
+					let run_gas_limit =
+						gas_limit.saturating_add(condition.as_ref().map_or(0, |condition| condition.gas_limit));
+					let weight = <Runtime as module_evm::Config>::GasToWeight::convert(run_gas_limit);
+					_fee = ChargeTransactionPayment::reserve_fees(&from_account, weight, count)
+						.map_err(|e| {
+							let err_msg: &str = e.into();
+							ExitError::Other(err_msg.into())
+						})?
+						.into();
+				}
+
+				let current_id = EvmSchedulerNextID::get();
+				let next_id = current_id
+					.checked_add(1)
+					.ok_or_else(|| ExitError::Other("Scheduler next id overflow".into()))?;
+				EvmSchedulerNextID::set(&next_id);
+
+				let task_id = TaskInfo {
+					prefix,
+					id: current_id,
+					sender: from,
+					fee: _fee,
+				}
+				.encode();
+
+				log::debug!(
+					target: "evm",
+					"schedule recurring call: task_id: {:?}",
+					task_id,
+				);
+
+				let call = module_evm::Call::<Runtime>::scheduled_recurring_call(
+					task_id.clone(),
+					context.address,
+					from,
+					target,
+					input_data,
+					value.into(),
+					gas_limit,
+					storage_limit,
+					condition,
+				)
+				.into();
+
+				Scheduler::schedule_named(
+					task_id.clone(),
+					DispatchTime::After(min_delay),
+					Some((period, count)),
+					0,
+					Origin::root().caller().clone(),
+					call,
+				)
+				.map_err(|_| ExitError::Other("Schedule failed".into()))?;
+
+				module_evm::ScheduledCallRemainingRuns::<Runtime>::insert(&task_id, count);
+
+				Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					cost: 0,
+					output: Output::default().encode_bytes(&task_id),
+					logs: Default::default(),
+				})
+			}
 		}
 	}
 }
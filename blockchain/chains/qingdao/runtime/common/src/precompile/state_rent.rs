@@ -41,6 +41,9 @@ use primitives::Balance;
 /// - QueryDeveloperDeposit.
 /// - QueryDeploymentFee.
 /// - TransferMaintainer. Rest `input` bytes: `from`, `contract`, `new_maintainer`.
+/// - QueryPendingUpgrade. Rest `input` bytes: `contract`.
+/// - QueryStorageUsage. Rest `input` bytes: `contract`.
+/// - QueryStorageDeposit. Rest `input` bytes: `contract`.
 pub struct StateRentPrecompile<AccountId, AddressMapping, CurrencyIdMapping, EVM>(
 	PhantomData<(AccountId, AddressMapping, CurrencyIdMapping, EVM)>,
 );
@@ -55,6 +58,9 @@ pub enum Action {
 	QueryDeveloperDeposit = "developerDeposit()",
 	QueryDeploymentFee = "deploymentFee()",
 	TransferMaintainer = "transferMaintainer(address,address,address)",
+	QueryPendingUpgrade = "pendingUpgradeOf(address)",
+	QueryStorageUsage = "storageUsage(address)",
+	QueryStorageDeposit = "storageDeposit(address)",
 }
 
 impl<AccountId, AddressMapping, CurrencyIdMapping, EVM> Precompile
@@ -145,6 +151,45 @@ where
 					logs: Default::default(),
 				})
 			}
+			Action::QueryPendingUpgrade => {
+				let contract = input.evm_address_at(1)?;
+
+				// zero code hash and block number if there is no pending upgrade
+				let (code_hash, apply_after) = EVM::query_pending_upgrade(contract).unwrap_or_default();
+
+				Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					cost: 0,
+					output: Output::default().encode_h256_u32_tuple(&code_hash, apply_after),
+					logs: Default::default(),
+				})
+			}
+			Action::QueryStorageUsage => {
+				let contract = input.evm_address_at(1)?;
+
+				let usage =
+					EVM::query_storage_usage(contract).map_err(|e| ExitError::Other(Cow::Borrowed(e.into())))?;
+
+				Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					cost: 0,
+					output: Output::default().encode_u32(usage),
+					logs: Default::default(),
+				})
+			}
+			Action::QueryStorageDeposit => {
+				let contract = input.evm_address_at(1)?;
+
+				let deposit =
+					EVM::query_storage_deposit(contract).map_err(|e| ExitError::Other(Cow::Borrowed(e.into())))?;
+
+				Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					cost: 0,
+					output: Output::default().encode_u128(deposit),
+					logs: Default::default(),
+				})
+			}
 		}
 	}
 }
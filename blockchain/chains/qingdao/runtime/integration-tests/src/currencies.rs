@@ -0,0 +1,133 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Transfers of native, multi-currency and ERC20 tokens across the EVM and
+//! Substrate accounts.
+
+use crate::setup::*;
+
+#[test]
+fn transfer_to_evm_address_works() {
+	ExtBuilder::default()
+		.balances(vec![
+			(alice(), SEE, 1_000 * dollar(SEE)),
+			(alice(), SETR, 1_000 * dollar(SETR)),
+		])
+		.build()
+		.execute_with(|| {
+			let evm = EvmAddress::repeat_byte(0x11);
+			let evm_account = EvmAddressMapping::<Runtime>::get_account_id(&evm);
+
+			assert_ok!(Currencies::transfer(
+				Origin::signed(alice()),
+				MultiAddress::Address20(evm.into()),
+				SETR,
+				100 * dollar(SETR)
+			));
+			assert_ok!(Currencies::transfer(
+				Origin::signed(alice()),
+				MultiAddress::Address20(evm.into()),
+				SEE,
+				100 * dollar(SEE)
+			));
+
+			assert_eq!(Currencies::free_balance(SETR, &evm_account), 100 * dollar(SETR));
+			assert_eq!(Currencies::free_balance(SEE, &evm_account), 100 * dollar(SEE));
+			assert_eq!(Currencies::free_balance(SETR, &alice()), 900 * dollar(SETR));
+			// the account of an unclaimed EVM address embeds the address.
+			assert_eq!(EvmAddressMapping::<Runtime>::get_evm_address(&evm_account), Some(evm));
+
+			// the EVM account spends the tokens as any other account.
+			assert_ok!(Currencies::transfer(
+				Origin::signed(evm_account.clone()),
+				MultiAddress::Id(bob()),
+				SETR,
+				40 * dollar(SETR)
+			));
+			assert_eq!(Currencies::free_balance(SETR, &bob()), 40 * dollar(SETR));
+			assert_eq!(Currencies::free_balance(SETR, &evm_account), 60 * dollar(SETR));
+		});
+}
+
+#[test]
+fn transfer_erc20_works() {
+	ExtBuilder::default()
+		.balances(vec![
+			(alice(), SEE, 1_000_000 * dollar(SEE)),
+			(bob(), SEE, 1_000 * dollar(SEE)),
+		])
+		.build()
+		.execute_with(|| {
+			let token = deploy_published_contract(alice(), TOKEN_MOCK);
+			let erc20 = CurrencyId::Erc20(token);
+
+			<EVM as EVMTrait<AccountId>>::set_origin(alice());
+			assert_ok!(Currencies::transfer(
+				Origin::signed(alice()),
+				MultiAddress::Id(bob()),
+				erc20,
+				1_000
+			));
+			assert_eq!(Currencies::free_balance(erc20, &alice()), TOKEN_MOCK_SUPPLY - 1_000);
+			assert_eq!(Currencies::free_balance(erc20, &bob()), 1_000);
+			assert_eq!(Currencies::total_issuance(erc20), TOKEN_MOCK_SUPPLY);
+
+			// ERC20 tokens sent to an EVM address are held by the address itself.
+			let evm = EvmAddress::repeat_byte(0x22);
+			<EVM as EVMTrait<AccountId>>::set_origin(bob());
+			assert_ok!(Currencies::transfer(
+				Origin::signed(bob()),
+				MultiAddress::Address20(evm.into()),
+				erc20,
+				300
+			));
+			assert_eq!(Currencies::free_balance(erc20, &bob()), 700);
+			assert_eq!(
+				Currencies::free_balance(erc20, &EvmAddressMapping::<Runtime>::get_account_id(&evm)),
+				300
+			);
+
+			assert_eq!(
+				Currencies::ensure_can_withdraw(erc20, &bob(), 701),
+				Err(module_currencies::Error::<Runtime>::BalanceTooLow.into())
+			);
+		});
+}
+
+#[test]
+fn transfer_erc20_requires_evm_origin() {
+	ExtBuilder::default()
+		.balances(vec![(alice(), SEE, 1_000_000 * dollar(SEE))])
+		.build()
+		.execute_with(|| {
+			let token = deploy_published_contract(alice(), TOKEN_MOCK);
+
+			// the origin is set by the `SetEvmOrigin` signed extension of extrinsics.
+			assert_noop!(
+				Currencies::transfer(
+					Origin::signed(alice()),
+					MultiAddress::Id(bob()),
+					CurrencyId::Erc20(token),
+					1_000
+				),
+				module_currencies::Error::<Runtime>::RealOriginNotFound
+			);
+		});
+}
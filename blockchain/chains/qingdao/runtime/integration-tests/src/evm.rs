@@ -0,0 +1,223 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Deployment of the Solidity contracts and mapping of ERC20 tokens to currencies.
+
+use crate::setup::*;
+use module_evm_manager::EvmCurrencyIdMapping;
+use module_support::CurrencyIdMapping;
+use orml_utilities::with_transaction_result;
+use sp_runtime::DispatchResult;
+
+#[test]
+fn deploy_token_mock_works() {
+	ExtBuilder::default()
+		.balances(vec![(alice(), SEE, 1_000_000 * dollar(SEE))])
+		.build()
+		.execute_with(|| {
+			let token = deploy_contract(alice(), TOKEN_MOCK);
+
+			let contract_info = EVM::accounts(token).unwrap().contract_info.unwrap();
+			assert_eq!(contract_info.maintainer, evm_address(&alice()));
+
+			// the constructor mints the supply to the deployer.
+			assert_eq!(Currencies::total_issuance(CurrencyId::Erc20(token)), TOKEN_MOCK_SUPPLY);
+			assert_eq!(
+				Currencies::free_balance(CurrencyId::Erc20(token), &alice()),
+				TOKEN_MOCK_SUPPLY
+			);
+
+			// the storage deposit of the contract is reserved from the deployer.
+			assert!(Balances::reserved_balance(alice()) > 0);
+		});
+}
+
+#[test]
+fn deploy_libraries_works() {
+	ExtBuilder::default()
+		.balances(vec![(alice(), SEE, 1_000_000 * dollar(SEE))])
+		.build()
+		.execute_with(|| {
+			let address = deploy_contract(alice(), ADDRESS_LIBRARY);
+			let counters = deploy_contract(alice(), COUNTERS_LIBRARY);
+
+			assert_ne!(address, counters);
+			assert!(EVM::accounts(address).unwrap().contract_info.is_some());
+			assert!(EVM::accounts(counters).unwrap().contract_info.is_some());
+		});
+}
+
+#[test]
+fn deploy_network_contract_works() {
+	ExtBuilder::default()
+		.balances(vec![(network_contract_account(), SEE, 1_000_000 * dollar(SEE))])
+		.build()
+		.execute_with(|| {
+			let token = deploy_network_contract(TOKEN_MOCK);
+			let library = deploy_network_contract(ADDRESS_LIBRARY);
+
+			assert!(runtime_common::is_system_contract(token));
+			assert!(runtime_common::is_system_contract(library));
+			assert_eq!(library.to_low_u64_be(), token.to_low_u64_be() + 1);
+			assert_eq!(
+				Currencies::free_balance(CurrencyId::Erc20(token), &network_contract_account()),
+				TOKEN_MOCK_SUPPLY
+			);
+		});
+}
+
+#[test]
+fn set_erc20_mapping_works() {
+	ExtBuilder::default()
+		.balances(vec![(alice(), SEE, 1_000_000 * dollar(SEE))])
+		.build()
+		.execute_with(|| {
+			let token = deploy_published_contract(alice(), TOKEN_MOCK);
+			let currency_id = CurrencyId::Erc20(token);
+
+			assert_eq!(EvmCurrencyIdMapping::<Runtime>::name(currency_id), None);
+			assert_ok!(with_transaction_result(|| -> DispatchResult {
+				EvmCurrencyIdMapping::<Runtime>::set_erc20_mapping(token)
+			}));
+			// mapping the same contract again is a no-op.
+			assert_ok!(with_transaction_result(|| -> DispatchResult {
+				EvmCurrencyIdMapping::<Runtime>::set_erc20_mapping(token)
+			}));
+
+			assert_eq!(
+				EvmCurrencyIdMapping::<Runtime>::name(currency_id),
+				Some(b"Mock Token".to_vec())
+			);
+			assert_eq!(
+				EvmCurrencyIdMapping::<Runtime>::symbol(currency_id),
+				Some(b"MOCK".to_vec())
+			);
+			assert!(EvmCurrencyIdMapping::<Runtime>::decimals(currency_id).is_some());
+			assert_eq!(
+				EvmCurrencyIdMapping::<Runtime>::encode_evm_address(currency_id),
+				Some(token)
+			);
+		});
+}
+
+#[test]
+fn set_erc20_mapping_fails_for_non_erc20_contracts() {
+	ExtBuilder::default()
+		.balances(vec![(alice(), SEE, 1_000_000 * dollar(SEE))])
+		.build()
+		.execute_with(|| {
+			let library = deploy_published_contract(alice(), ADDRESS_LIBRARY);
+
+			assert!(with_transaction_result(|| -> DispatchResult {
+				EvmCurrencyIdMapping::<Runtime>::set_erc20_mapping(library)
+			})
+			.is_err());
+			assert_eq!(EvmCurrencyIdMapping::<Runtime>::name(CurrencyId::Erc20(library)), None);
+		});
+}
+
+#[test]
+fn call_erc20_from_substrate_works() {
+	ExtBuilder::default()
+		.balances(vec![
+			(alice(), SEE, 1_000_000 * dollar(SEE)),
+			(bob(), SEE, 1_000 * dollar(SEE)),
+		])
+		.build()
+		.execute_with(|| {
+			let token = deploy_published_contract(alice(), TOKEN_MOCK);
+
+			assert_ok!(EVM::call(
+				Origin::signed(alice()),
+				token,
+				erc20_transfer_input(evm_address(&bob()), 1_000),
+				0,
+				1_000_000,
+				1_000,
+				vec![]
+			));
+			assert_eq!(Currencies::free_balance(CurrencyId::Erc20(token), &bob()), 1_000);
+			assert_eq!(
+				Currencies::free_balance(CurrencyId::Erc20(token), &alice()),
+				TOKEN_MOCK_SUPPLY - 1_000
+			);
+
+			assert_ok!(EVM::call(
+				Origin::signed(bob()),
+				token,
+				erc20_transfer_input(evm_address(&alice()), 400),
+				0,
+				1_000_000,
+				1_000,
+				vec![]
+			));
+			assert_eq!(Currencies::free_balance(CurrencyId::Erc20(token), &bob()), 600);
+		});
+}
+
+#[cfg(not(feature = "with-ethereum-compatibility"))]
+#[test]
+fn only_maintainer_calls_contracts_before_deployment() {
+	ExtBuilder::default()
+		.balances(vec![
+			(alice(), SEE, 1_000_000 * dollar(SEE)),
+			(bob(), SEE, 1_000 * dollar(SEE)),
+		])
+		.build()
+		.execute_with(|| {
+			let token = deploy_contract(alice(), TOKEN_MOCK);
+			assert!(!EVM::accounts(token).unwrap().contract_info.unwrap().deployed);
+
+			assert_noop!(
+				EVM::call(
+					Origin::signed(bob()),
+					token,
+					erc20_transfer_input(evm_address(&alice()), 1),
+					0,
+					1_000_000,
+					1_000,
+					vec![]
+				),
+				module_evm::Error::<Runtime>::NoPermission
+			);
+			assert_ok!(EVM::call(
+				Origin::signed(alice()),
+				token,
+				erc20_transfer_input(evm_address(&bob()), 1_000),
+				0,
+				1_000_000,
+				1_000,
+				vec![]
+			));
+
+			assert_ok!(EVM::deploy_free(Origin::root(), token));
+			assert!(EVM::accounts(token).unwrap().contract_info.unwrap().deployed);
+			assert_ok!(EVM::call(
+				Origin::signed(bob()),
+				token,
+				erc20_transfer_input(evm_address(&alice()), 400),
+				0,
+				1_000_000,
+				1_000,
+				vec![]
+			));
+			assert_eq!(Currencies::free_balance(CurrencyId::Erc20(token), &bob()), 600);
+		});
+}
@@ -0,0 +1,139 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Payment of transaction fees with non-native tokens, swapped to the native
+//! token through the stable swap pools.
+
+use crate::setup::*;
+use frame_support::weights::GetDispatchInfo;
+use module_transaction_payment::ChargeTransactionPayment;
+use setheum_runtime::{NativeTokenExistentialDeposit, Rate, StableSwap, TransactionPayment};
+use sp_runtime::{
+	traits::SignedExtension,
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+	FixedPointNumber,
+};
+
+const LEN: usize = 100;
+
+fn transfer_call() -> Call {
+	Call::Currencies(module_currencies::Call::transfer(
+		MultiAddress::Id(alice()),
+		SETR,
+		10 * dollar(SETR),
+	))
+}
+
+fn create_setr_see_pool() {
+	assert_ok!(StableSwap::create_pool(
+		Origin::root(),
+		vec![SETR, SEE],
+		vec![1, 1],
+		Rate::saturating_from_rational(1, 1000),
+		1_000
+	));
+	assert_ok!(StableSwap::add_liquidity(
+		Origin::signed(alice()),
+		0,
+		vec![100_000 * dollar(SETR), 100_000 * dollar(SEE)],
+		0
+	));
+}
+
+#[test]
+fn charge_fee_in_setr_works() {
+	ExtBuilder::default()
+		.balances(vec![
+			(alice(), SEE, 1_000_000 * dollar(SEE)),
+			(alice(), SETR, 1_000_000 * dollar(SETR)),
+			(bob(), SETR, 1_000 * dollar(SETR)),
+		])
+		.build()
+		.execute_with(|| {
+			create_setr_see_pool();
+			let pool_setr = Currencies::free_balance(SETR, &StableSwap::account_id());
+			let pool_see = Currencies::free_balance(SEE, &StableSwap::account_id());
+
+			let call = transfer_call();
+			let info = call.get_dispatch_info();
+			let fee = TransactionPayment::compute_fee_details(LEN as u32, &info, 0).final_fee();
+			assert_eq!(Balances::free_balance(bob()), 0);
+
+			// the fee and the existential deposit are swapped from SETR with the
+			// default fee swap path.
+			assert_ok!(ChargeTransactionPayment::<Runtime>::from(0).pre_dispatch(&bob(), &call, &info, LEN));
+			assert_eq!(Balances::free_balance(bob()), NativeTokenExistentialDeposit::get());
+			assert!(Currencies::free_balance(SETR, &bob()) < 1_000 * dollar(SETR));
+			assert!(Currencies::free_balance(SETR, &bob()) > 990 * dollar(SETR));
+			assert_eq!(
+				Currencies::free_balance(SETR, &StableSwap::account_id()),
+				pool_setr + 1_000 * dollar(SETR) - Currencies::free_balance(SETR, &bob())
+			);
+			assert_eq!(
+				Currencies::free_balance(SEE, &StableSwap::account_id()),
+				pool_see - fee - NativeTokenExistentialDeposit::get()
+			);
+		});
+}
+
+#[test]
+fn charge_fee_fails_without_swap_pool() {
+	ExtBuilder::default()
+		.balances(vec![(bob(), SETR, 1_000 * dollar(SETR))])
+		.build()
+		.execute_with(|| {
+			let call = transfer_call();
+			let info = call.get_dispatch_info();
+
+			assert_noop!(
+				ChargeTransactionPayment::<Runtime>::from(0).validate(&bob(), &call, &info, LEN),
+				TransactionValidityError::Invalid(InvalidTransaction::Payment)
+			);
+			assert_eq!(Currencies::free_balance(SETR, &bob()), 1_000 * dollar(SETR));
+		});
+}
+
+#[test]
+fn charge_fee_with_alternative_fee_swap_path_works() {
+	ExtBuilder::default()
+		.balances(vec![
+			(alice(), SEE, 1_000_000 * dollar(SEE)),
+			(alice(), SETR, 1_000_000 * dollar(SETR)),
+			(bob(), SETR, 1_000 * dollar(SETR)),
+		])
+		.build()
+		.execute_with(|| {
+			create_setr_see_pool();
+
+			assert_noop!(
+				TransactionPayment::set_alternative_fee_swap_path(Origin::signed(bob()), Some(vec![SEE, SETR])),
+				module_transaction_payment::Error::<Runtime>::InvalidSwapPath
+			);
+			assert_ok!(TransactionPayment::set_alternative_fee_swap_path(
+				Origin::signed(bob()),
+				Some(vec![SETR, SEE])
+			));
+
+			let call = transfer_call();
+			let info = call.get_dispatch_info();
+			assert_ok!(ChargeTransactionPayment::<Runtime>::from(0).validate(&bob(), &call, &info, LEN));
+			assert_eq!(Balances::free_balance(bob()), NativeTokenExistentialDeposit::get());
+		});
+}
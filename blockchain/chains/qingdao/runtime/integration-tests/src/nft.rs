@@ -0,0 +1,179 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Queries and transfers of NFTs by system contracts through the NFT precompile.
+
+use crate::setup::*;
+use frame_support::traits::Get;
+use module_evm::{precompiles::PrecompileSet, Context, ExitError, ExitSucceed};
+use module_nft::{ClassProperty, Properties};
+use primitives::PRECOMPILE_ADDRESS_START;
+use runtime_common::precompile::nft::Action;
+use setheum_runtime::{NftPalletId, OrmlNFT, NFT};
+use sp_runtime::traits::AccountIdConversion;
+
+type Precompiles = <Runtime as module_evm::Config>::Precompiles;
+
+fn nft_precompile() -> EvmAddress {
+	EvmAddress::from_low_u64_be(PRECOMPILE_ADDRESS_START + 1)
+}
+
+fn address_param(address: EvmAddress) -> [u8; 32] {
+	let mut param = [0u8; 32];
+	param[12..].copy_from_slice(address.as_bytes());
+	param
+}
+
+fn u64_param(value: u64) -> [u8; 32] {
+	let mut param = [0u8; 32];
+	param[24..].copy_from_slice(&value.to_be_bytes());
+	param
+}
+
+fn input(action: Action, params: &[[u8; 32]]) -> Vec<u8> {
+	let mut input = Into::<u32>::into(action).to_be_bytes().to_vec();
+	params.iter().for_each(|param| input.extend_from_slice(param));
+	input
+}
+
+fn call_nft_precompile(caller: EvmAddress, input: &[u8]) -> Result<Vec<u8>, ExitError> {
+	let context = Context {
+		address: nft_precompile(),
+		caller,
+		apparent_value: Default::default(),
+	};
+	let output = Precompiles::execute(nft_precompile(), input, None, &context).expect("the NFT precompile exists")?;
+	assert_eq!(output.exit_status, ExitSucceed::Returned);
+	Ok(output.output)
+}
+
+/// Create a transferable class and mint a token of it to `to`.
+fn create_class_and_mint(to: AccountId) -> (u32, u64) {
+	let class_id = OrmlNFT::next_class_id();
+	assert_ok!(NFT::create_class(
+		Origin::signed(alice()),
+		vec![1],
+		Properties(ClassProperty::Transferable | ClassProperty::Burnable | ClassProperty::Mintable),
+		Default::default(),
+	));
+
+	// the class is owned by its own account, which pays the token deposits.
+	let class_account: AccountId = NftPalletId::get().into_sub_account(class_id);
+	assert_ok!(Balances::transfer(
+		Origin::signed(alice()),
+		MultiAddress::Id(class_account.clone()),
+		100 * dollar(SEE)
+	));
+	let token_id = OrmlNFT::next_token_id(class_id);
+	assert_ok!(NFT::mint(
+		Origin::signed(class_account),
+		MultiAddress::Id(to),
+		class_id,
+		vec![2],
+		Default::default(),
+		1
+	));
+	(class_id, token_id)
+}
+
+#[test]
+fn nft_precompile_queries_work() {
+	ExtBuilder::default()
+		.balances(vec![
+			(alice(), SEE, 1_000 * dollar(SEE)),
+			(network_contract_account(), SEE, 1_000_000 * dollar(SEE)),
+		])
+		.build()
+		.execute_with(|| {
+			let caller = deploy_network_contract(TOKEN_MOCK);
+			let (class_id, token_id) = create_class_and_mint(bob());
+			let bob_evm = evm_address(&bob());
+
+			assert_eq!(
+				call_nft_precompile(caller, &input(Action::QueryBalance, &[address_param(bob_evm)])),
+				Ok(u64_param(1).to_vec())
+			);
+			assert_eq!(
+				call_nft_precompile(
+					caller,
+					&input(Action::QueryBalance, &[address_param(evm_address(&alice()))])
+				),
+				Ok(u64_param(0).to_vec())
+			);
+			assert_eq!(
+				call_nft_precompile(
+					caller,
+					&input(Action::QueryOwner, &[u64_param(class_id.into()), u64_param(token_id)])
+				),
+				Ok(address_param(bob_evm).to_vec())
+			);
+		});
+}
+
+#[test]
+fn nft_precompile_transfer_works() {
+	ExtBuilder::default()
+		.balances(vec![
+			(alice(), SEE, 1_000 * dollar(SEE)),
+			(bob(), SEE, 1_000 * dollar(SEE)),
+			(network_contract_account(), SEE, 1_000_000 * dollar(SEE)),
+		])
+		.build()
+		.execute_with(|| {
+			let caller = deploy_network_contract(TOKEN_MOCK);
+			let (class_id, token_id) = create_class_and_mint(bob());
+
+			assert_ok!(call_nft_precompile(
+				caller,
+				&input(
+					Action::Transfer,
+					&[
+						address_param(evm_address(&bob())),
+						address_param(evm_address(&alice())),
+						u64_param(class_id.into()),
+						u64_param(token_id),
+					]
+				)
+			));
+			assert_eq!(
+				OrmlNFT::tokens(class_id, token_id).map(|token| token.owner),
+				Some(alice())
+			);
+		});
+}
+
+#[test]
+fn nft_precompile_rejects_non_system_callers() {
+	ExtBuilder::default()
+		.balances(vec![(alice(), SEE, 1_000_000 * dollar(SEE))])
+		.build()
+		.execute_with(|| {
+			let caller = deploy_published_contract(alice(), TOKEN_MOCK);
+			assert!(!runtime_common::is_system_contract(caller));
+
+			assert_eq!(
+				call_nft_precompile(
+					caller,
+					&input(Action::QueryBalance, &[address_param(evm_address(&bob()))])
+				),
+				Err(ExitError::Other("no permission".into()))
+			);
+		});
+}
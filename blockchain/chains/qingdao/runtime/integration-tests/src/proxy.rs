@@ -0,0 +1,124 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The calls each proxy type can dispatch on behalf of its delegator.
+
+use crate::setup::*;
+use frame_support::traits::InstanceFilter;
+use setheum_runtime::{Proxy, ProxyType};
+use sp_core::H256;
+
+fn balances_transfer() -> Call {
+	Call::Balances(pallet_balances::Call::transfer(
+		MultiAddress::Id(charlie()),
+		10 * dollar(SEE),
+	))
+}
+
+fn treasury_reject_proposal() -> Call {
+	Call::Treasury(pallet_treasury::Call::reject_proposal(0))
+}
+
+fn reject_announcement() -> Call {
+	Call::Proxy(pallet_proxy::Call::reject_announcement(bob(), H256::zero()))
+}
+
+fn batch(calls: Vec<Call>) -> Call {
+	Call::Utility(pallet_utility::Call::batch(calls))
+}
+
+#[test]
+fn proxy_type_filter_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert!(ProxyType::Any.filter(&balances_transfer()));
+		assert!(ProxyType::Any.filter(&treasury_reject_proposal()));
+
+		assert!(ProxyType::Governance.filter(&treasury_reject_proposal()));
+		assert!(!ProxyType::Governance.filter(&balances_transfer()));
+		assert!(!ProxyType::Governance.filter(&reject_announcement()));
+
+		assert!(ProxyType::CancelProxy.filter(&reject_announcement()));
+		assert!(!ProxyType::CancelProxy.filter(&balances_transfer()));
+		assert!(!ProxyType::CancelProxy.filter(&treasury_reject_proposal()));
+
+		assert!(!ProxyType::Auction.filter(&balances_transfer()));
+		assert!(!ProxyType::Swap.filter(&balances_transfer()));
+		assert!(!ProxyType::Loan.filter(&balances_transfer()));
+
+		// batches pass the filter, their calls are filtered when dispatched.
+		assert!(ProxyType::Governance.filter(&batch(vec![balances_transfer()])));
+	});
+}
+
+#[test]
+fn proxy_type_superset_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert!(ProxyType::Any.is_superset(&ProxyType::Governance));
+		assert!(ProxyType::Governance.is_superset(&ProxyType::Governance));
+		assert!(!ProxyType::Governance.is_superset(&ProxyType::Any));
+		assert!(!ProxyType::Governance.is_superset(&ProxyType::CancelProxy));
+	});
+}
+
+#[test]
+fn proxy_dispatches_allowed_calls_only() {
+	ExtBuilder::default()
+		.balances(vec![
+			(alice(), SEE, 1_000 * dollar(SEE)),
+			(bob(), SEE, 1_000 * dollar(SEE)),
+		])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Proxy::add_proxy(
+				Origin::signed(alice()),
+				bob(),
+				ProxyType::Governance,
+				0
+			));
+
+			// the proxy call succeeds, the filtered call is not dispatched.
+			assert_ok!(Proxy::proxy(
+				Origin::signed(bob()),
+				alice(),
+				None,
+				Box::new(balances_transfer())
+			));
+			assert!(matches!(
+				System::events().last().map(|record| &record.event),
+				Some(Event::Proxy(pallet_proxy::Event::ProxyExecuted(Err(_))))
+			));
+			assert_ok!(Proxy::proxy(
+				Origin::signed(bob()),
+				alice(),
+				None,
+				Box::new(batch(vec![balances_transfer()]))
+			));
+			assert_eq!(Balances::free_balance(charlie()), 0);
+
+			assert_ok!(Proxy::add_proxy(Origin::signed(alice()), bob(), ProxyType::Any, 0));
+			assert_ok!(Proxy::proxy(
+				Origin::signed(bob()),
+				alice(),
+				Some(ProxyType::Any),
+				Box::new(balances_transfer())
+			));
+			assert_eq!(Balances::free_balance(charlie()), 10 * dollar(SEE));
+		});
+}
@@ -0,0 +1,190 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Externalities of the full runtime and the helpers shared by the tests.
+
+pub use frame_support::{assert_noop, assert_ok};
+pub use module_evm_accounts::EvmAddressMapping;
+pub use module_support::{AddressMapping, EVM as EVMTrait};
+pub use orml_traits::MultiCurrency;
+pub use primitives::evm::EvmAddress;
+pub use setheum_runtime::{
+	dollar, AccountId, Balance, Balances, Call, Currencies, CurrencyId, Event, NetworkContractSource, Origin, Runtime,
+	System, EVM, SEE, SETR,
+};
+pub use sp_runtime::MultiAddress;
+
+use frame_support::traits::GenesisBuild;
+use serde_json::Value;
+
+pub const ALICE: [u8; 32] = [1u8; 32];
+pub const BOB: [u8; 32] = [2u8; 32];
+pub const CHARLIE: [u8; 32] = [3u8; 32];
+
+/// The Solidity artifacts the tests deploy.
+pub const TOKEN_MOCK: &str = include_str!("../../tests/solidity_test/TokenMock.json");
+pub const ADDRESS_LIBRARY: &str = include_str!("../../tests/solidity_test/Address.json");
+pub const COUNTERS_LIBRARY: &str = include_str!("../../tests/solidity_test/Counters.json");
+
+/// The supply `TokenMock` mints to its deployer.
+pub const TOKEN_MOCK_SUPPLY: Balance = 1_000_000_000;
+
+pub fn alice() -> AccountId {
+	AccountId::from(ALICE)
+}
+
+pub fn bob() -> AccountId {
+	AccountId::from(BOB)
+}
+
+pub fn charlie() -> AccountId {
+	AccountId::from(CHARLIE)
+}
+
+/// The EVM address of `who`, mapping it to its default address if it has none.
+pub fn evm_address(who: &AccountId) -> EvmAddress {
+	EvmAddressMapping::<Runtime>::get_or_create_evm_address(who)
+}
+
+/// The creation bytecode of a Solidity artifact.
+pub fn bytecode(artifact: &str) -> Vec<u8> {
+	let artifact: Value = serde_json::from_str(artifact).expect("artifacts are valid json");
+	let code = artifact["bytecode"].as_str().expect("artifacts have a bytecode");
+	hex::decode(code.trim_start_matches("0x")).expect("bytecodes are valid hex")
+}
+
+/// Create a contract from a Solidity artifact and return its address.
+pub fn deploy_contract(who: AccountId, artifact: &str) -> EvmAddress {
+	assert_ok!(EVM::create(
+		Origin::signed(who),
+		bytecode(artifact),
+		0,
+		10_000_000,
+		100_000,
+		vec![]
+	));
+	created_contract()
+}
+
+/// The address of the last created contract.
+fn created_contract() -> EvmAddress {
+	System::events()
+		.into_iter()
+		.rev()
+		.find_map(|record| match record.event {
+			Event::EVM(module_evm::Event::Created(_, address, _)) => Some(address),
+			_ => None,
+		})
+		.expect("contract is created")
+}
+
+/// Create a contract and make it callable by any account.
+pub fn deploy_published_contract(who: AccountId, artifact: &str) -> EvmAddress {
+	let address = deploy_contract(who, artifact);
+	// contracts are deployed on creation with ethereum compatibility.
+	#[cfg(not(feature = "with-ethereum-compatibility"))]
+	assert_ok!(EVM::deploy_free(Origin::root(), address));
+	address
+}
+
+/// The account paying the storage of network contracts.
+pub fn network_contract_account() -> AccountId {
+	EvmAddressMapping::<Runtime>::get_account_id(&NetworkContractSource::get())
+}
+
+/// Create a contract at the next system contract address and return the address.
+pub fn deploy_network_contract(artifact: &str) -> EvmAddress {
+	let address = EvmAddress::from_low_u64_be(EVM::network_contract_index());
+	assert_ok!(EVM::create_network_contract(
+		Origin::root(),
+		bytecode(artifact),
+		0,
+		10_000_000,
+		100_000
+	));
+	assert_eq!(created_contract(), address);
+	address
+}
+
+/// The ABI encoding of a call to an ERC20 `transfer(address,uint256)`.
+pub fn erc20_transfer_input(to: EvmAddress, amount: Balance) -> Vec<u8> {
+	let mut input = hex::decode("a9059cbb").unwrap();
+	input.extend_from_slice(&[0u8; 12]);
+	input.extend_from_slice(to.as_bytes());
+	input.extend_from_slice(&[0u8; 16]);
+	input.extend_from_slice(&amount.to_be_bytes());
+	input
+}
+
+pub struct ExtBuilder {
+	balances: Vec<(AccountId, CurrencyId, Balance)>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self { balances: vec![] }
+	}
+}
+
+impl ExtBuilder {
+	pub fn balances(mut self, balances: Vec<(AccountId, CurrencyId, Balance)>) -> Self {
+		self.balances = balances;
+		self
+	}
+
+	pub fn build(self) -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap();
+
+		let native_currency_id = SEE;
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: self
+				.balances
+				.clone()
+				.into_iter()
+				.filter(|(_, currency_id, _)| *currency_id == native_currency_id)
+				.map(|(account_id, _, initial_balance)| (account_id, initial_balance))
+				.collect::<Vec<_>>(),
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+		orml_tokens::GenesisConfig::<Runtime> {
+			balances: self
+				.balances
+				.into_iter()
+				.filter(|(_, currency_id, _)| *currency_id != native_currency_id)
+				.collect::<Vec<_>>(),
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+		// sets the first system contract address of `create_network_contract`.
+		module_evm::GenesisConfig::<Runtime>::default()
+			.assimilate_storage(&mut t)
+			.unwrap();
+
+		let mut ext = sp_io::TestExternalities::new(t);
+		// events are not deposited on the genesis block.
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
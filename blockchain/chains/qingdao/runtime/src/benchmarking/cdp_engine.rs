@@ -72,10 +72,47 @@ fn inject_liquidity(
 runtime_benchmarks! {
 	{ Runtime, cdp_engine }
 
+	on_initialize {
+		let c in 0 .. CollateralCurrencyIds::get().len() as u32;
+		let owner: AccountId = account("owner", 0, SEED);
+		let currency_ids = CollateralCurrencyIds::get();
+		let min_debit_value = MinimumDebitValue::get();
+		let debit_exchange_rate = CdpEngine::get_debit_exchange_rate(DINARID);
+		let min_debit_amount = debit_exchange_rate.reciprocal().unwrap().saturating_mul_int(min_debit_value);
+		let min_debit_amount: Amount = min_debit_amount.unique_saturated_into();
+		let collateral_value = 2 * min_debit_value;
+
+		for i in 0 .. c {
+			let currency_id = currency_ids[i as usize];
+			let collateral_amount = Price::saturating_from_rational(dollar(currency_id), dollar(STABLECOIN)).saturating_mul_int(collateral_value);
+
+			// set balance and price
+			set_balance(currency_id, &owner, collateral_amount + ExistentialDeposits::get(&currency_id));
+			feed_price(vec![(currency_id, Price::one())])?;
+
+			// set risk params with a stability fee
+			CdpEngine::set_collateral_params(
+				RawOrigin::Root.into(),
+				currency_id,
+				Change::NewValue(Some(Rate::saturating_from_rational(1, 1_000_000))),
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NewValue(min_debit_value * 100),
+			)?;
+
+			// adjust position
+			CdpEngine::adjust_position(&owner, currency_id, collateral_amount.try_into().unwrap(), min_debit_amount)?;
+		}
+	}: {
+		CdpEngine::on_initialize(1);
+	}
+
 	set_collateral_params {
 	}: _(
 		RawOrigin::Root,
 		DINARID,
+		Change::NewValue(Some(Rate::saturating_from_rational(1, 1_000_000))),
 		Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
 		Change::NewValue(Some(Rate::saturating_from_rational(20, 100))),
 		Change::NewValue(Some(Ratio::saturating_from_rational(180, 100))),
@@ -104,6 +141,7 @@ runtime_benchmarks! {
 		CdpEngine::set_collateral_params(
 			RawOrigin::Root.into(),
 			DINARID,
+			Change::NoChange,
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
 			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
@@ -117,6 +155,7 @@ runtime_benchmarks! {
 		CdpEngine::set_collateral_params(
 			RawOrigin::Root.into(),
 			DINARID,
+			Change::NoChange,
 			Change::NewValue(Some(Ratio::saturating_from_rational(1000, 100))),
 			Change::NoChange,
 			Change::NoChange,
@@ -153,6 +192,7 @@ runtime_benchmarks! {
 		CdpEngine::set_collateral_params(
 			RawOrigin::Root.into(),
 			DINARID,
+			Change::NoChange,
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
 			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
@@ -166,6 +206,7 @@ runtime_benchmarks! {
 		CdpEngine::set_collateral_params(
 			RawOrigin::Root.into(),
 			DINARID,
+			Change::NoChange,
 			Change::NewValue(Some(Ratio::saturating_from_rational(1000, 100))),
 			Change::NoChange,
 			Change::NoChange,
@@ -199,6 +240,7 @@ runtime_benchmarks! {
 		CdpEngine::set_collateral_params(
 			RawOrigin::Root.into(),
 			DINARID,
+			Change::NoChange,
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
 			Change::NewValue(Some(Rate::saturating_from_rational(10, 100))),
 			Change::NewValue(Some(Ratio::saturating_from_rational(150, 100))),
@@ -59,7 +59,7 @@ fn deploy_contract(caller: AccountId) -> Result<H160, DispatchError> {
 	let contract = hex_literal::hex!("608060405234801561001057600080fd5b5061016f806100206000396000f3fe608060405260043610610041576000357c0100000000000000000000000000000000000000000000000000000000900463ffffffff168063412a5a6d14610046575b600080fd5b61004e610050565b005b600061005a6100e2565b604051809103906000f080158015610076573d6000803e3d6000fd5b50905060008190806001815401808255809150509060018203906000526020600020016000909192909190916101000a81548173ffffffffffffffffffffffffffffffffffffffff021916908373ffffffffffffffffffffffffffffffffffffffff1602179055505050565b6040516052806100f28339019056fe6080604052348015600f57600080fd5b50603580601d6000396000f3fe6080604052600080fdfea165627a7a7230582092dc1966a8880ddf11e067f9dd56a632c11a78a4afd4a9f05924d427367958cc0029a165627a7a723058202b2cc7384e11c452cdbf39b68dada2d5e10a632cc0174a354b8b8c83237e28a40029").to_vec();
 
 	System::set_block_number(1);
-	EVM::create(Origin::signed(caller.clone()), contract, 0, 1000000000, 1000000000)
+	EVM::create(Origin::signed(caller.clone()), contract, 0, 1000000000, 1000000000, vec![])
 		.map_or_else(|e| Err(e.error), |_| Ok(()))?;
 
 	System::assert_last_event(Event::EVM(module_evm::Event::Created(
@@ -154,6 +154,41 @@ runtime_benchmarks! {
 
 		whitelist_account!(alice_account);
 	}: _(RawOrigin::Signed(alice_account_id()), contract)
+
+	propose_set_code {
+		let alice_account = alice_account_id();
+
+		set_balance(NATIVE, &alice_account, 1_000_000 * dollar(NATIVE));
+		let contract = deploy_contract(alice_account_id())?;
+		EVM::set_upgrade_delay(Origin::root(), contract, 10)?;
+
+		let code_hash = module_evm::code_hash(&hex_literal::hex!("600160005260206000f3"));
+
+		whitelist_account!(alice_account);
+	}: _(RawOrigin::Signed(alice_account_id()), contract, code_hash)
+
+	apply_set_code {
+		let alice_account = alice_account_id();
+
+		set_balance(NATIVE, &alice_account, 1_000_000 * dollar(NATIVE));
+		let contract = deploy_contract(alice_account_id())?;
+		EVM::set_upgrade_delay(Origin::root(), contract, 10)?;
+
+		let new_contract = hex_literal::hex!("600160005260206000f3").to_vec();
+		EVM::propose_set_code(Origin::signed(alice_account_id()), contract, module_evm::code_hash(&new_contract))?;
+		System::set_block_number(11);
+
+		whitelist_account!(alice_account);
+	}: _(RawOrigin::Signed(alice_account_id()), contract, new_contract)
+
+	set_upgrade_delay {
+		let alice_account = alice_account_id();
+
+		set_balance(NATIVE, &alice_account, 1_000_000 * dollar(NATIVE));
+		let contract = deploy_contract(alice_account_id())?;
+
+		whitelist_account!(alice_account);
+	}: _(RawOrigin::Signed(alice_account_id()), contract, 10)
 }
 
 #[cfg(test)]
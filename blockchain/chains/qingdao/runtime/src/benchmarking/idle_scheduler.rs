@@ -0,0 +1,91 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, AccountId, CurrencyId, Event, IdleScheduler, MaxIdleTasks, Runtime, ScheduledTasks, System, TokenSymbol,
+};
+
+use super::utils::set_balance;
+use frame_benchmarking::whitelisted_caller;
+use frame_system::RawOrigin;
+use module_swap_orders::SwapOrderTask;
+use orml_benchmarking::runtime_benchmarks;
+use sp_std::prelude::*;
+
+const SEE: CurrencyId = CurrencyId::Token(TokenSymbol::SEE);
+
+fn assert_last_event(generic_event: Event) {
+	System::assert_last_event(generic_event.into());
+}
+
+fn swap_order_task(owner: &AccountId) -> ScheduledTasks {
+	SwapOrderTask::<Runtime>::ExecuteOrder {
+		order_id: 0,
+		owner: owner.clone(),
+	}
+	.into()
+}
+
+// queue `q` tasks with the lowest priority, a signed task is inserted before
+// all of them
+fn fill_queue(q: u32) -> Result<(), &'static str> {
+	let owner: AccountId = whitelisted_caller();
+	for _ in 0..q {
+		IdleScheduler::schedule_task(RawOrigin::Root.into(), swap_order_task(&owner), 0)?;
+	}
+	Ok(())
+}
+
+runtime_benchmarks! {
+	{ Runtime, idle_scheduler }
+
+	schedule_task {
+		let q in 0 .. MaxIdleTasks::get() - 1;
+
+		let caller: AccountId = whitelisted_caller();
+		set_balance(SEE, &caller, 10 * dollar(SEE));
+		fill_queue(q)?;
+	}: _(RawOrigin::Signed(caller.clone()), swap_order_task(&caller), idle_scheduler::DEFAULT_TASK_PRIORITY)
+	verify {
+		assert_last_event(idle_scheduler::Event::TaskScheduled { task_id: q, priority: idle_scheduler::DEFAULT_TASK_PRIORITY }.into());
+	}
+
+	// cancel the last task of the queue
+	cancel_task {
+		let q in 1 .. MaxIdleTasks::get();
+
+		let caller: AccountId = whitelisted_caller();
+		set_balance(SEE, &caller, 10 * dollar(SEE));
+		fill_queue(q - 1)?;
+		IdleScheduler::schedule_task(RawOrigin::Signed(caller.clone()).into(), swap_order_task(&caller), 0)?;
+	}: _(RawOrigin::Signed(caller), q - 1)
+	verify {
+		assert_last_event(idle_scheduler::Event::TaskCancelled { task_id: q - 1, slashed: 0 }.into());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
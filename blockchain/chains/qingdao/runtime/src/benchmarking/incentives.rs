@@ -0,0 +1,104 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{dollar, AccountId, CurrencyId, Event, Incentives, Runtime, System, TokenSymbol};
+
+use super::utils::set_balance;
+use frame_benchmarking::whitelisted_caller;
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use primitives::DexShare;
+use sp_std::prelude::*;
+
+const SETUSD: CurrencyId = CurrencyId::Token(TokenSymbol::SETUSD);
+const LP: CurrencyId = CurrencyId::DexShare(DexShare::Token(TokenSymbol::SETR), DexShare::Token(TokenSymbol::SETUSD));
+const REWARD_CURRENCY_LIST: [CurrencyId; 4] = [
+	CurrencyId::Token(TokenSymbol::SEE),
+	CurrencyId::Token(TokenSymbol::SERP),
+	CurrencyId::Token(TokenSymbol::DNAR),
+	CurrencyId::Token(TokenSymbol::HELP),
+];
+
+fn assert_last_event(generic_event: Event) {
+	System::assert_last_event(generic_event.into());
+}
+
+// reward the pool in all of the reward currencies
+fn reward_pool() -> Result<(), &'static str> {
+	let updates = REWARD_CURRENCY_LIST
+		.iter()
+		.map(|currency_id| {
+			set_balance(*currency_id, &Incentives::account_id(), 1_000_000 * dollar(*currency_id));
+			(*currency_id, dollar(*currency_id))
+		})
+		.collect();
+	Incentives::update_reward_rates(RawOrigin::Root.into(), LP, updates)?;
+	Ok(())
+}
+
+runtime_benchmarks! {
+	{ Runtime, module_incentives }
+
+	update_reward_rates {
+		let c in 1 .. REWARD_CURRENCY_LIST.len() as u32;
+
+		let updates = REWARD_CURRENCY_LIST[..c as usize]
+			.iter()
+			.map(|currency_id| (*currency_id, dollar(*currency_id)))
+			.collect::<Vec<_>>();
+	}: _(RawOrigin::Root, LP, updates)
+
+	deposit_dex_share {
+		let caller: AccountId = whitelisted_caller();
+		reward_pool()?;
+		set_balance(LP, &caller, 100 * dollar(SETUSD));
+	}: _(RawOrigin::Signed(caller.clone()), LP, 100 * dollar(SETUSD))
+	verify {
+		assert_last_event(module_incentives::Event::DepositDexShare(caller, LP, 100 * dollar(SETUSD)).into());
+	}
+
+	withdraw_dex_share {
+		let caller: AccountId = whitelisted_caller();
+		reward_pool()?;
+		set_balance(LP, &caller, 100 * dollar(SETUSD));
+		Incentives::deposit_dex_share(RawOrigin::Signed(caller.clone()).into(), LP, 100 * dollar(SETUSD))?;
+		System::set_block_number(System::block_number() + 10);
+	}: _(RawOrigin::Signed(caller.clone()), LP, 100 * dollar(SETUSD))
+	verify {
+		assert_last_event(module_incentives::Event::WithdrawDexShare(caller, LP, 100 * dollar(SETUSD)).into());
+	}
+
+	claim_rewards {
+		let caller: AccountId = whitelisted_caller();
+		reward_pool()?;
+		set_balance(LP, &caller, 100 * dollar(SETUSD));
+		Incentives::deposit_dex_share(RawOrigin::Signed(caller.clone()).into(), LP, 100 * dollar(SETUSD))?;
+		System::set_block_number(System::block_number() + 10);
+	}: _(RawOrigin::Signed(caller), LP)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
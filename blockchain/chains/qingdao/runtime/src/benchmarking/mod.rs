@@ -31,12 +31,16 @@ pub mod cdp_treasury;
 pub mod currencies;
 pub mod dex;
 // pub mod dex_oracle;
-// pub mod emergency_shutdown;
+pub mod emergency_shutdown;
 // pub mod evm;
 pub mod evm_accounts;
+pub mod idle_scheduler;
+pub mod incentives;
 pub mod serp_setmint;
 pub mod serp_treasury;
 pub mod prices;
+pub mod stable_swap;
+pub mod swap_orders;
 pub mod transaction_pause;
 pub mod transaction_payment;
 pub mod vesting;
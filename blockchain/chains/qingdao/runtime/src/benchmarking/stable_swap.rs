@@ -0,0 +1,135 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, AccountId, Balance, CurrencyId, Event, Rate, Runtime, StableSwap, System, TokenSymbol, TradingPathLimit,
+};
+
+use super::utils::set_balance;
+use frame_benchmarking::{account, whitelisted_caller};
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use sp_runtime::FixedPointNumber;
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+const SETUSD: CurrencyId = CurrencyId::Token(TokenSymbol::SETUSD);
+const SETR: CurrencyId = CurrencyId::Token(TokenSymbol::SETR);
+const SERP: CurrencyId = CurrencyId::Token(TokenSymbol::SERP);
+const DNAR: CurrencyId = CurrencyId::Token(TokenSymbol::DNAR);
+// a pool of `MaxStablePoolCurrencies` currencies, the longest swap path stays in it
+const CURRENCY_LIST: [CurrencyId; 4] = [SETUSD, SETR, SERP, DNAR];
+
+fn assert_last_event(generic_event: Event) {
+	System::assert_last_event(generic_event.into());
+}
+
+fn create_pool() -> Result<(), &'static str> {
+	StableSwap::create_pool(
+		RawOrigin::Root.into(),
+		CURRENCY_LIST.to_vec(),
+		vec![1; CURRENCY_LIST.len()],
+		Rate::saturating_from_rational(1, 1000),
+		1_000,
+	)?;
+	Ok(())
+}
+
+fn inject_liquidity(maker: &AccountId, amount: Balance) -> Result<(), &'static str> {
+	let amounts = CURRENCY_LIST
+		.iter()
+		.map(|currency_id| {
+			set_balance(*currency_id, maker, amount * dollar(*currency_id));
+			amount * dollar(*currency_id)
+		})
+		.collect();
+	StableSwap::add_liquidity(RawOrigin::Signed(maker.clone()).into(), 0, amounts, 0)?;
+	Ok(())
+}
+
+runtime_benchmarks! {
+	{ Runtime, module_stable_swap }
+
+	create_pool {
+	}: _(RawOrigin::Root, CURRENCY_LIST.to_vec(), vec![1; CURRENCY_LIST.len()], Rate::saturating_from_rational(1, 1000), 1_000)
+	verify {
+		assert_last_event(module_stable_swap::Event::PoolCreated(0, CURRENCY_LIST.to_vec(), CurrencyId::StableAssetPoolToken(0)).into());
+	}
+
+	ramp_amplification {
+		create_pool()?;
+	}: _(RawOrigin::Root, 0, 2_000, 100)
+
+	// add liquidity to a pool which already has some
+	add_liquidity {
+		let first_maker: AccountId = account("first_maker", 0, SEED);
+		let maker: AccountId = whitelisted_caller();
+		create_pool()?;
+		inject_liquidity(&first_maker, 1_000_000)?;
+		let amounts = CURRENCY_LIST
+			.iter()
+			.map(|currency_id| {
+				set_balance(*currency_id, &maker, 100 * dollar(*currency_id));
+				100 * dollar(*currency_id)
+			})
+			.collect::<Vec<_>>();
+	}: _(RawOrigin::Signed(maker), 0, amounts, 0)
+
+	remove_liquidity {
+		let maker: AccountId = whitelisted_caller();
+		create_pool()?;
+		inject_liquidity(&maker, 1_000_000)?;
+	}: _(RawOrigin::Signed(maker), 0, 100 * dollar(SETUSD), vec![0; CURRENCY_LIST.len()])
+
+	swap_with_exact_supply {
+		let u in 2 .. TradingPathLimit::get();
+
+		let maker: AccountId = account("maker", 0, SEED);
+		let taker: AccountId = whitelisted_caller();
+		create_pool()?;
+		inject_liquidity(&maker, 1_000_000)?;
+		let path = CURRENCY_LIST[..u as usize].to_vec();
+		let supply_amount = 100 * dollar(path[0]);
+		set_balance(path[0], &taker, supply_amount);
+	}: swap_with_exact_supply(RawOrigin::Signed(taker), path, supply_amount, 0)
+
+	swap_with_exact_target {
+		let u in 2 .. TradingPathLimit::get();
+
+		let maker: AccountId = account("maker", 0, SEED);
+		let taker: AccountId = whitelisted_caller();
+		create_pool()?;
+		inject_liquidity(&maker, 1_000_000)?;
+		let path = CURRENCY_LIST[..u as usize].to_vec();
+		let target_amount = 10 * dollar(path[path.len() - 1]);
+		let max_supply_amount = 100 * dollar(path[0]);
+		set_balance(path[0], &taker, max_supply_amount);
+	}: swap_with_exact_target(RawOrigin::Signed(taker), path, target_amount, max_supply_amount)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
@@ -0,0 +1,135 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	dollar, AccountId, Balance, CurrencyId, Event, MaxTwapSlices, Rate, Runtime, StableSwap, SwapOrders, System,
+	TokenSymbol,
+};
+
+use super::utils::set_balance;
+use frame_benchmarking::{account, whitelisted_caller};
+use frame_support::weights::Weight;
+use frame_system::RawOrigin;
+use module_support::DispatchableTask;
+use module_swap_orders::SwapOrderTask;
+use orml_benchmarking::runtime_benchmarks;
+use sp_runtime::FixedPointNumber;
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+const SEE: CurrencyId = CurrencyId::Token(TokenSymbol::SEE);
+const SETUSD: CurrencyId = CurrencyId::Token(TokenSymbol::SETUSD);
+const SETR: CurrencyId = CurrencyId::Token(TokenSymbol::SETR);
+
+fn assert_last_event(generic_event: Event) {
+	System::assert_last_event(generic_event.into());
+}
+
+// a SETR/SETUSD pool the orders are executed on
+fn create_pool() -> Result<(), &'static str> {
+	let maker: AccountId = account("maker", 0, SEED);
+	StableSwap::create_pool(
+		RawOrigin::Root.into(),
+		vec![SETR, SETUSD],
+		vec![1, 1],
+		Rate::saturating_from_rational(1, 1000),
+		1_000,
+	)?;
+	set_balance(SETR, &maker, 1_000_000 * dollar(SETR));
+	set_balance(SETUSD, &maker, 1_000_000 * dollar(SETUSD));
+	StableSwap::add_liquidity(
+		RawOrigin::Signed(maker).into(),
+		0,
+		vec![1_000_000 * dollar(SETR), 1_000_000 * dollar(SETUSD)],
+		0,
+	)?;
+	Ok(())
+}
+
+// fund the supply of the order and the deposit of its idle task
+fn fund_caller(caller: &AccountId, supply_amount: Balance) {
+	set_balance(SETR, caller, supply_amount);
+	set_balance(SEE, caller, 10 * dollar(SEE));
+}
+
+fn place_limit_order(caller: &AccountId) -> Result<(), &'static str> {
+	fund_caller(caller, 100 * dollar(SETR));
+	SwapOrders::place_limit_order(
+		RawOrigin::Signed(caller.clone()).into(),
+		SETR,
+		SETUSD,
+		100 * dollar(SETR),
+		dollar(SETUSD),
+		System::block_number() + 100,
+	)?;
+	Ok(())
+}
+
+runtime_benchmarks! {
+	{ Runtime, module_swap_orders }
+
+	place_limit_order {
+		let caller: AccountId = whitelisted_caller();
+		fund_caller(&caller, 100 * dollar(SETR));
+	}: _(RawOrigin::Signed(caller.clone()), SETR, SETUSD, 100 * dollar(SETR), dollar(SETUSD), System::block_number() + 100)
+	verify {
+		assert_last_event(module_swap_orders::Event::OrderPlaced(0, caller, SETR, SETUSD, 100 * dollar(SETR), dollar(SETUSD)).into());
+	}
+
+	place_twap_order {
+		let caller: AccountId = whitelisted_caller();
+		let slices = MaxTwapSlices::get();
+		fund_caller(&caller, 100 * dollar(SETR));
+	}: _(RawOrigin::Signed(caller.clone()), SETR, SETUSD, 100 * dollar(SETR), dollar(SETUSD), slices, 10u32.into(), System::block_number() + 10 * slices)
+	verify {
+		assert_last_event(module_swap_orders::Event::OrderPlaced(0, caller, SETR, SETUSD, 100 * dollar(SETR), dollar(SETUSD)).into());
+	}
+
+	cancel_order {
+		let caller: AccountId = whitelisted_caller();
+		place_limit_order(&caller)?;
+	}: _(RawOrigin::Signed(caller), 0)
+	verify {
+		assert_last_event(module_swap_orders::Event::OrderCancelled(0, 100 * dollar(SETR)).into());
+	}
+
+	// the task of an order whose price is met executes and completes it
+	execute_order {
+		let caller: AccountId = whitelisted_caller();
+		create_pool()?;
+		place_limit_order(&caller)?;
+		let task = SwapOrderTask::<Runtime>::ExecuteOrder { order_id: 0, owner: caller };
+	}: {
+		let _ = task.dispatch(Weight::MAX);
+	}
+	verify {
+		assert_last_event(module_swap_orders::Event::OrderCompleted(0).into());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::benchmarking::utils::tests::new_test_ext;
+	use orml_benchmarking::impl_benchmark_test_suite;
+
+	impl_benchmark_test_suite!(new_test_ext(),);
+}
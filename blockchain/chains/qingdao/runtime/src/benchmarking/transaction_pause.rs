@@ -22,6 +22,7 @@ use crate::{Origin, Runtime, TransactionPause};
 
 use frame_system::RawOrigin;
 use orml_benchmarking::runtime_benchmarks;
+use sp_core::H160;
 
 runtime_benchmarks! {
 	{ Runtime, module_transaction_pause }
@@ -32,6 +33,20 @@ runtime_benchmarks! {
 	unpause_transaction {
 		TransactionPause::pause_transaction(Origin::root(), b"Balances".to_vec(), b"transfer".to_vec())?;
 	}: _(RawOrigin::Root, b"Balances".to_vec(), b"transfer".to_vec())
+
+	pause_evm_contract {
+	}: _(RawOrigin::Root, H160::repeat_byte(1))
+
+	unpause_evm_contract {
+		TransactionPause::pause_evm_contract(Origin::root(), H160::repeat_byte(1))?;
+	}: _(RawOrigin::Root, H160::repeat_byte(1))
+
+	pause_evm_selector {
+	}: _(RawOrigin::Root, H160::repeat_byte(1), [1u8; 4])
+
+	unpause_evm_selector {
+		TransactionPause::pause_evm_selector(Origin::root(), H160::repeat_byte(1), [1u8; 4])?;
+	}: _(RawOrigin::Root, H160::repeat_byte(1), [1u8; 4])
 }
 
 #[cfg(test)]
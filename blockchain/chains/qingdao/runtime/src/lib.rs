@@ -84,10 +84,12 @@ use orml_traits::{
 	// MultiCurrency,
 };
 use module_evm::Runner;
-use module_evm::{CallInfo, CreateInfo};
+use module_evm::{AccessListItem, CallInfo, ContractStorageInfo, CreateInfo, EvmTask};
 use module_evm_accounts::EvmAddressMapping;
 pub use module_evm_manager::EvmCurrencyIdMapping;
 use module_currencies::BasicCurrencyAdapter;
+use module_support::DispatchableTask;
+use module_swap_orders::SwapOrderTask;
 use module_transaction_payment::{Multiplier, TargetedFeeAdjustment};
 
 // re-exports
@@ -96,14 +98,14 @@ pub use pallet_staking::StakerStatus;
 
 pub use authority::AuthorityConfigImpl;
 pub use constants::{fee::*, time::*};
-use primitives::evm::EthereumTransactionMessage;
+use primitives::{define_combined_task, evm::EthereumTransactionMessage, task::TaskResult};
 pub use primitives::{
 	evm::EstimateResourcesRequest, AccountId, AccountIndex, Amount, AuctionId, AuthoritysOriginId, Balance, BlockNumber, CurrencyId,
 	DataProviderId, EraIndex, Hash, Moment, Nonce, ReserveIdentifier, Share, Signature, TokenSymbol, TradingPair, SerpStableCurrencyId,
 };
 // use module_support::Web3SettersClubAccounts;
 pub use runtime_common::{
-	BlockLength, BlockWeights, GasToWeight, OffchainSolutionWeightLimit,
+	BlockLength, BlockWeights, DexRouter, GasToWeight, OffchainSolutionWeightLimit,
 	Price, Rate, Ratio, SystemContractsFilter, ExchangeRate, TimeStampedPrice,
 	cent, dollar, microcent, millicent, nanocent, ProxyType,
 
@@ -138,6 +140,9 @@ parameter_types! {
 	pub const LoansPalletId: PalletId = PalletId(*b"set/loan");			// 5EYCAe5jKgkuYFMt7CDpD9JGyD8eLr9DKZZ9mBNibUbs5xXo
 	pub const NftPalletId: PalletId = PalletId(*b"set/sNFT");			// 5EYCAe5jKgkuYTZd9to8S5wCPjCUQnDg57tU9BDgakrywBM2
 	pub const SerpTreasuryPalletId: PalletId = PalletId(*b"set/serp");	// 5EYCAe5jKgkuYTiwwziYLaTt4ZTSEikGfWNVyZ1PUdkBg78Z
+	pub const IncentivesPalletId: PalletId = PalletId(*b"set/inct");
+	pub const LaunchpadPalletId: PalletId = PalletId(*b"set/lpad");
+	pub const StableSwapPalletId: PalletId = PalletId(*b"set/stsw");
 	pub const TreasuryPalletId: PalletId = PalletId(*b"set/trsy");		// 5EYCAe5jKgkuYVbBxj3Gqkgew54j9TmR4Q8QLuBWHCApVqWn
 }
 
@@ -148,6 +153,9 @@ pub fn get_all_module_accounts() -> Vec<AccountId> {
 		DEXPalletId::get().into_account(),
 		LoansPalletId::get().into_account(),
 		SerpTreasuryPalletId::get().into_account(),
+		IncentivesPalletId::get().into_account(),
+		LaunchpadPalletId::get().into_account(),
+		StableSwapPalletId::get().into_account(),
 		TreasuryPalletId::get().into_account(),
 		ZeroAccountId::get(),		 	// ACCOUNT 0
 	]
@@ -597,6 +605,16 @@ parameter_type_with_key! {
 	};
 }
 
+parameter_type_with_key! {
+	pub MaxSerpDownSupplyPerBlock: |currency_id: CurrencyId| -> Balance {
+		match currency_id {
+			&SETR => 1_000_000 * dollar(SETR),
+			&SEE => 100_000 * dollar(SEE),
+			_ => 0,
+		}
+	};
+}
+
 parameter_type_with_key! {
 	pub ExistentialDeposits: |currency_id: CurrencyId| -> Balance {
 		match currency_id {
@@ -623,6 +641,8 @@ parameter_type_with_key! {
 				}
 			},
 			CurrencyId::Erc20(_) => Balance::max_value(), // not handled by orml-tokens
+			CurrencyId::ForeignAsset(_) => Balance::max_value(), // unregistered foreign asset
+			CurrencyId::StableAssetPoolToken(_) => 1, // stable pool shares, issued only by their pool
 		}
 	};
 }
@@ -642,6 +662,8 @@ impl orml_tokens::Config for Runtime {
 	type ExistentialDeposits = ExistentialDeposits;
 	type OnDust = orml_tokens::TransferDust<Runtime, TreasuryAccount>;
 	type MaxLocks = MaxLocks;
+	type MaxReserves = MaxReserves;
+	type ReserveIdentifier = ReserveIdentifier;
 	type DustRemovalWhitelist = DustRemovalWhitelist;
 }
 
@@ -671,6 +693,14 @@ impl module_prices::Config for Runtime {
 // 	type WeightInfo = weights::dex_oracle::WeightInfo<Runtime>;
 // }
 
+// The asset registry stays out of the runtime until its weights are benchmarked, foreign assets
+// can not be registered until then.
+// impl module_asset_registry::Config for Runtime {
+// 	type Event = Event;
+// 	type RegisterOrigin = EnsureRootOrHalfShuraCouncil;
+// 	type WeightInfo = weights::module_asset_registry::WeightInfo<Runtime>;
+// }
+
 impl module_transaction_pause::Config for Runtime {
 	type Event = Event;
 	type UpdateOrigin = EnsureRootOrThreeFourthsShuraCouncil;
@@ -683,29 +713,28 @@ parameter_types! {
 	pub const AuctionDurationSoftCap: BlockNumber = 2 * HOURS;
 }
 
-// impl auction_manager::Config for Runtime {
-// 	type Event = Event;
-// 	type Currency = Currencies;
-// 	type Auction = Auction;
-// 	type MinimumIncrementSize = MinimumIncrementSize;
-// 	type AuctionTimeToClose = AuctionTimeToClose;
-// 	type AuctionDurationSoftCap = AuctionDurationSoftCap;
-// 	type GetSetUSDId = GetSetUSDId;
-// 	type CDPTreasury = CdpTreasury;
-// 	type DEX = Dex;
-// 	type PriceSource = module_prices::PriorityLockedPriceProvider<Runtime>;
-// 	type UnsignedPriority = runtime_common::AuctionManagerUnsignedPriority;
-// 	type EmergencyShutdown = EmergencyShutdown;
-// 	type WeightInfo = weights::module_auction_manager::WeightInfo<Runtime>;
-// }
+impl auction_manager::Config for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type Auction = Auction;
+	type MinimumIncrementSize = MinimumIncrementSize;
+	type AuctionTimeToClose = AuctionTimeToClose;
+	type AuctionDurationSoftCap = AuctionDurationSoftCap;
+	type GetSetUSDId = GetSetUSDId;
+	type CDPTreasury = CdpTreasury;
+	type PriceSource = module_prices::PriorityLockedPriceProvider<Runtime>;
+	type UnsignedPriority = runtime_common::AuctionManagerUnsignedPriority;
+	type EmergencyShutdown = EmergencyShutdown;
+	type WeightInfo = weights::module_auction_manager::WeightInfo<Runtime>;
+}
 
-// impl module_loans::Config for Runtime {
-// 	type Event = Event;
-// 	type Currency = Currencies;
-// 	type RiskManager = CdpEngine;
-// 	type CDPTreasury = CdpTreasury;
-// 	type PalletId = LoansPalletId;
-// }
+impl module_loans::Config for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type RiskManager = CdpEngine;
+	type CDPTreasury = CdpTreasury;
+	type PalletId = LoansPalletId;
+}
 
 impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
 where
@@ -784,25 +813,22 @@ parameter_types! {
 	pub MaxSwapSlippageCompareToOracle: Ratio = Ratio::saturating_from_rational(15, 100);
 }
 
-// impl cdp_engine::Config for Runtime {
-// 	type Event = Event;
-// 	type PriceSource = module_prices::PriorityLockedPriceProvider<Runtime>;
-// 	type CollateralCurrencyIds = CollateralCurrencyIds;
-// 	type DefaultLiquidationRatio = DefaultLiquidationRatio;
-// 	type DefaultDebitExchangeRate = DefaultDebitExchangeRate;
-// 	type DefaultLiquidationPenalty = DefaultLiquidationPenalty;
-// 	type MinimumDebitValue = MinimumDebitValue;
-// 	type GetSetUSDId = GetSetUSDId;
-// 	type CDPTreasury = CdpTreasury;
-// 	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
-// 	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
-// 	type UnsignedPriority = runtime_common::CdpEngineUnsignedPriority;
-// 	type EmergencyShutdown = EmergencyShutdown;
-// 	type Currency = Currencies;
-// 	type AlternativeSwapPathJointList = AlternativeSwapPathJointList;
-// 	type DEX = Dex;
-// 	type WeightInfo = weights::module_cdp_engine::WeightInfo<Runtime>;
-// }
+impl cdp_engine::Config for Runtime {
+	type Event = Event;
+	type PriceSource = module_prices::PriorityLockedPriceProvider<Runtime>;
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type DefaultLiquidationRatio = DefaultLiquidationRatio;
+	type DefaultDebitExchangeRate = DefaultDebitExchangeRate;
+	type DefaultLiquidationPenalty = DefaultLiquidationPenalty;
+	type MinimumDebitValue = MinimumDebitValue;
+	type GetSetUSDId = GetSetUSDId;
+	type CDPTreasury = CdpTreasury;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
+	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
+	type UnsignedPriority = runtime_common::CdpEngineUnsignedPriority;
+	type EmergencyShutdown = EmergencyShutdown;
+	type WeightInfo = weights::module_cdp_engine::WeightInfo<Runtime>;
+}
 
 parameter_types! {
 	pub DepositPerAuthorization: Balance = deposit(1, 64);
@@ -815,15 +841,15 @@ parameter_types! {
 // 	type WeightInfo = weights::serp_setmint::WeightInfo<Runtime>;
 // }
 
-// impl emergency_shutdown::Config for Runtime {
-// 	type Event = Event;
-// 	type CollateralCurrencyIds = CollateralCurrencyIds;
-// 	type PriceSource = Prices;
-// 	type CDPTreasury = CdpTreasury;
-// 	type AuctionManagerHandler = AuctionManager;
-// 	type ShutdownOrigin = EnsureRootOrHalfShuraCouncil;
-// 	type WeightInfo = weights::emergency_shutdown::WeightInfo<Runtime>;
-// }
+impl emergency_shutdown::Config for Runtime {
+	type Event = Event;
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type PriceSource = Prices;
+	type CDPTreasury = CdpTreasury;
+	type AuctionManagerHandler = AuctionManager;
+	type ShutdownOrigin = EnsureRootOrHalfShuraCouncil;
+	type WeightInfo = weights::emergency_shutdown::WeightInfo<Runtime>;
+}
 
 parameter_types! {
 	pub const GetExchangeFee: (u32, u32) = (3, 1000);	// 0.3%
@@ -855,6 +881,118 @@ parameter_types! {
 // 	type ListingOrigin = EnsureRootOrHalfFinancialCouncil;
 // }
 
+parameter_types! {
+	pub const MaxStablePoolCurrencies: u32 = 4;
+	pub const MaxStablePoolAmplification: u128 = 1_000_000;
+}
+
+impl module_stable_swap::Config for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
+	type MaxPoolCurrencies = MaxStablePoolCurrencies;
+	type MaxAmplification = MaxStablePoolAmplification;
+	type TradingPathLimit = TradingPathLimit;
+	type PalletId = StableSwapPalletId;
+	type CurrencyIdMapping = EvmCurrencyIdMapping<Runtime>;
+	type WeightInfo = weights::module_stable_swap::WeightInfo<Runtime>;
+}
+
+impl module_incentives::Config for Runtime {
+	type Event = Event;
+	type Currency = Tokens;
+	type RewardCurrency = Currencies;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
+	type PalletId = IncentivesPalletId;
+	type WeightInfo = weights::module_incentives::WeightInfo<Runtime>;
+}
+
+define_combined_task! {
+	#[derive(Clone, Encode, Decode, PartialEq, RuntimeDebug, scale_info::TypeInfo)]
+	pub enum ScheduledTasks {
+		SwapOrderTask(SwapOrderTask<Runtime>),
+		EvmTask(EvmTask<Runtime>),
+	}
+}
+
+parameter_types! {
+	pub MinimumWeightRemainInBlock: Weight = BlockWeights::get().max_block / 50;
+	pub const MaxTaskRetries: u32 = 5;
+	pub const TaskRetryBackoff: BlockNumber = 10;
+	pub IdleTaskDeposit: Balance = dollar(SEE);
+	pub const MaxIdleTasksPerAccount: u32 = 10;
+	pub const MaxIdleTasks: u32 = 1_000;
+}
+
+/// The idle tasks signed origins are allowed to schedule: the execution of their own swap orders.
+pub struct SignedIdleTaskFilter;
+impl Contains<(AccountId, ScheduledTasks)> for SignedIdleTaskFilter {
+	fn contains((who, task): &(AccountId, ScheduledTasks)) -> bool {
+		matches!(
+			task,
+			ScheduledTasks::SwapOrderTask(SwapOrderTask::ExecuteOrder { owner, .. }) if owner == who
+		)
+	}
+}
+
+impl idle_scheduler::Config for Runtime {
+	type Event = Event;
+	type WeightInfo = weights::idle_scheduler::WeightInfo<Runtime>;
+	type Task = ScheduledTasks;
+	type MinimumWeightRemainInBlock = MinimumWeightRemainInBlock;
+	type MaxTaskRetries = MaxTaskRetries;
+	type TaskRetryBackoff = TaskRetryBackoff;
+	type Currency = Balances;
+	type TaskDeposit = IdleTaskDeposit;
+	type MaxTasksPerAccount = MaxIdleTasksPerAccount;
+	type MaxTasks = MaxIdleTasks;
+	type SignedTaskFilter = SignedIdleTaskFilter;
+	type GovernanceOrigin = EnsureRootOrTwoThirdsTechnicalCommittee;
+}
+
+parameter_types! {
+	pub const MaxTwapSlices: u32 = 100;
+	pub const MaxSwapOrdersPerAccount: u32 = 20;
+	pub const MaxSwapOrderDuration: BlockNumber = 30 * DAYS;
+}
+
+impl module_swap_orders::Config for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type DEX = DexRouter<Dex, StableSwap>;
+	type Task = ScheduledTasks;
+	type IdleScheduler = IdleScheduler;
+	type MaxTwapSlices = MaxTwapSlices;
+	type MaxOrdersPerAccount = MaxSwapOrdersPerAccount;
+	type MaxOrderDuration = MaxSwapOrderDuration;
+	type WeightInfo = weights::module_swap_orders::WeightInfo<Runtime>;
+}
+
+// The launchpad stays out of the runtime until its weights are benchmarked.
+// parameter_types! {
+// 	pub LaunchpadProposalDeposit: Balance = 1_000 * dollar(SEE);
+// 	pub LaunchpadMinContribution: Balance = 10 * dollar(SETR);
+// 	pub const CampaignStartDelay: BlockNumber = DAYS;
+// 	pub const CampaignRetirementPeriod: BlockNumber = 30 * DAYS;
+// 	pub const ProposalRetirementPeriod: BlockNumber = 7 * DAYS;
+// 	pub const MaxContributionsSettledPerBlock: u32 = 50;
+// }
+
+// impl module_launchpad::Config for Runtime {
+// 	type Event = Event;
+// 	type MultiCurrency = Currencies;
+// 	type GetNativeCurrencyId = GetNativeCurrencyId;
+// 	type ProposalDeposit = LaunchpadProposalDeposit;
+// 	type MinContribution = LaunchpadMinContribution;
+// 	type CampaignStartDelay = CampaignStartDelay;
+// 	type CampaignRetirementPeriod = CampaignRetirementPeriod;
+// 	type ProposalRetirementPeriod = ProposalRetirementPeriod;
+// 	type MaxContributionsSettledPerBlock = MaxContributionsSettledPerBlock;
+// 	type UpdateOrigin = EnsureRootOrHalfShuraCouncil;
+// 	type PalletId = LaunchpadPalletId;
+// 	type WeightInfo = weights::module_launchpad::WeightInfo<Runtime>;
+// }
+
 // parameter_types! {
 // 	pub const MaxAirdropListSize: usize = 250;
 // }
@@ -875,51 +1013,55 @@ parameter_types! {
 	pub SetterMaximumClaimableTransferAmounts: Balance = 2_000_000 * dollar(SETR);
 	pub SetDollarMinimumClaimableTransferAmounts: Balance = 4 * dollar(SETUSD);
 	pub SetDollarMaximumClaimableTransferAmounts: Balance = 100_000 * dollar(SETUSD);
+	pub CashDropRate: Rate = Rate::saturating_from_rational(2, 100); // 2%
 }
 
-// impl serp_treasury::Config for Runtime {
-// 	type Event = Event;
-// 	type Currency = Currencies;
-// 	type StableCurrencyIds = StableCurrencyIds;
-// 	type StableCurrencyInflationPeriod = StableCurrencyInflationPeriod;
-// 	type GetStableCurrencyMinimumSupply = GetStableCurrencyMinimumSupply;
-// 	type GetNativeCurrencyId = GetNativeCurrencyId;
-// 	type GetSerpCurrencyId = GetSerpCurrencyId;
-// 	type GetDinarCurrencyId = GetDinarCurrencyId;
-// 	type GetHelpCurrencyId = GetHelpCurrencyId;
-// 	type SetterCurrencyId = SetterCurrencyId;
-// 	type GetSetUSDId = GetSetUSDId;
-// 	type CDPTreasuryAccountId = CDPTreasuryAccount;
-// 	type Dex = Dex;
-// 	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
-// 	type PriceSource = module_prices::RealTimePriceProvider<Runtime>;
-// 	type AlternativeSwapPathJointList = AlternativeSwapPathJointList;
-// 	type SetterMinimumClaimableTransferAmounts = SetterMinimumClaimableTransferAmounts;
-// 	type SetterMaximumClaimableTransferAmounts = SetterMaximumClaimableTransferAmounts;
-// 	type SetDollarMinimumClaimableTransferAmounts = SetDollarMinimumClaimableTransferAmounts;
-// 	type SetDollarMaximumClaimableTransferAmounts = SetDollarMaximumClaimableTransferAmounts;
-// 	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
-// 	type PalletId = SerpTreasuryPalletId;
-// 	type WeightInfo = ();
-// }
+impl serp_treasury::Config for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type StableCurrencyIds = StableCurrencyIds;
+	type StableCurrencyInflationPeriod = StableCurrencyInflationPeriod;
+	type GetStableCurrencyMinimumSupply = GetStableCurrencyMinimumSupply;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type GetSerpCurrencyId = GetSerpCurrencyId;
+	type GetDinarCurrencyId = GetDinarCurrencyId;
+	type GetHelpCurrencyId = GetHelpCurrencyId;
+	type SetterCurrencyId = SetterCurrencyId;
+	type GetSetUSDId = GetSetUSDId;
+	type CDPTreasuryAccountId = CDPTreasuryAccount;
+	type Dex = Dex;
+	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
+	type PriceSource = module_prices::RealTimePriceProvider<Runtime>;
+	type MarketPriceSource = AggregatedDataProvider;
+	type MaxSerpDownSupplyPerBlock = MaxSerpDownSupplyPerBlock;
+	type AlternativeSwapPathJointList = AlternativeSwapPathJointList;
+	type SetterMinimumClaimableTransferAmounts = SetterMinimumClaimableTransferAmounts;
+	type SetterMaximumClaimableTransferAmounts = SetterMaximumClaimableTransferAmounts;
+	type SetDollarMinimumClaimableTransferAmounts = SetDollarMinimumClaimableTransferAmounts;
+	type SetDollarMaximumClaimableTransferAmounts = SetDollarMaximumClaimableTransferAmounts;
+	type CashDropRate = CashDropRate;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
+	type PalletId = SerpTreasuryPalletId;
+	type WeightInfo = weights::serp_treasury::WeightInfo<Runtime>;
+}
 
 parameter_types! {
 	pub const MaxAuctionsCount: u32 = 100;
 }
 
-// impl cdp_treasury::Config for Runtime {
-// 	type Event = Event;
-// 	type Currency = Currencies;
-// 	type GetSetUSDId = GetSetUSDId;
-// 	type AuctionManagerHandler = AuctionManager;
-// 	type DEX = Dex;
-// 	type MaxAuctionsCount = MaxAuctionsCount;
-// 	type PalletId = CDPTreasuryPalletId;
-// 	type SerpTreasury = SerpTreasury;
-// 	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
-// 	type AlternativeSwapPathJointList = AlternativeSwapPathJointList;
-// 	type WeightInfo = weights::module_cdp_treasury::WeightInfo<Runtime>;
-// }
+impl cdp_treasury::Config for Runtime {
+	type Event = Event;
+	type Currency = Currencies;
+	type GetSetUSDId = GetSetUSDId;
+	type AuctionManagerHandler = AuctionManager;
+	type DEX = Dex;
+	type MaxAuctionsCount = MaxAuctionsCount;
+	type PalletId = CDPTreasuryPalletId;
+	type SerpTreasury = SerpTreasury;
+	type UpdateOrigin = EnsureRootOrHalfFinancialCouncil;
+	type AlternativeSwapPathJointList = AlternativeSwapPathJointList;
+	type WeightInfo = weights::module_cdp_treasury::WeightInfo<Runtime>;
+}
 
 parameter_types! {
 	// Sort by fee charge order
@@ -967,7 +1109,8 @@ impl module_transaction_payment::Config for Runtime {
 	type TransactionByteFee = TransactionByteFee;
 	type WeightToFee = WeightToFee;
 	type FeeMultiplierUpdate = TargetedFeeAdjustment<Self, TargetBlockFullness, AdjustmentVariable, MinimumMultiplier>;
-	type DEX = Dex;
+	// swap with the stable pools only when they give a better price than the dex
+	type DEX = DexRouter<Dex, StableSwap>;
 	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
 	type TradingPathLimit = TradingPathLimit;
 	type PriceSource = module_prices::RealTimePriceProvider<Runtime>;
@@ -985,13 +1128,13 @@ impl module_evm_accounts::Config for Runtime {
 impl module_evm_manager::Config for Runtime {
 	type Currency = Balances;
 	type EVMBridge = EVMBridge;
+	type AssetMetadataProvider = ();
 }
 
-#[cfg(feature = "with-ethereum-compatibility")]
-static ISTANBUL_CONFIG: evm::Config = evm::Config::istanbul();
-
 parameter_types! {
 	pub const ChainId: u64 = 258;
+	// Istanbul until a runtime upgrade schedules the switch to a later hard fork.
+	pub const EvmHardfork: module_evm::EvmHardfork = module_evm::EvmHardfork::Istanbul;
 	pub NetworkContractSource: H160 = H160::from_low_u64_be(0);
 }
 
@@ -1000,6 +1143,8 @@ parameter_types! {
 	pub StorageDepositPerByte: Balance = deposit(0, 1);
 	pub DeveloperDeposit: Balance = 7 * dollar(SEE);
 	pub DeploymentFee: Balance = 7 * dollar(SEE);
+	// contracts opt in to a code upgrade timelock with `set_upgrade_delay`.
+	pub const DefaultUpgradeDelay: BlockNumber = 0;
 }
 
 pub type MultiCurrencyPrecompile = runtime_common::MultiCurrencyPrecompile<
@@ -1030,8 +1175,16 @@ pub type ScheduleCallPrecompile = runtime_common::ScheduleCallPrecompile<
 	OriginCaller,
 	Runtime,
 >;
-// pub type DexPrecompile =
-// 	runtime_common::DexPrecompile<AccountId, EvmAddressMapping<Runtime>, EvmCurrencyIdMapping<Runtime>, Dex>;
+pub type DexPrecompile =
+	runtime_common::DexPrecompile<AccountId, EvmAddressMapping<Runtime>, EvmCurrencyIdMapping<Runtime>, DexRouter<Dex, StableSwap>>;
+pub type IncentivesPrecompile =
+	runtime_common::IncentivesPrecompile<AccountId, EvmAddressMapping<Runtime>, EvmCurrencyIdMapping<Runtime>, Incentives>;
+pub type IdleSchedulerPrecompile = runtime_common::IdleSchedulerPrecompile<
+	AccountId,
+	EvmAddressMapping<Runtime>,
+	EvmCurrencyIdMapping<Runtime>,
+	IdleScheduler,
+>;
 
 impl module_evm::Config for Runtime {
 	type AddressMapping = EvmAddressMapping<Runtime>;
@@ -1047,24 +1200,31 @@ impl module_evm::Config for Runtime {
 		StateRentPrecompile,
 		OraclePrecompile,
 		ScheduleCallPrecompile,
-		// DexPrecompile,
+		DexPrecompile,
+		IncentivesPrecompile,
+		IdleSchedulerPrecompile,
 	>;
 	type ChainId = ChainId;
+	type Hardfork = EvmHardfork;
 	type GasToWeight = GasToWeight;
 	type ChargeTransactionPayment = module_transaction_payment::ChargeTransactionPayment<Runtime>;
 	type NetworkContractOrigin = EnsureRootOrTwoThirdsTechnicalCommittee;
 	type NetworkContractSource = NetworkContractSource;
 	type DeveloperDeposit = DeveloperDeposit;
 	type DeploymentFee = DeploymentFee;
+	type DefaultUpgradeDelay = DefaultUpgradeDelay;
 	type TreasuryAccount = TreasuryAccount;
 	type FreeDeploymentOrigin = EnsureRootOrHalfShuraCouncil;
+	type CallFilter = TransactionPause;
 	type Runner = module_evm::runner::stack::Runner<Self>;
 	type FindAuthor = pallet_session::FindAccountFromAuthorIndex<Self, Babe>;
+	type Task = ScheduledTasks;
+	type IdleScheduler = IdleScheduler;
 	type WeightInfo = weights::module_evm::WeightInfo<Runtime>;
 
 	#[cfg(feature = "with-ethereum-compatibility")]
 	fn config() -> &'static evm::Config {
-		&ISTANBUL_CONFIG
+		EvmHardfork::get().ethereum_config()
 	}
 }
 
@@ -1494,13 +1654,13 @@ impl pallet_recovery::Config for Runtime {
 	type RecoveryDeposit = RecoveryDeposit;
 }
 
-// impl orml_auction::Config for Runtime {
-// 	type Event = Event;
-// 	type Balance = Balance;
-// 	type AuctionId = AuctionId;
-// 	type Handler = AuctionManager;
-// 	type WeightInfo = weights::orml_auction::WeightInfo<Runtime>;
-// }
+impl orml_auction::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type AuctionId = AuctionId;
+	type Handler = AuctionManager;
+	type WeightInfo = weights::orml_auction::WeightInfo<Runtime>;
+}
 
 impl pallet_randomness_collective_flip::Config for Runtime {}
 
@@ -1531,7 +1691,7 @@ construct_runtime!(
 		Proxy: pallet_proxy::{Pallet, Call, Storage, Event<T>} = 9,
 
 		// ORML Core
-		// Auction: orml_auction::{Pallet, Storage, Call, Event<T>} = 10,
+		Auction: orml_auction::{Pallet, Storage, Call, Event<T>} = 10,
 		OrmlNFT: orml_nft::{Pallet, Storage, Config<T>} = 11,
 
 		// Governance
@@ -1554,13 +1714,13 @@ construct_runtime!(
 		OperatorMembershipSetheum: pallet_membership::<Instance4>::{Pallet, Call, Storage, Event<T>, Config<T>} = 22,
 
 		// SERP
-		// AuctionManager: auction_manager::{Pallet, Storage, Call, Event<T>, ValidateUnsigned} = 23,
-		// Loans: module_loans::{Pallet, Storage, Call, Event<T>} = 24,
+		AuctionManager: auction_manager::{Pallet, Storage, Call, Event<T>, ValidateUnsigned} = 23,
+		Loans: module_loans::{Pallet, Storage, Call, Event<T>} = 24,
 		// Setmint: serp_setmint::{Pallet, Storage, Call, Event<T>} = 25,
-		// SerpTreasury: serp_treasury::{Pallet, Storage, Call, Config, Event<T>} = 26,
-		// CdpTreasury: cdp_treasury::{Pallet, Storage, Call, Config, Event<T>} = 27,
-		// CdpEngine: cdp_engine::{Pallet, Storage, Call, Event<T>, Config, ValidateUnsigned} = 28,
-		// EmergencyShutdown: emergency_shutdown::{Pallet, Storage, Call, Event<T>} = 29,
+		SerpTreasury: serp_treasury::{Pallet, Storage, Call, Config, Event<T>} = 26,
+		CdpTreasury: cdp_treasury::{Pallet, Storage, Call, Config, Event<T>} = 27,
+		CdpEngine: cdp_engine::{Pallet, Storage, Call, Event<T>, Config, ValidateUnsigned} = 28,
+		EmergencyShutdown: emergency_shutdown::{Pallet, Storage, Call, Event<T>} = 29,
 
 		// Treasury
 		Treasury: pallet_treasury::{Pallet, Call, Storage, Config, Event<T>} = 30,
@@ -1603,6 +1763,16 @@ construct_runtime!(
 		Offences: pallet_offences::{Pallet, Storage, Event} = 53,
 		ImOnline: pallet_im_online::{Pallet, Call, Storage, Event<T>, ValidateUnsigned, Config<T>} = 54,
 		AuthorityDiscovery: pallet_authority_discovery::{Pallet, Config} = 55,
+
+		// Asset registry
+		// AssetRegistry: module_asset_registry::{Pallet, Call, Storage, Event<T>} = 56,
+
+		// Stable swap
+		StableSwap: module_stable_swap::{Pallet, Call, Storage, Event<T>} = 57,
+		Incentives: module_incentives::{Pallet, Call, Storage, Event<T>} = 58,
+		IdleScheduler: idle_scheduler::{Pallet, Call, Storage, Event<T>} = 59,
+		SwapOrders: module_swap_orders::{Pallet, Call, Storage, Event<T>} = 60,
+		// Launchpad: module_launchpad::{Pallet, Call, Storage, Event<T>} = 61,
 	}
 );
 
@@ -1612,6 +1782,18 @@ impl frame_support::traits::OnRuntimeUpgrade for OnRuntimeUpgrade {
 		// no migration
 		0
 	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		// no migration
+		Ok(())
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		// no migration
+		Ok(())
+	}
 }
 
 #[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug)]
@@ -1905,6 +2087,7 @@ impl_runtime_apis! {
 			value: Balance,
 			gas_limit: u64,
 			storage_limit: u32,
+			access_list: Option<Vec<AccessListItem>>,
 			estimate: bool,
 		) -> Result<CallInfo, sp_runtime::DispatchError> {
 			let config = if estimate {
@@ -1923,6 +2106,7 @@ impl_runtime_apis! {
 				value,
 				gas_limit,
 				storage_limit,
+				access_list.unwrap_or_default(),
 				config.as_ref().unwrap_or(<Runtime as module_evm::Config>::config()),
 			)
 		}
@@ -1933,6 +2117,7 @@ impl_runtime_apis! {
 			value: Balance,
 			gas_limit: u64,
 			storage_limit: u32,
+			access_list: Option<Vec<AccessListItem>>,
 			estimate: bool,
 		) -> Result<CreateInfo, sp_runtime::DispatchError> {
 			let config = if estimate {
@@ -1949,6 +2134,7 @@ impl_runtime_apis! {
 				value,
 				gas_limit,
 				storage_limit,
+				access_list.unwrap_or_default(),
 				config.as_ref().unwrap_or(<Runtime as module_evm::Config>::config()),
 			)
 		}
@@ -1958,7 +2144,7 @@ impl_runtime_apis! {
 				.map_err(|_| sp_runtime::DispatchError::Other("Invalid parameter extrinsic, decode failed"))?;
 
 			let request = match utx.function {
-				Call::EVM(module_evm::Call::call(to, data, value, gas_limit, storage_limit)) => {
+				Call::EVM(module_evm::Call::call(to, data, value, gas_limit, storage_limit, access_list)) => {
 					Some(EstimateResourcesRequest {
 						from: None,
 						to: Some(to),
@@ -1966,9 +2152,10 @@ impl_runtime_apis! {
 						storage_limit: Some(storage_limit),
 						value: Some(value),
 						data: Some(data),
+						access_list: Some(access_list),
 					})
 				}
-				Call::EVM(module_evm::Call::create(data, value, gas_limit, storage_limit)) => {
+				Call::EVM(module_evm::Call::create(data, value, gas_limit, storage_limit, access_list)) => {
 					Some(EstimateResourcesRequest {
 						from: None,
 						to: None,
@@ -1976,6 +2163,7 @@ impl_runtime_apis! {
 						storage_limit: Some(storage_limit),
 						value: Some(value),
 						data: Some(data),
+						access_list: Some(access_list),
 					})
 				}
 				_ => None,
@@ -1983,6 +2171,32 @@ impl_runtime_apis! {
 
 			request.ok_or(sp_runtime::DispatchError::Other("Invalid parameter extrinsic, not evm Call"))
 		}
+
+		fn storage_info(contract: H160) -> Option<ContractStorageInfo<Balance>> {
+			EVM::contract_storage_info(&contract)
+		}
+
+		fn paused_contracts() -> Vec<H160> {
+			TransactionPause::paused_evm_contract_list()
+		}
+
+		fn paused_selectors(contract: H160) -> Vec<[u8; 4]> {
+			TransactionPause::paused_evm_selectors_of(&contract)
+		}
+
+		fn pending_contract_removals() -> Vec<(H160, u32)> {
+			EVM::contracts_pending_removal()
+		}
+	}
+
+	#[cfg(feature = "try-runtime")]
+	impl frame_try_runtime::TryRuntime<Block> for Runtime {
+		fn on_runtime_upgrade() -> Result<(Weight, Weight), sp_runtime::RuntimeString> {
+			// runs the `pre_upgrade` and `post_upgrade` checks of every pallet around the
+			// migrations, against the state given to `try-runtime`.
+			let weight = Executive::try_runtime_upgrade()?;
+			Ok((weight, BlockWeights::get().max_block))
+		}
 	}
 
 	#[cfg(feature = "runtime-benchmarks")]
@@ -2003,13 +2217,13 @@ impl_runtime_apis! {
 			list_benchmark!(list, extra, module_nft, NftBench::<Runtime>);
 
 			// orml_list_benchmark!(list, extra, module_dex, benchmarking::dex);
-			// orml_list_benchmark!(list, extra, auction_manager, benchmarking::auction_manager);
-			// orml_list_benchmark!(list, extra, cdp_engine, benchmarking::cdp_engine);
-			// orml_list_benchmark!(list, extra, emergency_shutdown, benchmarking::emergency_shutdown);
+			orml_list_benchmark!(list, extra, auction_manager, benchmarking::auction_manager);
+			orml_list_benchmark!(list, extra, cdp_engine, benchmarking::cdp_engine);
+			orml_list_benchmark!(list, extra, emergency_shutdown, benchmarking::emergency_shutdown);
 			// orml_list_benchmark!(list, extra, module_evm, benchmarking::evm);
 			// orml_list_benchmark!(list, extra, serp_setmint, benchmarking::serp_setmint);
-			// orml_list_benchmark!(list, extra, serp_treasury, benchmarking::serp_treasury);
-			// orml_list_benchmark!(list, extra, cdp_treasury, benchmarking::cdp_treasury);
+			orml_list_benchmark!(list, extra, serp_treasury, benchmarking::serp_treasury);
+			orml_list_benchmark!(list, extra, cdp_treasury, benchmarking::cdp_treasury);
 			orml_list_benchmark!(list, extra, module_transaction_pause, benchmarking::transaction_pause);
 			orml_list_benchmark!(list, extra, module_transaction_payment, benchmarking::transaction_payment);
 			orml_list_benchmark!(list, extra, module_prices, benchmarking::prices);
@@ -2061,13 +2275,17 @@ impl_runtime_apis! {
 
 			add_benchmark!(params, batches, module_nft, NftBench::<Runtime>);
 			// orml_add_benchmark!(params, batches, module_dex, benchmarking::dex);
-			// orml_add_benchmark!(params, batches, auction_manager, benchmarking::auction_manager);
-			// orml_add_benchmark!(params, batches, cdp_engine, benchmarking::cdp_engine);
-			// orml_add_benchmark!(params, batches, emergency_shutdown, benchmarking::emergency_shutdown);
+			orml_add_benchmark!(params, batches, auction_manager, benchmarking::auction_manager);
+			orml_add_benchmark!(params, batches, cdp_engine, benchmarking::cdp_engine);
+			orml_add_benchmark!(params, batches, emergency_shutdown, benchmarking::emergency_shutdown);
 			// orml_add_benchmark!(params, batches, module_evm, benchmarking::evm);
 			// orml_add_benchmark!(params, batches, serp_setmint, benchmarking::serp_setmint);
-			// orml_add_benchmark!(params, batches, serp_treasury, benchmarking::serp_treasury);
-			// orml_add_benchmark!(params, batches, cdp_treasury, benchmarking::cdp_treasury);
+			orml_add_benchmark!(params, batches, serp_treasury, benchmarking::serp_treasury);
+			orml_add_benchmark!(params, batches, cdp_treasury, benchmarking::cdp_treasury);
+			orml_add_benchmark!(params, batches, module_stable_swap, benchmarking::stable_swap);
+			orml_add_benchmark!(params, batches, module_incentives, benchmarking::incentives);
+			orml_add_benchmark!(params, batches, idle_scheduler, benchmarking::idle_scheduler);
+			orml_add_benchmark!(params, batches, module_swap_orders, benchmarking::swap_orders);
 			orml_add_benchmark!(params, batches, module_transaction_pause, benchmarking::transaction_pause);
 			orml_add_benchmark!(params, batches, module_transaction_payment, benchmarking::transaction_payment);
 			// orml_add_benchmark!(params, batches, dex_oracle, benchmarking::dex_oracle);
@@ -0,0 +1,49 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Weights for idle_scheduler
+//!
+//! Estimates until the benchmarks in `benchmarking/idle_scheduler.rs` are run,
+//! the pallet stays in the runtime for the contract removals of the EVM and the
+//! idle scheduler precompile.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions for idle_scheduler.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> idle_scheduler::WeightInfo for WeightInfo<T> {
+	fn schedule_task(q: u32, ) -> Weight {
+		(34_000_000 as Weight)
+			.saturating_add((120_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn cancel_task(q: u32, ) -> Weight {
+		(31_000_000 as Weight)
+			.saturating_add((110_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+}
@@ -28,12 +28,16 @@ pub mod module_cdp_treasury;
 pub mod module_currencies;
 pub mod module_dex;
 pub mod emergency_shutdown;
+pub mod idle_scheduler;
 pub mod module_evm;
 pub mod module_evm_accounts;
+pub mod module_incentives;
 pub mod serp_setmint;
 pub mod serp_treasury;
 pub mod module_nft;
 pub mod module_prices;
+pub mod module_stable_swap;
+pub mod module_swap_orders;
 pub mod module_transaction_pause;
 pub mod module_transaction_payment;
 pub mod module_vesting;
@@ -49,6 +49,19 @@ use sp_std::marker::PhantomData;
 /// Weight functions for cdp_engine.
 pub struct WeightInfo<T>(PhantomData<T>);
 impl<T: frame_system::Config> cdp_engine::WeightInfo for WeightInfo<T> {
+	// Storage: EmergencyShutdown IsShutdown (r:1 w:0)
+	// Storage: CdpEngine CollateralParams (r:1 w:0)
+	// Storage: Loans TotalPositions (r:1 w:0)
+	// Storage: CdpEngine DebitExchangeRate (r:1 w:1)
+	// Storage: Tokens Accounts (r:1 w:1)
+	fn on_initialize(c: u32, ) -> Weight {
+		(33_360_000 as Weight)
+			// Standard Error: 101_000
+			.saturating_add((23_139_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
+	}
 	fn set_collateral_params() -> Weight {
 		(57_130_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(1 as Weight))
@@ -0,0 +1,265 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Asset Registry Module
+//!
+//! ## Overview
+//!
+//! Register the assets unknown at compile time, with their name, symbol,
+//! decimals and existential deposit, so that new assets don't need a
+//! runtime upgrade. The registry allocates a `CurrencyId::ForeignAsset` id
+//! to every registered asset, the built-in tokens, ERC20s and dex shares
+//! can't be registered. A registered asset can be mirrored to the EVM at an
+//! address under `MIRRORED_TOKENS_ADDRESS_START`, outside of the range of
+//! the addresses of the built-in tokens.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{pallet_prelude::*, transactional};
+use frame_system::pallet_prelude::*;
+use primitives::{
+	currency::AssetMetadata, evm::EvmAddress, Balance, CurrencyId, ForeignAssetId, H160_PREFIX_FOREIGN_ASSET,
+	H160_PREFIX_STABLE_ASSET_POOL_TOKEN, H160_PREFIX_TOKEN, MIRRORED_NFT_ADDRESS_START, MIRRORED_TOKENS_ADDRESS_START,
+};
+use sp_runtime::traits::One;
+use sp_std::boxed::Box;
+use support::AssetMetadataProvider;
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The origin which may register and update assets.
+		type RegisterOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The asset has already been registered
+		AssetExisted,
+		/// The asset has not been registered
+		AssetNotExists,
+		/// All the foreign asset ids have been allocated
+		NoAvailableAssetId,
+		/// The mirrored address is out of the range of mirrored tokens
+		InvalidEvmAddress,
+		/// The mirrored address is already taken
+		EvmAddressExisted,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An asset has been registered. \[currency_id, metadata,
+		/// mirrored_evm_address\]
+		AssetRegistered(CurrencyId, AssetMetadata<Balance>, Option<EvmAddress>),
+		/// The metadata of an asset has been updated. \[currency_id, metadata\]
+		AssetUpdated(CurrencyId, AssetMetadata<Balance>),
+	}
+
+	/// The id of the next registered foreign asset.
+	///
+	/// NextForeignAssetId: ForeignAssetId
+	#[pallet::storage]
+	#[pallet::getter(fn next_foreign_asset_id)]
+	pub type NextForeignAssetId<T: Config> = StorageValue<_, ForeignAssetId, ValueQuery>;
+
+	/// The metadata of the registered assets.
+	///
+	/// AssetMetadatas: map CurrencyId => Option<AssetMetadata>
+	#[pallet::storage]
+	#[pallet::getter(fn asset_metadatas)]
+	pub type AssetMetadatas<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, AssetMetadata<Balance>, OptionQuery>;
+
+	/// The EVM addresses mirroring the registered assets.
+	///
+	/// MirroredEvmAddresses: map CurrencyId => Option<EvmAddress>
+	#[pallet::storage]
+	#[pallet::getter(fn mirrored_evm_addresses)]
+	pub type MirroredEvmAddresses<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, EvmAddress, OptionQuery>;
+
+	/// The registered assets mirrored at the EVM addresses.
+	///
+	/// MirroredCurrencyIds: map EvmAddress => Option<CurrencyId>
+	#[pallet::storage]
+	#[pallet::getter(fn mirrored_currency_ids)]
+	pub type MirroredCurrencyIds<T: Config> = StorageMap<_, Twox64Concat, EvmAddress, CurrencyId, OptionQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			Self::check_invariants()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::check_invariants()
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Register a new asset under the next `CurrencyId::ForeignAsset`
+		/// id, optionally mirrored to the EVM.
+		///
+		/// The dispatch origin of this call must be `RegisterOrigin`.
+		///
+		/// - `metadata`: the name, symbol, decimals and existential deposit.
+		/// - `mirrored_evm_address`: the address mirroring the asset in the
+		///   EVM, under `MIRRORED_TOKENS_ADDRESS_START`.
+		#[pallet::weight(T::WeightInfo::register_asset())]
+		#[transactional]
+		pub fn register_asset(
+			origin: OriginFor<T>,
+			metadata: Box<AssetMetadata<Balance>>,
+			mirrored_evm_address: Option<EvmAddress>,
+		) -> DispatchResult {
+			T::RegisterOrigin::ensure_origin(origin)?;
+
+			let foreign_asset_id =
+				NextForeignAssetId::<T>::try_mutate(|id| -> Result<ForeignAssetId, DispatchError> {
+					let current_id = *id;
+					*id = id.checked_add(One::one()).ok_or(Error::<T>::NoAvailableAssetId)?;
+					Ok(current_id)
+				})?;
+			let currency_id = CurrencyId::ForeignAsset(foreign_asset_id);
+			ensure!(
+				!AssetMetadatas::<T>::contains_key(currency_id),
+				Error::<T>::AssetExisted
+			);
+
+			if let Some(address) = mirrored_evm_address {
+				ensure!(Self::is_mirrored_token_address(&address), Error::<T>::InvalidEvmAddress);
+				ensure!(
+					!MirroredCurrencyIds::<T>::contains_key(address),
+					Error::<T>::EvmAddressExisted
+				);
+				MirroredEvmAddresses::<T>::insert(currency_id, address);
+				MirroredCurrencyIds::<T>::insert(address, currency_id);
+			}
+
+			AssetMetadatas::<T>::insert(currency_id, *metadata.clone());
+			Self::deposit_event(Event::AssetRegistered(currency_id, *metadata, mirrored_evm_address));
+			Ok(())
+		}
+
+		/// Update the metadata of a registered asset.
+		///
+		/// The dispatch origin of this call must be `RegisterOrigin`.
+		///
+		/// - `currency_id`: the currency id of the asset.
+		/// - `metadata`: the new name, symbol, decimals and existential deposit.
+		#[pallet::weight(T::WeightInfo::update_asset())]
+		#[transactional]
+		pub fn update_asset(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			metadata: Box<AssetMetadata<Balance>>,
+		) -> DispatchResult {
+			T::RegisterOrigin::ensure_origin(origin)?;
+
+			AssetMetadatas::<T>::try_mutate(currency_id, |maybe_metadata| -> DispatchResult {
+				let current_metadata = maybe_metadata.as_mut().ok_or(Error::<T>::AssetNotExists)?;
+				*current_metadata = *metadata.clone();
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::AssetUpdated(currency_id, *metadata));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Whether `address` is in the range of mirrored tokens, but not among
+	/// the addresses of the built-in tokens, foreign assets and stable pool
+	/// shares.
+	fn is_mirrored_token_address(address: &EvmAddress) -> bool {
+		let mirrored_tokens_end = EvmAddress::from_low_u64_be(MIRRORED_NFT_ADDRESS_START);
+		*address >= EvmAddress::from_low_u64_be(MIRRORED_TOKENS_ADDRESS_START)
+			&& *address < mirrored_tokens_end
+			&& !address.as_bytes().starts_with(&H160_PREFIX_TOKEN)
+			&& !address.as_bytes().starts_with(&H160_PREFIX_FOREIGN_ASSET)
+			&& !address.as_bytes().starts_with(&H160_PREFIX_STABLE_ASSET_POOL_TOKEN)
+	}
+
+	/// Check that every registered asset has an allocated foreign asset id,
+	/// and that every mirrored asset is registered and mirrored both ways.
+	#[cfg(feature = "try-runtime")]
+	pub fn check_invariants() -> Result<(), &'static str> {
+		let next_foreign_asset_id = NextForeignAssetId::<T>::get();
+		for currency_id in AssetMetadatas::<T>::iter_keys() {
+			ensure!(
+				matches!(currency_id, CurrencyId::ForeignAsset(id) if id < next_foreign_asset_id),
+				"Registered asset has no allocated foreign asset id"
+			);
+		}
+		for (currency_id, evm_address) in MirroredEvmAddresses::<T>::iter() {
+			ensure!(
+				AssetMetadatas::<T>::contains_key(currency_id),
+				"Mirrored asset has no metadata"
+			);
+			ensure!(
+				MirroredCurrencyIds::<T>::get(evm_address) == Some(currency_id),
+				"MirroredCurrencyIds does not map back to the mirrored asset"
+			);
+		}
+		for (evm_address, currency_id) in MirroredCurrencyIds::<T>::iter() {
+			ensure!(
+				MirroredEvmAddresses::<T>::get(currency_id) == Some(evm_address),
+				"MirroredEvmAddresses does not map back to the EVM address"
+			);
+		}
+		Ok(())
+	}
+}
+
+impl<T: Config> AssetMetadataProvider for Pallet<T> {
+	fn asset_metadata(currency_id: CurrencyId) -> Option<AssetMetadata<Balance>> {
+		Self::asset_metadatas(currency_id)
+	}
+
+	fn mirrored_evm_address(currency_id: CurrencyId) -> Option<EvmAddress> {
+		Self::mirrored_evm_addresses(currency_id)
+	}
+
+	fn mirrored_currency_id(address: EvmAddress) -> Option<CurrencyId> {
+		Self::mirrored_currency_ids(address)
+	}
+}
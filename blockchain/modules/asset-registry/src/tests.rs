@@ -0,0 +1,176 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the asset registry module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use sp_runtime::traits::BadOrigin;
+
+#[test]
+fn register_asset_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			AssetRegistryModule::register_asset(Origin::signed(BOB), Box::new(asset_metadata()), None),
+			BadOrigin
+		);
+
+		assert_ok!(AssetRegistryModule::register_asset(
+			Origin::signed(ALICE),
+			Box::new(asset_metadata()),
+			Some(mirrored_address(0x100))
+		));
+		System::assert_last_event(Event::AssetRegistryModule(crate::Event::AssetRegistered(
+			FOREIGN_ASSET_0,
+			asset_metadata(),
+			Some(mirrored_address(0x100)),
+		)));
+		assert_eq!(AssetRegistryModule::next_foreign_asset_id(), 1);
+		assert_eq!(
+			AssetRegistryModule::asset_metadata(FOREIGN_ASSET_0),
+			Some(asset_metadata())
+		);
+		assert_eq!(
+			AssetRegistryModule::mirrored_evm_address(FOREIGN_ASSET_0),
+			Some(mirrored_address(0x100))
+		);
+		assert_eq!(
+			AssetRegistryModule::mirrored_currency_id(mirrored_address(0x100)),
+			Some(FOREIGN_ASSET_0)
+		);
+	});
+}
+
+#[test]
+fn register_asset_allocates_new_ids() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(AssetRegistryModule::register_asset(
+			Origin::signed(ALICE),
+			Box::new(asset_metadata()),
+			None
+		));
+		assert_ok!(AssetRegistryModule::register_asset(
+			Origin::signed(ALICE),
+			Box::new(asset_metadata()),
+			None
+		));
+		System::assert_last_event(Event::AssetRegistryModule(crate::Event::AssetRegistered(
+			FOREIGN_ASSET_1,
+			asset_metadata(),
+			None,
+		)));
+		assert_eq!(AssetRegistryModule::next_foreign_asset_id(), 2);
+		assert_eq!(
+			AssetRegistryModule::asset_metadata(FOREIGN_ASSET_1),
+			Some(asset_metadata())
+		);
+		assert_eq!(AssetRegistryModule::mirrored_evm_address(FOREIGN_ASSET_1), None);
+
+		// the built-in tokens are never registered
+		assert_eq!(AssetRegistryModule::asset_metadata(SEE), None);
+
+		NextForeignAssetId::<Runtime>::put(ForeignAssetId::MAX);
+		assert_noop!(
+			AssetRegistryModule::register_asset(Origin::signed(ALICE), Box::new(asset_metadata()), None),
+			Error::<Runtime>::NoAvailableAssetId
+		);
+	});
+}
+
+#[test]
+fn register_asset_rejects_invalid_addresses() {
+	ExtBuilder::default().build().execute_with(|| {
+		// the addresses of the built-in tokens
+		assert_noop!(
+			AssetRegistryModule::register_asset(
+				Origin::signed(ALICE),
+				Box::new(asset_metadata()),
+				Some(mirrored_address(0xff))
+			),
+			Error::<Runtime>::InvalidEvmAddress
+		);
+		// out of the range of mirrored tokens
+		assert_noop!(
+			AssetRegistryModule::register_asset(
+				Origin::signed(ALICE),
+				Box::new(asset_metadata()),
+				Some(EvmAddress::from_low_u64_be(MIRRORED_NFT_ADDRESS_START))
+			),
+			Error::<Runtime>::InvalidEvmAddress
+		);
+
+		assert_ok!(AssetRegistryModule::register_asset(
+			Origin::signed(ALICE),
+			Box::new(asset_metadata()),
+			Some(mirrored_address(0x100))
+		));
+		assert_noop!(
+			AssetRegistryModule::register_asset(
+				Origin::signed(ALICE),
+				Box::new(asset_metadata()),
+				Some(mirrored_address(0x100))
+			),
+			Error::<Runtime>::EvmAddressExisted
+		);
+	});
+}
+
+#[test]
+fn update_asset_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let new_metadata = AssetMetadata {
+			minimal_balance: 100,
+			..asset_metadata()
+		};
+
+		assert_noop!(
+			AssetRegistryModule::update_asset(Origin::signed(ALICE), FOREIGN_ASSET_0, Box::new(new_metadata.clone())),
+			Error::<Runtime>::AssetNotExists
+		);
+		// the built-in tokens can't be overwritten
+		assert_noop!(
+			AssetRegistryModule::update_asset(Origin::signed(ALICE), SEE, Box::new(new_metadata.clone())),
+			Error::<Runtime>::AssetNotExists
+		);
+
+		assert_ok!(AssetRegistryModule::register_asset(
+			Origin::signed(ALICE),
+			Box::new(asset_metadata()),
+			None
+		));
+		assert_noop!(
+			AssetRegistryModule::update_asset(Origin::signed(BOB), FOREIGN_ASSET_0, Box::new(new_metadata.clone())),
+			BadOrigin
+		);
+		assert_ok!(AssetRegistryModule::update_asset(
+			Origin::signed(ALICE),
+			FOREIGN_ASSET_0,
+			Box::new(new_metadata.clone())
+		));
+		System::assert_last_event(Event::AssetRegistryModule(crate::Event::AssetUpdated(
+			FOREIGN_ASSET_0,
+			new_metadata.clone(),
+		)));
+		assert_eq!(AssetRegistryModule::asset_metadata(FOREIGN_ASSET_0), Some(new_metadata));
+	});
+}
@@ -0,0 +1,708 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Auction Manager Module
+//!
+//! ## Overview
+//!
+//! Auction the assets of the system for maintaining the normal operation of
+//! the business. Auction types include:
+//!   - `collateral auction`: sell collateral assets for getting stable
+//!     currency to eliminate the system's bad debit by auction
+//!
+//! A collateral auction starts in the forward stage, where bidders compete
+//! on the amount of stable currency paid for the whole lot. Once a bid
+//! reaches the target, the auction enters the reverse stage: the payment is
+//! fixed at the target and bidders compete on taking less collateral, the
+//! rest being refunded to the CDP owner. The minimum bid increment and the
+//! time to close are halved once an auction runs past
+//! `AuctionDurationSoftCap`.
+//!
+//! When an auction ends in the forward stage, or without any bid, the
+//! collateral is sold on the DEX instead if the DEX gives a better price.
+//! After emergency shutdown, the offchain worker cancels all the auctions
+//! by unsigned transactions.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+#![allow(clippy::upper_case_acronyms)]
+
+use codec::{Decode, Encode};
+use frame_support::{log, pallet_prelude::*, transactional};
+use frame_system::{
+	offchain::{SendTransactionTypes, SubmitTransaction},
+	pallet_prelude::*,
+};
+use orml_traits::{Auction, AuctionHandler, Change, MultiCurrency, OnNewBidResult};
+use primitives::{AuctionId, Balance, CurrencyId};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	offchain::{
+		storage::StorageValueRef,
+		storage_lock::{StorageLock, Time},
+		Duration,
+	},
+	traits::{CheckedDiv, Saturating, Zero},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity, ValidTransaction,
+	},
+	DispatchError, DispatchResult, FixedPointNumber, RuntimeDebug,
+};
+use sp_std::prelude::*;
+use support::{AuctionManager, CDPTreasury, CDPTreasuryExtended, EmergencyShutdown, PriceProvider, Rate, SwapLimit};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+pub const OFFCHAIN_WORKER_DATA: &[u8] = b"setheum/auction-manager/data/";
+pub const OFFCHAIN_WORKER_LOCK: &[u8] = b"setheum/auction-manager/lock/";
+pub const LOCK_DURATION: u64 = 100;
+
+/// Information of an collateral auction
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct CollateralAuctionItem<AccountId, BlockNumber> {
+	/// Refund recipient for may receive refund
+	pub refund_recipient: AccountId,
+	/// Collateral type for sale
+	pub currency_id: CurrencyId,
+	/// Initial collateral amount for sale
+	#[codec(compact)]
+	pub initial_amount: Balance,
+	/// Current collateral amount for sale
+	#[codec(compact)]
+	pub amount: Balance,
+	/// Target sales amount of this auction
+	/// if zero, collateral auction will never be reverse stage,
+	/// otherwise, target amount is the actual payment amount of active
+	/// bidder
+	#[codec(compact)]
+	pub target: Balance,
+	/// Auction start time
+	pub start_time: BlockNumber,
+}
+
+impl<AccountId, BlockNumber> CollateralAuctionItem<AccountId, BlockNumber> {
+	/// Return the collateral auction will never be reverse stage
+	fn always_forward(&self) -> bool {
+		self.target.is_zero()
+	}
+
+	/// Return whether the collateral auction is in reverse stage at
+	/// specific bid price
+	fn in_reverse_stage(&self, bid_price: Balance) -> bool {
+		!self.always_forward() && bid_price >= self.target
+	}
+
+	/// Return the actual number of stablecoins to be paid
+	fn payment_amount(&self, bid_price: Balance) -> Balance {
+		if self.always_forward() {
+			bid_price
+		} else {
+			sp_std::cmp::min(self.target, bid_price)
+		}
+	}
+
+	/// Return new collateral amount at specific last bid price and new bid
+	/// price
+	fn collateral_amount(&self, last_bid_price: Balance, new_bid_price: Balance) -> Balance {
+		if self.in_reverse_stage(new_bid_price) && new_bid_price > last_bid_price {
+			Rate::checked_from_rational(sp_std::cmp::max(last_bid_price, self.target), new_bid_price)
+				.and_then(|n| n.checked_mul_int(self.amount))
+				.unwrap_or(self.amount)
+		} else {
+			self.amount
+		}
+	}
+}
+
+#[derive(RuntimeDebug)]
+enum OffchainErr {
+	OffchainLock,
+	NotValidator,
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + SendTransactionTypes<Call<Self>> {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The minimum increment size of each bid compared to the previous
+		/// one
+		#[pallet::constant]
+		type MinimumIncrementSize: Get<Rate>;
+
+		/// The extended time for the auction to end after each successful bid
+		#[pallet::constant]
+		type AuctionTimeToClose: Get<Self::BlockNumber>;
+
+		/// When the total duration of the auction exceeds this soft cap,
+		/// the auction time to close and the minimum increment size are
+		/// halved
+		#[pallet::constant]
+		type AuctionDurationSoftCap: Get<Self::BlockNumber>;
+
+		/// The stable currency id
+		#[pallet::constant]
+		type GetSetUSDId: Get<CurrencyId>;
+
+		/// Currency to transfer assets
+		type Currency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+		/// Auction to manager the auction process
+		type Auction: Auction<Self::AccountId, Self::BlockNumber, AuctionId = AuctionId, Balance = Balance>;
+
+		/// CDP treasury to escrow assets related to auction
+		type CDPTreasury: CDPTreasuryExtended<Self::AccountId, Balance = Balance, CurrencyId = CurrencyId>;
+
+		/// The price source of currencies
+		type PriceSource: PriceProvider<CurrencyId>;
+
+		/// A configuration for base priority of unsigned transactions.
+		///
+		/// This is exposed so that it can be tuned for particular runtime, when
+		/// multiple modules send unsigned transactions.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+
+		/// Emergency shutdown.
+		type EmergencyShutdown: EmergencyShutdown;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The auction dose not exist
+		AuctionNotExists,
+		/// Feed price is invalid
+		InvalidFeedPrice,
+		/// Must after system shutdown
+		MustAfterShutdown,
+		/// Bid price is invalid
+		InvalidBidPrice,
+		/// Invalid input amount
+		InvalidAmount,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Collateral auction created. \[auction_id, collateral_type,
+		/// collateral_amount, target_bid_price\]
+		NewCollateralAuction(AuctionId, CurrencyId, Balance, Balance),
+		/// Active auction cancelled. \[auction_id\]
+		CancelAuction(AuctionId),
+		/// Collateral auction dealt. \[auction_id, collateral_type,
+		/// collateral_amount, winner, payment_amount\]
+		CollateralAuctionDealt(AuctionId, CurrencyId, Balance, T::AccountId, Balance),
+		/// Dex take collateral auction. \[auction_id, collateral_type,
+		/// collateral_amount, supply_collateral_amount, target_stable_amount\]
+		DEXTakeCollateralAuction(AuctionId, CurrencyId, Balance, Balance, Balance),
+		/// Collateral auction aborted. \[auction_id, collateral_type,
+		/// collateral_amount, target_stable_amount, refund_recipient\]
+		CollateralAuctionAborted(AuctionId, CurrencyId, Balance, Balance, T::AccountId),
+	}
+
+	/// Mapping from auction id to collateral auction info
+	///
+	/// CollateralAuctions: map AuctionId => Option<CollateralAuctionItem>
+	#[pallet::storage]
+	#[pallet::getter(fn collateral_auctions)]
+	pub type CollateralAuctions<T: Config> =
+		StorageMap<_, Twox64Concat, AuctionId, CollateralAuctionItem<T::AccountId, T::BlockNumber>, OptionQuery>;
+
+	/// Record of the total collateral amount of all active collateral
+	/// auctions under specific collateral type CollateralType -> TotalAmount
+	///
+	/// TotalCollateralInAuction: map CurrencyId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn total_collateral_in_auction)]
+	pub type TotalCollateralInAuction<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Balance, ValueQuery>;
+
+	/// Record of total target sales of all active collateral auctions
+	///
+	/// TotalTargetInAuction: Balance
+	#[pallet::storage]
+	#[pallet::getter(fn total_target_in_auction)]
+	pub type TotalTargetInAuction<T: Config> = StorageValue<_, Balance, ValueQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Start offchain worker in order to submit unsigned tx to cancel
+		/// active auction after system shutdown.
+		fn offchain_worker(now: T::BlockNumber) {
+			if T::EmergencyShutdown::is_shutdown() {
+				if let Err(e) = Self::_offchain_worker() {
+					log::info!(
+						target: "auction-manager offchain worker",
+						"cannot run offchain worker at {:?}: {:?}",
+						now,
+						e,
+					);
+				} else {
+					log::debug!(
+						target: "auction-manager offchain worker",
+						"offchain worker start at block: {:?} already done!",
+						now,
+					);
+				}
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Cancel active auction after system shutdown
+		///
+		/// The dispatch origin of this call must be _None_.
+		#[pallet::weight(T::WeightInfo::cancel_collateral_auction())]
+		#[transactional]
+		pub fn cancel(origin: OriginFor<T>, id: AuctionId) -> DispatchResult {
+			ensure_none(origin)?;
+			ensure!(T::EmergencyShutdown::is_shutdown(), Error::<T>::MustAfterShutdown);
+			<Self as AuctionManager<T::AccountId>>::cancel_auction(id)?;
+			Ok(())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			if let Call::cancel(auction_id) = call {
+				if !T::EmergencyShutdown::is_shutdown() {
+					return InvalidTransaction::Stale.into();
+				}
+
+				if !<CollateralAuctions<T>>::contains_key(auction_id) {
+					return InvalidTransaction::Stale.into();
+				}
+
+				ValidTransaction::with_tag_prefix("AuctionManagerOffchainWorker")
+					.priority(T::UnsignedPriority::get())
+					.and_provides(auction_id)
+					.longevity(64_u64)
+					.propagate(true)
+					.build()
+			} else {
+				InvalidTransaction::Call.into()
+			}
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	fn submit_cancel_auction_tx(auction_id: AuctionId) {
+		let call = Call::<T>::cancel(auction_id);
+		if SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into()).is_err() {
+			log::info!(
+				target: "auction-manager offchain worker",
+				"submit unsigned auction cancel tx for \nAuctionId {:?} \nfailed!",
+				auction_id,
+			);
+		}
+	}
+
+	fn _offchain_worker() -> Result<(), OffchainErr> {
+		if !sp_io::offchain::is_validator() {
+			return Err(OffchainErr::NotValidator);
+		}
+
+		// acquire offchain worker lock
+		let lock_expiration = Duration::from_millis(LOCK_DURATION);
+		let mut lock = StorageLock::<'_, Time>::with_deadline(OFFCHAIN_WORKER_LOCK, lock_expiration);
+		let _guard = lock.try_lock().map_err(|_| OffchainErr::OffchainLock)?;
+
+		// record the last block the offchain worker ran at
+		let last_run = StorageValueRef::persistent(OFFCHAIN_WORKER_DATA);
+		last_run.set(&<frame_system::Pallet<T>>::block_number());
+
+		for auction_id in <CollateralAuctions<T>>::iter_keys() {
+			Self::submit_cancel_auction_tx(auction_id);
+		}
+
+		Ok(())
+	}
+
+	fn cancel_collateral_auction(
+		id: AuctionId,
+		collateral_auction: CollateralAuctionItem<T::AccountId, T::BlockNumber>,
+	) -> DispatchResult {
+		let last_bid = T::Auction::auction_info(id).and_then(|auction_info| auction_info.bid);
+
+		// if there's bid, refund the payment of the last bidder, the
+		// collateral stays in the CDP treasury for the settlement
+		if let Some((bidder, bid_price)) = last_bid {
+			let refund = collateral_auction.payment_amount(bid_price);
+			<T as Config>::CDPTreasury::issue_debit(&bidder, refund, false)?;
+		}
+
+		// decrease total collateral and target in auction
+		TotalCollateralInAuction::<T>::mutate(collateral_auction.currency_id, |balance| {
+			*balance = balance.saturating_sub(collateral_auction.amount)
+		});
+		TotalTargetInAuction::<T>::mutate(|balance| *balance = balance.saturating_sub(collateral_auction.target));
+
+		T::Auction::remove_auction(id);
+		Self::deposit_event(Event::CancelAuction(id));
+		Ok(())
+	}
+
+	/// Get the minimum increment size of a bid, halved once the auction
+	/// runs past `AuctionDurationSoftCap`.
+	pub fn get_minimum_increment_size(now: T::BlockNumber, start_block: T::BlockNumber) -> Rate {
+		if now >= start_block.saturating_add(T::AuctionDurationSoftCap::get()) {
+			T::MinimumIncrementSize::get()
+				.checked_div(&Rate::saturating_from_integer(2))
+				.unwrap_or_default()
+		} else {
+			T::MinimumIncrementSize::get()
+		}
+	}
+
+	/// Get the time extended by a bid, halved once the auction runs past
+	/// `AuctionDurationSoftCap`.
+	pub fn get_auction_time_to_close(now: T::BlockNumber, start_block: T::BlockNumber) -> T::BlockNumber {
+		if now >= start_block.saturating_add(T::AuctionDurationSoftCap::get()) {
+			T::AuctionTimeToClose::get()
+				.checked_div(&2u32.into())
+				.expect("cannot overflow with positive divisor; qed")
+		} else {
+			T::AuctionTimeToClose::get()
+		}
+	}
+
+	/// Check whether `new_price` outbids `last_price` by the minimum
+	/// increment.
+	pub fn check_minimum_increment(new_price: Balance, last_price: Balance, increment_rate: Rate) -> bool {
+		new_price > last_price && new_price >= last_price.saturating_add(increment_rate.saturating_mul_int(last_price))
+	}
+
+	/// Handles collateral auction new bid, returns the new auction end time
+	/// if the bid is accepted
+	#[transactional]
+	pub fn collateral_auction_bid_handler(
+		now: T::BlockNumber,
+		id: AuctionId,
+		new_bid: (T::AccountId, Balance),
+		last_bid: Option<(T::AccountId, Balance)>,
+	) -> sp_std::result::Result<T::BlockNumber, DispatchError> {
+		let (new_bidder, new_bid_price) = new_bid;
+		ensure!(!new_bid_price.is_zero(), Error::<T>::InvalidBidPrice);
+
+		<CollateralAuctions<T>>::try_mutate_exists(
+			id,
+			|collateral_auction| -> sp_std::result::Result<T::BlockNumber, DispatchError> {
+				let collateral_auction = collateral_auction.as_mut().ok_or(Error::<T>::AuctionNotExists)?;
+				let last_bid_price = last_bid.clone().map_or(Zero::zero(), |(_, price)| price);
+
+				ensure!(
+					Self::check_minimum_increment(
+						new_bid_price,
+						last_bid_price,
+						Self::get_minimum_increment_size(now, collateral_auction.start_time),
+					),
+					Error::<T>::InvalidBidPrice
+				);
+
+				let last_bidder = last_bid.as_ref().map(|(who, _)| who);
+				let mut payment = collateral_auction.payment_amount(new_bid_price);
+
+				// if there's bid before, return stablecoin from new bidder to last bidder
+				if let Some(last_bidder) = last_bidder {
+					let refund = collateral_auction.payment_amount(last_bid_price);
+					T::Currency::transfer(T::GetSetUSDId::get(), &new_bidder, last_bidder, refund)?;
+					payment = payment.checked_sub(refund).ok_or(Error::<T>::InvalidBidPrice)?;
+				}
+
+				// transfer remain payment from new bidder to CDP treasury
+				<T as Config>::CDPTreasury::deposit_surplus(&new_bidder, payment)?;
+
+				// if collateral auction will be in reverse stage, refund collateral to it's
+				// origin from auction CDP treasury
+				if collateral_auction.in_reverse_stage(new_bid_price) {
+					let new_collateral_amount = collateral_auction.collateral_amount(last_bid_price, new_bid_price);
+					let refund_collateral_amount = collateral_auction.amount.saturating_sub(new_collateral_amount);
+
+					if !refund_collateral_amount.is_zero() {
+						<T as Config>::CDPTreasury::withdraw_collateral(
+							&collateral_auction.refund_recipient,
+							collateral_auction.currency_id,
+							refund_collateral_amount,
+						)?;
+
+						// update total collateral in auction after refund
+						TotalCollateralInAuction::<T>::mutate(collateral_auction.currency_id, |balance| {
+							*balance = balance.saturating_sub(refund_collateral_amount)
+						});
+						collateral_auction.amount = new_collateral_amount;
+					}
+				}
+
+				Ok(now + Self::get_auction_time_to_close(now, collateral_auction.start_time))
+			},
+		)
+	}
+
+	/// Handles collateral auction end
+	fn collateral_auction_end_handler(
+		auction_id: AuctionId,
+		collateral_auction: CollateralAuctionItem<T::AccountId, T::BlockNumber>,
+		winner: Option<(T::AccountId, Balance)>,
+	) {
+		let currency_id = collateral_auction.currency_id;
+		let amount = collateral_auction.amount;
+
+		match winner {
+			Some((bidder, bid_price)) if collateral_auction.in_reverse_stage(bid_price) => {
+				// the target is reached, the winner takes the collateral
+				Self::deal_collateral_auction(auction_id, &collateral_auction, bidder, bid_price);
+			}
+			Some((bidder, bid_price)) => {
+				// the DEX takes the collateral when it pays more than the bid
+				let payment = collateral_auction.payment_amount(bid_price);
+				if Self::try_dex_take_collateral_auction(auction_id, &collateral_auction, payment.saturating_add(1)) {
+					// refund the bid to the bidder, it shouldn't fail and affect the process.
+					// but even it failed, just the winner did not get the refund amount. it can be
+					// fixed by treasury council.
+					let _ = <T as Config>::CDPTreasury::issue_debit(&bidder, payment, false);
+				} else {
+					Self::deal_collateral_auction(auction_id, &collateral_auction, bidder, bid_price);
+				}
+			}
+			None => {
+				// without bid, the DEX takes the collateral when it pays the target, or
+				// the value of the collateral at the oracle price for the auctions
+				// without target
+				let minimum_target = if collateral_auction.always_forward() {
+					T::PriceSource::get_relative_price(currency_id, T::GetSetUSDId::get())
+						.map(|price| price.saturating_mul_int(amount))
+				} else {
+					Some(collateral_auction.target)
+				};
+
+				let taken = minimum_target
+					.map(|minimum_target| {
+						Self::try_dex_take_collateral_auction(auction_id, &collateral_auction, minimum_target)
+					})
+					.unwrap_or(false);
+				if !taken {
+					// the collateral stays in the CDP treasury, the treasury council can handle
+					// it by `auction_collateral` or `exchange_collateral_to_stable`
+					Self::deposit_event(Event::CollateralAuctionAborted(
+						auction_id,
+						currency_id,
+						amount,
+						collateral_auction.target,
+						collateral_auction.refund_recipient.clone(),
+					));
+				}
+			}
+		}
+
+		// decrease total collateral and target in auction
+		TotalCollateralInAuction::<T>::mutate(currency_id, |balance| *balance = balance.saturating_sub(amount));
+		TotalTargetInAuction::<T>::mutate(|balance| *balance = balance.saturating_sub(collateral_auction.target));
+	}
+
+	fn deal_collateral_auction(
+		auction_id: AuctionId,
+		collateral_auction: &CollateralAuctionItem<T::AccountId, T::BlockNumber>,
+		winner: T::AccountId,
+		bid_price: Balance,
+	) {
+		let payment_amount = collateral_auction.payment_amount(bid_price);
+		// transfer collateral to winner from CDP treasury, it shouldn't fail and affect
+		// the process. but even it failed, just the winner did not get the amount. it
+		// can be fixed by treasury council.
+		let res = <T as Config>::CDPTreasury::withdraw_collateral(
+			&winner,
+			collateral_auction.currency_id,
+			collateral_auction.amount,
+		);
+		if let Err(e) = res {
+			log::warn!(
+				target: "auction-manager",
+				"withdraw_collateral: failed to withdraw {:?} {:?} from CDP treasury to {:?}: {:?}. \
+				This is unexpected but should be safe",
+				collateral_auction.amount,
+				collateral_auction.currency_id,
+				winner,
+				e
+			);
+			debug_assert!(false);
+		}
+
+		Self::deposit_event(Event::CollateralAuctionDealt(
+			auction_id,
+			collateral_auction.currency_id,
+			collateral_auction.amount,
+			winner,
+			payment_amount,
+		));
+	}
+
+	/// Swap the collateral of the auction to stable currency by DEX if it
+	/// gets at least `minimum_target`, the amount beyond the target is
+	/// refunded to the refund recipient. Returns whether the DEX took it.
+	fn try_dex_take_collateral_auction(
+		auction_id: AuctionId,
+		collateral_auction: &CollateralAuctionItem<T::AccountId, T::BlockNumber>,
+		minimum_target: Balance,
+	) -> bool {
+		match <T as Config>::CDPTreasury::swap_collateral_to_stable(
+			collateral_auction.currency_id,
+			SwapLimit::ExactSupply(collateral_auction.amount, minimum_target),
+			true,
+		) {
+			Ok((supply_amount, target_amount)) => {
+				if !collateral_auction.always_forward() && target_amount > collateral_auction.target {
+					let refund_amount = target_amount.saturating_sub(collateral_auction.target);
+					// it shouldn't fail and affect the process. but even it failed, the refund
+					// stays in the surplus pool and can be fixed by treasury council.
+					let _ = <T as Config>::CDPTreasury::issue_debit(
+						&collateral_auction.refund_recipient,
+						refund_amount,
+						false,
+					);
+				}
+
+				Self::deposit_event(Event::DEXTakeCollateralAuction(
+					auction_id,
+					collateral_auction.currency_id,
+					collateral_auction.amount,
+					supply_amount,
+					target_amount,
+				));
+				true
+			}
+			Err(_) => false,
+		}
+	}
+}
+
+impl<T: Config> AuctionHandler<T::AccountId, Balance, T::BlockNumber, AuctionId> for Pallet<T> {
+	fn on_new_bid(
+		now: T::BlockNumber,
+		id: AuctionId,
+		new_bid: (T::AccountId, Balance),
+		last_bid: Option<(T::AccountId, Balance)>,
+	) -> OnNewBidResult<T::BlockNumber> {
+		// no bid is accepted after emergency shutdown
+		if T::EmergencyShutdown::is_shutdown() {
+			return OnNewBidResult {
+				accept_bid: false,
+				auction_end_change: Change::NoChange,
+			};
+		}
+
+		match Self::collateral_auction_bid_handler(now, id, new_bid, last_bid) {
+			Ok(new_auction_end_time) => OnNewBidResult {
+				accept_bid: true,
+				auction_end_change: Change::NewValue(Some(new_auction_end_time)),
+			},
+			Err(_) => OnNewBidResult {
+				accept_bid: false,
+				auction_end_change: Change::NoChange,
+			},
+		}
+	}
+
+	fn on_auction_ended(id: AuctionId, winner: Option<(T::AccountId, Balance)>) {
+		if let Some(collateral_auction) = <CollateralAuctions<T>>::take(id) {
+			Self::collateral_auction_end_handler(id, collateral_auction, winner);
+		}
+	}
+}
+
+impl<T: Config> AuctionManager<T::AccountId> for Pallet<T> {
+	type CurrencyId = CurrencyId;
+	type Balance = Balance;
+	type AuctionId = AuctionId;
+
+	fn new_collateral_auction(
+		refund_recipient: &T::AccountId,
+		currency_id: Self::CurrencyId,
+		amount: Self::Balance,
+		target: Self::Balance,
+	) -> DispatchResult {
+		ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+		TotalCollateralInAuction::<T>::try_mutate(currency_id, |total| -> DispatchResult {
+			*total = total.checked_add(amount).ok_or(Error::<T>::InvalidAmount)?;
+			Ok(())
+		})?;
+
+		if !target.is_zero() {
+			// no-op if target is zero
+			TotalTargetInAuction::<T>::try_mutate(|total| -> DispatchResult {
+				*total = total.checked_add(target).ok_or(Error::<T>::InvalidAmount)?;
+				Ok(())
+			})?;
+		}
+
+		let start_time = <frame_system::Pallet<T>>::block_number();
+
+		// the auction without bid ends after the duration soft cap
+		let auction_id = T::Auction::new_auction(
+			start_time,
+			Some(start_time.saturating_add(T::AuctionDurationSoftCap::get())),
+		)?;
+
+		<CollateralAuctions<T>>::insert(
+			auction_id,
+			CollateralAuctionItem {
+				refund_recipient: refund_recipient.clone(),
+				currency_id,
+				initial_amount: amount,
+				amount,
+				target,
+				start_time,
+			},
+		);
+
+		Self::deposit_event(Event::NewCollateralAuction(auction_id, currency_id, amount, target));
+		Ok(())
+	}
+
+	fn cancel_auction(id: Self::AuctionId) -> DispatchResult {
+		let collateral_auction = <CollateralAuctions<T>>::take(id).ok_or(Error::<T>::AuctionNotExists)?;
+		Self::cancel_collateral_auction(id, collateral_auction)
+	}
+
+	fn get_total_collateral_in_auction(id: Self::CurrencyId) -> Self::Balance {
+		Self::total_collateral_in_auction(id)
+	}
+
+	fn get_total_target_in_auction() -> Self::Balance {
+		Self::total_target_in_auction()
+	}
+}
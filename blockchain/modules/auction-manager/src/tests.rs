@@ -0,0 +1,372 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the auction manager module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use sp_core::offchain::{
+	testing::{TestOffchainExt, TestTransactionPoolExt},
+	OffchainDbExt, OffchainWorkerExt, TransactionPoolExt,
+};
+use sp_runtime::traits::{BadOrigin, Dispatchable};
+
+fn setup_collateral_auction(amount: Balance, target: Balance) {
+	assert_ok!(Tokens::deposit(SEE, &CDPTreasuryModule::account_id(), amount));
+	assert_ok!(AuctionManagerModule::new_collateral_auction(
+		&ALICE, SEE, amount, target
+	));
+}
+
+#[test]
+fn get_auction_time_to_close_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(AuctionManagerModule::get_auction_time_to_close(2, 1), 100);
+		assert_eq!(AuctionManagerModule::get_auction_time_to_close(2001, 1), 50);
+	});
+}
+
+#[test]
+fn get_minimum_increment_size_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(
+			AuctionManagerModule::get_minimum_increment_size(2, 1),
+			Rate::saturating_from_rational(1, 20)
+		);
+		assert_eq!(
+			AuctionManagerModule::get_minimum_increment_size(2001, 1),
+			Rate::saturating_from_rational(1, 40)
+		);
+	});
+}
+
+#[test]
+fn collateral_auction_methods() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral_auction(10, 100);
+		let collateral_auction_with_positive_target = AuctionManagerModule::collateral_auctions(0).unwrap();
+		assert!(!collateral_auction_with_positive_target.always_forward());
+		assert!(!collateral_auction_with_positive_target.in_reverse_stage(99));
+		assert!(collateral_auction_with_positive_target.in_reverse_stage(100));
+		assert!(collateral_auction_with_positive_target.in_reverse_stage(101));
+		assert_eq!(collateral_auction_with_positive_target.payment_amount(99), 99);
+		assert_eq!(collateral_auction_with_positive_target.payment_amount(101), 100);
+		assert_eq!(collateral_auction_with_positive_target.collateral_amount(80, 100), 10);
+		assert_eq!(collateral_auction_with_positive_target.collateral_amount(100, 200), 5);
+
+		setup_collateral_auction(10, 0);
+		let collateral_auction_with_zero_target = AuctionManagerModule::collateral_auctions(1).unwrap();
+		assert!(collateral_auction_with_zero_target.always_forward());
+		assert!(!collateral_auction_with_zero_target.in_reverse_stage(0));
+		assert!(!collateral_auction_with_zero_target.in_reverse_stage(100));
+		assert_eq!(collateral_auction_with_zero_target.payment_amount(100), 100);
+		assert_eq!(collateral_auction_with_zero_target.collateral_amount(100, 200), 10);
+	});
+}
+
+#[test]
+fn new_collateral_auction_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			AuctionManagerModule::new_collateral_auction(&ALICE, SEE, 0, 100),
+			Error::<Runtime>::InvalidAmount,
+		);
+
+		setup_collateral_auction(10, 100);
+		System::assert_last_event(Event::AuctionManagerModule(crate::Event::NewCollateralAuction(
+			0, SEE, 10, 100,
+		)));
+		assert_eq!(AuctionManagerModule::total_collateral_in_auction(SEE), 10);
+		assert_eq!(AuctionManagerModule::total_target_in_auction(), 100);
+		assert_eq!(AuctionModule::auction_info(0).unwrap().end, Some(2001));
+	});
+}
+
+#[test]
+fn collateral_auction_bid_handler_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			AuctionManagerModule::collateral_auction_bid_handler(1, 0, (BOB, 20), None),
+			Error::<Runtime>::AuctionNotExists,
+		);
+
+		setup_collateral_auction(10, 100);
+		assert_eq!(
+			AuctionManagerModule::collateral_auction_bid_handler(1, 0, (BOB, 20), None),
+			Ok(101)
+		);
+		assert_eq!(Tokens::free_balance(SETUSD, &BOB), 980);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 20);
+
+		// the bid must beat the last one by the minimum increment
+		assert_noop!(
+			AuctionManagerModule::collateral_auction_bid_handler(2, 0, (CAROL, 20), Some((BOB, 20))),
+			Error::<Runtime>::InvalidBidPrice,
+		);
+
+		// reaching the target enters the reverse stage and refunds collateral
+		assert_eq!(
+			AuctionManagerModule::collateral_auction_bid_handler(2, 0, (CAROL, 200), Some((BOB, 20))),
+			Ok(102)
+		);
+		assert_eq!(Tokens::free_balance(SETUSD, &BOB), 1000);
+		assert_eq!(Tokens::free_balance(SETUSD, &CAROL), 900);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 100);
+		assert_eq!(Tokens::free_balance(SEE, &ALICE), 5);
+		assert_eq!(AuctionManagerModule::collateral_auctions(0).unwrap().amount, 5);
+		assert_eq!(AuctionManagerModule::total_collateral_in_auction(SEE), 5);
+	});
+}
+
+#[test]
+fn bid_after_soft_cap_has_shrunk_increment_and_time_to_close() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral_auction(10, 100);
+		assert_ok!(AuctionManagerModule::collateral_auction_bid_handler(
+			1,
+			0,
+			(BOB, 40),
+			None
+		));
+
+		assert_noop!(
+			AuctionManagerModule::collateral_auction_bid_handler(2000, 0, (CAROL, 41), Some((BOB, 40))),
+			Error::<Runtime>::InvalidBidPrice,
+		);
+		assert_eq!(
+			AuctionManagerModule::collateral_auction_bid_handler(2001, 0, (CAROL, 41), Some((BOB, 40))),
+			Ok(2051)
+		);
+	});
+}
+
+#[test]
+fn bid_through_auction_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral_auction(10, 100);
+		assert_ok!(AuctionModule::bid(Origin::signed(BOB), 0, 20));
+		assert_eq!(AuctionModule::auction_info(0).unwrap().end, Some(101));
+		assert_eq!(Tokens::free_balance(SETUSD, &BOB), 980);
+
+		MockEmergencyShutdown::set_shutdown(true);
+		assert!(AuctionModule::bid(Origin::signed(CAROL), 0, 50).is_err());
+	});
+}
+
+#[test]
+fn collateral_auction_end_handler_in_reverse_stage() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral_auction(10, 100);
+		assert_ok!(AuctionManagerModule::collateral_auction_bid_handler(
+			1,
+			0,
+			(CAROL, 200),
+			None
+		));
+
+		AuctionManagerModule::on_auction_ended(0, Some((CAROL, 200)));
+		System::assert_last_event(Event::AuctionManagerModule(crate::Event::CollateralAuctionDealt(
+			0, SEE, 5, CAROL, 100,
+		)));
+		assert_eq!(Tokens::free_balance(SEE, &CAROL), 5);
+		assert_eq!(Tokens::free_balance(SEE, &ALICE), 5);
+		assert_eq!(AuctionManagerModule::collateral_auctions(0), None);
+		assert_eq!(AuctionManagerModule::total_collateral_in_auction(SEE), 0);
+		assert_eq!(AuctionManagerModule::total_target_in_auction(), 0);
+	});
+}
+
+#[test]
+fn collateral_auction_end_handler_in_forward_stage() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral_auction(10, 100);
+		assert_ok!(AuctionManagerModule::collateral_auction_bid_handler(
+			1,
+			0,
+			(BOB, 20),
+			None
+		));
+
+		// without DEX liquidity the winner takes the collateral
+		AuctionManagerModule::on_auction_ended(0, Some((BOB, 20)));
+		System::assert_last_event(Event::AuctionManagerModule(crate::Event::CollateralAuctionDealt(
+			0, SEE, 10, BOB, 20,
+		)));
+		assert_eq!(Tokens::free_balance(SEE, &BOB), 10);
+		assert_eq!(AuctionManagerModule::total_collateral_in_auction(SEE), 0);
+	});
+}
+
+#[test]
+fn collateral_auction_end_handler_dex_take_when_better() {
+	ExtBuilder::default().build().execute_with(|| {
+		MockDEX::set_pool(SEE, SETUSD, 100, 1000);
+		setup_collateral_auction(10, 100);
+		assert_ok!(AuctionManagerModule::collateral_auction_bid_handler(
+			1,
+			0,
+			(BOB, 20),
+			None
+		));
+		assert_eq!(Tokens::free_balance(SETUSD, &BOB), 980);
+
+		AuctionManagerModule::on_auction_ended(0, Some((BOB, 20)));
+		System::assert_last_event(Event::AuctionManagerModule(crate::Event::DEXTakeCollateralAuction(
+			0, SEE, 10, 10, 100,
+		)));
+		assert_eq!(Tokens::free_balance(SEE, &BOB), 0);
+		assert_eq!(Tokens::free_balance(SETUSD, &BOB), 1000);
+		assert_eq!(CDPTreasuryModule::total_collaterals(SEE), 0);
+		assert_eq!(AuctionManagerModule::total_collateral_in_auction(SEE), 0);
+	});
+}
+
+#[test]
+fn collateral_auction_end_handler_without_bid() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral_auction(10, 100);
+
+		// the collateral stays in the treasury if the DEX can't pay the target
+		AuctionManagerModule::on_auction_ended(0, None);
+		System::assert_last_event(Event::AuctionManagerModule(crate::Event::CollateralAuctionAborted(
+			0, SEE, 10, 100, ALICE,
+		)));
+		assert_eq!(CDPTreasuryModule::total_collaterals(SEE), 10);
+		assert_eq!(AuctionManagerModule::total_collateral_in_auction(SEE), 0);
+		assert_eq!(AuctionManagerModule::total_target_in_auction(), 0);
+
+		// the amount beyond the target is refunded to the refund recipient
+		MockDEX::set_pool(SEE, SETUSD, 100, 1500);
+		assert_ok!(AuctionManagerModule::new_collateral_auction(&ALICE, SEE, 10, 100));
+		AuctionManagerModule::on_auction_ended(1, None);
+		System::assert_last_event(Event::AuctionManagerModule(crate::Event::DEXTakeCollateralAuction(
+			1, SEE, 10, 10, 150,
+		)));
+		assert_eq!(Tokens::free_balance(SETUSD, &ALICE), 1050);
+		assert_eq!(CDPTreasuryModule::total_collaterals(SEE), 0);
+	});
+}
+
+#[test]
+fn collateral_auction_end_handler_without_bid_and_target() {
+	ExtBuilder::default().build().execute_with(|| {
+		MockDEX::set_pool(SEE, SETUSD, 100, 50);
+		setup_collateral_auction(10, 0);
+
+		// the DEX must pay at least the oracle value of the collateral
+		AuctionManagerModule::on_auction_ended(0, None);
+		System::assert_last_event(Event::AuctionManagerModule(crate::Event::CollateralAuctionAborted(
+			0, SEE, 10, 0, ALICE,
+		)));
+
+		MockDEX::set_pool(SEE, SETUSD, 100, 100);
+		assert_ok!(AuctionManagerModule::new_collateral_auction(&ALICE, SEE, 10, 0));
+		AuctionManagerModule::on_auction_ended(1, None);
+		System::assert_last_event(Event::AuctionManagerModule(crate::Event::DEXTakeCollateralAuction(
+			1, SEE, 10, 10, 10,
+		)));
+		assert_eq!(Tokens::free_balance(SETUSD, &ALICE), 1000);
+	});
+}
+
+#[test]
+fn cancel_collateral_auction_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral_auction(10, 100);
+		assert_ok!(AuctionModule::bid(Origin::signed(BOB), 0, 20));
+		assert_eq!(Tokens::free_balance(SETUSD, &BOB), 980);
+
+		assert_noop!(
+			AuctionManagerModule::cancel(Origin::none(), 0),
+			Error::<Runtime>::MustAfterShutdown,
+		);
+
+		MockEmergencyShutdown::set_shutdown(true);
+		assert_noop!(AuctionManagerModule::cancel(Origin::signed(ALICE), 0), BadOrigin);
+		assert_noop!(
+			AuctionManagerModule::cancel(Origin::none(), 1),
+			Error::<Runtime>::AuctionNotExists,
+		);
+
+		assert_ok!(AuctionManagerModule::cancel(Origin::none(), 0));
+		System::assert_last_event(Event::AuctionManagerModule(crate::Event::CancelAuction(0)));
+		assert_eq!(Tokens::free_balance(SETUSD, &BOB), 1000);
+		assert_eq!(CDPTreasuryModule::total_collaterals(SEE), 10);
+		assert_eq!(AuctionManagerModule::total_collateral_in_auction(SEE), 0);
+		assert_eq!(AuctionManagerModule::total_target_in_auction(), 0);
+		assert_eq!(AuctionManagerModule::collateral_auctions(0), None);
+		assert_eq!(AuctionModule::auction_info(0), None);
+	});
+}
+
+#[test]
+fn offchain_worker_cancels_auctions_after_shutdown() {
+	let (offchain, _offchain_state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = ExtBuilder::default().build();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.register_extension(OffchainDbExt::new(offchain));
+
+	ext.execute_with(|| {
+		setup_collateral_auction(10, 100);
+		setup_collateral_auction(10, 0);
+
+		AuctionManagerModule::offchain_worker(2);
+		assert!(pool_state.write().transactions.pop().is_none());
+
+		MockEmergencyShutdown::set_shutdown(true);
+		AuctionManagerModule::offchain_worker(3);
+		while let Some(tx) = pool_state.write().transactions.pop() {
+			let tx = Extrinsic::decode(&mut &*tx).unwrap();
+			assert_ok!(tx.call.dispatch(Origin::none()));
+		}
+		assert_eq!(AuctionManagerModule::collateral_auctions(0), None);
+		assert_eq!(AuctionManagerModule::collateral_auctions(1), None);
+		assert_eq!(AuctionManagerModule::total_collateral_in_auction(SEE), 0);
+	});
+}
+
+#[test]
+fn validate_unsigned_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_collateral_auction(10, 100);
+		let cancel = crate::Call::<Runtime>::cancel(0);
+		assert_eq!(
+			<AuctionManagerModule as ValidateUnsigned>::validate_unsigned(TransactionSource::Local, &cancel),
+			InvalidTransaction::Stale.into()
+		);
+
+		MockEmergencyShutdown::set_shutdown(true);
+		assert!(
+			<AuctionManagerModule as ValidateUnsigned>::validate_unsigned(TransactionSource::Local, &cancel).is_ok()
+		);
+		assert_eq!(
+			<AuctionManagerModule as ValidateUnsigned>::validate_unsigned(
+				TransactionSource::Local,
+				&crate::Call::<Runtime>::cancel(1)
+			),
+			InvalidTransaction::Stale.into()
+		);
+	});
+}
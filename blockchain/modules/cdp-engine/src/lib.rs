@@ -0,0 +1,859 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # CDP Engine Module
+//!
+//! ## Overview
+//!
+//! The core module of the Setmint protocol. CDP engine is responsible for
+//! handling the internal processes of CDPs, including liquidation,
+//! settlement and risk management.
+//!
+//! Every collateral type has its own risk management parameters: the
+//! stability fee accrued per block into the debit exchange rate, the
+//! liquidation ratio, the liquidation penalty, the required collateral ratio
+//! and the cap of the total debit value. As the `RiskManager` of the loans
+//! module, the engine validates every position change against them.
+//!
+//! Unsafe CDPs are liquidated by unsigned transactions submitted by the
+//! offchain worker: their collateral is confiscated to the CDP treasury and
+//! swapped to stable currency through the DEX when the price impact is
+//! within `MaxSwapSlippageCompareToOracle`, otherwise sold by collateral
+//! auctions. After emergency shutdown the offchain worker settles the CDPs
+//! which still have debit instead.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+#![allow(clippy::upper_case_acronyms)]
+
+use codec::{Decode, Encode};
+use frame_support::{log, pallet_prelude::*, transactional};
+use frame_system::{
+	offchain::{SendTransactionTypes, SubmitTransaction},
+	pallet_prelude::*,
+};
+use loans::Position;
+use orml_traits::Change;
+use primitives::{Amount, Balance, CurrencyId};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	offchain::{
+		storage::StorageValueRef,
+		storage_lock::{StorageLock, Time},
+		Duration,
+	},
+	traits::{Bounded, One, Saturating, StaticLookup, Zero},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity, ValidTransaction,
+	},
+	DispatchError, DispatchResult, FixedPointNumber, RuntimeDebug,
+};
+use sp_std::prelude::*;
+use support::{
+	CDPTreasury, CDPTreasuryExtended, EmergencyShutdown, ExchangeRate, Price, PriceProvider, Rate, Ratio, RiskManager,
+	SwapLimit,
+};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+pub const OFFCHAIN_WORKER_DATA: &[u8] = b"setheum/cdp-engine/data/";
+pub const OFFCHAIN_WORKER_LOCK: &[u8] = b"setheum/cdp-engine/lock/";
+pub const OFFCHAIN_WORKER_MAX_ITERATIONS: &[u8] = b"setheum/cdp-engine/max-iterations/";
+pub const LOCK_DURATION: u64 = 100;
+pub const DEFAULT_MAX_ITERATIONS: u32 = 1000;
+
+pub type LoansOf<T> = loans::Pallet<T>;
+
+/// Risk management params
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, Default, TypeInfo)]
+pub struct RiskManagementParams {
+	/// Maximum total debit value generated from it, when reach the hard
+	/// cap, CDP's owner cannot issue more stablecoin under the collateral
+	/// type.
+	pub maximum_total_debit_value: Balance,
+
+	/// Extra stability fee rate per block, `None` value means not set.
+	pub stability_fee: Option<Rate>,
+
+	/// Liquidation ratio, when the collateral ratio of
+	/// CDP under this collateral type is below the liquidation ratio, this
+	/// CDP is unsafe and can be liquidated. `None` value means not set
+	pub liquidation_ratio: Option<Ratio>,
+
+	/// Liquidation penalty rate, when liquidation occurs,
+	/// CDP will be deducted an additional penalty base on the product of
+	/// penalty rate and debit value. `None` value means not set
+	pub liquidation_penalty: Option<Rate>,
+
+	/// Required collateral ratio, if it's set, cannot adjust the position
+	/// of CDP so that the current collateral ratio is lower than the
+	/// required collateral ratio. `None` value means not set
+	pub required_collateral_ratio: Option<Ratio>,
+}
+
+// typedef to help polkadot.js disambiguate Change with different generic
+// parameters
+type ChangeOptionRate = Change<Option<Rate>>;
+type ChangeOptionRatio = Change<Option<Ratio>>;
+type ChangeBalance = Change<Balance>;
+
+/// Status of CDP
+#[derive(PartialEq, Eq, Clone, Copy, RuntimeDebug)]
+pub enum CDPStatus {
+	Safe,
+	Unsafe,
+	ChecksFailed(DispatchError),
+}
+
+/// Liquidation strategy available
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum LiquidationStrategy {
+	/// Liquidation CDP's collateral by create collateral auction
+	Auction,
+	/// Liquidation CDP's collateral by swap with DEX
+	Exchange,
+}
+
+#[derive(RuntimeDebug)]
+enum OffchainErr {
+	OffchainLock,
+	NotValidator,
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + loans::Config + SendTransactionTypes<Call<Self>> {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The origin which may update risk management parameters.
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The list of valid collateral currency types
+		type CollateralCurrencyIds: Get<Vec<CurrencyId>>;
+
+		/// The default liquidation ratio for all collateral types of CDP
+		#[pallet::constant]
+		type DefaultLiquidationRatio: Get<Ratio>;
+
+		/// The default debit exchange rate for all collateral types
+		#[pallet::constant]
+		type DefaultDebitExchangeRate: Get<ExchangeRate>;
+
+		/// The default liquidation penalty rate when liquidate unsafe CDP
+		#[pallet::constant]
+		type DefaultLiquidationPenalty: Get<Rate>;
+
+		/// The minimum debit value to avoid debit dust
+		#[pallet::constant]
+		type MinimumDebitValue: Get<Balance>;
+
+		/// Stablecoin currency id
+		#[pallet::constant]
+		type GetSetUSDId: Get<CurrencyId>;
+
+		/// The max slippage allowed when liquidate an unsafe CDP by swap with
+		/// DEX
+		#[pallet::constant]
+		type MaxSwapSlippageCompareToOracle: Get<Ratio>;
+
+		/// The CDP treasury to maintain bad debts and surplus generated by
+		/// CDPs
+		type CDPTreasury: CDPTreasuryExtended<Self::AccountId, Balance = Balance, CurrencyId = CurrencyId>;
+
+		/// The price source of all types of currencies related to CDP
+		type PriceSource: PriceProvider<CurrencyId>;
+
+		/// A configuration for base priority of unsigned transactions.
+		///
+		/// This is exposed so that it can be tuned for particular runtime, when
+		/// multiple modules send unsigned transactions.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+
+		/// Emergency shutdown.
+		type EmergencyShutdown: EmergencyShutdown;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The total debit value of specific collateral type already exceed the
+		/// hard cap
+		ExceedDebitValueHardCap,
+		/// The collateral ratio below the required collateral ratio
+		BelowRequiredCollateralRatio,
+		/// The collateral ratio below the liquidation ratio
+		BelowLiquidationRatio,
+		/// The CDP must be unsafe to be liquidated
+		MustBeUnsafe,
+		/// Invalid collateral type
+		InvalidCollateralType,
+		/// Remain debit value in CDP below the dust amount
+		RemainDebitValueTooSmall,
+		/// Feed price is invalid
+		InvalidFeedPrice,
+		/// No debit value in CDP so that it cannot be settled
+		NoDebitValue,
+		/// System has already been shutdown
+		AlreadyShutdown,
+		/// Must after system shutdown
+		MustAfterShutdown,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Liquidate the unsafe CDP. \[collateral_type, owner,
+		/// collateral_amount, bad_debt_value, liquidation_strategy\]
+		LiquidateUnsafeCDP(CurrencyId, T::AccountId, Balance, Balance, LiquidationStrategy),
+		/// Settle the CDP has debit. [collateral_type, owner]
+		SettleCDPInDebit(CurrencyId, T::AccountId),
+		/// The stability fee for specific collateral type updated.
+		/// \[collateral_type, new_stability_fee\]
+		StabilityFeeUpdated(CurrencyId, Option<Rate>),
+		/// The liquidation fee for specific collateral type updated.
+		/// \[collateral_type, new_liquidation_ratio\]
+		LiquidationRatioUpdated(CurrencyId, Option<Ratio>),
+		/// The liquidation penalty rate for specific collateral type updated.
+		/// \[collateral_type, new_liquidation_panelty\]
+		LiquidationPenaltyUpdated(CurrencyId, Option<Rate>),
+		/// The required collateral penalty rate for specific collateral type
+		/// updated. \[collateral_type, new_required_collateral_ratio\]
+		RequiredCollateralRatioUpdated(CurrencyId, Option<Ratio>),
+		/// The hard cap of total debit value for specific collateral type
+		/// updated. \[collateral_type, new_total_debit_value\]
+		MaximumTotalDebitValueUpdated(CurrencyId, Balance),
+	}
+
+	/// Mapping from collateral type to its exchange rate of debit units and
+	/// debit value
+	///
+	/// DebitExchangeRate: CurrencyId => Option<ExchangeRate>
+	#[pallet::storage]
+	#[pallet::getter(fn debit_exchange_rate)]
+	pub type DebitExchangeRate<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, ExchangeRate, OptionQuery>;
+
+	/// Mapping from collateral type to its risk management params
+	///
+	/// CollateralParams: CurrencyId => RiskManagementParams
+	#[pallet::storage]
+	#[pallet::getter(fn collateral_params)]
+	pub type CollateralParams<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, RiskManagementParams, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig {
+		#[allow(clippy::type_complexity)]
+		pub collaterals_params: Vec<(
+			CurrencyId,
+			Option<Rate>,
+			Option<Ratio>,
+			Option<Rate>,
+			Option<Ratio>,
+			Balance,
+		)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl Default for GenesisConfig {
+		fn default() -> Self {
+			GenesisConfig {
+				collaterals_params: vec![],
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			self.collaterals_params.iter().for_each(
+				|(
+					currency_id,
+					stability_fee,
+					liquidation_ratio,
+					liquidation_penalty,
+					required_collateral_ratio,
+					maximum_total_debit_value,
+				)| {
+					CollateralParams::<T>::insert(
+						currency_id,
+						RiskManagementParams {
+							maximum_total_debit_value: *maximum_total_debit_value,
+							stability_fee: *stability_fee,
+							liquidation_ratio: *liquidation_ratio,
+							liquidation_penalty: *liquidation_penalty,
+							required_collateral_ratio: *required_collateral_ratio,
+						},
+					);
+				},
+			);
+		}
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Accumulate the stability fee of every collateral type into its
+		/// debit exchange rate, issuing the accrued fee as system surplus.
+		fn on_initialize(_now: T::BlockNumber) -> Weight {
+			// stop accruing stability fee after emergency shutdown
+			if T::EmergencyShutdown::is_shutdown() {
+				return T::WeightInfo::on_initialize(0);
+			}
+
+			let collateral_currency_ids = T::CollateralCurrencyIds::get();
+			for currency_id in collateral_currency_ids.iter() {
+				Self::accumulate_stability_fee(*currency_id);
+			}
+			T::WeightInfo::on_initialize(collateral_currency_ids.len() as u32)
+		}
+
+		/// Runs after every block. Start offchain worker to check CDP and
+		/// submit unsigned tx to trigger liquidation or settlement.
+		fn offchain_worker(now: T::BlockNumber) {
+			if let Err(e) = Self::_offchain_worker() {
+				log::info!(
+					target: "cdp-engine offchain worker",
+					"cannot run offchain worker at {:?}: {:?}",
+					now,
+					e,
+				);
+			} else {
+				log::debug!(
+					target: "cdp-engine offchain worker",
+					"offchain worker start at block: {:?} already done!",
+					now,
+				);
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Liquidate unsafe CDP
+		///
+		/// The dispatch origin of this call must be _None_.
+		///
+		/// - `currency_id`: CDP's collateral type.
+		/// - `who`: CDP's owner.
+		#[pallet::weight(T::WeightInfo::liquidate_by_auction(<T as Config>::CDPTreasury::max_auction())
+			.max(T::WeightInfo::liquidate_by_dex()))]
+		#[transactional]
+		pub fn liquidate(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			who: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			ensure!(!T::EmergencyShutdown::is_shutdown(), Error::<T>::AlreadyShutdown);
+			let consumed_weight: Weight = match Self::liquidate_unsafe_cdp(who, currency_id)? {
+				LiquidationStrategy::Auction => {
+					T::WeightInfo::liquidate_by_auction(<T as Config>::CDPTreasury::max_auction())
+				}
+				LiquidationStrategy::Exchange => T::WeightInfo::liquidate_by_dex(),
+			};
+			Ok(Some(consumed_weight).into())
+		}
+
+		/// Settle CDP has debit after system shutdown
+		///
+		/// The dispatch origin of this call must be _None_.
+		///
+		/// - `currency_id`: CDP's collateral type.
+		/// - `who`: CDP's owner.
+		#[pallet::weight(T::WeightInfo::settle())]
+		#[transactional]
+		pub fn settle(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			who: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+			let who = T::Lookup::lookup(who)?;
+			ensure!(T::EmergencyShutdown::is_shutdown(), Error::<T>::MustAfterShutdown);
+			Self::settle_cdp_has_debit(who, currency_id)?;
+			Ok(())
+		}
+
+		/// Update parameters related to risk management of CDP under specific
+		/// collateral type
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: collateral type.
+		/// - `stability_fee`: extra stability fee rate per block, `None` means do not update,
+		///   `Some(None)` means update it to `None`.
+		/// - `liquidation_ratio`: liquidation ratio, `None` means do not update, `Some(None)` means
+		///   update it to `None`.
+		/// - `liquidation_penalty`: liquidation penalty, `None` means do not update, `Some(None)`
+		///   means update it to `None`.
+		/// - `required_collateral_ratio`: required collateral ratio, `None` means do not update,
+		///   `Some(None)` means update it to `None`.
+		/// - `maximum_total_debit_value`: maximum total debit value.
+		#[pallet::weight((T::WeightInfo::set_collateral_params(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_collateral_params(
+			origin: OriginFor<T>,
+			currency_id: CurrencyId,
+			stability_fee: ChangeOptionRate,
+			liquidation_ratio: ChangeOptionRatio,
+			liquidation_penalty: ChangeOptionRate,
+			required_collateral_ratio: ChangeOptionRatio,
+			maximum_total_debit_value: ChangeBalance,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(
+				T::CollateralCurrencyIds::get().contains(&currency_id),
+				Error::<T>::InvalidCollateralType,
+			);
+
+			let mut collateral_params = Self::collateral_params(currency_id);
+			if let Change::NewValue(update) = stability_fee {
+				collateral_params.stability_fee = update;
+				Self::deposit_event(Event::StabilityFeeUpdated(currency_id, update));
+			}
+			if let Change::NewValue(update) = liquidation_ratio {
+				collateral_params.liquidation_ratio = update;
+				Self::deposit_event(Event::LiquidationRatioUpdated(currency_id, update));
+			}
+			if let Change::NewValue(update) = liquidation_penalty {
+				collateral_params.liquidation_penalty = update;
+				Self::deposit_event(Event::LiquidationPenaltyUpdated(currency_id, update));
+			}
+			if let Change::NewValue(update) = required_collateral_ratio {
+				collateral_params.required_collateral_ratio = update;
+				Self::deposit_event(Event::RequiredCollateralRatioUpdated(currency_id, update));
+			}
+			if let Change::NewValue(val) = maximum_total_debit_value {
+				collateral_params.maximum_total_debit_value = val;
+				Self::deposit_event(Event::MaximumTotalDebitValueUpdated(currency_id, val));
+			}
+			CollateralParams::<T>::insert(currency_id, collateral_params);
+			Ok(())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			match call {
+				Call::liquidate(currency_id, who) => {
+					let account = T::Lookup::lookup(who.clone())?;
+					let Position { collateral, debit } = <LoansOf<T>>::positions(currency_id, &account);
+					if !matches!(
+						Self::check_cdp_status(*currency_id, collateral, debit),
+						CDPStatus::Unsafe
+					) || T::EmergencyShutdown::is_shutdown()
+					{
+						return InvalidTransaction::Stale.into();
+					}
+
+					ValidTransaction::with_tag_prefix("CDPEngineOffchainWorker")
+						.priority(T::UnsignedPriority::get())
+						.and_provides((<frame_system::Pallet<T>>::block_number(), currency_id, who))
+						.longevity(64_u64)
+						.propagate(true)
+						.build()
+				}
+				Call::settle(currency_id, who) => {
+					let account = T::Lookup::lookup(who.clone())?;
+					let Position { debit, .. } = <LoansOf<T>>::positions(currency_id, &account);
+					if debit.is_zero() || !T::EmergencyShutdown::is_shutdown() {
+						return InvalidTransaction::Stale.into();
+					}
+
+					ValidTransaction::with_tag_prefix("CDPEngineOffchainWorker")
+						.priority(T::UnsignedPriority::get())
+						.and_provides((currency_id, who))
+						.longevity(64_u64)
+						.propagate(true)
+						.build()
+				}
+				_ => InvalidTransaction::Call.into(),
+			}
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	fn accumulate_stability_fee(currency_id: CurrencyId) {
+		let stability_fee = Self::get_stability_fee(currency_id);
+		if stability_fee.is_zero() {
+			return;
+		}
+
+		let total_debits = <LoansOf<T>>::total_positions(currency_id).debit;
+		let debit_exchange_rate = Self::get_debit_exchange_rate(currency_id);
+		let debit_exchange_rate_increment = debit_exchange_rate.saturating_mul(stability_fee);
+		if !debit_exchange_rate_increment.is_zero() && !total_debits.is_zero() {
+			// issue stablecoin to surplus pool
+			let issued_stable_coin_balance = debit_exchange_rate_increment.saturating_mul_int(total_debits);
+			if <T as Config>::CDPTreasury::on_system_surplus(issued_stable_coin_balance).is_ok() {
+				// update exchange rate when issue success
+				let new_debit_exchange_rate = debit_exchange_rate.saturating_add(debit_exchange_rate_increment);
+				DebitExchangeRate::<T>::insert(currency_id, new_debit_exchange_rate);
+			}
+		}
+	}
+
+	fn submit_unsigned_liquidation_tx(currency_id: CurrencyId, who: T::AccountId) {
+		let who = T::Lookup::unlookup(who);
+		let call = Call::<T>::liquidate(currency_id, who.clone());
+		if SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into()).is_err() {
+			log::info!(
+				target: "cdp-engine offchain worker",
+				"submit unsigned liquidation tx for \nCDP - AccountId {:?} CurrencyId {:?} \nfailed!",
+				who,
+				currency_id,
+			);
+		}
+	}
+
+	fn submit_unsigned_settlement_tx(currency_id: CurrencyId, who: T::AccountId) {
+		let who = T::Lookup::unlookup(who);
+		let call = Call::<T>::settle(currency_id, who.clone());
+		if SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into()).is_err() {
+			log::info!(
+				target: "cdp-engine offchain worker",
+				"submit unsigned settlement tx for \nCDP - AccountId {:?} CurrencyId {:?} \nfailed!",
+				who,
+				currency_id,
+			);
+		}
+	}
+
+	/// Check at most `max_iterations` CDPs of one collateral type per run,
+	/// resuming from the last CDP checked and rotating through
+	/// `CollateralCurrencyIds` once all CDPs of a type are checked.
+	fn _offchain_worker() -> Result<(), OffchainErr> {
+		let collateral_currency_ids = T::CollateralCurrencyIds::get();
+		if collateral_currency_ids.is_empty() {
+			return Ok(());
+		}
+
+		// check if we are a potential validator
+		if !sp_io::offchain::is_validator() {
+			return Err(OffchainErr::NotValidator);
+		}
+
+		// acquire offchain worker lock
+		let lock_expiration = Duration::from_millis(LOCK_DURATION);
+		let mut lock = StorageLock::<'_, Time>::with_deadline(OFFCHAIN_WORKER_LOCK, lock_expiration);
+		let _guard = lock.try_lock().map_err(|_| OffchainErr::OffchainLock)?;
+
+		let to_be_continue = StorageValueRef::persistent(OFFCHAIN_WORKER_DATA);
+		let (collateral_position, start_key) = match to_be_continue.get::<(u32, Option<Vec<u8>>)>() {
+			Ok(Some((position, start_key))) if (position as usize) < collateral_currency_ids.len() => {
+				(position as usize, start_key)
+			}
+			_ => (0, None),
+		};
+		// the number of CDPs checked per run can be overridden in the offchain storage
+		let max_iterations = StorageValueRef::persistent(OFFCHAIN_WORKER_MAX_ITERATIONS)
+			.get::<u32>()
+			.ok()
+			.flatten()
+			.unwrap_or(DEFAULT_MAX_ITERATIONS);
+
+		let currency_id = collateral_currency_ids[collateral_position];
+		let is_shutdown = T::EmergencyShutdown::is_shutdown();
+		let positions = match start_key {
+			Some(key) => <loans::Positions<T>>::iter_prefix_from(currency_id, key),
+			None => <loans::Positions<T>>::iter_prefix(currency_id),
+		};
+
+		let mut iteration_count: u32 = 0;
+		let mut last_checked = None;
+		for (who, Position { collateral, debit }) in positions.take(max_iterations as usize) {
+			iteration_count += 1;
+			if !is_shutdown
+				&& matches!(
+					Self::check_cdp_status(currency_id, collateral, debit),
+					CDPStatus::Unsafe
+				) {
+				// liquidate unsafe CDPs before emergency shutdown occurs
+				Self::submit_unsigned_liquidation_tx(currency_id, who.clone());
+			} else if is_shutdown && !debit.is_zero() {
+				// settle CDPs with debit after emergency shutdown occurs.
+				Self::submit_unsigned_settlement_tx(currency_id, who.clone());
+			}
+			last_checked = Some(who);
+		}
+
+		match last_checked {
+			// continue after the last CDP checked in the next run
+			Some(who) if iteration_count == max_iterations => to_be_continue.set(&(
+				collateral_position as u32,
+				Some(<loans::Positions<T>>::hashed_key_for(currency_id, &who)),
+			)),
+			// move on to the next collateral type in the next run
+			_ => to_be_continue.set(&(
+				((collateral_position + 1) % collateral_currency_ids.len()) as u32,
+				Option::<Vec<u8>>::None,
+			)),
+		}
+
+		Ok(())
+	}
+
+	pub fn check_cdp_status(currency_id: CurrencyId, collateral_amount: Balance, debit_amount: Balance) -> CDPStatus {
+		if debit_amount.is_zero() {
+			return CDPStatus::Safe;
+		}
+
+		let stable_currency_id = T::GetSetUSDId::get();
+		if let Some(feed_price) = T::PriceSource::get_relative_price(currency_id, stable_currency_id) {
+			let collateral_ratio =
+				Self::calculate_collateral_ratio(currency_id, collateral_amount, debit_amount, feed_price);
+			if collateral_ratio < Self::get_liquidation_ratio(currency_id) {
+				CDPStatus::Unsafe
+			} else {
+				CDPStatus::Safe
+			}
+		} else {
+			CDPStatus::ChecksFailed(Error::<T>::InvalidFeedPrice.into())
+		}
+	}
+
+	pub fn maximum_total_debit_value(currency_id: CurrencyId) -> Balance {
+		Self::collateral_params(currency_id).maximum_total_debit_value
+	}
+
+	pub fn required_collateral_ratio(currency_id: CurrencyId) -> Option<Ratio> {
+		Self::collateral_params(currency_id).required_collateral_ratio
+	}
+
+	pub fn get_stability_fee(currency_id: CurrencyId) -> Rate {
+		Self::collateral_params(currency_id).stability_fee.unwrap_or_default()
+	}
+
+	pub fn get_liquidation_ratio(currency_id: CurrencyId) -> Ratio {
+		Self::collateral_params(currency_id)
+			.liquidation_ratio
+			.unwrap_or_else(T::DefaultLiquidationRatio::get)
+	}
+
+	pub fn get_liquidation_penalty(currency_id: CurrencyId) -> Rate {
+		Self::collateral_params(currency_id)
+			.liquidation_penalty
+			.unwrap_or_else(T::DefaultLiquidationPenalty::get)
+	}
+
+	pub fn get_debit_exchange_rate(currency_id: CurrencyId) -> ExchangeRate {
+		Self::debit_exchange_rate(currency_id).unwrap_or_else(T::DefaultDebitExchangeRate::get)
+	}
+
+	pub fn get_debit_value(currency_id: CurrencyId, debit_balance: Balance) -> Balance {
+		Self::get_debit_exchange_rate(currency_id).saturating_mul_int(debit_balance)
+	}
+
+	pub fn calculate_collateral_ratio(
+		currency_id: CurrencyId,
+		collateral_balance: Balance,
+		debit_balance: Balance,
+		price: Price,
+	) -> Ratio {
+		let locked_collateral_value = price.saturating_mul_int(collateral_balance);
+		let debit_value = Self::get_debit_value(currency_id, debit_balance);
+
+		Ratio::checked_from_rational(locked_collateral_value, debit_value).unwrap_or_else(Ratio::max_value)
+	}
+
+	/// Adjust the position of `who` under `currency_id`, used by the
+	/// protocols minting stable currency against collateral.
+	#[transactional]
+	pub fn adjust_position(
+		who: &T::AccountId,
+		currency_id: CurrencyId,
+		collateral_adjustment: Amount,
+		debit_adjustment: Amount,
+	) -> DispatchResult {
+		ensure!(
+			T::CollateralCurrencyIds::get().contains(&currency_id),
+			Error::<T>::InvalidCollateralType,
+		);
+		// no new debit can be issued after emergency shutdown
+		ensure!(
+			!debit_adjustment.is_positive() || !T::EmergencyShutdown::is_shutdown(),
+			Error::<T>::AlreadyShutdown,
+		);
+		<LoansOf<T>>::adjust_position(who, currency_id, collateral_adjustment, debit_adjustment)?;
+		Ok(())
+	}
+
+	/// Settle the CDP of `who` with debit by confiscating the collateral
+	/// worth its debit value at the locked price.
+	pub fn settle_cdp_has_debit(who: T::AccountId, currency_id: CurrencyId) -> DispatchResult {
+		let Position { collateral, debit } = <LoansOf<T>>::positions(currency_id, &who);
+		ensure!(!debit.is_zero(), Error::<T>::NoDebitValue);
+
+		// confiscate collateral in cdp to cdp treasury
+		// and decrease CDP's debit to zero
+		let settle_price: Price = T::PriceSource::get_relative_price(T::GetSetUSDId::get(), currency_id)
+			.ok_or(Error::<T>::InvalidFeedPrice)?;
+		let bad_debt_value = Self::get_debit_value(currency_id, debit);
+		let confiscate_collateral_amount =
+			sp_std::cmp::min(settle_price.saturating_mul_int(bad_debt_value), collateral);
+
+		// confiscate collateral and all debit
+		<LoansOf<T>>::confiscate_collateral_and_debit(&who, currency_id, confiscate_collateral_amount, debit)?;
+
+		Self::deposit_event(Event::SettleCDPInDebit(currency_id, who));
+		Ok(())
+	}
+
+	/// Liquidate the unsafe CDP of `who`: confiscate it to the CDP treasury
+	/// and sell the collateral through the DEX when the price impact is
+	/// acceptable, or by collateral auctions otherwise.
+	pub fn liquidate_unsafe_cdp(
+		who: T::AccountId,
+		currency_id: CurrencyId,
+	) -> sp_std::result::Result<LiquidationStrategy, DispatchError> {
+		let Position { collateral, debit } = <LoansOf<T>>::positions(currency_id, &who);
+
+		// ensure the cdp is unsafe
+		ensure!(
+			matches!(
+				Self::check_cdp_status(currency_id, collateral, debit),
+				CDPStatus::Unsafe
+			),
+			Error::<T>::MustBeUnsafe
+		);
+
+		// confiscate all collateral and debit of unsafe cdp to cdp treasury
+		<LoansOf<T>>::confiscate_collateral_and_debit(&who, currency_id, collateral, debit)?;
+
+		let bad_debt_value = Self::get_debit_value(currency_id, debit);
+		let target_stable_amount = Self::get_liquidation_penalty(currency_id).saturating_mul_acc_int(bad_debt_value);
+
+		// the collateral supply accepted for the swap, limited by the slippage
+		// allowed compared to the oracle price
+		let max_supply_limit = Ratio::one()
+			.saturating_sub(T::MaxSwapSlippageCompareToOracle::get())
+			.reciprocal()
+			.unwrap_or_else(Ratio::max_value)
+			.saturating_mul_int(
+				T::PriceSource::get_relative_price(T::GetSetUSDId::get(), currency_id)
+					.ok_or(Error::<T>::InvalidFeedPrice)?
+					.saturating_mul_int(target_stable_amount),
+			);
+		let swap_limit = SwapLimit::ExactTarget(sp_std::cmp::min(max_supply_limit, collateral), target_stable_amount);
+
+		let liquidation_strategy =
+			match <T as Config>::CDPTreasury::swap_collateral_to_stable(currency_id, swap_limit, false) {
+				Ok((actual_supply_collateral, _)) => {
+					// refund the remaining collateral to the CDP owner
+					let refund_collateral_amount = collateral.saturating_sub(actual_supply_collateral);
+					if !refund_collateral_amount.is_zero() {
+						<T as Config>::CDPTreasury::withdraw_collateral(&who, currency_id, refund_collateral_amount)?;
+					}
+					LiquidationStrategy::Exchange
+				}
+				Err(_) => {
+					// the refund of the collateral is done by the auctions
+					<T as Config>::CDPTreasury::create_collateral_auctions(
+						currency_id,
+						collateral,
+						target_stable_amount,
+						who.clone(),
+						true,
+					)?;
+					LiquidationStrategy::Auction
+				}
+			};
+
+		Self::deposit_event(Event::LiquidateUnsafeCDP(
+			currency_id,
+			who,
+			collateral,
+			bad_debt_value,
+			liquidation_strategy,
+		));
+		Ok(liquidation_strategy)
+	}
+}
+
+impl<T: Config> RiskManager<T::AccountId, CurrencyId, Balance, Balance> for Pallet<T> {
+	fn get_debit_value(currency_id: CurrencyId, debit_balance: Balance) -> Balance {
+		Self::get_debit_value(currency_id, debit_balance)
+	}
+
+	fn check_position_valid(
+		currency_id: CurrencyId,
+		collateral_balance: Balance,
+		debit_balance: Balance,
+		check_required_ratio: bool,
+	) -> DispatchResult {
+		if !debit_balance.is_zero() {
+			let debit_value = Self::get_debit_value(currency_id, debit_balance);
+			let feed_price = T::PriceSource::get_relative_price(currency_id, T::GetSetUSDId::get())
+				.ok_or(Error::<T>::InvalidFeedPrice)?;
+			let collateral_ratio =
+				Self::calculate_collateral_ratio(currency_id, collateral_balance, debit_balance, feed_price);
+
+			// check the required collateral ratio
+			if check_required_ratio {
+				if let Some(required_collateral_ratio) = Self::required_collateral_ratio(currency_id) {
+					ensure!(
+						collateral_ratio >= required_collateral_ratio,
+						Error::<T>::BelowRequiredCollateralRatio
+					);
+				}
+			}
+
+			// check the liquidation ratio
+			ensure!(
+				collateral_ratio >= Self::get_liquidation_ratio(currency_id),
+				Error::<T>::BelowLiquidationRatio
+			);
+
+			// check the minimum_debit_value
+			ensure!(
+				debit_value >= T::MinimumDebitValue::get(),
+				Error::<T>::RemainDebitValueTooSmall,
+			);
+		}
+
+		Ok(())
+	}
+
+	fn check_debit_cap(currency_id: CurrencyId, total_debit_balance: Balance) -> DispatchResult {
+		let hard_cap = Self::maximum_total_debit_value(currency_id);
+		let total_debit_value = Self::get_debit_value(currency_id, total_debit_balance);
+
+		ensure!(total_debit_value <= hard_cap, Error::<T>::ExceedDebitValueHardCap);
+
+		Ok(())
+	}
+}
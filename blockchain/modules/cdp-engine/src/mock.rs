@@ -0,0 +1,524 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Mocks for the cdp engine module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{construct_runtime, ord_parameter_types, parameter_types, PalletId};
+use frame_system::EnsureSignedBy;
+use orml_traits::{parameter_type_with_key, MultiCurrency};
+use primitives::TokenSymbol;
+use sp_core::{H160, H256};
+use sp_runtime::{
+	testing::{Header, TestXt},
+	traits::IdentityLookup,
+};
+use sp_std::{cell::RefCell, collections::btree_map::BTreeMap};
+use support::{AuctionManager, DEXManager, SerpTreasury};
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+pub type AuctionId = u32;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CAROL: AccountId = 3;
+pub const SERP_TREASURY: AccountId = 4;
+pub const DEX: AccountId = 5;
+pub const SEE: CurrencyId = CurrencyId::Token(TokenSymbol::SEE);
+pub const SETUSD: CurrencyId = CurrencyId::Token(TokenSymbol::SETUSD);
+pub const SERP: CurrencyId = CurrencyId::Token(TokenSymbol::SERP);
+pub const DNAR: CurrencyId = CurrencyId::Token(TokenSymbol::DNAR);
+
+mod cdp_engine {
+	pub use super::super::*;
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_type_with_key! {
+	pub ExistentialDeposits: |_currency_id: CurrencyId| -> Balance {
+		Default::default()
+	};
+}
+
+impl orml_tokens::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type WeightInfo = ();
+	type ExistentialDeposits = ExistentialDeposits;
+	type OnDust = ();
+	type MaxLocks = ();
+	type DustRemovalWhitelist = ();
+}
+
+thread_local! {
+	static LIQUIDITY_POOLS: RefCell<BTreeMap<(CurrencyId, CurrencyId), (Balance, Balance)>> = RefCell::new(BTreeMap::new());
+	static AUCTIONS: RefCell<Vec<(AccountId, CurrencyId, Balance, Balance)>> = RefCell::new(vec![]);
+	static RELATIVE_PRICE: RefCell<Option<Price>> = RefCell::new(Some(Price::one()));
+	static IS_SHUTDOWN: RefCell<bool> = RefCell::new(false);
+}
+
+/// A DEX swapping at the fixed ratio of its configured pools, with the `DEX`
+/// account as counterparty.
+pub struct MockDEX;
+impl MockDEX {
+	pub fn set_pool(currency_id_a: CurrencyId, currency_id_b: CurrencyId, pool_a: Balance, pool_b: Balance) {
+		LIQUIDITY_POOLS.with(|v| {
+			let mut pools = v.borrow_mut();
+			pools.insert((currency_id_a, currency_id_b), (pool_a, pool_b));
+			pools.insert((currency_id_b, currency_id_a), (pool_b, pool_a));
+		});
+	}
+}
+
+impl DEXManager<AccountId, CurrencyId, Balance> for MockDEX {
+	fn get_liquidity_pool(currency_id_a: CurrencyId, currency_id_b: CurrencyId) -> (Balance, Balance) {
+		LIQUIDITY_POOLS.with(|v| {
+			v.borrow()
+				.get(&(currency_id_a, currency_id_b))
+				.cloned()
+				.unwrap_or_default()
+		})
+	}
+
+	fn get_liquidity_token_address(_currency_id_a: CurrencyId, _currency_id_b: CurrencyId) -> Option<H160> {
+		unimplemented!()
+	}
+
+	fn get_swap_amount(path: &[CurrencyId], limit: SwapLimit<Balance>) -> Option<(Balance, Balance)> {
+		let (supply_pool, target_pool) = Self::get_liquidity_pool(path[0], path[1]);
+		if supply_pool.is_zero() || target_pool.is_zero() {
+			return None;
+		}
+		match limit {
+			SwapLimit::ExactSupply(supply_amount, minimum_target_amount) => {
+				let target_amount = supply_amount * target_pool / supply_pool;
+				if target_amount >= minimum_target_amount {
+					Some((supply_amount, target_amount))
+				} else {
+					None
+				}
+			}
+			SwapLimit::ExactTarget(maximum_supply_amount, target_amount) => {
+				let supply_amount = target_amount * supply_pool / target_pool;
+				if supply_amount <= maximum_supply_amount {
+					Some((supply_amount, target_amount))
+				} else {
+					None
+				}
+			}
+		}
+	}
+
+	fn get_best_price_swap_path(
+		supply_currency_id: CurrencyId,
+		target_currency_id: CurrencyId,
+		_limit: SwapLimit<Balance>,
+		_alternative_path_joint_list: Vec<Vec<CurrencyId>>,
+	) -> Option<Vec<CurrencyId>> {
+		let (supply_pool, _) = Self::get_liquidity_pool(supply_currency_id, target_currency_id);
+		if supply_pool.is_zero() {
+			None
+		} else {
+			Some(vec![supply_currency_id, target_currency_id])
+		}
+	}
+
+	fn swap_with_specific_path(
+		who: &AccountId,
+		path: &[CurrencyId],
+		limit: SwapLimit<Balance>,
+	) -> sp_std::result::Result<(Balance, Balance), DispatchError> {
+		let (supply_amount, target_amount) =
+			Self::get_swap_amount(path, limit).ok_or(DispatchError::Other("mock swap failed"))?;
+		Tokens::transfer(path[0], who, &DEX, supply_amount)?;
+		Tokens::transfer(path[1], &DEX, who, target_amount)?;
+		Ok((supply_amount, target_amount))
+	}
+
+	fn buyback_swap_with_specific_path(
+		who: &AccountId,
+		path: &[CurrencyId],
+		limit: SwapLimit<Balance>,
+	) -> sp_std::result::Result<(Balance, Balance), DispatchError> {
+		Self::swap_with_specific_path(who, path, limit)
+	}
+
+	fn swap_with_exact_target(
+		_who: &AccountId,
+		_path: &[CurrencyId],
+		_exact_target_amount: Balance,
+		_max_supply_amount: Balance,
+	) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn add_liquidity(
+		_who: &AccountId,
+		_currency_id_a: CurrencyId,
+		_currency_id_b: CurrencyId,
+		_max_amount_a: Balance,
+		_max_amount_b: Balance,
+		_min_share_increment: Balance,
+	) -> sp_std::result::Result<(Balance, Balance, Balance), DispatchError> {
+		unimplemented!()
+	}
+
+	fn remove_liquidity(
+		_who: &AccountId,
+		_currency_id_a: CurrencyId,
+		_currency_id_b: CurrencyId,
+		_remove_share: Balance,
+		_min_withdrawn_a: Balance,
+		_min_withdrawn_b: Balance,
+	) -> sp_std::result::Result<(Balance, Balance), DispatchError> {
+		unimplemented!()
+	}
+}
+
+/// An auction manager recording the created collateral auctions.
+pub struct MockAuctionManager;
+impl MockAuctionManager {
+	pub fn auctions() -> Vec<(AccountId, CurrencyId, Balance, Balance)> {
+		AUCTIONS.with(|v| v.borrow().clone())
+	}
+}
+
+impl AuctionManager<AccountId> for MockAuctionManager {
+	type CurrencyId = CurrencyId;
+	type Balance = Balance;
+	type AuctionId = AuctionId;
+
+	fn new_collateral_auction(
+		refund_recipient: &AccountId,
+		currency_id: Self::CurrencyId,
+		amount: Self::Balance,
+		target: Self::Balance,
+	) -> DispatchResult {
+		AUCTIONS.with(|v| v.borrow_mut().push((*refund_recipient, currency_id, amount, target)));
+		Ok(())
+	}
+
+	fn cancel_auction(_id: Self::AuctionId) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn get_total_collateral_in_auction(id: Self::CurrencyId) -> Self::Balance {
+		AUCTIONS.with(|v| {
+			v.borrow()
+				.iter()
+				.filter(|(_, currency_id, _, _)| *currency_id == id)
+				.map(|(_, _, amount, _)| amount)
+				.sum()
+		})
+	}
+
+	fn get_total_target_in_auction() -> Self::Balance {
+		AUCTIONS.with(|v| v.borrow().iter().map(|(_, _, _, target)| target).sum())
+	}
+}
+
+/// A SERP treasury receiving the extracted serplus on the `SERP_TREASURY`
+/// account.
+pub struct MockSerpTreasury;
+impl SerpTreasury<AccountId> for MockSerpTreasury {
+	type Balance = Balance;
+	type CurrencyId = CurrencyId;
+
+	fn calculate_supply_change(_numerator: Balance, _denominator: Balance, _supply: Balance) -> Balance {
+		unimplemented!()
+	}
+
+	fn serp_tes_now() -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn issue_stablecurrency_inflation() -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn get_buyback_serpup(_amount: Balance, _currency_id: CurrencyId) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn add_cashdrop_to_pool(_currency_id: CurrencyId, _amount: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn issue_cashdrop_from_pool(
+		_claimant_id: &AccountId,
+		_currency_id: CurrencyId,
+		_amount: Balance,
+	) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn get_cashdrop_serpup(_amount: Balance, _currency_id: CurrencyId) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn get_buyback_serplus(_amount: Balance, _currency_id: CurrencyId) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn get_cashdrop_serplus(_amount: Balance, _currency_id: CurrencyId) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn on_serplus(currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		Tokens::transfer(currency_id, &CDPTreasuryModule::account_id(), &SERP_TREASURY, amount)
+	}
+
+	fn on_serpup(_currency_id: CurrencyId, _amount: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn on_serpdown(_currency_id: CurrencyId, _amount: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn get_minimum_supply(_currency_id: CurrencyId) -> Balance {
+		unimplemented!()
+	}
+
+	fn issue_standard(_currency_id: CurrencyId, _who: &AccountId, _standard: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn burn_standard(_currency_id: CurrencyId, _who: &AccountId, _standard: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn issue_setter(_who: &AccountId, _setter: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn burn_setter(_who: &AccountId, _setter: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn deposit_setter(_from: &AccountId, _amount: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn claim_cashdrop(_currency_id: CurrencyId, _who: &AccountId, _transfer_amount: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+}
+
+/// A price source with the same relative price for every collateral
+/// against the stable currency.
+pub struct MockPriceSource;
+impl MockPriceSource {
+	pub fn set_relative_price(price: Option<Price>) {
+		RELATIVE_PRICE.with(|v| *v.borrow_mut() = price);
+	}
+}
+
+impl PriceProvider<CurrencyId> for MockPriceSource {
+	fn get_price(_currency_id: CurrencyId) -> Option<Price> {
+		unimplemented!()
+	}
+
+	fn get_relative_price(base: CurrencyId, quote: CurrencyId) -> Option<Price> {
+		let price = RELATIVE_PRICE.with(|v| *v.borrow())?;
+		match (base, quote) {
+			(SETUSD, SETUSD) => Some(Price::one()),
+			(SETUSD, _) => price.reciprocal(),
+			(_, SETUSD) => Some(price),
+			_ => None,
+		}
+	}
+}
+
+pub struct MockEmergencyShutdown;
+impl MockEmergencyShutdown {
+	pub fn set_shutdown(is_shutdown: bool) {
+		IS_SHUTDOWN.with(|v| *v.borrow_mut() = is_shutdown);
+	}
+}
+
+impl EmergencyShutdown for MockEmergencyShutdown {
+	fn is_shutdown() -> bool {
+		IS_SHUTDOWN.with(|v| *v.borrow())
+	}
+}
+
+parameter_types! {
+	pub const LoansPalletId: PalletId = PalletId(*b"set/loan");
+}
+
+impl loans::Config for Runtime {
+	type Event = Event;
+	type Currency = Tokens;
+	type RiskManager = CDPEngineModule;
+	type CDPTreasury = CDPTreasuryModule;
+	type PalletId = LoansPalletId;
+}
+
+ord_parameter_types! {
+	pub const Admin: AccountId = ALICE;
+}
+
+parameter_types! {
+	pub const GetSetUSDId: CurrencyId = SETUSD;
+	pub const MaxAuctionsCount: u32 = 10_000;
+	pub const CDPTreasuryPalletId: PalletId = PalletId(*b"set/cdpt");
+	pub AlternativeSwapPathJointList: Vec<Vec<CurrencyId>> = vec![];
+}
+
+impl cdp_treasury::Config for Runtime {
+	type Event = Event;
+	type UpdateOrigin = EnsureSignedBy<Admin, AccountId>;
+	type Currency = Tokens;
+	type GetSetUSDId = GetSetUSDId;
+	type AuctionManagerHandler = MockAuctionManager;
+	type DEX = MockDEX;
+	type MaxAuctionsCount = MaxAuctionsCount;
+	type PalletId = CDPTreasuryPalletId;
+	type SerpTreasury = MockSerpTreasury;
+	type AlternativeSwapPathJointList = AlternativeSwapPathJointList;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub CollateralCurrencyIds: Vec<CurrencyId> = vec![SEE, SERP];
+	pub DefaultLiquidationRatio: Ratio = Ratio::saturating_from_rational(3, 2);
+	pub DefaultDebitExchangeRate: ExchangeRate = ExchangeRate::saturating_from_rational(1, 10);
+	pub DefaultLiquidationPenalty: Rate = Rate::saturating_from_rational(10, 100);
+	pub const MinimumDebitValue: Balance = 2;
+	pub MaxSwapSlippageCompareToOracle: Ratio = Ratio::saturating_from_rational(50, 100);
+	pub const UnsignedPriority: u64 = 1 << 20;
+}
+
+impl Config for Runtime {
+	type Event = Event;
+	type UpdateOrigin = EnsureSignedBy<Admin, AccountId>;
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type DefaultLiquidationRatio = DefaultLiquidationRatio;
+	type DefaultDebitExchangeRate = DefaultDebitExchangeRate;
+	type DefaultLiquidationPenalty = DefaultLiquidationPenalty;
+	type MinimumDebitValue = MinimumDebitValue;
+	type GetSetUSDId = GetSetUSDId;
+	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
+	type CDPTreasury = CDPTreasuryModule;
+	type PriceSource = MockPriceSource;
+	type UnsignedPriority = UnsignedPriority;
+	type EmergencyShutdown = MockEmergencyShutdown;
+	type WeightInfo = ();
+}
+
+pub type Extrinsic = TestXt<Call, ()>;
+
+impl<LocalCall> SendTransactionTypes<LocalCall> for Runtime
+where
+	Call: From<LocalCall>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = Extrinsic;
+}
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Storage, Config, Event<T>},
+		CDPEngineModule: cdp_engine::{Pallet, Storage, Call, Event<T>, Config, ValidateUnsigned},
+		CDPTreasuryModule: cdp_treasury::{Pallet, Storage, Call, Config, Event<T>},
+		Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
+		LoansModule: loans::{Pallet, Storage, Call, Event<T>},
+	}
+);
+
+pub struct ExtBuilder {
+	balances: Vec<(AccountId, CurrencyId, Balance)>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			balances: vec![
+				(ALICE, SEE, 1000),
+				(BOB, SEE, 1000),
+				(CAROL, SEE, 100),
+				(ALICE, SERP, 1000),
+				(BOB, SERP, 1000),
+				(DEX, SEE, 10_000),
+				(DEX, SETUSD, 10_000),
+			],
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap();
+
+		orml_tokens::GenesisConfig::<Runtime> {
+			balances: self.balances,
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
@@ -0,0 +1,432 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the cdp engine module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use orml_traits::MultiCurrency;
+use sp_core::offchain::{
+	testing::{TestOffchainExt, TestTransactionPoolExt},
+	OffchainDbExt, OffchainWorkerExt, TransactionPoolExt,
+};
+use sp_runtime::traits::{BadOrigin, Dispatchable};
+
+fn setup_default_collateral(currency_id: CurrencyId) {
+	assert_ok!(CDPEngineModule::set_collateral_params(
+		Origin::signed(ALICE),
+		currency_id,
+		Change::NoChange,
+		Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+		Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+		Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+		Change::NewValue(10_000),
+	));
+}
+
+fn make_unsafe(currency_id: CurrencyId) {
+	assert_ok!(CDPEngineModule::set_collateral_params(
+		Origin::signed(ALICE),
+		currency_id,
+		Change::NoChange,
+		Change::NewValue(Some(Ratio::saturating_from_rational(3, 1))),
+		Change::NoChange,
+		Change::NoChange,
+		Change::NoChange,
+	));
+}
+
+#[test]
+fn set_collateral_params_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			CDPEngineModule::set_collateral_params(
+				Origin::signed(BOB),
+				SEE,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+			),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPEngineModule::set_collateral_params(
+				Origin::signed(ALICE),
+				DNAR,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+				Change::NoChange,
+			),
+			Error::<Runtime>::InvalidCollateralType
+		);
+
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(ALICE),
+			SEE,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 100_000))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(3, 2))),
+			Change::NewValue(Some(Rate::saturating_from_rational(2, 10))),
+			Change::NewValue(Some(Ratio::saturating_from_rational(9, 5))),
+			Change::NewValue(10_000),
+		));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::MaximumTotalDebitValueUpdated(
+			SEE, 10_000,
+		)));
+
+		let params = CDPEngineModule::collateral_params(SEE);
+		assert_eq!(params.stability_fee, Some(Rate::saturating_from_rational(1, 100_000)));
+		assert_eq!(params.liquidation_ratio, Some(Ratio::saturating_from_rational(3, 2)));
+		assert_eq!(params.liquidation_penalty, Some(Rate::saturating_from_rational(2, 10)));
+		assert_eq!(
+			params.required_collateral_ratio,
+			Some(Ratio::saturating_from_rational(9, 5))
+		);
+		assert_eq!(params.maximum_total_debit_value, 10_000);
+
+		// `Some(None)` resets to the default
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(ALICE),
+			SEE,
+			Change::NoChange,
+			Change::NewValue(None),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		assert_eq!(
+			CDPEngineModule::get_liquidation_ratio(SEE),
+			DefaultLiquidationRatio::get()
+		);
+	});
+}
+
+#[test]
+fn adjust_position_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_default_collateral(SEE);
+		assert_noop!(
+			CDPEngineModule::adjust_position(&ALICE, DNAR, 100, 500),
+			Error::<Runtime>::InvalidCollateralType
+		);
+		assert_noop!(
+			CDPEngineModule::adjust_position(&ALICE, SEE, 100, 600),
+			Error::<Runtime>::BelowRequiredCollateralRatio
+		);
+
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, SEE, 100, 500));
+		assert_eq!(Tokens::free_balance(SEE, &ALICE), 900);
+		assert_eq!(Tokens::free_balance(SETUSD, &ALICE), 50);
+		assert_eq!(LoansModule::positions(SEE, ALICE).collateral, 100);
+		assert_eq!(LoansModule::positions(SEE, ALICE).debit, 500);
+
+		assert_noop!(
+			CDPEngineModule::adjust_position(&ALICE, SEE, 0, -490),
+			Error::<Runtime>::RemainDebitValueTooSmall
+		);
+
+		// the total debit value is capped
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(ALICE),
+			SEE,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NewValue(60),
+		));
+		assert_noop!(
+			CDPEngineModule::adjust_position(&BOB, SEE, 1000, 200),
+			Error::<Runtime>::ExceedDebitValueHardCap
+		);
+
+		// no more debit after emergency shutdown, repay still works
+		MockEmergencyShutdown::set_shutdown(true);
+		assert_noop!(
+			CDPEngineModule::adjust_position(&ALICE, SEE, 0, 10),
+			Error::<Runtime>::AlreadyShutdown
+		);
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, SEE, -100, -500));
+		assert_eq!(Tokens::free_balance(SEE, &ALICE), 1000);
+		assert_eq!(Tokens::free_balance(SETUSD, &ALICE), 0);
+	});
+}
+
+#[test]
+fn check_cdp_status_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_default_collateral(SEE);
+		assert_eq!(CDPEngineModule::check_cdp_status(SEE, 100, 0), CDPStatus::Safe);
+		assert_eq!(CDPEngineModule::check_cdp_status(SEE, 100, 500), CDPStatus::Safe);
+		assert_eq!(CDPEngineModule::check_cdp_status(SEE, 100, 700), CDPStatus::Unsafe);
+
+		MockPriceSource::set_relative_price(Some(Price::saturating_from_rational(1, 2)));
+		assert_eq!(CDPEngineModule::check_cdp_status(SEE, 100, 500), CDPStatus::Unsafe);
+
+		MockPriceSource::set_relative_price(None);
+		assert_eq!(
+			CDPEngineModule::check_cdp_status(SEE, 100, 500),
+			CDPStatus::ChecksFailed(Error::<Runtime>::InvalidFeedPrice.into())
+		);
+	});
+}
+
+#[test]
+fn liquidate_unsafe_cdp_by_dex_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_default_collateral(SEE);
+		MockDEX::set_pool(SEE, SETUSD, 1000, 1000);
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, SEE, 100, 500));
+
+		assert_noop!(CDPEngineModule::liquidate(Origin::signed(BOB), SEE, ALICE), BadOrigin);
+		assert_noop!(
+			CDPEngineModule::liquidate(Origin::none(), SEE, ALICE),
+			Error::<Runtime>::MustBeUnsafe
+		);
+
+		make_unsafe(SEE);
+		assert_ok!(CDPEngineModule::liquidate(Origin::none(), SEE, ALICE));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::LiquidateUnsafeCDP(
+			SEE,
+			ALICE,
+			100,
+			50,
+			LiquidationStrategy::Exchange,
+		)));
+
+		// 60 SEE are swapped to cover the debit value and the 20% penalty,
+		// the remaining 40 SEE are refunded
+		assert_eq!(LoansModule::positions(SEE, ALICE), Default::default());
+		assert_eq!(Tokens::free_balance(SEE, &ALICE), 940);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 50);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 60);
+		assert_eq!(CDPTreasuryModule::total_collaterals(SEE), 0);
+	});
+}
+
+#[test]
+fn liquidate_unsafe_cdp_by_auction_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_default_collateral(SEE);
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, SEE, 100, 500));
+		make_unsafe(SEE);
+
+		MockEmergencyShutdown::set_shutdown(true);
+		assert_noop!(
+			CDPEngineModule::liquidate(Origin::none(), SEE, ALICE),
+			Error::<Runtime>::AlreadyShutdown
+		);
+		MockEmergencyShutdown::set_shutdown(false);
+
+		// no liquidity in DEX
+		assert_ok!(CDPEngineModule::liquidate(Origin::none(), SEE, ALICE));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::LiquidateUnsafeCDP(
+			SEE,
+			ALICE,
+			100,
+			50,
+			LiquidationStrategy::Auction,
+		)));
+		assert_eq!(MockAuctionManager::auctions(), vec![(ALICE, SEE, 100, 60)]);
+		assert_eq!(CDPTreasuryModule::total_collaterals(SEE), 100);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 50);
+	});
+}
+
+#[test]
+fn settle_cdp_has_debit_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_default_collateral(SEE);
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, SEE, 100, 500));
+		assert_noop!(
+			CDPEngineModule::settle(Origin::none(), SEE, ALICE),
+			Error::<Runtime>::MustAfterShutdown
+		);
+
+		MockEmergencyShutdown::set_shutdown(true);
+		assert_noop!(
+			CDPEngineModule::settle(Origin::none(), SEE, BOB),
+			Error::<Runtime>::NoDebitValue
+		);
+		assert_ok!(CDPEngineModule::settle(Origin::none(), SEE, ALICE));
+		System::assert_last_event(Event::CDPEngineModule(crate::Event::SettleCDPInDebit(SEE, ALICE)));
+		assert_eq!(LoansModule::positions(SEE, ALICE).collateral, 50);
+		assert_eq!(LoansModule::positions(SEE, ALICE).debit, 0);
+		assert_eq!(CDPTreasuryModule::total_collaterals(SEE), 50);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 50);
+	});
+}
+
+#[test]
+fn accumulate_stability_fee_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_default_collateral(SEE);
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, SEE, 100, 500));
+		CDPEngineModule::on_initialize(2);
+		assert_eq!(
+			CDPEngineModule::get_debit_exchange_rate(SEE),
+			DefaultDebitExchangeRate::get()
+		);
+
+		assert_ok!(CDPEngineModule::set_collateral_params(
+			Origin::signed(ALICE),
+			SEE,
+			Change::NewValue(Some(Rate::saturating_from_rational(1, 10))),
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+			Change::NoChange,
+		));
+		CDPEngineModule::on_initialize(3);
+		assert_eq!(
+			CDPEngineModule::get_debit_exchange_rate(SEE),
+			ExchangeRate::saturating_from_rational(11, 100)
+		);
+		assert_eq!(CDPEngineModule::get_debit_value(SEE, 500), 55);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 5);
+
+		// no stability fee after emergency shutdown
+		MockEmergencyShutdown::set_shutdown(true);
+		CDPEngineModule::on_initialize(4);
+		assert_eq!(
+			CDPEngineModule::get_debit_exchange_rate(SEE),
+			ExchangeRate::saturating_from_rational(11, 100)
+		);
+	});
+}
+
+#[test]
+fn offchain_worker_works_cdp() {
+	let (offchain, _offchain_state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = ExtBuilder::default().build();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.register_extension(OffchainDbExt::new(offchain));
+
+	ext.execute_with(|| {
+		let run_offchain_worker_and_dispatch = |block_number: BlockNumber| {
+			System::set_block_number(block_number);
+			CDPEngineModule::offchain_worker(block_number);
+			let tx = pool_state.write().transactions.pop().unwrap();
+			let tx = Extrinsic::decode(&mut &*tx).unwrap();
+			assert_ok!(tx.call.dispatch(Origin::none()));
+		};
+
+		setup_default_collateral(SEE);
+		setup_default_collateral(SERP);
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, SEE, 100, 500));
+		assert_ok!(CDPEngineModule::adjust_position(&BOB, SERP, 100, 500));
+
+		// safe CDPs are left alone
+		CDPEngineModule::offchain_worker(2);
+		assert!(pool_state.write().transactions.pop().is_none());
+
+		// the next run checks the next collateral type
+		make_unsafe(SEE);
+		make_unsafe(SERP);
+		run_offchain_worker_and_dispatch(3);
+		assert_eq!(LoansModule::positions(SERP, BOB), Default::default());
+		assert_eq!(LoansModule::positions(SEE, ALICE).debit, 500);
+
+		run_offchain_worker_and_dispatch(4);
+		assert_eq!(LoansModule::positions(SEE, ALICE), Default::default());
+
+		// settle instead of liquidate after emergency shutdown
+		setup_default_collateral(SEE);
+		assert_ok!(CDPEngineModule::adjust_position(&CAROL, SEE, 100, 500));
+		MockEmergencyShutdown::set_shutdown(true);
+		CDPEngineModule::offchain_worker(5);
+		assert!(pool_state.write().transactions.pop().is_none());
+		run_offchain_worker_and_dispatch(6);
+		assert_eq!(LoansModule::positions(SEE, CAROL).debit, 0);
+		assert_eq!(LoansModule::positions(SEE, CAROL).collateral, 50);
+		assert!(pool_state.write().transactions.pop().is_none());
+	});
+}
+
+#[test]
+fn offchain_worker_checks_limited_cdps_per_run() {
+	let (offchain, _offchain_state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = ExtBuilder::default().build();
+	ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.register_extension(OffchainDbExt::new(offchain));
+
+	ext.execute_with(|| {
+		StorageValueRef::persistent(OFFCHAIN_WORKER_MAX_ITERATIONS).set(&1u32);
+		setup_default_collateral(SEE);
+		setup_default_collateral(SERP);
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, SEE, 100, 500));
+		assert_ok!(CDPEngineModule::adjust_position(&BOB, SEE, 100, 500));
+		make_unsafe(SEE);
+
+		// one CDP is checked per run
+		CDPEngineModule::offchain_worker(2);
+		assert_eq!(pool_state.read().transactions.len(), 1);
+		CDPEngineModule::offchain_worker(3);
+		assert_eq!(pool_state.read().transactions.len(), 2);
+
+		// all SEE CDPs are checked, the next runs move on to SERP and back to SEE
+		CDPEngineModule::offchain_worker(4);
+		CDPEngineModule::offchain_worker(5);
+		assert_eq!(pool_state.read().transactions.len(), 2);
+		CDPEngineModule::offchain_worker(6);
+		assert_eq!(pool_state.read().transactions.len(), 3);
+	});
+}
+
+#[test]
+fn validate_unsigned_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_default_collateral(SEE);
+		assert_ok!(CDPEngineModule::adjust_position(&ALICE, SEE, 100, 500));
+
+		let settle = crate::Call::<Runtime>::settle(SEE, ALICE);
+		assert_eq!(
+			<CDPEngineModule as ValidateUnsigned>::validate_unsigned(
+				TransactionSource::Local,
+				&crate::Call::<Runtime>::liquidate(SEE, ALICE)
+			),
+			InvalidTransaction::Stale.into()
+		);
+
+		make_unsafe(SEE);
+		assert!(<CDPEngineModule as ValidateUnsigned>::validate_unsigned(
+			TransactionSource::Local,
+			&crate::Call::<Runtime>::liquidate(SEE, ALICE)
+		)
+		.is_ok());
+		assert_eq!(
+			<CDPEngineModule as ValidateUnsigned>::validate_unsigned(TransactionSource::Local, &settle),
+			InvalidTransaction::Stale.into()
+		);
+
+		MockEmergencyShutdown::set_shutdown(true);
+		assert!(<CDPEngineModule as ValidateUnsigned>::validate_unsigned(TransactionSource::Local, &settle).is_ok());
+	});
+}
@@ -0,0 +1,108 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Weights for cdp_engine
+//!
+//! Mirrors the weights of the Setheum runtime in
+//! `chains/qingdao/runtime/src/weights/module_cdp_engine.rs`.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(clippy::unnecessary_cast)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for cdp_engine.
+pub trait WeightInfo {
+	fn on_initialize(c: u32, ) -> Weight;
+	fn set_collateral_params() -> Weight;
+	fn liquidate_by_auction(b: u32, ) -> Weight;
+	fn liquidate_by_dex() -> Weight;
+	fn settle() -> Weight;
+}
+
+/// Weights for cdp_engine using the Setheum node and recommended hardware.
+pub struct SetheumWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SetheumWeight<T> {
+	fn on_initialize(c: u32, ) -> Weight {
+		(33_360_000 as Weight)
+			.saturating_add((23_139_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
+	}
+	fn set_collateral_params() -> Weight {
+		(57_130_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn liquidate_by_auction(b: u32, ) -> Weight {
+		(295_312_000 as Weight)
+			.saturating_add((27_764_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(T::DbWeight::get().reads(23 as Weight))
+			.saturating_add(T::DbWeight::get().writes(15 as Weight))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(b as Weight)))
+	}
+	fn liquidate_by_dex() -> Weight {
+		(408_674_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(31 as Weight))
+			.saturating_add(T::DbWeight::get().writes(16 as Weight))
+	}
+	fn settle() -> Weight {
+		(160_596_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(13 as Weight))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn on_initialize(c: u32, ) -> Weight {
+		(33_360_000 as Weight)
+			.saturating_add((23_139_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((4 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(c as Weight)))
+	}
+	fn set_collateral_params() -> Weight {
+		(57_130_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn liquidate_by_auction(b: u32, ) -> Weight {
+		(295_312_000 as Weight)
+			.saturating_add((27_764_000 as Weight).saturating_mul(b as Weight))
+			.saturating_add(RocksDbWeight::get().reads(23 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(15 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(b as Weight)))
+	}
+	fn liquidate_by_dex() -> Weight {
+		(408_674_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(31 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(16 as Weight))
+	}
+	fn settle() -> Weight {
+		(160_596_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(13 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(8 as Weight))
+	}
+}
@@ -0,0 +1,213 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the cdp treasury module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use sp_runtime::traits::BadOrigin;
+
+#[test]
+fn genesis_config_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(CDPTreasuryModule::expected_collateral_auction_size(SEE), 100);
+	});
+}
+
+#[test]
+fn surplus_pool_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 0);
+		assert_ok!(Tokens::deposit(SETUSD, &CDPTreasuryModule::account_id(), 500));
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 500);
+	});
+}
+
+#[test]
+fn on_system_debit_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
+		assert_ok!(CDPTreasuryModule::on_system_debit(1000));
+		assert_eq!(CDPTreasuryModule::debit_pool(), 1000);
+		assert_noop!(
+			CDPTreasuryModule::on_system_debit(Balance::max_value()),
+			Error::<Runtime>::DebitPoolOverflow,
+		);
+	});
+}
+
+#[test]
+fn issue_debit_and_burn_debit_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPTreasuryModule::issue_debit(&BOB, 300, true));
+		assert_eq!(Tokens::free_balance(SETUSD, &BOB), 300);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
+
+		assert_ok!(CDPTreasuryModule::issue_debit(&BOB, 200, false));
+		assert_eq!(Tokens::free_balance(SETUSD, &BOB), 500);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 200);
+
+		assert_ok!(CDPTreasuryModule::burn_debit(&BOB, 500));
+		assert_eq!(Tokens::free_balance(SETUSD, &BOB), 0);
+		assert!(CDPTreasuryModule::burn_debit(&BOB, 1).is_err());
+	});
+}
+
+#[test]
+fn offset_surplus_and_debit_on_finalize_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPTreasuryModule::on_system_surplus(1000));
+		assert_ok!(CDPTreasuryModule::on_system_debit(300));
+		CDPTreasuryModule::on_finalize(1);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 700);
+		assert_eq!(CDPTreasuryModule::debit_pool(), 0);
+		assert_eq!(Tokens::total_issuance(SETUSD), 11_700);
+	});
+}
+
+#[test]
+fn deposit_and_withdraw_collateral_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&BOB, SEE, 500));
+		assert_eq!(CDPTreasuryModule::total_collaterals(SEE), 500);
+		assert_eq!(Tokens::free_balance(SEE, &BOB), 500);
+		assert!(CDPTreasuryModule::withdraw_collateral(&BOB, SEE, 501).is_err());
+		assert_ok!(CDPTreasuryModule::withdraw_collateral(&BOB, SEE, 400));
+		assert_eq!(CDPTreasuryModule::total_collaterals(SEE), 100);
+		assert_eq!(Tokens::free_balance(SEE, &BOB), 900);
+	});
+}
+
+#[test]
+fn get_debit_proportion_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		// total issuance of SETUSD is 11_000
+		assert_eq!(
+			CDPTreasuryModule::get_debit_proportion(1_100),
+			Ratio::saturating_from_rational(1, 10)
+		);
+	});
+}
+
+#[test]
+fn swap_collateral_to_stable_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&BOB, SEE, 500));
+		assert_noop!(
+			CDPTreasuryModule::swap_collateral_to_stable(SEE, SwapLimit::ExactSupply(600, 0), false),
+			Error::<Runtime>::CollateralNotEnough,
+		);
+		assert_noop!(
+			CDPTreasuryModule::swap_collateral_to_stable(SERP, SwapLimit::ExactSupply(0, 0), false),
+			Error::<Runtime>::InvalidSwapPath,
+		);
+
+		assert_eq!(
+			CDPTreasuryModule::swap_collateral_to_stable(SEE, SwapLimit::ExactTarget(500, 400), false),
+			Ok((200, 400))
+		);
+		assert_eq!(CDPTreasuryModule::total_collaterals(SEE), 300);
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 400);
+
+		// collateral in auction can only be swapped with `collateral_in_auction`
+		assert_ok!(CDPTreasuryModule::create_collateral_auctions(SEE, 300, 600, BOB, false));
+		assert_noop!(
+			CDPTreasuryModule::swap_collateral_to_stable(SEE, SwapLimit::ExactSupply(100, 0), false),
+			Error::<Runtime>::CollateralNotEnough,
+		);
+		assert_eq!(
+			CDPTreasuryModule::swap_collateral_to_stable(SEE, SwapLimit::ExactSupply(100, 0), true),
+			Ok((100, 200))
+		);
+	});
+}
+
+#[test]
+fn create_collateral_auctions_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&BOB, SEE, 1000));
+		assert_noop!(
+			CDPTreasuryModule::create_collateral_auctions(SEE, 1001, 1000, ALICE, true),
+			Error::<Runtime>::CollateralNotEnough,
+		);
+
+		// not splited
+		assert_ok!(CDPTreasuryModule::create_collateral_auctions(
+			SEE, 250, 500, ALICE, false
+		));
+		assert_eq!(MockAuctionManager::auctions(), vec![(ALICE, SEE, 250, 500)]);
+
+		// splited into lots of the expected size
+		assert_ok!(CDPTreasuryModule::create_collateral_auctions(
+			SEE, 250, 500, ALICE, true
+		));
+		assert_eq!(
+			MockAuctionManager::auctions()[1..],
+			[(ALICE, SEE, 83, 166), (ALICE, SEE, 83, 166), (ALICE, SEE, 84, 168)]
+		);
+
+		// capped by MaxAuctionsCount
+		assert_ok!(CDPTreasuryModule::create_collateral_auctions(
+			SEE, 500, 1000, ALICE, true
+		));
+		assert_eq!(MockAuctionManager::auctions().len(), 9);
+		assert_eq!(MockAuctionManager::get_total_collateral_in_auction(SEE), 1000);
+	});
+}
+
+#[test]
+fn extract_surplus_to_serp_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPTreasuryModule::on_system_surplus(1000));
+		assert_noop!(
+			CDPTreasuryModule::extract_surplus_to_serp(Origin::signed(BOB), 200),
+			BadOrigin
+		);
+		assert_noop!(
+			CDPTreasuryModule::extract_surplus_to_serp(Origin::signed(ALICE), 1001),
+			Error::<Runtime>::SurplusPoolNotEnough
+		);
+		assert_ok!(CDPTreasuryModule::extract_surplus_to_serp(Origin::signed(ALICE), 200));
+		assert_eq!(CDPTreasuryModule::surplus_pool(), 800);
+		assert_eq!(Tokens::free_balance(SETUSD, &SERP_TREASURY), 200);
+	});
+}
+
+#[test]
+fn set_expected_collateral_auction_size_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			CDPTreasuryModule::set_expected_collateral_auction_size(Origin::signed(BOB), SEE, 200),
+			BadOrigin
+		);
+		assert_ok!(CDPTreasuryModule::set_expected_collateral_auction_size(
+			Origin::signed(ALICE),
+			SEE,
+			200
+		));
+		assert_eq!(CDPTreasuryModule::expected_collateral_auction_size(SEE), 200);
+		System::assert_last_event(Event::CDPTreasuryModule(
+			crate::Event::ExpectedCollateralAuctionSizeUpdated(SEE, 200),
+		));
+	});
+}
@@ -0,0 +1,353 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Mocks for the emergency shutdown module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{construct_runtime, ord_parameter_types, parameter_types, PalletId};
+use frame_system::EnsureSignedBy;
+use orml_traits::{parameter_type_with_key, MultiCurrency};
+use primitives::{Amount, TokenSymbol};
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup, DispatchError};
+use sp_std::{cell::RefCell, collections::btree_set::BTreeSet};
+use support::SerpTreasury;
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+pub type AuctionId = u32;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CAROL: AccountId = 3;
+pub const SERP_TREASURY: AccountId = 4;
+pub const SETUSD: CurrencyId = CurrencyId::Token(TokenSymbol::SETUSD);
+pub const SEE: CurrencyId = CurrencyId::Token(TokenSymbol::SEE);
+pub const SERP: CurrencyId = CurrencyId::Token(TokenSymbol::SERP);
+pub const DNAR: CurrencyId = CurrencyId::Token(TokenSymbol::DNAR);
+
+mod emergency_shutdown {
+	pub use super::super::*;
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_type_with_key! {
+	pub ExistentialDeposits: |_currency_id: CurrencyId| -> Balance {
+		Default::default()
+	};
+}
+
+impl orml_tokens::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type WeightInfo = ();
+	type ExistentialDeposits = ExistentialDeposits;
+	type OnDust = ();
+	type MaxLocks = ();
+	type DustRemovalWhitelist = ();
+}
+
+parameter_types! {
+	pub const LoansPalletId: PalletId = PalletId(*b"set/loan");
+}
+
+impl loans::Config for Runtime {
+	type Event = Event;
+	type Currency = Tokens;
+	type RiskManager = ();
+	type CDPTreasury = CDPTreasuryModule;
+	type PalletId = LoansPalletId;
+}
+
+thread_local! {
+	static TOTAL_COLLATERAL_IN_AUCTION: RefCell<Balance> = RefCell::new(0);
+	static LOCKED_PRICES: RefCell<BTreeSet<CurrencyId>> = RefCell::new(BTreeSet::new());
+}
+
+/// An auction manager with a configurable amount of collateral in auction.
+pub struct MockAuctionManager;
+impl MockAuctionManager {
+	pub fn set_total_collateral_in_auction(amount: Balance) {
+		TOTAL_COLLATERAL_IN_AUCTION.with(|v| *v.borrow_mut() = amount);
+	}
+}
+
+impl AuctionManager<AccountId> for MockAuctionManager {
+	type CurrencyId = CurrencyId;
+	type Balance = Balance;
+	type AuctionId = AuctionId;
+
+	fn new_collateral_auction(
+		_refund_recipient: &AccountId,
+		_currency_id: Self::CurrencyId,
+		_amount: Self::Balance,
+		_target: Self::Balance,
+	) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn cancel_auction(_id: Self::AuctionId) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn get_total_collateral_in_auction(_id: Self::CurrencyId) -> Self::Balance {
+		TOTAL_COLLATERAL_IN_AUCTION.with(|v| *v.borrow())
+	}
+
+	fn get_total_target_in_auction() -> Self::Balance {
+		Default::default()
+	}
+}
+
+/// A SERP treasury receiving the extracted serplus on the `SERP_TREASURY`
+/// account.
+pub struct MockSerpTreasury;
+impl SerpTreasury<AccountId> for MockSerpTreasury {
+	type Balance = Balance;
+	type CurrencyId = CurrencyId;
+
+	fn calculate_supply_change(_numerator: Balance, _denominator: Balance, _supply: Balance) -> Balance {
+		unimplemented!()
+	}
+
+	fn serp_tes_now() -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn issue_stablecurrency_inflation() -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn get_buyback_serpup(_amount: Balance, _currency_id: CurrencyId) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn add_cashdrop_to_pool(_currency_id: CurrencyId, _amount: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn issue_cashdrop_from_pool(
+		_claimant_id: &AccountId,
+		_currency_id: CurrencyId,
+		_amount: Balance,
+	) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn get_cashdrop_serpup(_amount: Balance, _currency_id: CurrencyId) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn get_buyback_serplus(_amount: Balance, _currency_id: CurrencyId) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn get_cashdrop_serplus(_amount: Balance, _currency_id: CurrencyId) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn on_serplus(currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		Tokens::transfer(currency_id, &CDPTreasuryModule::account_id(), &SERP_TREASURY, amount)
+	}
+
+	fn on_serpup(_currency_id: CurrencyId, _amount: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn on_serpdown(_currency_id: CurrencyId, _amount: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn get_minimum_supply(_currency_id: CurrencyId) -> Balance {
+		unimplemented!()
+	}
+
+	fn issue_standard(_currency_id: CurrencyId, _who: &AccountId, _standard: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn burn_standard(_currency_id: CurrencyId, _who: &AccountId, _standard: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn issue_setter(_who: &AccountId, _setter: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn burn_setter(_who: &AccountId, _setter: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn deposit_setter(_from: &AccountId, _amount: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn claim_cashdrop(_currency_id: CurrencyId, _who: &AccountId, _transfer_amount: Balance) -> DispatchResult {
+		unimplemented!()
+	}
+}
+
+/// A price source with feed prices for all currencies but `DNAR`, recording
+/// the locked ones.
+pub struct MockLockablePrice;
+impl MockLockablePrice {
+	pub fn is_locked(currency_id: CurrencyId) -> bool {
+		LOCKED_PRICES.with(|v| v.borrow().contains(&currency_id))
+	}
+}
+
+impl LockablePrice<CurrencyId> for MockLockablePrice {
+	fn lock_price(currency_id: CurrencyId) -> DispatchResult {
+		if currency_id == DNAR {
+			return Err(DispatchError::Other("no feed price"));
+		}
+		LOCKED_PRICES.with(|v| v.borrow_mut().insert(currency_id));
+		Ok(())
+	}
+
+	fn unlock_price(currency_id: CurrencyId) -> DispatchResult {
+		LOCKED_PRICES.with(|v| v.borrow_mut().remove(&currency_id));
+		Ok(())
+	}
+}
+
+ord_parameter_types! {
+	pub const Admin: AccountId = ALICE;
+}
+
+parameter_types! {
+	pub const GetSetUSDId: CurrencyId = SETUSD;
+	pub const MaxAuctionsCount: u32 = 10_000;
+	pub const CDPTreasuryPalletId: PalletId = PalletId(*b"set/cdpt");
+	pub AlternativeSwapPathJointList: Vec<Vec<CurrencyId>> = vec![];
+}
+
+impl cdp_treasury::Config for Runtime {
+	type Event = Event;
+	type UpdateOrigin = EnsureSignedBy<Admin, AccountId>;
+	type Currency = Tokens;
+	type GetSetUSDId = GetSetUSDId;
+	type AuctionManagerHandler = MockAuctionManager;
+	type DEX = ();
+	type MaxAuctionsCount = MaxAuctionsCount;
+	type PalletId = CDPTreasuryPalletId;
+	type SerpTreasury = MockSerpTreasury;
+	type AlternativeSwapPathJointList = AlternativeSwapPathJointList;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub CollateralCurrencyIds: Vec<CurrencyId> = vec![SEE, SERP, DNAR];
+}
+
+impl Config for Runtime {
+	type Event = Event;
+	type CollateralCurrencyIds = CollateralCurrencyIds;
+	type PriceSource = MockLockablePrice;
+	type CDPTreasury = CDPTreasuryModule;
+	type AuctionManagerHandler = MockAuctionManager;
+	type ShutdownOrigin = EnsureSignedBy<Admin, AccountId>;
+	type WeightInfo = ();
+}
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Storage, Config, Event<T>},
+		EmergencyShutdownModule: emergency_shutdown::{Pallet, Storage, Call, Event<T>},
+		Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
+		LoansModule: loans::{Pallet, Storage, Call, Event<T>},
+		CDPTreasuryModule: cdp_treasury::{Pallet, Storage, Call, Config, Event<T>},
+	}
+);
+
+pub struct ExtBuilder {
+	balances: Vec<(AccountId, CurrencyId, Balance)>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			balances: vec![
+				(ALICE, SEE, 1000),
+				(ALICE, SERP, 1000),
+				(BOB, SETUSD, 200),
+				(CAROL, SETUSD, 800),
+			],
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap();
+
+		orml_tokens::GenesisConfig::<Runtime> {
+			balances: self.balances,
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
@@ -0,0 +1,119 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the emergency shutdown module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use sp_runtime::traits::BadOrigin;
+
+#[test]
+fn emergency_shutdown_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert!(!EmergencyShutdownModule::is_shutdown());
+		assert_noop!(
+			EmergencyShutdownModule::emergency_shutdown(Origin::signed(BOB)),
+			BadOrigin
+		);
+
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(Origin::signed(ALICE)));
+		System::assert_last_event(Event::EmergencyShutdownModule(crate::Event::Shutdown(1)));
+		assert!(EmergencyShutdownModule::is_shutdown());
+		assert!(<EmergencyShutdownModule as EmergencyShutdown>::is_shutdown());
+		assert!(MockLockablePrice::is_locked(SEE));
+		assert!(MockLockablePrice::is_locked(SERP));
+		assert!(!MockLockablePrice::is_locked(DNAR));
+
+		assert_noop!(
+			EmergencyShutdownModule::emergency_shutdown(Origin::signed(ALICE)),
+			Error::<Runtime>::AlreadyShutdown,
+		);
+	});
+}
+
+#[test]
+fn open_collateral_refund_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			EmergencyShutdownModule::open_collateral_refund(Origin::signed(ALICE)),
+			Error::<Runtime>::MustAfterShutdown,
+		);
+
+		assert_ok!(LoansModule::adjust_position(&ALICE, SEE, 100, 50));
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(Origin::signed(ALICE)));
+		assert_noop!(
+			EmergencyShutdownModule::open_collateral_refund(Origin::signed(BOB)),
+			BadOrigin
+		);
+
+		MockAuctionManager::set_total_collateral_in_auction(100);
+		assert_noop!(
+			EmergencyShutdownModule::open_collateral_refund(Origin::signed(ALICE)),
+			Error::<Runtime>::ExistPotentialSurplus,
+		);
+
+		MockAuctionManager::set_total_collateral_in_auction(0);
+		assert_noop!(
+			EmergencyShutdownModule::open_collateral_refund(Origin::signed(ALICE)),
+			Error::<Runtime>::ExistUnhandledDebit,
+		);
+
+		assert_ok!(LoansModule::adjust_position(&ALICE, SEE, 0, -50));
+		assert_ok!(EmergencyShutdownModule::open_collateral_refund(Origin::signed(ALICE)));
+		System::assert_last_event(Event::EmergencyShutdownModule(crate::Event::OpenRefund(1)));
+		assert!(EmergencyShutdownModule::can_refund());
+	});
+}
+
+#[test]
+fn refund_collaterals_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&ALICE, SEE, 100));
+		assert_ok!(CDPTreasuryModule::deposit_collateral(&ALICE, SERP, 200));
+		assert_ok!(EmergencyShutdownModule::emergency_shutdown(Origin::signed(ALICE)));
+		assert_noop!(
+			EmergencyShutdownModule::refund_collaterals(Origin::signed(BOB), 200),
+			Error::<Runtime>::CanNotRefund,
+		);
+
+		assert_ok!(EmergencyShutdownModule::open_collateral_refund(Origin::signed(ALICE)));
+		assert!(EmergencyShutdownModule::refund_collaterals(Origin::signed(BOB), 201).is_err());
+
+		// BOB holds a fifth of the stable currency supply
+		assert_ok!(EmergencyShutdownModule::refund_collaterals(Origin::signed(BOB), 200));
+		System::assert_last_event(Event::EmergencyShutdownModule(crate::Event::Refund(
+			BOB,
+			200,
+			vec![(SEE, 20), (SERP, 40)],
+		)));
+		assert_eq!(Tokens::free_balance(SETUSD, &BOB), 0);
+		assert_eq!(Tokens::free_balance(SEE, &BOB), 20);
+		assert_eq!(Tokens::free_balance(SERP, &BOB), 40);
+		assert_eq!(CDPTreasuryModule::total_collaterals(SEE), 80);
+		assert_eq!(CDPTreasuryModule::total_collaterals(SERP), 160);
+
+		assert_ok!(EmergencyShutdownModule::refund_collaterals(Origin::signed(CAROL), 800));
+		assert_eq!(Tokens::free_balance(SEE, &CAROL), 80);
+		assert_eq!(Tokens::free_balance(SERP, &CAROL), 160);
+	});
+}
@@ -122,7 +122,17 @@ pub mod module {
 	pub struct Pallet<T>(_);
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			Self::check_invariants()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::check_invariants()
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
@@ -236,6 +246,24 @@ impl<T: Config> Pallet<T> {
 		r[64] = recovery_id.serialize();
 		EcdsaSignature::from_slice(&r)
 	}
+
+	/// Check that the account mappings are the inverse of each other.
+	#[cfg(feature = "try-runtime")]
+	pub fn check_invariants() -> Result<(), &'static str> {
+		for (evm_address, account_id) in Accounts::<T>::iter() {
+			ensure!(
+				EvmAddresses::<T>::get(&account_id) == Some(evm_address),
+				"EvmAddresses does not map back to the claimed EVM address"
+			);
+		}
+		for (account_id, evm_address) in EvmAddresses::<T>::iter() {
+			ensure!(
+				Accounts::<T>::get(evm_address) == Some(account_id),
+				"Accounts does not map back to the claiming account"
+			);
+		}
+		Ok(())
+	}
 }
 
 // Creates a an EvmAddress from an AccountId by appending the bytes "evm:" to
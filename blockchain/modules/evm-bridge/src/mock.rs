@@ -100,6 +100,7 @@ impl pallet_timestamp::Config for Runtime {
 parameter_types! {
 	pub const NewContractExtraBytes: u32 = 1;
 	pub NetworkContractSource: EvmAddress = alice_evm_addr();
+	pub const Hardfork: module_evm::EvmHardfork = module_evm::EvmHardfork::Istanbul;
 }
 
 ord_parameter_types! {
@@ -109,6 +110,7 @@ ord_parameter_types! {
 	pub const StorageDepositPerByte: u128 = 10;
 	pub const DeveloperDeposit: u64 = 1000;
 	pub const DeploymentFee: u64 = 200;
+	pub const DefaultUpgradeDelay: BlockNumber = 0;
 }
 
 impl module_evm::Config for Runtime {
@@ -120,6 +122,7 @@ impl module_evm::Config for Runtime {
 	type Event = Event;
 	type Precompiles = ();
 	type ChainId = ();
+	type Hardfork = Hardfork;
 	type GasToWeight = ();
 	type ChargeTransactionPayment = ();
 	type NetworkContractOrigin = EnsureSignedBy<NetworkContractAccount, AccountId32>;
@@ -127,11 +130,15 @@ impl module_evm::Config for Runtime {
 
 	type DeveloperDeposit = DeveloperDeposit;
 	type DeploymentFee = DeploymentFee;
+	type DefaultUpgradeDelay = DefaultUpgradeDelay;
 	type TreasuryAccount = TreasuryAccount;
 	type FreeDeploymentOrigin = EnsureSignedBy<CouncilAccount, AccountId32>;
 
+	type CallFilter = ();
 	type Runner = module_evm::runner::stack::Runner<Self>;
 	type FindAuthor = ();
+	type Task = module_evm::EvmTask<Runtime>;
+	type IdleScheduler = ();
 	type WeightInfo = ();
 }
 
@@ -188,7 +195,7 @@ pub fn bob_evm_addr() -> EvmAddress {
 
 pub fn deploy_contracts() {
 	let code = from_hex(include!("./erc20_demo_contract")).unwrap();
-	assert_ok!(EVM::create(Origin::signed(alice()), code, 0, 2_100_000, 10000));
+	assert_ok!(EVM::create(Origin::signed(alice()), code, 0, 2_100_000, 10000, vec![]));
 
 	System::assert_last_event(Event::EVM(module_evm::Event::Created(
 		alice_evm_addr(),
@@ -24,14 +24,16 @@
 //!
 //! Evm manager module provides common support features for Evm, including:
 //! - A two way mapping between `u32` and `Erc20 address` so user can use Erc20 address as LP token.
+//! - The names, symbols, decimals and mirrored addresses of the assets registered at runtime, which
+//!   take precedence over the built-in `TokenInfo`.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::unused_unit)]
 
 use frame_support::{ensure, pallet_prelude::*, require_transactional, traits::Currency};
-use module_support::{CurrencyIdMapping, EVMBridge, InvokeContext};
+use module_support::{AssetMetadataProvider, CurrencyIdMapping, EVMBridge, InvokeContext};
 use primitives::{
-	currency::TokenInfo,
+	currency::{DexShareType, TokenInfo},
 	evm::{Erc20Info, EvmAddress},
 	*,
 };
@@ -55,6 +57,8 @@ pub mod module {
 	pub trait Config: frame_system::Config {
 		type Currency: Currency<Self::AccountId>;
 		type EVMBridge: EVMBridge<Self::AccountId, BalanceOf<Self>>;
+		/// The assets registered at runtime.
+		type AssetMetadataProvider: AssetMetadataProvider;
 	}
 
 	/// Error for evm accounts module.
@@ -127,33 +131,47 @@ impl<T: Config> CurrencyIdMapping for EvmCurrencyIdMapping<T> {
 	// If CurrencyId is CurrencyId::DexShare and contain DexShare::Erc20,
 	// the EvmAddress must have been mapped.
 	fn name(currency_id: CurrencyId) -> Option<Vec<u8>> {
-		let name = match currency_id {
-			CurrencyId::Token(_) => currency_id.name().map(|v| v.as_bytes().to_vec()),
-			CurrencyId::DexShare(symbol_0, symbol_1) => {
-				let name_0 = match symbol_0 {
-					DexShare::Token(symbol) => CurrencyId::Token(symbol).name().map(|v| v.as_bytes().to_vec()),
-					DexShare::Erc20(address) => CurrencyIdMap::<T>::get(Into::<u32>::into(symbol_0))
-						.filter(|v| v.address == address)
-						.map(|v| v.name),
-				}?;
-				let name_1 = match symbol_1 {
-					DexShare::Token(symbol) => CurrencyId::Token(symbol).name().map(|v| v.as_bytes().to_vec()),
-					DexShare::Erc20(address) => CurrencyIdMap::<T>::get(Into::<u32>::into(symbol_1))
-						.filter(|v| v.address == address)
-						.map(|v| v.name),
-				}?;
-
-				let mut vec = Vec::new();
-				vec.extend_from_slice(&b"LP "[..]);
-				vec.extend_from_slice(&name_0);
-				vec.extend_from_slice(&b" - ".to_vec());
-				vec.extend_from_slice(&name_1);
-				Some(vec)
-			}
-			CurrencyId::Erc20(address) => CurrencyIdMap::<T>::get(Into::<u32>::into(DexShare::Erc20(address)))
-				.filter(|v| v.address == address)
-				.map(|v| v.name),
-		}?;
+		let name = match T::AssetMetadataProvider::asset_metadata(currency_id) {
+			// Registered asset
+			Some(metadata) => metadata.name,
+			None => match currency_id {
+				CurrencyId::Token(_) => currency_id.name().map(|v| v.as_bytes().to_vec()),
+				CurrencyId::DexShare(symbol_0, symbol_1) => {
+					let name_0 = match symbol_0 {
+						DexShare::Token(symbol) => CurrencyId::Token(symbol).name().map(|v| v.as_bytes().to_vec()),
+						DexShare::Erc20(address) => CurrencyIdMap::<T>::get(Into::<u32>::into(symbol_0))
+							.filter(|v| v.address == address)
+							.map(|v| v.name),
+						DexShare::ForeignAsset(foreign_asset_id) => {
+							T::AssetMetadataProvider::asset_metadata(CurrencyId::ForeignAsset(foreign_asset_id))
+								.map(|v| v.name)
+						}
+					}?;
+					let name_1 = match symbol_1 {
+						DexShare::Token(symbol) => CurrencyId::Token(symbol).name().map(|v| v.as_bytes().to_vec()),
+						DexShare::Erc20(address) => CurrencyIdMap::<T>::get(Into::<u32>::into(symbol_1))
+							.filter(|v| v.address == address)
+							.map(|v| v.name),
+						DexShare::ForeignAsset(foreign_asset_id) => {
+							T::AssetMetadataProvider::asset_metadata(CurrencyId::ForeignAsset(foreign_asset_id))
+								.map(|v| v.name)
+						}
+					}?;
+
+					let mut vec = Vec::new();
+					vec.extend_from_slice(&b"LP "[..]);
+					vec.extend_from_slice(&name_0);
+					vec.extend_from_slice(&b" - ".to_vec());
+					vec.extend_from_slice(&name_1);
+					Some(vec)
+				}
+				CurrencyId::Erc20(address) => CurrencyIdMap::<T>::get(Into::<u32>::into(DexShare::Erc20(address)))
+					.filter(|v| v.address == address)
+					.map(|v| v.name),
+				// Unregistered foreign asset, or stable pool share without metadata
+				CurrencyId::ForeignAsset(_) | CurrencyId::StableAssetPoolToken(_) => None,
+			}?,
+		};
 
 		// More than 32 bytes will be truncated.
 		if name.len() > 32 {
@@ -167,33 +185,47 @@ impl<T: Config> CurrencyIdMapping for EvmCurrencyIdMapping<T> {
 	// If CurrencyId is CurrencyId::DexShare and contain DexShare::Erc20,
 	// the EvmAddress must have been mapped.
 	fn symbol(currency_id: CurrencyId) -> Option<Vec<u8>> {
-		let symbol = match currency_id {
-			CurrencyId::Token(_) => currency_id.symbol().map(|v| v.as_bytes().to_vec()),
-			CurrencyId::DexShare(symbol_0, symbol_1) => {
-				let token_symbol_0 = match symbol_0 {
-					DexShare::Token(symbol) => CurrencyId::Token(symbol).symbol().map(|v| v.as_bytes().to_vec()),
-					DexShare::Erc20(address) => CurrencyIdMap::<T>::get(Into::<u32>::into(symbol_0))
-						.filter(|v| v.address == address)
-						.map(|v| v.symbol),
-				}?;
-				let token_symbol_1 = match symbol_1 {
-					DexShare::Token(symbol) => CurrencyId::Token(symbol).symbol().map(|v| v.as_bytes().to_vec()),
-					DexShare::Erc20(address) => CurrencyIdMap::<T>::get(Into::<u32>::into(symbol_1))
-						.filter(|v| v.address == address)
-						.map(|v| v.symbol),
-				}?;
-
-				let mut vec = Vec::new();
-				vec.extend_from_slice(&b"LP_"[..]);
-				vec.extend_from_slice(&token_symbol_0);
-				vec.extend_from_slice(&b"_".to_vec());
-				vec.extend_from_slice(&token_symbol_1);
-				Some(vec)
-			}
-			CurrencyId::Erc20(address) => CurrencyIdMap::<T>::get(Into::<u32>::into(DexShare::Erc20(address)))
-				.filter(|v| v.address == address)
-				.map(|v| v.symbol),
-		}?;
+		let symbol = match T::AssetMetadataProvider::asset_metadata(currency_id) {
+			// Registered asset
+			Some(metadata) => metadata.symbol,
+			None => match currency_id {
+				CurrencyId::Token(_) => currency_id.symbol().map(|v| v.as_bytes().to_vec()),
+				CurrencyId::DexShare(symbol_0, symbol_1) => {
+					let token_symbol_0 = match symbol_0 {
+						DexShare::Token(symbol) => CurrencyId::Token(symbol).symbol().map(|v| v.as_bytes().to_vec()),
+						DexShare::Erc20(address) => CurrencyIdMap::<T>::get(Into::<u32>::into(symbol_0))
+							.filter(|v| v.address == address)
+							.map(|v| v.symbol),
+						DexShare::ForeignAsset(foreign_asset_id) => {
+							T::AssetMetadataProvider::asset_metadata(CurrencyId::ForeignAsset(foreign_asset_id))
+								.map(|v| v.symbol)
+						}
+					}?;
+					let token_symbol_1 = match symbol_1 {
+						DexShare::Token(symbol) => CurrencyId::Token(symbol).symbol().map(|v| v.as_bytes().to_vec()),
+						DexShare::Erc20(address) => CurrencyIdMap::<T>::get(Into::<u32>::into(symbol_1))
+							.filter(|v| v.address == address)
+							.map(|v| v.symbol),
+						DexShare::ForeignAsset(foreign_asset_id) => {
+							T::AssetMetadataProvider::asset_metadata(CurrencyId::ForeignAsset(foreign_asset_id))
+								.map(|v| v.symbol)
+						}
+					}?;
+
+					let mut vec = Vec::new();
+					vec.extend_from_slice(&b"LP_"[..]);
+					vec.extend_from_slice(&token_symbol_0);
+					vec.extend_from_slice(&b"_".to_vec());
+					vec.extend_from_slice(&token_symbol_1);
+					Some(vec)
+				}
+				CurrencyId::Erc20(address) => CurrencyIdMap::<T>::get(Into::<u32>::into(DexShare::Erc20(address)))
+					.filter(|v| v.address == address)
+					.map(|v| v.symbol),
+				// Unregistered foreign asset, or stable pool share without metadata
+				CurrencyId::ForeignAsset(_) | CurrencyId::StableAssetPoolToken(_) => None,
+			}?,
+		};
 
 		// More than 32 bytes will be truncated.
 		if symbol.len() > 32 {
@@ -207,6 +239,11 @@ impl<T: Config> CurrencyIdMapping for EvmCurrencyIdMapping<T> {
 	// If CurrencyId is CurrencyId::DexShare and contain DexShare::Erc20,
 	// the EvmAddress must have been mapped.
 	fn decimals(currency_id: CurrencyId) -> Option<u8> {
+		// Registered asset
+		if let Some(metadata) = T::AssetMetadataProvider::asset_metadata(currency_id) {
+			return Some(metadata.decimals);
+		}
+
 		match currency_id {
 			CurrencyId::Token(_) => currency_id.decimals(),
 			CurrencyId::DexShare(symbol_0, _) => {
@@ -217,11 +254,17 @@ impl<T: Config> CurrencyIdMapping for EvmCurrencyIdMapping<T> {
 					DexShare::Erc20(address) => CurrencyIdMap::<T>::get(Into::<u32>::into(symbol_0))
 						.filter(|v| v.address == address)
 						.map(|v| v.decimals),
+					DexShare::ForeignAsset(foreign_asset_id) => {
+						T::AssetMetadataProvider::asset_metadata(CurrencyId::ForeignAsset(foreign_asset_id))
+							.map(|v| v.decimals)
+					}
 				}
 			}
 			CurrencyId::Erc20(address) => CurrencyIdMap::<T>::get(Into::<u32>::into(DexShare::Erc20(address)))
 				.filter(|v| v.address == address)
 				.map(|v| v.decimals),
+			// Unregistered foreign asset, or stable pool share without metadata
+			CurrencyId::ForeignAsset(_) | CurrencyId::StableAssetPoolToken(_) => None,
 		}
 	}
 
@@ -229,17 +272,22 @@ impl<T: Config> CurrencyIdMapping for EvmCurrencyIdMapping<T> {
 	// If is CurrencyId::DexShare and contain DexShare::Erc20,
 	// will use the u32 to get the DexShare::Erc20 from the mapping.
 	fn encode_evm_address(v: CurrencyId) -> Option<EvmAddress> {
+		// Registered asset mirrored to the EVM
+		if let Some(address) = T::AssetMetadataProvider::mirrored_evm_address(v) {
+			return Some(address);
+		}
+
 		match v {
 			CurrencyId::DexShare(left, right) => {
 				let symbol_0 = match left {
-					DexShare::Token(_) => Some(left.into()),
+					DexShare::Token(_) | DexShare::ForeignAsset(_) => Some(left.into()),
 					DexShare::Erc20(address) => {
 						let id: u32 = left.into();
 						CurrencyIdMap::<T>::get(id).filter(|v| v.address == address).map(|_| id)
 					}
 				}?;
 				let symbol_1 = match right {
-					DexShare::Token(_) => Some(right.into()),
+					DexShare::Token(_) | DexShare::ForeignAsset(_) => Some(right.into()),
 					DexShare::Erc20(address) => {
 						let id: u32 = right.into();
 						CurrencyIdMap::<T>::get(id).filter(|v| v.address == address).map(|_| id)
@@ -251,7 +299,7 @@ impl<T: Config> CurrencyIdMapping for EvmCurrencyIdMapping<T> {
 				Some(prefix | EvmAddress::from_low_u64_be(u64::from(symbol_0) << 32 | u64::from(symbol_1)))
 			}
 
-			// Token or Erc20
+			// Token, Erc20, ForeignAsset or StableAssetPoolToken
 			_ => EvmAddress::try_from(v).ok(),
 		}
 	}
@@ -260,6 +308,11 @@ impl<T: Config> CurrencyIdMapping for EvmCurrencyIdMapping<T> {
 	// If is CurrencyId::DexShare and contain DexShare::Erc20,
 	// will use the u32 to get the DexShare::Erc20 from the mapping.
 	fn decode_evm_address(addr: EvmAddress) -> Option<CurrencyId> {
+		// Registered asset mirrored to the EVM
+		if let Some(currency_id) = T::AssetMetadataProvider::mirrored_currency_id(addr) {
+			return Some(currency_id);
+		}
+
 		let address = addr.as_bytes();
 
 		// Token
@@ -267,6 +320,18 @@ impl<T: Config> CurrencyIdMapping for EvmCurrencyIdMapping<T> {
 			return address[H160_POSITION_TOKEN].try_into().map(CurrencyId::Token).ok();
 		}
 
+		// ForeignAsset
+		if address.starts_with(&H160_PREFIX_FOREIGN_ASSET) {
+			let id = ForeignAssetId::from_be_bytes(address[H160_POSITION_FOREIGN_ASSET].try_into().ok()?);
+			return Some(CurrencyId::ForeignAsset(id));
+		}
+
+		// StableAssetPoolToken
+		if address.starts_with(&H160_PREFIX_STABLE_ASSET_POOL_TOKEN) {
+			let id = StableAssetPoolId::from_be_bytes(address[H160_POSITION_STABLE_ASSET_POOL_TOKEN].try_into().ok()?);
+			return Some(CurrencyId::StableAssetPoolToken(id));
+		}
+
 		// DexShare
 		if address.starts_with(&H160_PREFIX_DEXSHARE) {
 			let left = {
@@ -276,6 +341,10 @@ impl<T: Config> CurrencyIdMapping for EvmCurrencyIdMapping<T> {
 						.try_into()
 						.map(DexShare::Token)
 						.ok()
+				} else if address[H160_POSITION_DEXSHARE_LEFT].starts_with(&[0u8, DexShareType::ForeignAsset.into()]) {
+					// ForeignAsset
+					let id = ForeignAssetId::from_be_bytes(address[H160_POSITION_DEXSHARE_LEFT][2..].try_into().ok()?);
+					Some(DexShare::ForeignAsset(id))
 				} else {
 					// Erc20
 					let id = u32::from_be_bytes(address[H160_POSITION_DEXSHARE_LEFT].try_into().ok()?);
@@ -289,6 +358,10 @@ impl<T: Config> CurrencyIdMapping for EvmCurrencyIdMapping<T> {
 						.try_into()
 						.map(DexShare::Token)
 						.ok()
+				} else if address[H160_POSITION_DEXSHARE_RIGHT].starts_with(&[0u8, DexShareType::ForeignAsset.into()]) {
+					// ForeignAsset
+					let id = ForeignAssetId::from_be_bytes(address[H160_POSITION_DEXSHARE_RIGHT][2..].try_into().ok()?);
+					Some(DexShare::ForeignAsset(id))
 				} else {
 					// Erc20
 					let id = u32::from_be_bytes(address[H160_POSITION_DEXSHARE_RIGHT].try_into().ok()?);
@@ -129,6 +129,7 @@ impl pallet_timestamp::Config for Runtime {
 parameter_types! {
 	pub const NewContractExtraBytes: u32 = 1;
 	pub NetworkContractSource: EvmAddress = alice_evm_addr();
+	pub const Hardfork: module_evm::EvmHardfork = module_evm::EvmHardfork::Istanbul;
 }
 
 ord_parameter_types! {
@@ -138,6 +139,7 @@ ord_parameter_types! {
 	pub const StorageDepositPerByte: u128 = 10;
 	pub const DeveloperDeposit: u64 = 1000;
 	pub const DeploymentFee: u64 = 200;
+	pub const DefaultUpgradeDelay: BlockNumber = 0;
 }
 
 impl module_evm::Config for Runtime {
@@ -149,6 +151,7 @@ impl module_evm::Config for Runtime {
 	type Event = Event;
 	type Precompiles = ();
 	type ChainId = ();
+	type Hardfork = Hardfork;
 	type GasToWeight = ();
 	type ChargeTransactionPayment = ();
 	type NetworkContractOrigin = EnsureSignedBy<NetworkContractAccount, AccountId>;
@@ -156,11 +159,15 @@ impl module_evm::Config for Runtime {
 
 	type DeveloperDeposit = DeveloperDeposit;
 	type DeploymentFee = DeploymentFee;
+	type DefaultUpgradeDelay = DefaultUpgradeDelay;
 	type TreasuryAccount = TreasuryAccount;
 	type FreeDeploymentOrigin = EnsureSignedBy<CouncilAccount, AccountId32>;
 
+	type CallFilter = ();
 	type Runner = module_evm::runner::stack::Runner<Self>;
 	type FindAuthor = ();
+	type Task = module_evm::EvmTask<Runtime>;
+	type IdleScheduler = ();
 	type WeightInfo = ();
 }
 
@@ -171,6 +178,7 @@ impl module_evm_bridge::Config for Runtime {
 impl Config for Runtime {
 	type Currency = Balances;
 	type EVMBridge = EVMBridge;
+	type AssetMetadataProvider = ();
 }
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
@@ -413,6 +413,34 @@ fn decode_evm_address_works() {
 				))
 			);
 
+			assert_eq!(
+				EvmCurrencyIdMapping::<Runtime>::decode_evm_address(
+					EvmCurrencyIdMapping::<Runtime>::encode_evm_address(CurrencyId::ForeignAsset(1)).unwrap()
+				),
+				Some(CurrencyId::ForeignAsset(1))
+			);
+
+			assert_eq!(
+				EvmCurrencyIdMapping::<Runtime>::decode_evm_address(
+					EvmCurrencyIdMapping::<Runtime>::encode_evm_address(CurrencyId::StableAssetPoolToken(1)).unwrap()
+				),
+				Some(CurrencyId::StableAssetPoolToken(1))
+			);
+
+			assert_eq!(
+				EvmCurrencyIdMapping::<Runtime>::decode_evm_address(
+					EvmCurrencyIdMapping::<Runtime>::encode_evm_address(CurrencyId::DexShare(
+						DexShare::ForeignAsset(1),
+						DexShare::Erc20(erc20_address())
+					))
+					.unwrap()
+				),
+				Some(CurrencyId::DexShare(
+					DexShare::ForeignAsset(1),
+					DexShare::Erc20(erc20_address())
+				))
+			);
+
 			// decode invalid evm address
 			// CurrencyId::DexShare(DexShare::Token(TokenSymbol::SEE),
 			// DexShare::Erc20(erc20_address_not_exists()))
@@ -22,7 +22,7 @@
 #![allow(clippy::all)]
 
 use ethereum_types::H160;
-use primitives::evm::{CallInfo, CreateInfo, EstimateResourcesRequest};
+use primitives::evm::{AccessListItem, CallInfo, ContractStorageInfo, CreateInfo, EstimateResourcesRequest};
 use sp_runtime::{
 	codec::Codec,
 	traits::{MaybeDisplay, MaybeFromStr},
@@ -30,9 +30,11 @@ use sp_runtime::{
 use sp_std::vec::Vec;
 
 sp_api::decl_runtime_apis! {
+	#[api_version(5)]
 	pub trait EVMRuntimeRPCApi<Balance> where
 		Balance: Codec + MaybeDisplay + MaybeFromStr,
 	{
+		#[changed_in(2)]
 		fn call(
 			from: H160,
 			to: H160,
@@ -43,6 +45,18 @@ sp_api::decl_runtime_apis! {
 			estimate: bool,
 		) -> Result<CallInfo, sp_runtime::DispatchError>;
 
+		fn call(
+			from: H160,
+			to: H160,
+			data: Vec<u8>,
+			value: Balance,
+			gas_limit: u64,
+			storage_limit: u32,
+			access_list: Option<Vec<AccessListItem>>,
+			estimate: bool,
+		) -> Result<CallInfo, sp_runtime::DispatchError>;
+
+		#[changed_in(2)]
 		fn create(
 			from: H160,
 			data: Vec<u8>,
@@ -52,6 +66,24 @@ sp_api::decl_runtime_apis! {
 			estimate: bool,
 		) -> Result<CreateInfo, sp_runtime::DispatchError>;
 
+		fn create(
+			from: H160,
+			data: Vec<u8>,
+			value: Balance,
+			gas_limit: u64,
+			storage_limit: u32,
+			access_list: Option<Vec<AccessListItem>>,
+			estimate: bool,
+		) -> Result<CreateInfo, sp_runtime::DispatchError>;
+
 		fn get_estimate_resources_request(data: Vec<u8>) -> Result<EstimateResourcesRequest, sp_runtime::DispatchError>;
+
+		fn storage_info(contract: H160) -> Option<ContractStorageInfo<Balance>>;
+
+		fn paused_contracts() -> Vec<H160>;
+
+		fn paused_selectors(contract: H160) -> Vec<[u8; 4]>;
+
+		fn pending_contract_removals() -> Vec<(H160, u32)>;
 	}
 }
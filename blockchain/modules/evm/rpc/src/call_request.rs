@@ -19,6 +19,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use ethereum_types::{H160, U256};
+use module_evm::AccessListItem;
 use serde::{Deserialize, Serialize};
 use sp_core::Bytes;
 use sp_rpc::number::NumberOrHex;
@@ -40,6 +41,8 @@ pub struct CallRequest {
 	pub value: Option<NumberOrHex>,
 	/// Data
 	pub data: Option<Bytes>,
+	/// AccessList
+	pub access_list: Option<Vec<AccessListItem>>,
 }
 
 /// EstimateResources response
@@ -23,7 +23,9 @@
 use ethereum_types::H160;
 use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
+use module_evm::ContractStorageInfo;
 use sp_core::Bytes;
+use sp_rpc::number::NumberOrHex;
 
 pub use rpc_impl_EVMApi::gen_server::EVMApi as EVMApiServer;
 
@@ -44,4 +46,22 @@ pub trait EVMApi<BlockHash> {
 		unsigned_extrinsic: Bytes,
 		at: Option<BlockHash>,
 	) -> Result<EstimateResourcesResponse>;
+
+	/// Get the storage accounting of given contract, the bytes used, the deposit reserved and
+	/// the maintainer.
+	#[rpc(name = "evm_storageInfo")]
+	fn storage_info(&self, contract: H160, at: Option<BlockHash>) -> Result<Option<ContractStorageInfo<NumberOrHex>>>;
+
+	/// Get the contracts whose calls are paused.
+	#[rpc(name = "evm_pausedContracts")]
+	fn paused_contracts(&self, at: Option<BlockHash>) -> Result<Vec<H160>>;
+
+	/// Get the paused function selectors of given contract.
+	#[rpc(name = "evm_pausedSelectors")]
+	fn paused_selectors(&self, contract: H160, at: Option<BlockHash>) -> Result<Vec<Bytes>>;
+
+	/// Get the removed contracts whose storages are still being deleted, with the bytes of the
+	/// storages left.
+	#[rpc(name = "evm_pendingContractRemovals")]
+	fn pending_contract_removals(&self, at: Option<BlockHash>) -> Result<Vec<(H160, u32)>>;
 }
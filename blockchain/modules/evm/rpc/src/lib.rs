@@ -26,7 +26,7 @@ use jsonrpc_core::{Error, ErrorCode, Result, Value};
 use pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi;
 use rustc_hex::ToHex;
 use sc_rpc_api::DenyUnsafe;
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_core::{Bytes, Decode};
 use sp_rpc::number::NumberOrHex;
@@ -40,7 +40,8 @@ use std::convert::{TryFrom, TryInto};
 use std::{marker::PhantomData, sync::Arc};
 
 use call_request::{CallRequest, EstimateResourcesResponse};
-pub use module_evm::{ExitError, ExitReason};
+use module_evm::{AccessListItem, CallInfo, CreateInfo};
+pub use module_evm::{ContractStorageInfo, ExitError, ExitReason};
 pub use module_evm_rpc_runtime_api::EVMRuntimeRPCApi;
 
 pub use crate::evm_api::{EVMApi as EVMApiT, EVMApiServer};
@@ -124,6 +125,78 @@ impl<B, C, Balance> EVMApi<B, C, Balance> {
 	}
 }
 
+impl<B, C, Balance> EVMApi<B, C, Balance>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>,
+	C::Api: EVMRuntimeRPCApi<B, Balance>,
+	Balance: Codec + MaybeDisplay + MaybeFromStr,
+{
+	/// The version of `EVMRuntimeRPCApi` implemented by the runtime at `hash`.
+	fn api_version(&self, hash: <B as BlockT>::Hash) -> Result<u32> {
+		self.client
+			.runtime_api()
+			.api_version::<dyn EVMRuntimeRPCApi<B, Balance>>(&BlockId::Hash(hash))
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+			.ok_or_else(|| internal_err("EVMRuntimeRPCApi is not supported"))
+	}
+
+	/// Dry run a call, without the access list on runtimes before version 2.
+	#[allow(clippy::too_many_arguments)]
+	fn runtime_call(
+		&self,
+		hash: <B as BlockT>::Hash,
+		from: H160,
+		to: H160,
+		data: Vec<u8>,
+		value: Balance,
+		gas_limit: u64,
+		storage_limit: u32,
+		access_list: Option<Vec<AccessListItem>>,
+	) -> Result<CallInfo> {
+		let api = self.client.runtime_api();
+		let at = BlockId::Hash(hash);
+
+		let result = if self.api_version(hash)? < 2 {
+			#[allow(deprecated)]
+			api.call_before_version_2(&at, from, to, data, value, gas_limit, storage_limit, true)
+		} else {
+			api.call(&at, from, to, data, value, gas_limit, storage_limit, access_list, true)
+		};
+
+		result
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+			.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))
+	}
+
+	/// Dry run a create, without the access list on runtimes before version 2.
+	#[allow(clippy::too_many_arguments)]
+	fn runtime_create(
+		&self,
+		hash: <B as BlockT>::Hash,
+		from: H160,
+		data: Vec<u8>,
+		value: Balance,
+		gas_limit: u64,
+		storage_limit: u32,
+		access_list: Option<Vec<AccessListItem>>,
+	) -> Result<CreateInfo> {
+		let api = self.client.runtime_api();
+		let at = BlockId::Hash(hash);
+
+		let result = if self.api_version(hash)? < 2 {
+			#[allow(deprecated)]
+			api.create_before_version_2(&at, from, data, value, gas_limit, storage_limit, true)
+		} else {
+			api.create(&at, from, data, value, gas_limit, storage_limit, access_list, true)
+		};
+
+		result
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+			.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))
+	}
+}
+
 fn to_u128(val: NumberOrHex) -> std::result::Result<u128, ()> {
 	val.into_u256().try_into().map_err(|_| ())
 }
@@ -157,14 +230,13 @@ where
 			storage_limit,
 			value,
 			data,
+			access_list,
 		} = request;
 
 		let gas_limit = gas_limit.unwrap_or(MAX_GAS_LIMIT);
 		let storage_limit = storage_limit.unwrap_or(MAX_STORAGE_LIMIT);
 		let data = data.map(|d| d.0).unwrap_or_default();
 
-		let api = self.client.runtime_api();
-
 		let balance_value = if let Some(value) = value {
 			to_u128(value).and_then(|v| TryInto::<Balance>::try_into(v).map_err(|_| ()))
 		} else {
@@ -179,19 +251,16 @@ where
 
 		match to {
 			Some(to) => {
-				let info = api
-					.call(
-						&BlockId::Hash(hash),
-						from.unwrap_or_default(),
-						to,
-						data,
-						balance_value,
-						gas_limit,
-						storage_limit,
-						true,
-					)
-					.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-					.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+				let info = self.runtime_call(
+					hash,
+					from.unwrap_or_default(),
+					to,
+					data,
+					balance_value,
+					gas_limit,
+					storage_limit,
+					access_list,
+				)?;
 
 				log::debug!(
 					target: "evm",
@@ -233,6 +302,7 @@ where
 			storage_limit: request.storage_limit,
 			value: request.value.map(|v| NumberOrHex::Hex(U256::from(v))),
 			data: request.data.map(Bytes),
+			access_list: request.access_list,
 		};
 
 		let calculate_gas_used = |request| -> Result<(U256, i32)> {
@@ -243,6 +313,7 @@ where
 				storage_limit,
 				value,
 				data,
+				access_list,
 			} = request;
 
 			let gas_limit = gas_limit.unwrap_or_else(u64::max_value); // TODO: set a limit
@@ -263,41 +334,31 @@ where
 
 			let (used_gas, used_storage) = match to {
 				Some(to) => {
-					let info = self
-						.client
-						.runtime_api()
-						.call(
-							&BlockId::Hash(hash),
-							from.unwrap_or_default(),
-							to,
-							data,
-							balance_value,
-							gas_limit,
-							storage_limit,
-							true,
-						)
-						.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-						.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+					let info = self.runtime_call(
+						hash,
+						from.unwrap_or_default(),
+						to,
+						data,
+						balance_value,
+						gas_limit,
+						storage_limit,
+						access_list,
+					)?;
 
 					error_on_execution_failure(&info.exit_reason, &info.value)?;
 
 					(info.used_gas, info.used_storage)
 				}
 				None => {
-					let info = self
-						.client
-						.runtime_api()
-						.create(
-							&BlockId::Hash(hash),
-							from.unwrap_or_default(),
-							data,
-							balance_value,
-							gas_limit,
-							storage_limit,
-							true,
-						)
-						.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
-						.map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+					let info = self.runtime_create(
+						hash,
+						from.unwrap_or_default(),
+						data,
+						balance_value,
+						gas_limit,
+						storage_limit,
+						access_list,
+					)?;
 
 					error_on_execution_failure(&info.exit_reason, &[])?;
 
@@ -425,6 +486,57 @@ where
 			})
 		}
 	}
+
+	fn storage_info(
+		&self,
+		contract: H160,
+		at: Option<<B as BlockT>::Hash>,
+	) -> Result<Option<ContractStorageInfo<NumberOrHex>>> {
+		let hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let info = self
+			.client
+			.runtime_api()
+			.storage_info(&BlockId::Hash(hash), contract)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+
+		Ok(info.map(|info| ContractStorageInfo {
+			used_storage: info.used_storage,
+			deposit: NumberOrHex::Hex(info.deposit.into()),
+			maintainer: info.maintainer,
+			refund_per_slot: NumberOrHex::Hex(info.refund_per_slot.into()),
+		}))
+	}
+
+	fn paused_contracts(&self, at: Option<<B as BlockT>::Hash>) -> Result<Vec<H160>> {
+		let hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		self.client
+			.runtime_api()
+			.paused_contracts(&BlockId::Hash(hash))
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))
+	}
+
+	fn paused_selectors(&self, contract: H160, at: Option<<B as BlockT>::Hash>) -> Result<Vec<Bytes>> {
+		let hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		let selectors = self
+			.client
+			.runtime_api()
+			.paused_selectors(&BlockId::Hash(hash), contract)
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+
+		Ok(selectors.into_iter().map(|selector| Bytes(selector.to_vec())).collect())
+	}
+
+	fn pending_contract_removals(&self, at: Option<<B as BlockT>::Hash>) -> Result<Vec<(H160, u32)>> {
+		let hash = at.unwrap_or_else(|| self.client.info().best_hash);
+
+		self.client
+			.runtime_api()
+			.pending_contract_removals(&BlockId::Hash(hash))
+			.map_err(|err| internal_err(format!("runtime error: {:?}", err)))
+	}
 }
 
 #[test]
@@ -48,23 +48,31 @@ use frame_support::{
 	},
 	transactional,
 	weights::{Pays, PostDispatchInfo, Weight},
-	BoundedVec, RuntimeDebug,
+	BoundedVec, CloneNoBound, PartialEqNoBound, RuntimeDebug, RuntimeDebugNoBound,
 };
 use frame_system::{ensure_root, ensure_signed, pallet_prelude::*, EnsureOneOf, EnsureRoot, EnsureSigned};
 use hex_literal::hex;
 pub use module_support::{
-	AddressMapping, EVMStateRentTrait, ExecutionMode, InvokeContext, TransactionPayment, EVM as EVMTrait,
+	AddressMapping, DispatchableTask, EVMStateRentTrait, EvmCallFilter, ExecutionMode, IdleScheduler, InvokeContext,
+	TransactionPayment, EVM as EVMTrait,
 };
 pub use orml_traits::currency::TransferAll;
+use orml_utilities::with_transaction_result;
 use primitive_types::{H160, H256, U256};
 pub use primitives::{
-	evm::{Account, CallInfo, CreateInfo, EvmAddress, ExecutionInfo, Log, TransactionAction, Vicinity},
+	evm::{
+		AccessListItem, Account, CallInfo, ContractStorageInfo, CreateInfo, EvmAddress, ExecutionInfo, Log,
+		TransactionAction, Vicinity,
+	},
+	task::TaskResult,
 	ReserveIdentifier, H160_PREFIX_DEXSHARE, H160_PREFIX_TOKEN, MIRRORED_NFT_ADDRESS_START, PRECOMPILE_ADDRESS_START,
 	SYSTEM_CONTRACT_ADDRESS_PREFIX,
 };
+use scale_info::TypeInfo;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256};
+use sp_io::KillStorageResult::{AllRemoved, SomeRemaining};
 use sp_runtime::{
 	traits::{
 		Convert, DispatchInfoOf, One, PostDispatchInfoOf, Saturating, SignedExtension, UniqueSaturatedInto, Zero,
@@ -87,6 +95,9 @@ pub use weights::WeightInfo;
 /// Storage key size and storage value size.
 pub const STORAGE_SIZE: u32 = 64;
 
+/// The `ExitError::Other` reason of calls to paused contracts.
+pub const CONTRACT_PAUSED: &str = "ContractPaused";
+
 /// Type alias for currency balance.
 pub type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 pub type NegativeImbalanceOf<T> =
@@ -94,45 +105,141 @@ pub type NegativeImbalanceOf<T> =
 pub const RESERVE_ID_STORAGE_DEPOSIT: ReserveIdentifier = ReserveIdentifier::EvmStorageDeposit;
 pub const RESERVE_ID_DEVELOPER_DEPOSIT: ReserveIdentifier = ReserveIdentifier::EvmDeveloperDeposit;
 
-// Initially based on Istanbul hard fork configuration.
-static SETHEUM_CONFIG: EvmConfig = EvmConfig {
-	gas_ext_code: 700,
-	gas_ext_code_hash: 700,
-	gas_balance: 700,
-	gas_sload: 800,
-	gas_sstore_set: 20000,
-	gas_sstore_reset: 5000,
-	refund_sstore_clears: 0, // no gas refund
-	gas_suicide: 5000,
-	gas_suicide_new_account: 25000,
-	gas_call: 700,
-	gas_expbyte: 50,
-	gas_transaction_create: 53000,
-	gas_transaction_call: 21000,
-	gas_transaction_zero_data: 4,
-	gas_transaction_non_zero_data: 16,
+/// The max number of the storages of a removed contract deleted by one dispatch of
+/// `EvmTask::Remove`.
+pub const REMOVE_STORAGES_LIMIT: u32 = 100;
+
+// Based on Istanbul hard fork configuration.
+static ISTANBUL_CONFIG: EvmConfig = EvmConfig {
+	refund_sstore_clears: 0,            // no gas refund
+	sstore_gas_metering: false,         // no gas refund
+	sstore_revert_under_stipend: false, // ignored
+	create_contract_limit: Some(MaxCodeSize::get() as usize),
+	..EvmConfig::istanbul()
+};
+
+// Based on Berlin hard fork configuration, with EIP-2929 cold/warm state access costs.
+static BERLIN_CONFIG: EvmConfig = EvmConfig {
+	refund_sstore_clears: 0,            // no gas refund
 	sstore_gas_metering: false,         // no gas refund
 	sstore_revert_under_stipend: false, // ignored
-	err_on_call_with_more_gas: false,
-	empty_considered_exists: false,
-	create_increase_nonce: true,
-	call_l64_after_gas: true,
-	stack_limit: 1024,
-	memory_limit: usize::max_value(),
-	call_stack_limit: 1024,
 	create_contract_limit: Some(MaxCodeSize::get() as usize),
-	call_stipend: 2300,
-	has_delegate_call: true,
-	has_create2: true,
-	has_revert: true,
-	has_return_data: true,
-	has_bitwise_shifting: true,
-	has_chain_id: true,
-	has_self_balance: true,
-	has_ext_code_hash: true,
-	estimate: false,
+	..EvmConfig::berlin()
 };
 
+// Based on London hard fork configuration, with Berlin gas costs and the `BASEFEE` opcode.
+static LONDON_CONFIG: EvmConfig = EvmConfig {
+	refund_sstore_clears: 0,            // no gas refund
+	sstore_gas_metering: false,         // no gas refund
+	sstore_revert_under_stipend: false, // ignored
+	create_contract_limit: Some(MaxCodeSize::get() as usize),
+	..EvmConfig::london()
+};
+
+// The unmodified Ethereum configurations, with gas refunds.
+static ETHEREUM_ISTANBUL_CONFIG: EvmConfig = EvmConfig::istanbul();
+static ETHEREUM_BERLIN_CONFIG: EvmConfig = EvmConfig::berlin();
+static ETHEREUM_LONDON_CONFIG: EvmConfig = EvmConfig::london();
+
+/// The Ethereum hard fork the EVM config is based on.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
+pub enum EvmHardfork {
+	/// Istanbul, without state access gas changes.
+	Istanbul,
+	/// EIP-2929 cold/warm state access costs and EIP-2930 access lists.
+	Berlin,
+	/// Berlin, plus the `BASEFEE` opcode.
+	London,
+}
+
+impl EvmHardfork {
+	/// The EVM config used for the hard fork.
+	pub fn config(&self) -> &'static EvmConfig {
+		match self {
+			EvmHardfork::Istanbul => &ISTANBUL_CONFIG,
+			EvmHardfork::Berlin => &BERLIN_CONFIG,
+			EvmHardfork::London => &LONDON_CONFIG,
+		}
+	}
+
+	/// The unmodified Ethereum config of the hard fork, for runtimes with full
+	/// Ethereum compatibility.
+	pub fn ethereum_config(&self) -> &'static EvmConfig {
+		match self {
+			EvmHardfork::Istanbul => &ETHEREUM_ISTANBUL_CONFIG,
+			EvmHardfork::Berlin => &ETHEREUM_BERLIN_CONFIG,
+			EvmHardfork::London => &ETHEREUM_LONDON_CONFIG,
+		}
+	}
+}
+
+/// A view call that must return `true` for a scheduled call to run.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
+pub struct ScheduledCallCondition {
+	/// The contract address to call.
+	pub target: EvmAddress,
+	/// The data supplied for the view call.
+	pub input: Vec<u8>,
+	/// The maximum gas the view call can use, paid by the scheduled call.
+	pub gas_limit: u64,
+}
+
+/// The `idle_scheduler` tasks of the EVM module.
+#[derive(Encode, Decode, RuntimeDebugNoBound, CloneNoBound, PartialEqNoBound, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub enum EvmTask<T: Config> {
+	/// Delete the storages of a removed contract in chunks, releasing the storage deposit of the
+	/// deleted storages to `refund_to` if any.
+	#[codec(index = 0)]
+	Remove {
+		contract: EvmAddress,
+		refund_to: Option<T::AccountId>,
+	},
+}
+
+impl<T: Config> DispatchableTask for EvmTask<T> {
+	fn dispatch(self, weight: Weight) -> (TaskResult, Option<Self>) {
+		match self {
+			EvmTask::Remove { contract, refund_to } => {
+				let db_weight = <T as frame_system::Config>::DbWeight::get();
+				let base_weight = db_weight.reads_writes(3, 3);
+				if weight < base_weight.saturating_add(db_weight.write) {
+					let task_result = TaskResult {
+						result: Ok(()),
+						used_weight: Zero::zero(),
+						finished: false,
+					};
+					return (task_result, None);
+				}
+
+				let limit = weight
+					.saturating_sub(base_weight)
+					.checked_div(db_weight.write)
+					.unwrap_or(REMOVE_STORAGES_LIMIT.into())
+					.min(REMOVE_STORAGES_LIMIT.into()) as u32;
+
+				// the progress of the removal is kept in `PendingContractRemovals`, the task
+				// itself never changes.
+				let (result, count, finished) =
+					Pallet::<T>::remove_contract_storages(&contract, refund_to.as_ref(), limit);
+				let task_result = TaskResult {
+					result,
+					used_weight: base_weight.saturating_add(db_weight.writes(count.into())),
+					finished,
+				};
+				(task_result, None)
+			}
+		}
+	}
+
+	fn on_dropped(self) {
+		match self {
+			// the storages left are never deleted, stop treating the address as pending removal.
+			EvmTask::Remove { contract, .. } => PendingContractRemovals::<T>::remove(contract),
+		}
+	}
+}
+
 #[frame_support::pallet]
 pub mod module {
 	use super::*;
@@ -180,9 +287,13 @@ pub mod module {
 		/// ChargeTransactionPayment convert weight to fee.
 		type ChargeTransactionPayment: TransactionPayment<Self::AccountId, BalanceOf<Self>, NegativeImbalanceOf<Self>>;
 
+		/// The hard fork the EVM config is based on.
+		#[pallet::constant]
+		type Hardfork: Get<EvmHardfork>;
+
 		/// EVM config used in the module.
 		fn config() -> &'static EvmConfig {
-			&SETHEUM_CONFIG
+			Self::Hardfork::get().config()
 		}
 
 		/// Required origin for creating system contract.
@@ -200,17 +311,30 @@ pub mod module {
 		#[pallet::constant]
 		type DeploymentFee: Get<BalanceOf<Self>>;
 
+		/// The upgrade delay of the contracts without their own delay.
+		#[pallet::constant]
+		type DefaultUpgradeDelay: Get<Self::BlockNumber>;
+
 		#[pallet::constant]
 		type TreasuryAccount: Get<Self::AccountId>;
 
 		type FreeDeploymentOrigin: EnsureOrigin<Self::Origin>;
 
+		/// Filter of the paused contract calls, checked before executing a call or nested call.
+		type CallFilter: EvmCallFilter;
+
 		/// EVM execution runner.
 		type Runner: Runner<Self>;
 
 		/// Find author for the current block.
 		type FindAuthor: FindAuthor<Self::AccountId>;
 
+		/// The task type of the idle scheduler.
+		type Task: From<EvmTask<Self>>;
+
+		/// The idle scheduler to delete the storages of removed contracts.
+		type IdleScheduler: IdleScheduler<Self::Task>;
+
 		/// Weight information for the extrinsics in this module.
 		type WeightInfo: WeightInfo;
 	}
@@ -240,6 +364,15 @@ pub mod module {
 		pub ref_count: u32,
 	}
 
+	/// A contract code upgrade waiting for its timelock to pass.
+	#[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
+	pub struct PendingUpgrade<BlockNumber> {
+		/// The Keccak256 hash of the proposed code.
+		pub code_hash: H256,
+		/// The block number from which the upgrade can be applied.
+		pub apply_after: BlockNumber,
+	}
+
 	#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
 	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 	/// Account definition used for genesis block construction.
@@ -307,6 +440,47 @@ pub mod module {
 	#[pallet::getter(fn extrinsic_origin)]
 	pub type ExtrinsicOrigin<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
 
+	/// The delay between proposing and applying a code upgrade of a contract, `DefaultUpgradeDelay`
+	/// if not set.
+	///
+	/// ContractUpgradeDelays: map EvmAddress => Option<BlockNumber>
+	#[pallet::storage]
+	#[pallet::getter(fn contract_upgrade_delays)]
+	pub type ContractUpgradeDelays<T: Config> = StorageMap<_, Twox64Concat, EvmAddress, T::BlockNumber, OptionQuery>;
+
+	/// The proposed code upgrades of contracts.
+	///
+	/// PendingUpgrades: map EvmAddress => Option<PendingUpgrade>
+	#[pallet::storage]
+	#[pallet::getter(fn pending_upgrades)]
+	pub type PendingUpgrades<T: Config> =
+		StorageMap<_, Twox64Concat, EvmAddress, PendingUpgrade<T::BlockNumber>, OptionQuery>;
+
+	/// The runs left of the recurring scheduled calls, whose fee is still reserved.
+	///
+	/// ScheduledCallRemainingRuns: map Vec<u8> => Option<u32>
+	#[pallet::storage]
+	#[pallet::getter(fn scheduled_call_remaining_runs)]
+	pub type ScheduledCallRemainingRuns<T: Config> = StorageMap<_, Blake2_128Concat, Vec<u8>, u32, OptionQuery>;
+
+	/// The removed contracts whose storages are still being deleted by the idle scheduler, with
+	/// the bytes of the storages left.
+	///
+	/// PendingContractRemovals: map EvmAddress => Option<u32>
+	#[pallet::storage]
+	#[pallet::getter(fn pending_contract_removals)]
+	pub type PendingContractRemovals<T: Config> = StorageMap<_, Twox64Concat, EvmAddress, u32, OptionQuery>;
+
+	/// The removed contracts whose `EvmTask::Remove` could not be scheduled because the task queue
+	/// of the idle scheduler was full, with the account to refund the storage deposit to. The
+	/// scheduling is retried on idle.
+	///
+	/// UnscheduledContractRemovals: map EvmAddress => Option<Option<AccountId>>
+	#[pallet::storage]
+	#[pallet::getter(fn unscheduled_contract_removals)]
+	pub type UnscheduledContractRemovals<T: Config> =
+		StorageMap<_, Twox64Concat, EvmAddress, Option<T::AccountId>, OptionQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
 		pub accounts: BTreeMap<EvmAddress, GenesisAccount<BalanceOf<T>, T::Index>>,
@@ -414,6 +588,19 @@ pub mod module {
 		ContractSetCode(EvmAddress),
 		/// Selfdestructed contract code. \[contract\]
 		ContractSelfdestructed(EvmAddress),
+		/// Proposed a contract code upgrade. \[contract, code_hash, apply_after\]
+		ContractUpgradeProposed(EvmAddress, H256, T::BlockNumber),
+		/// Set the delay of contract code upgrades. \[contract, delay\]
+		ContractUpgradeDelaySet(EvmAddress, T::BlockNumber),
+		/// A run of a scheduled call has been executed. \[task_id, logs\]
+		ScheduledCallExecuted(Vec<u8>, Vec<Log>),
+		/// A run of a scheduled call has been skipped as its condition is not met. \[task_id, logs\]
+		ScheduledCallSkipped(Vec<u8>, Vec<Log>),
+		/// A run of a scheduled call has been executed but reverted or failed. \[task_id,
+		/// exit_reason, logs\]
+		ScheduledCallFailed(Vec<u8>, ExitReason, Vec<Log>),
+		/// All the storages of a removed contract have been deleted. \[contract\]
+		ContractStoragesRemoved(EvmAddress),
 	}
 
 	#[pallet::error]
@@ -446,13 +633,37 @@ pub mod module {
 		UnreserveStorageFailed,
 		/// Charge storage failed
 		ChargeStorageFailed,
+		/// The contract code upgrade is timelocked, it must be proposed first
+		ContractUpgradeTimelocked,
+		/// No code upgrade proposed for the contract
+		ContractUpgradeNotProposed,
+		/// The code does not match the proposed code upgrade
+		ContractUpgradeCodeMismatch,
+		/// The delay of the proposed code upgrade has not passed
+		ContractUpgradeNotReady,
+		/// The maintainer cannot reduce the delay of contract code upgrades
+		CannotReduceUpgradeDelay,
 	}
 
 	#[pallet::pallet]
 	pub struct Pallet<T>(_);
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		fn on_idle(_n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::schedule_contract_removals(remaining_weight)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			Self::check_invariants()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::check_invariants()
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
@@ -468,8 +679,10 @@ pub mod module {
 			#[pallet::compact] _valid_until: T::BlockNumber, // checked by tx validation logic
 		) -> DispatchResultWithPostInfo {
 			match action {
-				TransactionAction::Call(target) => Self::call(origin, target, input, value, gas_limit, storage_limit),
-				TransactionAction::Create => Self::create(origin, input, value, gas_limit, storage_limit),
+				TransactionAction::Call(target) => {
+					Self::call(origin, target, input, value, gas_limit, storage_limit, vec![])
+				}
+				TransactionAction::Create => Self::create(origin, input, value, gas_limit, storage_limit, vec![]),
 			}
 		}
 
@@ -481,6 +694,7 @@ pub mod module {
 		/// - `value`: the amount sent for payable calls
 		/// - `gas_limit`: the maximum gas the call can use
 		/// - `storage_limit`: the total bytes the contract's storage can increase by
+		/// - `access_list`: the EIP-2930 access list, the addresses and storage keys to warm up
 		#[pallet::weight(T::GasToWeight::convert(*gas_limit))]
 		#[transactional]
 		pub fn call(
@@ -490,6 +704,7 @@ pub mod module {
 			#[pallet::compact] value: BalanceOf<T>,
 			#[pallet::compact] gas_limit: u64,
 			#[pallet::compact] storage_limit: u32,
+			access_list: Vec<AccessListItem>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 			let source = T::AddressMapping::get_or_create_evm_address(&who);
@@ -502,6 +717,7 @@ pub mod module {
 				value,
 				gas_limit,
 				storage_limit,
+				access_list,
 				T::config(),
 			)?;
 
@@ -546,7 +762,17 @@ pub mod module {
 				_payed = imbalance;
 			}
 
-			let info = T::Runner::call(from, from, target, input, value, gas_limit, storage_limit, T::config())?;
+			let info = T::Runner::call(
+				from,
+				from,
+				target,
+				input,
+				value,
+				gas_limit,
+				storage_limit,
+				vec![],
+				T::config(),
+			)?;
 
 			let used_gas: u64 = info.used_gas.unique_saturated_into();
 
@@ -572,6 +798,161 @@ pub mod module {
 			})
 		}
 
+		/// Issue one run of a recurring or conditional scheduled contract call.
+		/// The call only runs if the view call of `condition` returns `true`,
+		/// and the unused gas reserved for this run is refunded.
+		///
+		/// - `task_id`: the scheduler task the run belongs to
+		/// - `log_address`: the address the run logs are emitted from
+		/// - `from`: the address the scheduled call originates from
+		/// - `target`: the contract address to call
+		/// - `input`: the data supplied for the call
+		/// - `value`: the amount sent for payable calls
+		/// - `gas_limit`: the maximum gas the call can use
+		/// - `storage_limit`: the total bytes the contract's storage can increase by
+		/// - `condition`: the view call deciding whether the call runs
+		#[pallet::weight(T::GasToWeight::convert(
+			gas_limit.saturating_add(condition.as_ref().map_or(0, |condition| condition.gas_limit))
+		))]
+		#[allow(clippy::too_many_arguments)]
+		pub fn scheduled_recurring_call(
+			origin: OriginFor<T>,
+			task_id: Vec<u8>,
+			log_address: EvmAddress,
+			from: EvmAddress,
+			target: EvmAddress,
+			input: Vec<u8>,
+			#[pallet::compact] value: BalanceOf<T>,
+			#[pallet::compact] gas_limit: u64,
+			#[pallet::compact] storage_limit: u32,
+			condition: Option<ScheduledCallCondition>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			// the remaining runs and the fee of this run are settled outside of the transaction
+			// of the run, so a failed run still uses up its reserved fee.
+			ScheduledCallRemainingRuns::<T>::mutate_exists(&task_id, |maybe_remaining| {
+				if let Some(remaining) = maybe_remaining {
+					*remaining = remaining.saturating_sub(1);
+					if remaining.is_zero() {
+						*maybe_remaining = None;
+					}
+				}
+			});
+
+			let total_gas = gas_limit.saturating_add(condition.as_ref().map_or(0, |condition| condition.gas_limit));
+			let _from_account = T::AddressMapping::get_account_id(&from);
+			let _payed: NegativeImbalanceOf<T>;
+			#[cfg(not(feature = "with-ethereum-compatibility"))]
+			{
+				// unreserve the transaction fee of this run for gas_limit and the condition gas_limit
+				let weight = T::GasToWeight::convert(total_gas);
+				let (_, imbalance) = T::ChargeTransactionPayment::unreserve_and_charge_fee(&_from_account, weight)
+					.map_err(|_| Error::<T>::ChargeFeeFailed)?;
+				_payed = imbalance;
+			}
+
+			let run = || -> Result<u64, DispatchError> {
+				let (should_run, condition_used_gas) = match condition {
+					Some(condition) => {
+						let info = <Self as EVMTrait<T::AccountId>>::execute(
+							InvokeContext {
+								contract: condition.target,
+								sender: from,
+								origin: from,
+							},
+							condition.input,
+							Default::default(),
+							condition.gas_limit,
+							0,
+							ExecutionMode::View,
+						)?;
+						let used_gas: u64 = info.used_gas.unique_saturated_into();
+						// a solidity `bool` is returned as a 32 bytes word
+						let is_true = info.exit_reason.is_succeed() && info.output.iter().any(|b| *b != 0);
+						(is_true, used_gas)
+					}
+					None => (true, 0),
+				};
+
+				let mut used_gas = condition_used_gas;
+				if should_run {
+					let info = T::Runner::call(
+						from,
+						from,
+						target,
+						input,
+						value,
+						gas_limit,
+						storage_limit,
+						vec![],
+						T::config(),
+					)?;
+					used_gas = used_gas.saturating_add(info.used_gas.unique_saturated_into());
+
+					if info.exit_reason.is_succeed() {
+						let log = Self::scheduled_call_log(
+							log_address,
+							b"ScheduledCallExecuted(address,address,bytes)",
+							from,
+							target,
+							&task_id,
+						);
+						Pallet::<T>::deposit_event(Event::<T>::ScheduledCallExecuted(task_id, vec![log]));
+					} else {
+						let log = Self::scheduled_call_log(
+							log_address,
+							b"ScheduledCallFailed(address,address,bytes)",
+							from,
+							target,
+							&task_id,
+						);
+						Pallet::<T>::deposit_event(Event::<T>::ScheduledCallFailed(
+							task_id,
+							info.exit_reason,
+							vec![log],
+						));
+					}
+				} else {
+					let log = Self::scheduled_call_log(
+						log_address,
+						b"ScheduledCallSkipped(address,address,bytes)",
+						from,
+						target,
+						&task_id,
+					);
+					Pallet::<T>::deposit_event(Event::<T>::ScheduledCallSkipped(task_id, vec![log]));
+				}
+
+				Ok(used_gas)
+			};
+			let result = frame_support::storage::with_transaction(|| match run() {
+				Ok(used_gas) => TransactionOutcome::Commit(Ok(used_gas)),
+				Err(e) => TransactionOutcome::Rollback(Err(e)),
+			});
+			// a failed run uses all of its gas
+			let used_gas = result.as_ref().map_or(total_gas, |used_gas| *used_gas);
+
+			#[cfg(not(feature = "with-ethereum-compatibility"))]
+			{
+				let refund_gas = total_gas.saturating_sub(used_gas);
+				// ignore the result to continue. if it fails, just the user will not
+				// be refunded, there will not increase user balance.
+				let res = T::ChargeTransactionPayment::refund_fee(
+					&_from_account,
+					T::GasToWeight::convert(refund_gas),
+					_payed,
+				);
+				debug_assert!(res.is_ok());
+			}
+
+			result?;
+			Ok(PostDispatchInfo {
+				actual_weight: Some(T::GasToWeight::convert(used_gas)),
+				pays_fee: Pays::Yes,
+			})
+		}
+
 		/// Issue an EVM create operation. This is similar to a contract
 		/// creation transaction in Ethereum.
 		///
@@ -579,6 +960,7 @@ pub mod module {
 		/// - `value`: the amount sent to the contract upon creation
 		/// - `gas_limit`: the maximum gas the call can use
 		/// - `storage_limit`: the total bytes the contract's storage can increase by
+		/// - `access_list`: the EIP-2930 access list, the addresses and storage keys to warm up
 		#[pallet::weight(T::GasToWeight::convert(*gas_limit))]
 		#[transactional]
 		pub fn create(
@@ -587,11 +969,12 @@ pub mod module {
 			#[pallet::compact] value: BalanceOf<T>,
 			#[pallet::compact] gas_limit: u64,
 			#[pallet::compact] storage_limit: u32,
+			access_list: Vec<AccessListItem>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 			let source = T::AddressMapping::get_or_create_evm_address(&who);
 
-			let info = T::Runner::create(source, init, value, gas_limit, storage_limit, T::config())?;
+			let info = T::Runner::create(source, init, value, gas_limit, storage_limit, access_list, T::config())?;
 
 			let used_gas: u64 = info.used_gas.unique_saturated_into();
 
@@ -622,7 +1005,7 @@ pub mod module {
 			let who = ensure_signed(origin)?;
 			let source = T::AddressMapping::get_or_create_evm_address(&who);
 
-			let info = T::Runner::create2(source, init, salt, value, gas_limit, storage_limit, T::config())?;
+			let info = T::Runner::create2(source, init, salt, value, gas_limit, storage_limit, vec![], T::config())?;
 
 			let used_gas: u64 = info.used_gas.unique_saturated_into();
 
@@ -652,8 +1035,16 @@ pub mod module {
 
 			let source = T::NetworkContractSource::get();
 			let address = EvmAddress::from_low_u64_be(Self::network_contract_index());
-			let info =
-				T::Runner::create_at_address(source, address, init, value, gas_limit, storage_limit, T::config())?;
+			let info = T::Runner::create_at_address(
+				source,
+				address,
+				init,
+				value,
+				gas_limit,
+				storage_limit,
+				vec![],
+				T::config(),
+			)?;
 
 			NetworkContractIndex::<T>::mutate(|v| *v = v.saturating_add(One::one()));
 
@@ -708,7 +1099,16 @@ pub mod module {
 					logs: vec![],
 				}
 			} else {
-				T::Runner::create_at_address(source, target, init, value, gas_limit, storage_limit, T::config())?
+				T::Runner::create_at_address(
+					source,
+					target,
+					init,
+					value,
+					gas_limit,
+					storage_limit,
+					vec![],
+					T::config(),
+				)?
 			};
 
 			let used_gas: u64 = info.used_gas.unique_saturated_into();
@@ -810,13 +1210,104 @@ pub mod module {
 		#[transactional]
 		pub fn set_code(origin: OriginFor<T>, contract: EvmAddress, code: Vec<u8>) -> DispatchResultWithPostInfo {
 			let root_or_signed = Self::ensure_root_or_signed(origin)?;
+			if root_or_signed.is_right() {
+				ensure!(
+					Self::upgrade_delay(&contract).is_zero(),
+					Error::<T>::ContractUpgradeTimelocked
+				);
+			}
+			Self::do_set_code(root_or_signed, contract, code)?;
+
+			Pallet::<T>::deposit_event(Event::<T>::ContractSetCode(contract));
+
+			Ok(().into())
+		}
+
+		/// Propose a code upgrade of a contract, which can be applied with `apply_set_code`
+		/// once the contract's upgrade delay has passed.
+		///
+		/// - `contract`: The contract whose code is being upgraded, must not be marked as deployed
+		/// - `code_hash`: The Keccak256 hash of the new code
+		#[pallet::weight(<T as Config>::WeightInfo::propose_set_code())]
+		#[transactional]
+		pub fn propose_set_code(
+			origin: OriginFor<T>,
+			contract: EvmAddress,
+			code_hash: H256,
+		) -> DispatchResultWithPostInfo {
+			let root_or_signed = Self::ensure_root_or_signed(origin)?;
+			Self::ensure_can_set_code(&root_or_signed, &contract)?;
+
+			let apply_after =
+				frame_system::Pallet::<T>::block_number().saturating_add(Self::upgrade_delay(&contract));
+			PendingUpgrades::<T>::insert(
+				contract,
+				PendingUpgrade {
+					code_hash,
+					apply_after,
+				},
+			);
+
+			Pallet::<T>::deposit_event(Event::<T>::ContractUpgradeProposed(contract, code_hash, apply_after));
+
+			Ok(().into())
+		}
+
+		/// Apply the proposed code upgrade of a contract.
+		///
+		/// - `contract`: The contract whose code is being upgraded, must not be marked as deployed
+		/// - `code`: The new code, must match the proposed code hash
+		#[pallet::weight(<T as Config>::WeightInfo::apply_set_code())]
+		#[transactional]
+		pub fn apply_set_code(origin: OriginFor<T>, contract: EvmAddress, code: Vec<u8>) -> DispatchResultWithPostInfo {
+			let root_or_signed = Self::ensure_root_or_signed(origin)?;
+
+			let pending_upgrade = Self::pending_upgrades(contract).ok_or(Error::<T>::ContractUpgradeNotProposed)?;
+			ensure!(
+				code_hash(&code) == pending_upgrade.code_hash,
+				Error::<T>::ContractUpgradeCodeMismatch
+			);
+			ensure!(
+				frame_system::Pallet::<T>::block_number() >= pending_upgrade.apply_after,
+				Error::<T>::ContractUpgradeNotReady
+			);
+
 			Self::do_set_code(root_or_signed, contract, code)?;
+			PendingUpgrades::<T>::remove(contract);
 
 			Pallet::<T>::deposit_event(Event::<T>::ContractSetCode(contract));
 
 			Ok(().into())
 		}
 
+		/// Set the delay between proposing and applying a code upgrade of a contract.
+		/// The maintainer can only increase the delay.
+		///
+		/// - `contract`: The contract whose upgrade delay is being set
+		/// - `delay`: The number of blocks a proposed code upgrade is timelocked
+		#[pallet::weight(<T as Config>::WeightInfo::set_upgrade_delay())]
+		#[transactional]
+		pub fn set_upgrade_delay(
+			origin: OriginFor<T>,
+			contract: EvmAddress,
+			delay: T::BlockNumber,
+		) -> DispatchResultWithPostInfo {
+			let root_or_signed = Self::ensure_root_or_signed(origin)?;
+			Self::ensure_can_set_code(&root_or_signed, &contract)?;
+
+			if root_or_signed.is_right() {
+				ensure!(
+					delay >= Self::upgrade_delay(&contract),
+					Error::<T>::CannotReduceUpgradeDelay
+				);
+			}
+			ContractUpgradeDelays::<T>::insert(contract, delay);
+
+			Pallet::<T>::deposit_event(Event::<T>::ContractUpgradeDelaySet(contract, delay));
+
+			Ok(().into())
+		}
+
 		/// Remove a contract at a given address.
 		///
 		/// - `contract`: The contract to remove, must not be marked as deployed
@@ -835,6 +1326,11 @@ pub mod module {
 }
 
 impl<T: Config> Pallet<T> {
+	/// The delay between proposing and applying a code upgrade of a contract.
+	pub fn upgrade_delay(contract: &EvmAddress) -> T::BlockNumber {
+		Self::contract_upgrade_delays(contract).unwrap_or_else(T::DefaultUpgradeDelay::get)
+	}
+
 	/// Check whether an account is empty.
 	pub fn is_account_empty(address: &H160) -> bool {
 		let account_id = T::AddressMapping::get_account_id(address);
@@ -844,6 +1340,11 @@ impl<T: Config> Pallet<T> {
 			return false;
 		}
 
+		// the storages of a removed contract are still being deleted.
+		if PendingContractRemovals::<T>::contains_key(address) {
+			return false;
+		}
+
 		Self::accounts(address).map_or(true, |account_info| {
 			account_info.contract_info.is_none() && account_info.nonce.is_zero()
 		})
@@ -858,8 +1359,13 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
+	/// Remove a contract. The contract is dead right away, its storages are deleted later by the
+	/// idle scheduler, which releases the storage deposit of them to `refund_to` if any. If the
+	/// task queue of the idle scheduler is full, the deletion is scheduled later on idle.
+	///
+	/// Returns the storage bytes released right away, the code size and extra bytes.
 	#[transactional]
-	pub fn remove_contract(address: &EvmAddress) -> Result<u32, DispatchError> {
+	pub fn remove_contract(address: &EvmAddress, refund_to: Option<T::AccountId>) -> Result<u32, DispatchError> {
 		let address_account = T::AddressMapping::get_account_id(address);
 
 		let size = Accounts::<T>::try_mutate_exists(address, |account_info| -> Result<u32, DispatchError> {
@@ -869,8 +1375,10 @@ impl<T: Config> Pallet<T> {
 			let maintainer_account = T::AddressMapping::get_account_id(&contract_info.maintainer);
 			T::TransferAll::transfer_all(&address_account, &maintainer_account)?;
 
+			let mut code_size = 0;
 			CodeInfos::<T>::mutate_exists(&contract_info.code_hash, |maybe_code_info| {
 				if let Some(code_info) = maybe_code_info.as_mut() {
+					code_size = code_info.code_size;
 					code_info.ref_count = code_info.ref_count.saturating_sub(1);
 					if code_info.ref_count == 0 {
 						Codes::<T>::remove(&contract_info.code_hash);
@@ -882,9 +1390,13 @@ impl<T: Config> Pallet<T> {
 				}
 			});
 
-			AccountStorages::<T>::remove_prefix(address, None);
+			let total_size = ContractStorageSizes::<T>::take(address);
+			let size = total_size.min(code_size.saturating_add(T::NewContractExtraBytes::get()));
+			ContractUpgradeDelays::<T>::remove(address);
+			PendingUpgrades::<T>::remove(address);
 
-			let size = ContractStorageSizes::<T>::take(address);
+			PendingContractRemovals::<T>::insert(address, total_size.saturating_sub(size));
+			Self::schedule_contract_removal(address, refund_to);
 
 			Ok(size)
 		})?;
@@ -896,6 +1408,115 @@ impl<T: Config> Pallet<T> {
 		Ok(size)
 	}
 
+	/// Schedule the deletion of the storages of a removed contract, or keep it in
+	/// `UnscheduledContractRemovals` if the task queue of the idle scheduler is full. Returns
+	/// whether the deletion is scheduled.
+	fn schedule_contract_removal(address: &EvmAddress, refund_to: Option<T::AccountId>) -> bool {
+		let task = EvmTask::Remove {
+			contract: *address,
+			refund_to: refund_to.clone(),
+		};
+		if T::IdleScheduler::schedule(task.into()).is_ok() {
+			UnscheduledContractRemovals::<T>::remove(address);
+			true
+		} else {
+			UnscheduledContractRemovals::<T>::insert(address, refund_to);
+			false
+		}
+	}
+
+	/// Retry scheduling the contract removals kept in `UnscheduledContractRemovals`, as many as
+	/// `remaining_weight` allows, until the task queue of the idle scheduler is full again.
+	/// Returns the used weight.
+	fn schedule_contract_removals(remaining_weight: Weight) -> Weight {
+		let db_weight = <T as frame_system::Config>::DbWeight::get();
+		// iterate the removal, read and write the task queue, the next task id and the task, and
+		// remove the removal.
+		let weight_per_removal = db_weight.reads_writes(3, 4);
+		let max_removals = remaining_weight.checked_div(weight_per_removal).unwrap_or(Weight::MAX);
+		if max_removals.is_zero() {
+			return Zero::zero();
+		}
+
+		let removals: Vec<(EvmAddress, Option<T::AccountId>)> = UnscheduledContractRemovals::<T>::iter()
+			.take(max_removals.unique_saturated_into())
+			.collect();
+		let mut used_weight: Weight = Zero::zero();
+		for (address, refund_to) in removals {
+			used_weight = used_weight.saturating_add(weight_per_removal);
+			if !Self::schedule_contract_removal(&address, refund_to) {
+				break;
+			}
+		}
+		used_weight
+	}
+
+	/// Delete up to `limit` storages of a removed contract, releasing the storage deposit of them
+	/// to `refund_to` if any. Once all storages are deleted, the rest of the storage deposit is
+	/// released too.
+	///
+	/// Returns the result, the number of the deleted storages and whether all storages are deleted.
+	/// The deletion is reverted if releasing the storage deposit fails, in which case `limit`
+	/// storages are reported as deleted to account for the weight.
+	pub fn remove_contract_storages(
+		contract: &EvmAddress,
+		refund_to: Option<&T::AccountId>,
+		limit: u32,
+	) -> (DispatchResult, u32, bool) {
+		let result = with_transaction_result(|| -> Result<(u32, bool), DispatchError> {
+			let (count, finished) = match AccountStorages::<T>::remove_prefix(contract, Some(limit)) {
+				AllRemoved(count) => (count, true),
+				SomeRemaining(count) => (count, false),
+			};
+			let removed = count.saturating_mul(STORAGE_SIZE);
+
+			if finished {
+				PendingContractRemovals::<T>::remove(contract);
+				Self::deposit_event(Event::<T>::ContractStoragesRemoved(*contract));
+			} else {
+				PendingContractRemovals::<T>::mutate_exists(contract, |maybe_remaining| {
+					if let Some(remaining) = maybe_remaining.as_mut() {
+						*remaining = remaining.saturating_sub(removed);
+					}
+				});
+			}
+
+			if let Some(who) = refund_to {
+				let contract_account = T::AddressMapping::get_account_id(contract);
+				let reserved = T::Currency::reserved_balance_named(&RESERVE_ID_STORAGE_DEPOSIT, &contract_account);
+				let amount = if finished {
+					reserved
+				} else {
+					T::StorageDepositPerByte::get()
+						.saturating_mul(removed.into())
+						.min(reserved)
+				};
+				if !amount.is_zero() {
+					T::Currency::repatriate_reserved_named(
+						&RESERVE_ID_STORAGE_DEPOSIT,
+						&contract_account,
+						who,
+						amount,
+						BalanceStatus::Free,
+					)?;
+				}
+			}
+
+			Ok((count, finished))
+		});
+
+		match result {
+			Ok((count, finished)) => (Ok(()), count, finished),
+			Err(e) => (Err(e), limit, false),
+		}
+	}
+
+	/// The removed contracts whose storages are still being deleted, with the bytes of the
+	/// storages left.
+	pub fn contracts_pending_removal() -> Vec<(EvmAddress, u32)> {
+		PendingContractRemovals::<T>::iter().collect()
+	}
+
 	/// Removes an account from Accounts and AccountStorages.
 	pub fn remove_account(address: &EvmAddress) -> DispatchResult {
 		// Deref code, and remove it if ref count is zero.
@@ -936,7 +1557,7 @@ impl<T: Config> Pallet<T> {
 	pub fn create_contract(source: H160, address: H160, code: Vec<u8>) {
 		let bounded_code: BoundedVec<u8, MaxCodeSize> = code
 			.try_into()
-			.expect("checked by create_contract_limit in SETHEUM_CONFIG; qed");
+			.expect("checked by create_contract_limit in EVM config; qed");
 		if bounded_code.is_empty() {
 			return;
 		}
@@ -992,6 +1613,32 @@ impl<T: Config> Pallet<T> {
 		frame_system::Pallet::<T>::inc_providers(&T::AddressMapping::get_account_id(&address));
 	}
 
+	/// The EVM log of a scheduled call run, with the event signature, `from` and `target` as
+	/// topics and the ABI encoded `task_id` as data.
+	fn scheduled_call_log(
+		address: EvmAddress,
+		signature: &[u8],
+		from: EvmAddress,
+		target: EvmAddress,
+		task_id: &[u8],
+	) -> Log {
+		let mut data = Vec::with_capacity(64 + (task_id.len() + 31) / 32 * 32);
+		data.extend_from_slice(H256::from_low_u64_be(32).as_bytes());
+		data.extend_from_slice(H256::from_low_u64_be(task_id.len() as u64).as_bytes());
+		data.extend_from_slice(task_id);
+		data.resize(64 + (task_id.len() + 31) / 32 * 32, 0);
+
+		Log {
+			address,
+			topics: vec![
+				H256::from_slice(Keccak256::digest(signature).as_slice()),
+				H256::from(from),
+				H256::from(target),
+			],
+			data,
+		}
+	}
+
 	/// Get the account basic in EVM format.
 	pub fn account_basic(address: &EvmAddress) -> Account {
 		let account_id = T::AddressMapping::get_account_id(address);
@@ -1170,6 +1817,36 @@ impl<T: Config> Pallet<T> {
 		})
 	}
 
+	/// The storage accounting of a contract, the bytes used, the deposit reserved and the
+	/// maintainer.
+	pub fn contract_storage_info(contract: &EvmAddress) -> Option<ContractStorageInfo<BalanceOf<T>>> {
+		let contract_info = Self::accounts(contract)?.contract_info?;
+		let contract_account = T::AddressMapping::get_account_id(contract);
+
+		Some(ContractStorageInfo {
+			used_storage: Self::contract_storage_sizes(contract),
+			deposit: T::Currency::reserved_balance_named(&RESERVE_ID_STORAGE_DEPOSIT, &contract_account),
+			maintainer: contract_info.maintainer,
+			refund_per_slot: T::StorageDepositPerByte::get().saturating_mul(STORAGE_SIZE.into()),
+		})
+	}
+
+	/// Ensure the origin can set the code of the contract, the signer must be the maintainer of a
+	/// contract not marked as deployed.
+	fn ensure_can_set_code(root_or_signed: &Either<(), T::AccountId>, contract: &EvmAddress) -> DispatchResult {
+		let contract_info = Self::accounts(contract)
+			.and_then(|account_info| account_info.contract_info)
+			.ok_or(Error::<T>::ContractNotFound)?;
+
+		if let Either::Right(signer) = root_or_signed {
+			let maintainer = T::AddressMapping::get_evm_address(signer).ok_or(Error::<T>::AddressNotMapped)?;
+			ensure!(contract_info.maintainer == maintainer, Error::<T>::NoPermission);
+			ensure!(!contract_info.deployed, Error::<T>::ContractAlreadyDeployed);
+		}
+
+		Ok(())
+	}
+
 	/// Selfdestruct a contract at a given address.
 	fn do_selfdestruct(who: T::AccountId, maintainer: &EvmAddress, contract: EvmAddress) -> DispatchResult {
 		let account_info = Self::accounts(contract).ok_or(Error::<T>::ContractNotFound)?;
@@ -1181,7 +1858,7 @@ impl<T: Config> Pallet<T> {
 		ensure!(contract_info.maintainer == *maintainer, Error::<T>::NoPermission);
 		ensure!(!contract_info.deployed, Error::<T>::ContractAlreadyDeployed);
 
-		let storage = Self::remove_contract(&contract)?;
+		let storage = Self::remove_contract(&contract, Some(who.clone()))?;
 
 		let contract_account = T::AddressMapping::get_account_id(&contract);
 
@@ -1307,6 +1984,25 @@ impl<T: Config> Pallet<T> {
 
 		Ok(())
 	}
+
+	/// Check that contracts pending removal are gone from `Accounts`, and that the unscheduled
+	/// removals are pending.
+	#[cfg(feature = "try-runtime")]
+	pub fn check_invariants() -> Result<(), &'static str> {
+		for (address, _) in PendingContractRemovals::<T>::iter() {
+			ensure!(
+				Self::accounts(address).map_or(true, |account_info| account_info.contract_info.is_none()),
+				"Contract pending removal still has contract info"
+			);
+		}
+		for address in UnscheduledContractRemovals::<T>::iter_keys() {
+			ensure!(
+				PendingContractRemovals::<T>::contains_key(address),
+				"Unscheduled contract removal is not pending"
+			);
+		}
+		Ok(())
+	}
 }
 
 impl<T: Config> EVMTrait<T::AccountId> for Pallet<T> {
@@ -1333,6 +2029,7 @@ impl<T: Config> EVMTrait<T::AccountId> for Pallet<T> {
 				value,
 				gas_limit,
 				storage_limit,
+				vec![],
 				&config,
 			);
 
@@ -1391,6 +2088,27 @@ impl<T: Config> EVMStateRentTrait<T::AccountId, BalanceOf<T>> for Pallet<T> {
 	fn transfer_maintainer(from: T::AccountId, contract: EvmAddress, new_maintainer: EvmAddress) -> DispatchResult {
 		Pallet::<T>::do_transfer_maintainer(from, contract, new_maintainer)
 	}
+
+	fn query_storage_usage(contract: EvmAddress) -> Result<u32, DispatchError> {
+		Self::contract_storage_info(&contract)
+			.map(|info| info.used_storage)
+			.ok_or_else(|| Error::<T>::ContractNotFound.into())
+	}
+
+	fn query_storage_deposit(contract: EvmAddress) -> Result<BalanceOf<T>, DispatchError> {
+		Self::contract_storage_info(&contract)
+			.map(|info| info.deposit)
+			.ok_or_else(|| Error::<T>::ContractNotFound.into())
+	}
+
+	fn query_pending_upgrade(contract: EvmAddress) -> Option<(H256, u32)> {
+		Self::pending_upgrades(contract).map(|pending_upgrade| {
+			(
+				pending_upgrade.code_hash,
+				pending_upgrade.apply_after.unique_saturated_into(),
+			)
+		})
+	}
 }
 
 pub struct CallKillAccount<T>(PhantomData<T>);
@@ -22,18 +22,22 @@
 
 use super::*;
 
-use frame_support::{construct_runtime, ord_parameter_types, parameter_types, traits::FindAuthor, ConsensusEngineId};
-use frame_system::EnsureSignedBy;
+use frame_support::{
+	construct_runtime, ord_parameter_types, parameter_types,
+	traits::{FindAuthor, Nothing},
+	ConsensusEngineId,
+};
+use frame_system::{EnsureRoot, EnsureSignedBy};
 use module_support::mocks::MockAddressMapping;
 use orml_traits::parameter_type_with_key;
-use primitives::{Amount, BlockNumber, CurrencyId, ReserveIdentifier, TokenSymbol};
+use primitives::{define_combined_task, Amount, BlockNumber, CurrencyId, ReserveIdentifier, TokenSymbol};
 use sp_core::{H160, H256};
 use sp_runtime::{
 	testing::Header,
 	traits::{BlakeTwo256, IdentityLookup},
 	AccountId32,
 };
-use std::{collections::BTreeMap, str::FromStr};
+use std::{cell::RefCell, collections::BTreeMap, str::FromStr};
 
 mod evm_mod {
 	pub use super::super::*;
@@ -126,6 +130,37 @@ impl orml_currencies::Config for Runtime {
 }
 pub type AdaptedBasicCurrency = orml_currencies::BasicCurrencyAdapter<Runtime, Balances, Amount, BlockNumber>;
 
+define_combined_task! {
+	#[derive(Clone, Debug, PartialEq, Encode, Decode, TypeInfo)]
+	pub enum ScheduledTasks {
+		EvmTask(EvmTask<Runtime>),
+	}
+}
+
+parameter_types! {
+	pub const MinimumWeightRemainInBlock: Weight = 0;
+	pub const MaxTaskRetries: u32 = 3;
+	pub const TaskRetryBackoff: u64 = 10;
+	pub const TaskDeposit: u64 = 10;
+	pub const MaxTasksPerAccount: u32 = 10;
+	pub const MaxTasks: u32 = 100;
+}
+
+impl idle_scheduler::Config for Runtime {
+	type Event = Event;
+	type WeightInfo = ();
+	type Task = ScheduledTasks;
+	type MinimumWeightRemainInBlock = MinimumWeightRemainInBlock;
+	type MaxTaskRetries = MaxTaskRetries;
+	type TaskRetryBackoff = TaskRetryBackoff;
+	type Currency = Balances;
+	type TaskDeposit = TaskDeposit;
+	type MaxTasksPerAccount = MaxTasksPerAccount;
+	type MaxTasks = MaxTasks;
+	type SignedTaskFilter = Nothing;
+	type GovernanceOrigin = EnsureRoot<AccountId32>;
+}
+
 pub struct GasToWeight;
 
 impl Convert<u64, u64> for GasToWeight {
@@ -146,6 +181,8 @@ impl FindAuthor<AccountId32> for AuthorGiven {
 
 parameter_types! {
 	pub NetworkContractSource: H160 = alice();
+	pub const Hardfork: EvmHardfork = EvmHardfork::Istanbul;
+	pub static DefaultUpgradeDelay: u64 = 0;
 }
 
 ord_parameter_types! {
@@ -159,6 +196,27 @@ ord_parameter_types! {
 	pub const ChainId: u64 = 1;
 }
 
+thread_local! {
+	static PAUSED_CALLS: RefCell<Vec<(H160, Option<[u8; 4]>)>> = RefCell::new(Vec::new());
+}
+
+/// Pause the calls to `contract`, or only to its function `selector`.
+pub fn pause_call(contract: H160, selector: Option<[u8; 4]>) {
+	PAUSED_CALLS.with(|paused| paused.borrow_mut().push((contract, selector)));
+}
+
+pub struct MockCallFilter;
+impl EvmCallFilter for MockCallFilter {
+	fn is_call_paused(contract: &H160, selector: Option<[u8; 4]>) -> bool {
+		PAUSED_CALLS.with(|paused| {
+			paused
+				.borrow()
+				.iter()
+				.any(|(c, s)| c == contract && (s.is_none() || *s == selector))
+		})
+	}
+}
+
 impl Config for Runtime {
 	type AddressMapping = MockAddressMapping;
 	type Currency = Balances;
@@ -169,6 +227,7 @@ impl Config for Runtime {
 	type Event = Event;
 	type Precompiles = ();
 	type ChainId = ChainId;
+	type Hardfork = Hardfork;
 	type GasToWeight = GasToWeight;
 	type ChargeTransactionPayment = ();
 
@@ -176,11 +235,15 @@ impl Config for Runtime {
 	type NetworkContractSource = NetworkContractSource;
 	type DeveloperDeposit = DeveloperDeposit;
 	type DeploymentFee = DeploymentFee;
+	type DefaultUpgradeDelay = DefaultUpgradeDelay;
 	type TreasuryAccount = TreasuryAccount;
 	type FreeDeploymentOrigin = EnsureSignedBy<CouncilAccount, AccountId32>;
 
+	type CallFilter = MockCallFilter;
 	type Runner = crate::runner::stack::Runner<Self>;
 	type FindAuthor = AuthorGiven;
+	type Task = ScheduledTasks;
+	type IdleScheduler = IdleScheduler;
 	type WeightInfo = ();
 }
 
@@ -198,6 +261,7 @@ construct_runtime!(
 		Tokens: orml_tokens::{Pallet, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		Currencies: orml_currencies::{Pallet, Call, Event<T>},
+		IdleScheduler: idle_scheduler::{Pallet, Call, Storage, Event<T>},
 	}
 );
 
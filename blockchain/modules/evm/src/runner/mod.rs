@@ -26,7 +26,7 @@ use crate::{BalanceOf, CallInfo, Config, CreateInfo, ExitError};
 use evm::{backend::Backend, Transfer};
 use frame_support::dispatch::DispatchError;
 pub use primitives::{
-	evm::{Account, EvmAddress, Log, Vicinity},
+	evm::{AccessListItem, Account, EvmAddress, Log, Vicinity},
 	ReserveIdentifier, MIRRORED_NFT_ADDRESS_START,
 };
 use sp_core::{H160, H256};
@@ -42,6 +42,7 @@ pub trait Runner<T: Config> {
 		value: BalanceOf<T>,
 		gas_limit: u64,
 		storage_limit: u32,
+		access_list: Vec<AccessListItem>,
 		config: &evm::Config,
 	) -> Result<CallInfo, DispatchError>;
 
@@ -51,6 +52,7 @@ pub trait Runner<T: Config> {
 		value: BalanceOf<T>,
 		gas_limit: u64,
 		storage_limit: u32,
+		access_list: Vec<AccessListItem>,
 		config: &evm::Config,
 	) -> Result<CreateInfo, DispatchError>;
 
@@ -61,6 +63,7 @@ pub trait Runner<T: Config> {
 		value: BalanceOf<T>,
 		gas_limit: u64,
 		storage_limit: u32,
+		access_list: Vec<AccessListItem>,
 		config: &evm::Config,
 	) -> Result<CreateInfo, DispatchError>;
 
@@ -71,6 +74,7 @@ pub trait Runner<T: Config> {
 		value: BalanceOf<T>,
 		gas_limit: u64,
 		storage_limit: u32,
+		access_list: Vec<AccessListItem>,
 		config: &evm::Config,
 	) -> Result<CreateInfo, DispatchError>;
 }
@@ -86,6 +90,10 @@ pub trait StackState<'config>: Backend {
 
 	fn is_empty(&self, address: H160) -> bool;
 	fn deleted(&self, address: H160) -> bool;
+	fn is_pending_removal(&self, address: H160) -> bool;
+	fn is_cold(&self, address: H160) -> bool;
+	fn is_storage_cold(&self, address: H160, key: H256) -> bool;
+	fn is_call_paused(&self, address: H160, input: &[u8]) -> bool;
 
 	fn inc_nonce(&mut self, address: H160);
 	fn set_storage(&mut self, address: H160, key: H256, value: H256);
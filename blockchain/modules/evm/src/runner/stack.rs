@@ -24,11 +24,11 @@
 use crate::{
 	precompiles::PrecompileSet,
 	runner::{
-		state::{StackExecutor, StackSubstateMetadata},
+		state::{Accessed, StackExecutor, StackSubstateMetadata},
 		Runner as RunnerT, StackState as StackStateT,
 	},
 	AccountInfo, AccountStorages, Accounts, BalanceOf, CallInfo, Config, ContractStorageSizes, CreateInfo, Error,
-	Event, ExecutionInfo, One, Pallet, STORAGE_SIZE,
+	Event, ExecutionInfo, One, Pallet, PendingContractRemovals, STORAGE_SIZE,
 };
 use evm::{backend::Backend as BackendT, ExitError, ExitReason, Transfer};
 use frame_support::{
@@ -38,7 +38,7 @@ use frame_support::{
 };
 use module_support::AddressMapping;
 pub use primitives::{
-	evm::{Account, EvmAddress, Log, Vicinity},
+	evm::{AccessListItem, Account, EvmAddress, Log, Vicinity},
 	ReserveIdentifier, MIRRORED_NFT_ADDRESS_START,
 };
 use sha3::{Digest, Keccak256};
@@ -162,7 +162,11 @@ impl<T: Config> Runner<T> {
 				"Deleting account at {:?}",
 				address
 			);
-			Pallet::<T>::remove_contract(&address).map_err(|e| {
+			// the storage deposit of the deleted storages is released to the maintainer
+			let maintainer = Pallet::<T>::accounts(&address)
+				.and_then(|account_info| account_info.contract_info)
+				.map(|contract_info| T::AddressMapping::get_account_id(&contract_info.maintainer));
+			Pallet::<T>::remove_contract(&address, maintainer).map_err(|e| {
 				log::debug!(
 					target: "evm",
 					"CannotKillContract address {:?}, reason: {:?}",
@@ -198,6 +202,7 @@ impl<T: Config> RunnerT<T> for Runner<T> {
 		value: BalanceOf<T>,
 		gas_limit: u64,
 		storage_limit: u32,
+		access_list: Vec<AccessListItem>,
 		config: &evm::Config,
 	) -> Result<CallInfo, DispatchError> {
 		// if the contract not deployed, the caller must be developer or contract or maintainer.
@@ -209,7 +214,14 @@ impl<T: Config> RunnerT<T> for Runner<T> {
 
 		let value = U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(value));
 		let info = Self::execute(source, origin, value, gas_limit, storage_limit, config, |executor| {
-			executor.transact_call(source, target, value, input, gas_limit)
+			executor.transact_call(
+				source,
+				target,
+				value,
+				input,
+				gas_limit,
+				access_list_to_tuples(access_list),
+			)
 		})?;
 
 		if info.exit_reason.is_succeed() {
@@ -233,6 +245,7 @@ impl<T: Config> RunnerT<T> for Runner<T> {
 		value: BalanceOf<T>,
 		gas_limit: u64,
 		storage_limit: u32,
+		access_list: Vec<AccessListItem>,
 		config: &evm::Config,
 	) -> Result<CreateInfo, DispatchError> {
 		let value = U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(value));
@@ -240,7 +253,10 @@ impl<T: Config> RunnerT<T> for Runner<T> {
 			let address = executor
 				.create_address(evm::CreateScheme::Legacy { caller: source })
 				.unwrap_or_default(); // transact_create will check the address
-			(executor.transact_create(source, value, init, gas_limit), address)
+			(
+				executor.transact_create(source, value, init, gas_limit, access_list_to_tuples(access_list)),
+				address,
+			)
 		})?;
 
 		if info.exit_reason.is_succeed() {
@@ -264,6 +280,7 @@ impl<T: Config> RunnerT<T> for Runner<T> {
 		value: BalanceOf<T>,
 		gas_limit: u64,
 		storage_limit: u32,
+		access_list: Vec<AccessListItem>,
 		config: &evm::Config,
 	) -> Result<CreateInfo, DispatchError> {
 		let value = U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(value));
@@ -276,7 +293,17 @@ impl<T: Config> RunnerT<T> for Runner<T> {
 					salt,
 				})
 				.unwrap_or_default(); // transact_create2 will check the address
-			(executor.transact_create2(source, value, init, salt, gas_limit), address)
+			(
+				executor.transact_create2(
+					source,
+					value,
+					init,
+					salt,
+					gas_limit,
+					access_list_to_tuples(access_list),
+				),
+				address,
+			)
 		})?;
 
 		if info.exit_reason.is_succeed() {
@@ -300,12 +327,20 @@ impl<T: Config> RunnerT<T> for Runner<T> {
 		value: BalanceOf<T>,
 		gas_limit: u64,
 		storage_limit: u32,
+		access_list: Vec<AccessListItem>,
 		config: &evm::Config,
 	) -> Result<CreateInfo, DispatchError> {
 		let value = U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(value));
 		let info = Self::execute(source, source, value, gas_limit, storage_limit, config, |executor| {
 			(
-				executor.transact_create_at_address(source, address, value, init, gas_limit),
+				executor.transact_create_at_address(
+					source,
+					address,
+					value,
+					init,
+					gas_limit,
+					access_list_to_tuples(access_list),
+				),
 				address,
 			)
 		})?;
@@ -325,6 +360,13 @@ impl<T: Config> RunnerT<T> for Runner<T> {
 	}
 }
 
+fn access_list_to_tuples(access_list: Vec<AccessListItem>) -> Vec<(H160, Vec<H256>)> {
+	access_list
+		.into_iter()
+		.map(|item| (item.address, item.storage_keys))
+		.collect()
+}
+
 struct SubstrateStackSubstate<'config> {
 	metadata: StackSubstateMetadata<'config>,
 	deletes: BTreeSet<H160>,
@@ -418,6 +460,15 @@ impl<'config> SubstrateStackSubstate<'config> {
 		self.deletes.insert(address);
 	}
 
+	pub fn recursive_is_cold<F: Fn(&Accessed) -> bool>(&self, f: &F) -> bool {
+		let local_is_accessed = self.metadata.accessed().as_ref().map(f).unwrap_or(false);
+		if local_is_accessed {
+			false
+		} else {
+			self.parent.as_ref().map(|p| p.recursive_is_cold(f)).unwrap_or(true)
+		}
+	}
+
 	pub fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>) {
 		self.logs.push(Log { address, topics, data });
 	}
@@ -486,6 +537,11 @@ impl<'vicinity, 'config, T: Config> BackendT for SubstrateStackState<'vicinity,
 		U256::zero()
 	}
 
+	fn block_base_fee_per_gas(&self) -> U256 {
+		// there is no fee market, the base fee is the fixed gas price
+		self.vicinity.gas_price
+	}
+
 	fn chain_id(&self) -> U256 {
 		U256::from(T::ChainId::get())
 	}
@@ -549,6 +605,29 @@ impl<'vicinity, 'config, T: Config> StackStateT<'config> for SubstrateStackState
 		self.substate.deleted(address)
 	}
 
+	fn is_pending_removal(&self, address: H160) -> bool {
+		PendingContractRemovals::<T>::contains_key(address)
+	}
+
+	fn is_cold(&self, address: H160) -> bool {
+		self.substate
+			.recursive_is_cold(&|a| a.accessed_addresses.contains(&address))
+	}
+
+	fn is_storage_cold(&self, address: H160, key: H256) -> bool {
+		self.substate
+			.recursive_is_cold(&|a: &Accessed| a.accessed_storage.contains(&(address, key)))
+	}
+
+	fn is_call_paused(&self, address: H160, input: &[u8]) -> bool {
+		let selector = input.get(0..4).map(|selector| {
+			let mut bytes = [0u8; 4];
+			bytes.copy_from_slice(selector);
+			bytes
+		});
+		T::CallFilter::is_call_paused(&address, selector)
+	}
+
 	fn inc_nonce(&mut self, address: H160) {
 		Accounts::<T>::mutate(&address, |maybe_account| {
 			if let Some(account) = maybe_account.as_mut() {
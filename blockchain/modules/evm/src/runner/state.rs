@@ -20,23 +20,23 @@
 
 // Synchronize with https://github.com/rust-blockchain/evm/blob/master/src/executor/stack/mod.rs
 
-use crate::{encode_revert_message, runner::StackState, StorageMeter};
+use crate::{encode_revert_message, runner::StackState, StorageMeter, CONTRACT_PAUSED};
 use core::{cmp::min, convert::Infallible};
 use evm::{
 	Capture, Config, Context, CreateScheme, ExitError, ExitReason, ExitRevert, ExitSucceed, Opcode, Runtime, Stack,
 	Transfer,
 };
-use evm_gasometer::{self as gasometer, Gasometer};
+use evm_gasometer::{self as gasometer, Gasometer, StorageTarget};
 use evm_runtime::Handler;
 use frame_support::log;
 use primitive_types::{H160, H256, U256};
 pub use primitives::{
 	evm::{Account, EvmAddress, Log, Vicinity},
-	ReserveIdentifier, H160_PREFIX_DEXSHARE, H160_PREFIX_TOKEN, MIRRORED_NFT_ADDRESS_START, PREDEPLOY_ADDRESS_START,
-	SYSTEM_CONTRACT_ADDRESS_PREFIX,
+	ReserveIdentifier, H160_PREFIX_DEXSHARE, H160_PREFIX_TOKEN, MIRRORED_NFT_ADDRESS_START, PRECOMPILE_ADDRESS_START,
+	PREDEPLOY_ADDRESS_START, SYSTEM_CONTRACT_ADDRESS_PREFIX,
 };
 use sha3::{Digest, Keccak256};
-use sp_std::{rc::Rc, vec::Vec};
+use sp_std::{collections::btree_set::BTreeSet, rc::Rc, vec::Vec};
 
 macro_rules! event {
 	($x:expr) => {};
@@ -63,6 +63,37 @@ pub enum StackExitKind {
 	Failed,
 }
 
+/// Addresses and storage keys accessed in a substate, see EIP-2929.
+#[derive(Default, Clone, Debug, Eq, PartialEq)]
+pub struct Accessed {
+	pub accessed_addresses: BTreeSet<H160>,
+	pub accessed_storage: BTreeSet<(H160, H256)>,
+}
+
+impl Accessed {
+	pub fn access_address(&mut self, address: H160) {
+		self.accessed_addresses.insert(address);
+	}
+
+	pub fn access_addresses<I>(&mut self, addresses: I)
+	where
+		I: Iterator<Item = H160>,
+	{
+		for address in addresses {
+			self.accessed_addresses.insert(address);
+		}
+	}
+
+	pub fn access_storages<I>(&mut self, storages: I)
+	where
+		I: Iterator<Item = (H160, H256)>,
+	{
+		for storage in storages {
+			self.accessed_storage.insert((storage.0, storage.1));
+		}
+	}
+}
+
 pub struct StackSubstateMetadata<'config> {
 	gasometer: Gasometer<'config>,
 	storage_meter: StorageMeter,
@@ -72,10 +103,17 @@ pub struct StackSubstateMetadata<'config> {
 	caller: Option<H160>,
 	// save the contract to charge storage
 	target: Option<H160>,
+	// `None` if the config doesn't increase state access gas
+	accessed: Option<Accessed>,
 }
 
 impl<'config> StackSubstateMetadata<'config> {
 	pub fn new(gas_limit: u64, storage_limit: u32, extra_bytes: u32, config: &'config Config) -> Self {
+		let accessed = if config.increase_state_access_gas {
+			Some(Accessed::default())
+		} else {
+			None
+		};
 		Self {
 			gasometer: Gasometer::new(gas_limit, config),
 			storage_meter: StorageMeter::new(storage_limit, extra_bytes),
@@ -83,6 +121,7 @@ impl<'config> StackSubstateMetadata<'config> {
 			depth: None,
 			caller: None,
 			target: None,
+			accessed,
 		}
 	}
 
@@ -93,6 +132,16 @@ impl<'config> StackSubstateMetadata<'config> {
 		// merge child meter into parent meter
 		self.storage_meter.merge(other.storage_meter());
 
+		// the accessed addresses and storage keys are kept only when the substate is committed
+		if let (Some(mut other_accessed), Some(self_accessed)) = (other.accessed, self.accessed.as_mut()) {
+			self_accessed
+				.accessed_addresses
+				.append(&mut other_accessed.accessed_addresses);
+			self_accessed
+				.accessed_storage
+				.append(&mut other_accessed.accessed_storage);
+		}
+
 		Ok(())
 	}
 
@@ -120,6 +169,7 @@ impl<'config> StackSubstateMetadata<'config> {
 			},
 			caller: None,
 			target: None,
+			accessed: self.accessed.as_ref().map(|_| Accessed::default()),
 		}
 	}
 
@@ -162,6 +212,40 @@ impl<'config> StackSubstateMetadata<'config> {
 	pub fn target_mut(&mut self) -> &mut Option<H160> {
 		&mut self.target
 	}
+
+	pub fn accessed(&self) -> &Option<Accessed> {
+		&self.accessed
+	}
+
+	pub fn access_address(&mut self, address: H160) {
+		if let Some(accessed) = &mut self.accessed {
+			accessed.access_address(address)
+		}
+	}
+
+	pub fn access_addresses<I>(&mut self, addresses: I)
+	where
+		I: Iterator<Item = H160>,
+	{
+		if let Some(accessed) = &mut self.accessed {
+			accessed.access_addresses(addresses);
+		}
+	}
+
+	pub fn access_storage(&mut self, address: H160, key: H256) {
+		if let Some(accessed) = &mut self.accessed {
+			accessed.accessed_storage.insert((address, key));
+		}
+	}
+
+	pub fn access_storages<I>(&mut self, storages: I)
+	where
+		I: Iterator<Item = (H160, H256)>,
+	{
+		if let Some(accessed) = &mut self.accessed {
+			accessed.access_storages(storages);
+		}
+	}
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -254,8 +338,15 @@ impl<'config, S: StackState<'config>> StackExecutor<'config, S> {
 	}
 
 	/// Execute a `CREATE` transaction.
-	pub fn transact_create(&mut self, caller: H160, value: U256, init_code: Vec<u8>, gas_limit: u64) -> ExitReason {
-		let transaction_cost = gasometer::create_transaction_cost(&init_code);
+	pub fn transact_create(
+		&mut self,
+		caller: H160,
+		value: U256,
+		init_code: Vec<u8>,
+		gas_limit: u64,
+		access_list: Vec<(H160, Vec<H256>)>,
+	) -> ExitReason {
+		let transaction_cost = gasometer::create_transaction_cost(&init_code, &access_list);
 		match self
 			.state
 			.metadata_mut()
@@ -266,6 +357,8 @@ impl<'config, S: StackState<'config>> StackExecutor<'config, S> {
 			Err(e) => return e.into(),
 		}
 
+		self.initialize_with_access_list(access_list);
+
 		match self.create_inner(
 			caller,
 			CreateScheme::Legacy { caller },
@@ -287,8 +380,9 @@ impl<'config, S: StackState<'config>> StackExecutor<'config, S> {
 		init_code: Vec<u8>,
 		salt: H256,
 		gas_limit: u64,
+		access_list: Vec<(H160, Vec<H256>)>,
 	) -> ExitReason {
-		let transaction_cost = gasometer::create_transaction_cost(&init_code);
+		let transaction_cost = gasometer::create_transaction_cost(&init_code, &access_list);
 		match self
 			.state
 			.metadata_mut()
@@ -298,6 +392,8 @@ impl<'config, S: StackState<'config>> StackExecutor<'config, S> {
 			Ok(()) => (),
 			Err(e) => return e.into(),
 		}
+
+		self.initialize_with_access_list(access_list);
 		let code_hash = H256::from_slice(Keccak256::digest(&init_code).as_slice());
 
 		match self.create_inner(
@@ -325,8 +421,9 @@ impl<'config, S: StackState<'config>> StackExecutor<'config, S> {
 		value: U256,
 		init_code: Vec<u8>,
 		gas_limit: u64,
+		access_list: Vec<(H160, Vec<H256>)>,
 	) -> ExitReason {
-		let transaction_cost = gasometer::create_transaction_cost(&init_code);
+		let transaction_cost = gasometer::create_transaction_cost(&init_code, &access_list);
 		match self
 			.state
 			.metadata_mut()
@@ -337,6 +434,8 @@ impl<'config, S: StackState<'config>> StackExecutor<'config, S> {
 			Err(e) => return e.into(),
 		}
 
+		self.initialize_with_access_list(access_list);
+
 		match self.create_inner(
 			caller,
 			CreateScheme::Fixed(address),
@@ -358,8 +457,9 @@ impl<'config, S: StackState<'config>> StackExecutor<'config, S> {
 		value: U256,
 		data: Vec<u8>,
 		gas_limit: u64,
+		access_list: Vec<(H160, Vec<H256>)>,
 	) -> (ExitReason, Vec<u8>) {
-		let transaction_cost = gasometer::call_transaction_cost(&data);
+		let transaction_cost = gasometer::call_transaction_cost(&data, &access_list);
 		match self
 			.state
 			.metadata_mut()
@@ -370,6 +470,12 @@ impl<'config, S: StackState<'config>> StackExecutor<'config, S> {
 			Err(e) => return (e.into(), Vec::new()),
 		}
 
+		// the caller and the callee are always warm, see EIP-2929
+		self.state
+			.metadata_mut()
+			.access_addresses(sp_std::iter::once(caller).chain(sp_std::iter::once(address)));
+		self.initialize_with_access_list(access_list);
+
 		self.state.inc_nonce(caller);
 
 		let context = Context {
@@ -397,11 +503,22 @@ impl<'config, S: StackState<'config>> StackExecutor<'config, S> {
 		}
 	}
 
+	/// Warm up the addresses and storage keys of the EIP-2930 access list.
+	fn initialize_with_access_list(&mut self, access_list: Vec<(H160, Vec<H256>)>) {
+		let addresses = access_list.iter().map(|a| a.0);
+		self.state.metadata_mut().access_addresses(addresses);
+
+		let storage_keys = access_list
+			.into_iter()
+			.flat_map(|(address, keys)| keys.into_iter().map(move |key| (address, key)));
+		self.state.metadata_mut().access_storages(storage_keys);
+	}
+
 	/// Get used gas for the current executor, given the price.
 	pub fn used_gas(&self) -> u64 {
 		self.state.metadata().gasometer().total_used_gas()
 			- min(
-				self.state.metadata().gasometer().total_used_gas() / 2,
+				self.state.metadata().gasometer().total_used_gas() / self.config.max_refund_quotient,
 				self.state.metadata().gasometer().refunded_gas() as u64,
 			)
 	}
@@ -513,6 +630,10 @@ impl<'config, S: StackState<'config>> StackExecutor<'config, S> {
 		*self.state.metadata_mut().caller_mut() = Some(caller);
 		*self.state.metadata_mut().target_mut() = Some(address);
 
+		// the creator and the created address are always warm, see EIP-2929
+		self.state.metadata_mut().access_address(caller);
+		self.state.metadata_mut().access_address(address);
+
 		event!(Create {
 			caller,
 			address,
@@ -565,6 +686,12 @@ impl<'config, S: StackState<'config>> StackExecutor<'config, S> {
 				return Capture::Exit((ExitError::CreateCollision.into(), None, Vec::new()));
 			}
 
+			// the storages of a removed contract at the address are still being deleted
+			if self.state.is_pending_removal(address) {
+				let _ = self.exit_substate(StackExitKind::Failed);
+				return Capture::Exit((ExitError::CreateCollision.into(), None, Vec::new()));
+			}
+
 			self.state.reset_storage(address);
 		}
 
@@ -713,6 +840,12 @@ impl<'config, S: StackState<'config>> StackExecutor<'config, S> {
 			}
 		}
 
+		// calls to a paused contract fail, and so do delegate calls into its code
+		if self.state.is_call_paused(context.address, &input) || self.state.is_call_paused(code_address, &input) {
+			let _ = self.exit_substate(StackExitKind::Failed);
+			return Capture::Exit((ExitError::Other(CONTRACT_PAUSED.into()).into(), Vec::new()));
+		}
+
 		if let Some(transfer) = transfer {
 			match self.state.transfer(transfer) {
 				Ok(()) => (),
@@ -858,10 +991,21 @@ impl<'config, S: StackState<'config>> Handler for StackExecutor<'config, S> {
 	fn block_gas_limit(&self) -> U256 {
 		self.state.block_gas_limit()
 	}
+	fn block_base_fee_per_gas(&self) -> U256 {
+		self.state.block_base_fee_per_gas()
+	}
 	fn chain_id(&self) -> U256 {
 		self.state.chain_id()
 	}
 
+	fn is_cold(&self, address: H160, maybe_index: Option<H256>) -> bool {
+		match maybe_index {
+			// precompiles are always warm
+			None => !is_precompile(address) && self.state.is_cold(address),
+			Some(index) => self.state.is_storage_cold(address, index),
+		}
+	}
+
 	fn deleted(&self, address: H160) -> bool {
 		self.state.deleted(address)
 	}
@@ -937,14 +1081,28 @@ impl<'config, S: StackState<'config>> Handler for StackExecutor<'config, S> {
 			self.state.metadata_mut().gasometer_mut().record_cost(cost)?;
 		} else {
 			let is_static = self.state.metadata().is_static();
-			let (gas_cost, memory_cost) =
+			let (gas_cost, target, memory_cost) =
 				gasometer::dynamic_opcode_cost(context.address, opcode, stack, is_static, self.config, self)?;
 
 			let gasometer = &mut self.state.metadata_mut().gasometer_mut();
 
 			gasometer.record_dynamic_cost(gas_cost, memory_cost)?;
+
+			match target {
+				StorageTarget::Address(address) => self.state.metadata_mut().access_address(address),
+				StorageTarget::Slot(address, key) => self.state.metadata_mut().access_storage(address, key),
+				StorageTarget::None => (),
+			}
 		}
 
 		Ok(())
 	}
 }
+
+/// The addresses of `EvmPrecompiles` (1-4 and 128-130) and of the Setheum precompiles.
+fn is_precompile(address: H160) -> bool {
+	let in_range = |start: u64, end: u64| {
+		address >= H160::from_low_u64_be(start) && address < H160::from_low_u64_be(end)
+	};
+	in_range(1, 5) || in_range(128, 131) || in_range(PRECOMPILE_ADDRESS_START, PREDEPLOY_ADDRESS_START)
+}
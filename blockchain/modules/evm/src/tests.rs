@@ -45,8 +45,8 @@ fn fail_call_return_ok() {
 		let signer: AccountId32 = AccountId32::from(data).into();
 
 		let origin = Origin::signed(signer);
-		assert_ok!(EVM::call(origin.clone(), contract_a(), Vec::new(), 0, 1000000, 0));
-		assert_ok!(EVM::call(origin, contract_b(), Vec::new(), 0, 1000000, 0));
+		assert_ok!(EVM::call(origin.clone(), contract_a(), Vec::new(), 0, 1000000, 0, vec![]));
+		assert_ok!(EVM::call(origin, contract_b(), Vec::new(), 0, 1000000, 0, vec![]));
 	});
 }
 
@@ -59,9 +59,9 @@ fn should_calculate_contract_address() {
 			gas_price: U256::one(),
 			origin: Default::default(),
 		};
-		let metadata = StackSubstateMetadata::new(1000, 1000, NewContractExtraBytes::get(), &SETHEUM_CONFIG);
+		let metadata = StackSubstateMetadata::new(1000, 1000, NewContractExtraBytes::get(), <Runtime as Config>::config());
 		let state = SubstrateStackState::<Runtime>::new(&vicinity, metadata);
-		let mut executor = StackExecutor::new(state, &SETHEUM_CONFIG);
+		let mut executor = StackExecutor::new(state, <Runtime as Config>::config());
 
 		assert_eq!(
 			executor.create_address(evm::CreateScheme::Legacy { caller: addr }),
@@ -111,6 +111,7 @@ fn should_create_and_call_contract() {
 			0,
 			1000000,
 			1000000,
+			vec![],
 			<Runtime as Config>::config(),
 		).unwrap();
 		assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
@@ -138,6 +139,7 @@ fn should_create_and_call_contract() {
 			0,
 			1000000,
 			1000000,
+			vec![],
 			<Runtime as Config>::config(),
 		).unwrap();
 		assert_eq!(
@@ -187,6 +189,7 @@ fn create_reverts_with_message() {
 			0,
 			12_000_000,
 			12_000_000,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.unwrap();
@@ -220,6 +223,7 @@ fn call_reverts_with_message() {
 			0,
 			1000000,
 			1000000,
+			vec![],
 			<Runtime as Config>::config(),
 		).unwrap();
 
@@ -244,6 +248,7 @@ fn call_reverts_with_message() {
 			0,
 			1000000,
 			1000000,
+			vec![],
 			<Runtime as Config>::config(),
 		).unwrap();
 
@@ -292,6 +297,7 @@ fn should_deploy_payable_contract() {
 			amount,
 			1000000,
 			100000,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.unwrap();
@@ -316,6 +322,7 @@ fn should_deploy_payable_contract() {
 			amount,
 			100000,
 			100000,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.unwrap();
@@ -373,6 +380,7 @@ fn should_transfer_from_contract() {
 			0,
 			10000000,
 			10000000,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.expect("create shouldn't fail");
@@ -399,6 +407,7 @@ fn should_transfer_from_contract() {
 			amount,
 			1000000,
 			1000000,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.unwrap();
@@ -419,6 +428,7 @@ fn should_transfer_from_contract() {
 			amount,
 			1000000,
 			1000000,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.unwrap();
@@ -439,6 +449,7 @@ fn should_transfer_from_contract() {
 			amount,
 			1000000,
 			1000000,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.unwrap();
@@ -473,6 +484,7 @@ fn contract_should_deploy_contracts() {
 			0,
 			1000000000,
 			1000000000,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.unwrap();
@@ -504,6 +516,7 @@ fn contract_should_deploy_contracts() {
 			amount,
 			1000000000,
 			1000000000,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.unwrap();
@@ -546,6 +559,7 @@ fn contract_should_deploy_contracts_without_payable() {
 			0,
 			1000000000,
 			1000000000,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.unwrap();
@@ -571,6 +585,7 @@ fn contract_should_deploy_contracts_without_payable() {
 			0,
 			1000000000,
 			1000000000,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.unwrap();
@@ -607,7 +622,7 @@ fn deploy_factory() {
 	).unwrap();
 	new_test_ext().execute_with(|| {
 		let result =
-			<Runtime as Config>::Runner::create(alice(), contract, 0, 2_000_000, 5000, <Runtime as Config>::config())
+			<Runtime as Config>::Runner::create(alice(), contract, 0, 2_000_000, 5000, vec![], <Runtime as Config>::config())
 				.unwrap();
 		assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
 		assert_eq!(result.used_gas.as_u64(), 156_479u64);
@@ -776,6 +791,7 @@ fn should_transfer_maintainer() {
 			0,
 			12_000_000,
 			12_000_000,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.unwrap();
@@ -840,13 +856,14 @@ fn should_deploy() {
 			0,
 			1000000,
 			1000000,
+			vec![],
 			<Runtime as Config>::config(),
 		).unwrap();
 		assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Stopped));
 		assert_eq!(result.used_storage, 0);
 
 		// create contract
-		let result = <Runtime as Config>::Runner::create(alice(), contract, 0, 21_000_000, 21_000_000, <Runtime as Config>::config()).unwrap();
+		let result = <Runtime as Config>::Runner::create(alice(), contract, 0, 21_000_000, 21_000_000, vec![], <Runtime as Config>::config()).unwrap();
 		let contract_address = result.value;
 
 		assert_eq!(result.used_storage, 284);
@@ -868,6 +885,7 @@ fn should_deploy() {
 			0,
 			1000000,
 			1000000,
+			vec![],
 			<Runtime as Config>::config(),
 		));
 
@@ -879,6 +897,7 @@ fn should_deploy() {
 			0,
 			1000000,
 			1000000,
+			vec![],
 		), Error::<Runtime>::NoPermission);
 
 		// developer can call the undeployed contract
@@ -891,6 +910,7 @@ fn should_deploy() {
 			0,
 			1000000,
 			1000000,
+			vec![],
 			<Runtime as Config>::config(),
 		));
 
@@ -913,6 +933,7 @@ fn should_deploy() {
 			0,
 			1000000,
 			1000000,
+			vec![],
 			<Runtime as Config>::config(),
 		));
 
@@ -939,7 +960,7 @@ fn should_deploy_free() {
 		assert_noop!(EVM::deploy_free(Origin::signed(CouncilAccount::get()), H160::default()), Error::<Runtime>::ContractNotFound);
 
 		// create contract
-		let result = <Runtime as Config>::Runner::create(alice(), contract, 0, 21_000_000, 21_000_000, <Runtime as Config>::config()).unwrap();
+		let result = <Runtime as Config>::Runner::create(alice(), contract, 0, 21_000_000, 21_000_000, vec![], <Runtime as Config>::config()).unwrap();
 		let contract_address = result.value;
 
 		// multiply(2, 3)
@@ -956,6 +977,7 @@ fn should_deploy_free() {
 			0,
 			1000000,
 			1000000,
+			vec![],
 		), Error::<Runtime>::NoPermission);
 
 		assert_ok!(EVM::deploy_free(Origin::signed(CouncilAccount::get()), contract_address));
@@ -969,6 +991,7 @@ fn should_deploy_free() {
 			0,
 			1000000,
 			1000000,
+			vec![],
 			<Runtime as Config>::config(),
 		));
 
@@ -1053,6 +1076,7 @@ fn should_set_code() {
 			0,
 			21_000_000,
 			21_000_000,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.unwrap();
@@ -1197,6 +1221,7 @@ fn should_selfdestruct() {
 			amount,
 			1000000,
 			100000,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.unwrap();
@@ -1231,13 +1256,177 @@ fn should_selfdestruct() {
 		assert_eq!(System::providers(&contract_account_id), 2);
 		assert_ok!(EVM::selfdestruct(Origin::signed(alice_account_id), contract_address));
 
+		// the contract is dead, its storage is deleted later
+		assert_eq!(
+			Accounts::<Runtime>::get(&contract_address).and_then(|account_info| account_info.contract_info),
+			None
+		);
+		assert!(!ContractStorageSizes::<Runtime>::contains_key(&contract_address));
+		assert!(!CodeInfos::<Runtime>::contains_key(&code_hash));
+		assert!(!Codes::<Runtime>::contains_key(&code_hash));
+		assert_eq!(
+			PendingContractRemovals::<Runtime>::get(&contract_address),
+			Some(STORAGE_SIZE)
+		);
+		assert_eq!(AccountStorages::<Runtime>::iter_prefix(&contract_address).count(), 1);
+		assert_eq!(
+			reserved_balance(contract_address),
+			STORAGE_SIZE as u64 * <Runtime as Config>::StorageDepositPerByte::get()
+		);
+		assert!(!EVM::is_account_empty(&contract_address));
+
+		IdleScheduler::on_idle(System::block_number(), 1_000_000_000_000);
+
+		System::assert_has_event(Event::EVM(crate::Event::ContractStoragesRemoved(contract_address)));
+		assert_eq!(PendingContractRemovals::<Runtime>::get(&contract_address), None);
+		assert_eq!(AccountStorages::<Runtime>::iter_prefix(&contract_address).count(), 0);
 		assert_eq!(System::providers(&contract_account_id), 0);
 		assert!(!System::account_exists(&contract_account_id));
 		assert!(!Accounts::<Runtime>::contains_key(&contract_address));
-		assert!(!ContractStorageSizes::<Runtime>::contains_key(&contract_address));
+		assert_eq!(
+			balance(alice()),
+			alice_balance + 287 * <Runtime as Config>::StorageDepositPerByte::get() + amount
+		);
+	});
+}
+
+#[test]
+fn remove_contract_storages_in_chunks() {
+	new_test_ext().execute_with(|| {
+		let contract_account_id = <Runtime as Config>::AddressMapping::get_account_id(&contract_a());
+		let alice_account_id = <Runtime as Config>::AddressMapping::get_account_id(&alice());
+		let deposit_per_slot = STORAGE_SIZE as u64 * <Runtime as Config>::StorageDepositPerByte::get();
+
+		for i in 0..(REMOVE_STORAGES_LIMIT + 10) {
+			AccountStorages::<Runtime>::insert(contract_a(), H256::from_low_u64_be(i.into()), H256::from_low_u64_be(1));
+		}
+		PendingContractRemovals::<Runtime>::insert(contract_a(), (REMOVE_STORAGES_LIMIT + 10) * STORAGE_SIZE);
+		let deposit = (REMOVE_STORAGES_LIMIT + 10) as u64 * deposit_per_slot;
+		let _ = Balances::deposit_creating(&contract_account_id, deposit + 1);
+		assert_ok!(Balances::reserve_named(
+			&RESERVE_ID_STORAGE_DEPOSIT,
+			&contract_account_id,
+			deposit
+		));
+		assert_eq!(
+			EVM::contracts_pending_removal(),
+			vec![(contract_a(), (REMOVE_STORAGES_LIMIT + 10) * STORAGE_SIZE)]
+		);
+
+		let alice_balance = balance(alice());
+		let (task_result, updated_task) = EvmTask::<Runtime>::Remove {
+			contract: contract_a(),
+			refund_to: Some(alice_account_id.clone()),
+		}
+		.dispatch(1_000_000_000_000);
+		assert_eq!(task_result.result, Ok(()));
+		assert!(!task_result.finished);
+		assert_eq!(updated_task, None);
+		assert_eq!(AccountStorages::<Runtime>::iter_prefix(&contract_a()).count(), 10);
+		assert_eq!(
+			EVM::contracts_pending_removal(),
+			vec![(contract_a(), 10 * STORAGE_SIZE)]
+		);
+		assert_eq!(
+			balance(alice()),
+			alice_balance + REMOVE_STORAGES_LIMIT as u64 * deposit_per_slot
+		);
+		assert_eq!(reserved_balance(contract_a()), 10 * deposit_per_slot);
+
+		let (task_result, _) = EvmTask::<Runtime>::Remove {
+			contract: contract_a(),
+			refund_to: Some(alice_account_id),
+		}
+		.dispatch(1_000_000_000_000);
+		assert_eq!(task_result.result, Ok(()));
+		assert!(task_result.finished);
+		assert_eq!(AccountStorages::<Runtime>::iter_prefix(&contract_a()).count(), 0);
+		assert_eq!(EVM::contracts_pending_removal(), vec![]);
+		assert_eq!(balance(alice()), alice_balance + deposit);
+		assert_eq!(reserved_balance(contract_a()), 0);
+	});
+}
+
+#[test]
+fn dropped_remove_task_clears_pending_removal() {
+	new_test_ext().execute_with(|| {
+		AccountStorages::<Runtime>::insert(contract_a(), H256::default(), H256::from_low_u64_be(1));
+		PendingContractRemovals::<Runtime>::insert(contract_a(), STORAGE_SIZE);
+
+		EvmTask::<Runtime>::Remove {
+			contract: contract_a(),
+			refund_to: None,
+		}
+		.on_dropped();
+		assert_eq!(EVM::contracts_pending_removal(), vec![]);
+	});
+}
+
+#[test]
+fn selfdestruct_is_scheduled_once_the_task_queue_has_capacity() {
+	// the contract of `should_selfdestruct`
+	let mut contract = from_hex(
+		"0x60806040526040516100c73803806100c783398181016040526020811015602557600080fd5b81019080805190602001909291905050508060008190555050607b8061004c6000396000f3fe608060405260043610601c5760003560e01c806320965255146021575b600080fd5b6027603d565b6040518082815260200191505060405180910390f35b6000805490509056fea265627a7a72315820b832564a9db725638dcef03d07bfbdd2dc818020ea359630317e2126e95c314964736f6c63430005110032"
+	).unwrap();
+
+	new_test_ext().execute_with(|| {
+		let alice_account_id = <Runtime as Config>::AddressMapping::get_account_id(&alice());
+		contract.append(&mut from_hex("0x000000000000000000000000000000000000000000000000000000000000007b").unwrap());
+		let contract_address = <Runtime as Config>::Runner::create(
+			alice(),
+			contract,
+			0,
+			1000000,
+			100000,
+			vec![],
+			<Runtime as Config>::config(),
+		)
+		.unwrap()
+		.value;
+
+		// fill the task queue
+		for _ in 0..MaxTasks::get() {
+			assert_ok!(IdleScheduler::schedule_task(
+				Origin::root(),
+				ScheduledTasks::EvmTask(EvmTask::Remove {
+					contract: contract_b(),
+					refund_to: None,
+				}),
+				0
+			));
+		}
+
+		// the contract is removed although its storages can not be scheduled for deletion
+		assert_ok!(EVM::selfdestruct(Origin::signed(alice_account_id.clone()), contract_address));
+		assert_eq!(
+			Accounts::<Runtime>::get(&contract_address).and_then(|account_info| account_info.contract_info),
+			None
+		);
+		assert_eq!(
+			PendingContractRemovals::<Runtime>::get(&contract_address),
+			Some(STORAGE_SIZE)
+		);
+		assert_eq!(
+			EVM::unscheduled_contract_removals(&contract_address),
+			Some(Some(alice_account_id.clone()))
+		);
+
+		// the queue is still full
+		EVM::on_idle(System::block_number(), 1_000_000_000_000);
+		assert!(UnscheduledContractRemovals::<Runtime>::contains_key(&contract_address));
+
+		// the queued tasks are done, the removal is scheduled and then done
+		IdleScheduler::on_idle(System::block_number(), 1_000_000_000_000);
+		assert_eq!(IdleScheduler::task_queue().len(), 0);
+		EVM::on_idle(System::block_number(), 1_000_000_000_000);
+		assert_eq!(EVM::unscheduled_contract_removals(&contract_address), None);
+		assert_eq!(IdleScheduler::task_queue().len(), 1);
+
+		IdleScheduler::on_idle(System::block_number(), 1_000_000_000_000);
+		System::assert_has_event(Event::EVM(crate::Event::ContractStoragesRemoved(contract_address)));
+		assert_eq!(PendingContractRemovals::<Runtime>::get(&contract_address), None);
 		assert_eq!(AccountStorages::<Runtime>::iter_prefix(&contract_address).count(), 0);
-		assert!(!CodeInfos::<Runtime>::contains_key(&code_hash));
-		assert!(!Codes::<Runtime>::contains_key(&code_hash));
+		assert_eq!(reserved_balance(contract_address), 0);
 	});
 }
 
@@ -1268,6 +1457,7 @@ fn storage_limit_should_work() {
 			0,
 			200_000,
 			1000,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.unwrap();
@@ -1300,6 +1490,7 @@ fn storage_limit_should_work() {
 				amount,
 				1000000000,
 				0,
+				vec![],
 			),
 			DispatchErrorWithPostInfo {
 				post_info: PostDispatchInfo {
@@ -1322,6 +1513,7 @@ fn storage_limit_should_work() {
 			amount,
 			1000000000,
 			1000000000,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.unwrap();
@@ -1340,6 +1532,7 @@ fn storage_limit_should_work() {
 				amount,
 				1000000000,
 				127,
+				vec![],
 			),
 			DispatchErrorWithPostInfo {
 				post_info: PostDispatchInfo {
@@ -1362,6 +1555,7 @@ fn storage_limit_should_work() {
 			amount,
 			1000000000,
 			1000000000,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.unwrap();
@@ -1399,6 +1593,7 @@ fn evm_execute_mode_should_work() {
 			0,
 			1000000000,
 			1000000000,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.unwrap();
@@ -1558,7 +1753,7 @@ fn should_update_storage() {
 	new_test_ext().execute_with(|| {
 		// create contract
 		let result =
-			<Runtime as Config>::Runner::create(alice(), contract, 0, 500000, 100000, <Runtime as Config>::config())
+			<Runtime as Config>::Runner::create(alice(), contract, 0, 500000, 100000, vec![], <Runtime as Config>::config())
 				.unwrap();
 
 		let contract_address = result.value;
@@ -1584,6 +1779,7 @@ fn should_update_storage() {
 				0,
 				1000000,
 				0,
+				vec![],
 			),
 			DispatchErrorWithPostInfo {
 				post_info: PostDispatchInfo {
@@ -1603,6 +1799,7 @@ fn should_update_storage() {
 			0,
 			1000000,
 			STORAGE_SIZE,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.unwrap();
@@ -1621,6 +1818,7 @@ fn should_update_storage() {
 			0,
 			1000000,
 			STORAGE_SIZE,
+			vec![],
 			<Runtime as Config>::config(),
 		)
 		.unwrap();
@@ -1641,3 +1839,566 @@ fn code_hash_with_non_existent_address_should_work() {
 		);
 	});
 }
+
+#[test]
+fn access_list_should_warm_up_storage() {
+	// runtime code: PUSH1 0x00 SLOAD POP STOP
+	let contract = from_hex("0x6005600c60003960056000f36000545000").unwrap();
+
+	new_test_ext().execute_with(|| {
+		let config = EvmHardfork::Berlin.config();
+		let result =
+			<Runtime as Config>::Runner::create(alice(), contract, 0, 1000000, 1000000, vec![], config).unwrap();
+		assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+		let contract_address = result.value;
+
+		#[cfg(not(feature = "with-ethereum-compatibility"))]
+		deploy_free(contract_address);
+
+		let cold = <Runtime as Config>::Runner::call(
+			alice(),
+			alice(),
+			contract_address,
+			vec![],
+			0,
+			1000000,
+			1000000,
+			vec![],
+			config,
+		)
+		.unwrap();
+		assert_eq!(cold.exit_reason, ExitReason::Succeed(ExitSucceed::Stopped));
+
+		let warm = <Runtime as Config>::Runner::call(
+			alice(),
+			alice(),
+			contract_address,
+			vec![],
+			0,
+			1000000,
+			1000000,
+			vec![AccessListItem {
+				address: contract_address,
+				storage_keys: vec![H256::zero()],
+			}],
+			config,
+		)
+		.unwrap();
+		assert_eq!(warm.exit_reason, ExitReason::Succeed(ExitSucceed::Stopped));
+
+		// the access list is paid upfront, then `SLOAD` costs the warm price
+		let access_list_cost = config.gas_access_list_address + config.gas_access_list_storage_key;
+		assert_eq!(
+			warm.used_gas,
+			cold.used_gas + access_list_cost + config.gas_storage_read_warm - config.gas_sload_cold
+		);
+	});
+}
+
+#[test]
+fn basefee_opcode_requires_london() {
+	// BASEFEE PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+	let contract = from_hex("0x4860005260206000f3").unwrap();
+
+	new_test_ext().execute_with(|| {
+		let result = <Runtime as Config>::Runner::create(
+			alice(),
+			contract.clone(),
+			0,
+			1000000,
+			1000000,
+			vec![],
+			EvmHardfork::Berlin.config(),
+		)
+		.unwrap();
+		assert!(!result.exit_reason.is_succeed());
+
+		let result = <Runtime as Config>::Runner::create(
+			alice(),
+			contract,
+			0,
+			1000000,
+			1000000,
+			vec![],
+			EvmHardfork::London.config(),
+		)
+		.unwrap();
+		assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+
+		// the base fee is the fixed gas price
+		assert_eq!(
+			U256::from(EVM::code_at_address(&result.value).as_slice()),
+			U256::one()
+		);
+	});
+}
+
+#[test]
+fn scheduled_recurring_call_works() {
+	// PUSH1 0x01 PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+	let condition_true = from_hex("0x600a600c600039600a6000f3600160005260206000f3").unwrap();
+	// PUSH1 0x00 PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+	let condition_false = from_hex("0x600a600c600039600a6000f3600060005260206000f3").unwrap();
+	let task_id = b"task".to_vec();
+	let log_address = H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 4);
+
+	let scheduled_call_log = |signature: &[u8]| Log {
+		address: log_address,
+		topics: vec![
+			H256::from_slice(Keccak256::digest(signature).as_slice()),
+			H256::from(alice()),
+			H256::from(bob()),
+		],
+		data: from_hex(
+			"0x\
+			0000000000000000000000000000000000000000000000000000000000000020\
+			0000000000000000000000000000000000000000000000000000000000000004\
+			7461736b00000000000000000000000000000000000000000000000000000000",
+		)
+		.unwrap(),
+	};
+
+	new_test_ext().execute_with(|| {
+		let result = <Runtime as Config>::Runner::create(
+			alice(),
+			condition_true,
+			0,
+			1000000,
+			1000000,
+			vec![],
+			<Runtime as Config>::config(),
+		)
+		.unwrap();
+		let condition_true_address = result.value;
+		let result = <Runtime as Config>::Runner::create(
+			alice(),
+			condition_false,
+			0,
+			1000000,
+			1000000,
+			vec![],
+			<Runtime as Config>::config(),
+		)
+		.unwrap();
+		let condition_false_address = result.value;
+
+		#[cfg(not(feature = "with-ethereum-compatibility"))]
+		{
+			deploy_free(condition_true_address);
+			deploy_free(condition_false_address);
+		}
+
+		ScheduledCallRemainingRuns::<Runtime>::insert(&task_id, 3);
+
+		assert_noop!(
+			EVM::scheduled_recurring_call(
+				Origin::signed(AccountId32::from([1u8; 32])),
+				task_id.clone(),
+				log_address,
+				alice(),
+				bob(),
+				vec![],
+				0,
+				100000,
+				0,
+				None,
+			),
+			BadOrigin
+		);
+
+		// recurring call without condition always runs
+		assert_ok!(EVM::scheduled_recurring_call(
+			Origin::root(),
+			task_id.clone(),
+			log_address,
+			alice(),
+			bob(),
+			vec![],
+			0,
+			100000,
+			0,
+			None,
+		));
+		System::assert_last_event(Event::EVM(crate::Event::ScheduledCallExecuted(
+			task_id.clone(),
+			vec![scheduled_call_log(b"ScheduledCallExecuted(address,address,bytes)")],
+		)));
+		assert_eq!(EVM::scheduled_call_remaining_runs(&task_id), Some(2));
+
+		// the condition is not met
+		assert_ok!(EVM::scheduled_recurring_call(
+			Origin::root(),
+			task_id.clone(),
+			log_address,
+			alice(),
+			bob(),
+			vec![],
+			0,
+			100000,
+			0,
+			Some(ScheduledCallCondition {
+				target: condition_false_address,
+				input: vec![],
+				gas_limit: 100000,
+			}),
+		));
+		System::assert_last_event(Event::EVM(crate::Event::ScheduledCallSkipped(
+			task_id.clone(),
+			vec![scheduled_call_log(b"ScheduledCallSkipped(address,address,bytes)")],
+		)));
+		assert_eq!(EVM::scheduled_call_remaining_runs(&task_id), Some(1));
+
+		// the condition is met, and the last run clears the remaining runs
+		assert_ok!(EVM::scheduled_recurring_call(
+			Origin::root(),
+			task_id.clone(),
+			log_address,
+			alice(),
+			bob(),
+			vec![],
+			0,
+			100000,
+			0,
+			Some(ScheduledCallCondition {
+				target: condition_true_address,
+				input: vec![],
+				gas_limit: 100000,
+			}),
+		));
+		System::assert_last_event(Event::EVM(crate::Event::ScheduledCallExecuted(
+			task_id.clone(),
+			vec![scheduled_call_log(b"ScheduledCallExecuted(address,address,bytes)")],
+		)));
+		assert_eq!(EVM::scheduled_call_remaining_runs(&task_id), None);
+	});
+}
+
+#[test]
+fn failed_scheduled_recurring_call_uses_up_the_run() {
+	let task_id = b"task".to_vec();
+	let log_address = H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 4);
+
+	new_test_ext().execute_with(|| {
+		ScheduledCallRemainingRuns::<Runtime>::insert(&task_id, 1);
+
+		// the storage deposit of the run cannot be reserved
+		assert_eq!(
+			EVM::scheduled_recurring_call(
+				Origin::root(),
+				task_id.clone(),
+				log_address,
+				alice(),
+				bob(),
+				vec![],
+				0,
+				100000,
+				u32::MAX,
+				None,
+			)
+			.map_err(|e| e.error),
+			Err(Error::<Runtime>::ReserveStorageFailed.into())
+		);
+		assert_eq!(EVM::scheduled_call_remaining_runs(&task_id), None);
+	});
+}
+
+#[test]
+fn reverted_scheduled_recurring_call_fails() {
+	// PUSH1 0x00 PUSH1 0x00 REVERT
+	let contract = from_hex("0x6005600c60003960056000f360006000fd").unwrap();
+	let task_id = b"task".to_vec();
+	let log_address = H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 4);
+
+	new_test_ext().execute_with(|| {
+		let result = <Runtime as Config>::Runner::create(
+			alice(),
+			contract,
+			0,
+			1000000,
+			1000000,
+			vec![],
+			<Runtime as Config>::config(),
+		)
+		.unwrap();
+		let contract_address = result.value;
+		#[cfg(not(feature = "with-ethereum-compatibility"))]
+		deploy_free(contract_address);
+
+		ScheduledCallRemainingRuns::<Runtime>::insert(&task_id, 2);
+
+		assert_ok!(EVM::scheduled_recurring_call(
+			Origin::root(),
+			task_id.clone(),
+			log_address,
+			alice(),
+			contract_address,
+			vec![],
+			0,
+			100000,
+			0,
+			None,
+		));
+		System::assert_last_event(Event::EVM(crate::Event::ScheduledCallFailed(
+			task_id.clone(),
+			ExitReason::Revert(ExitRevert::Reverted),
+			vec![EVM::scheduled_call_log(
+				log_address,
+				b"ScheduledCallFailed(address,address,bytes)",
+				alice(),
+				contract_address,
+				&task_id,
+			)],
+		)));
+		assert_eq!(EVM::scheduled_call_remaining_runs(&task_id), Some(1));
+	});
+}
+
+#[test]
+fn should_propose_and_apply_set_code() {
+	// PUSH1 0x00 PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+	let contract = from_hex("0x600a600c600039600a6000f3600060005260206000f3").unwrap();
+	// PUSH1 0x01 PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+	let new_code = from_hex("0x600160005260206000f3").unwrap();
+	let new_code_hash = code_hash(&new_code);
+
+	new_test_ext().execute_with(|| {
+		let alice_account_id = <Runtime as Config>::AddressMapping::get_account_id(&alice());
+		let bob_account_id = <Runtime as Config>::AddressMapping::get_account_id(&bob());
+
+		let result = <Runtime as Config>::Runner::create(
+			alice(),
+			contract,
+			0,
+			1000000,
+			1000000,
+			vec![],
+			<Runtime as Config>::config(),
+		)
+		.unwrap();
+		let contract_address = result.value;
+
+		assert_noop!(
+			EVM::set_upgrade_delay(Origin::signed(bob_account_id.clone()), contract_address, 10),
+			Error::<Runtime>::NoPermission
+		);
+		assert_ok!(EVM::set_upgrade_delay(
+			Origin::signed(alice_account_id.clone()),
+			contract_address,
+			10
+		));
+		System::assert_last_event(Event::EVM(crate::Event::ContractUpgradeDelaySet(contract_address, 10)));
+		assert_noop!(
+			EVM::set_upgrade_delay(Origin::signed(alice_account_id.clone()), contract_address, 5),
+			Error::<Runtime>::CannotReduceUpgradeDelay
+		);
+
+		// the maintainer can no longer set code instantly
+		assert_noop!(
+			EVM::set_code(Origin::signed(alice_account_id.clone()), contract_address, new_code.clone()),
+			Error::<Runtime>::ContractUpgradeTimelocked
+		);
+
+		assert_noop!(
+			EVM::apply_set_code(Origin::signed(alice_account_id.clone()), contract_address, new_code.clone()),
+			Error::<Runtime>::ContractUpgradeNotProposed
+		);
+		assert_noop!(
+			EVM::propose_set_code(Origin::signed(bob_account_id), contract_address, new_code_hash),
+			Error::<Runtime>::NoPermission
+		);
+		assert_ok!(EVM::propose_set_code(
+			Origin::signed(alice_account_id.clone()),
+			contract_address,
+			new_code_hash
+		));
+		System::assert_last_event(Event::EVM(crate::Event::ContractUpgradeProposed(
+			contract_address,
+			new_code_hash,
+			11,
+		)));
+		assert_eq!(EVM::query_pending_upgrade(contract_address), Some((new_code_hash, 11)));
+
+		assert_noop!(
+			EVM::apply_set_code(Origin::signed(alice_account_id.clone()), contract_address, new_code.clone()),
+			Error::<Runtime>::ContractUpgradeNotReady
+		);
+
+		System::set_block_number(11);
+		assert_noop!(
+			EVM::apply_set_code(Origin::signed(alice_account_id.clone()), contract_address, vec![]),
+			Error::<Runtime>::ContractUpgradeCodeMismatch
+		);
+		assert_ok!(EVM::apply_set_code(
+			Origin::signed(alice_account_id),
+			contract_address,
+			new_code
+		));
+		System::assert_last_event(Event::EVM(crate::Event::ContractSetCode(contract_address)));
+		assert_eq!(EVM::query_pending_upgrade(contract_address), None);
+		assert_eq!(
+			Accounts::<Runtime>::get(&contract_address)
+				.and_then(|account_info| account_info.contract_info)
+				.map(|contract_info| contract_info.code_hash),
+			Some(new_code_hash)
+		);
+	});
+}
+
+#[test]
+fn default_upgrade_delay_works() {
+	// PUSH1 0x00 PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+	let contract = from_hex("0x600a600c600039600a6000f3600060005260206000f3").unwrap();
+	// PUSH1 0x01 PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+	let new_code = from_hex("0x600160005260206000f3").unwrap();
+
+	new_test_ext().execute_with(|| {
+		DefaultUpgradeDelay::set(&5);
+		let alice_account_id = <Runtime as Config>::AddressMapping::get_account_id(&alice());
+
+		let result = <Runtime as Config>::Runner::create(
+			alice(),
+			contract,
+			0,
+			1000000,
+			1000000,
+			vec![],
+			<Runtime as Config>::config(),
+		)
+		.unwrap();
+		let contract_address = result.value;
+
+		assert_eq!(EVM::upgrade_delay(&contract_address), 5);
+		assert_noop!(
+			EVM::set_code(
+				Origin::signed(alice_account_id.clone()),
+				contract_address,
+				new_code.clone()
+			),
+			Error::<Runtime>::ContractUpgradeTimelocked
+		);
+		assert_noop!(
+			EVM::set_upgrade_delay(Origin::signed(alice_account_id.clone()), contract_address, 4),
+			Error::<Runtime>::CannotReduceUpgradeDelay
+		);
+
+		// root can still set the code and lift the delay of a contract
+		assert_ok!(EVM::set_upgrade_delay(Origin::root(), contract_address, 0));
+		assert_eq!(EVM::upgrade_delay(&contract_address), 0);
+		assert_ok!(EVM::set_code(Origin::signed(alice_account_id), contract_address, new_code));
+	});
+}
+
+#[test]
+fn should_query_contract_storage_info() {
+	// PUSH1 0x01 PUSH1 0x00 SSTORE STOP
+	let contract = from_hex("0x6006600c60003960066000f3600160005500").unwrap();
+
+	new_test_ext().execute_with(|| {
+		let result = <Runtime as Config>::Runner::create(
+			alice(),
+			contract,
+			0,
+			1000000,
+			1000000,
+			vec![],
+			<Runtime as Config>::config(),
+		)
+		.unwrap();
+		let contract_address = result.value;
+		assert_eq!(EVM::contract_storage_info(&bob()), None);
+		assert_noop!(EVM::query_storage_usage(bob()), Error::<Runtime>::ContractNotFound);
+
+		let code_storage = result.used_storage as u32;
+		assert_eq!(
+			EVM::contract_storage_info(&contract_address),
+			Some(ContractStorageInfo {
+				used_storage: code_storage,
+				deposit: reserved_balance(contract_address),
+				maintainer: alice(),
+				refund_per_slot: STORAGE_SIZE as u64 * <Runtime as Config>::StorageDepositPerByte::get(),
+			})
+		);
+
+		#[cfg(not(feature = "with-ethereum-compatibility"))]
+		deploy_free(contract_address);
+
+		// one storage slot is used
+		let result = <Runtime as Config>::Runner::call(
+			alice(),
+			alice(),
+			contract_address,
+			vec![],
+			0,
+			1000000,
+			1000000,
+			vec![],
+			<Runtime as Config>::config(),
+		)
+		.unwrap();
+		assert_eq!(result.used_storage, STORAGE_SIZE as i32);
+
+		assert_eq!(EVM::query_storage_usage(contract_address), Ok(code_storage + STORAGE_SIZE));
+		assert_eq!(
+			EVM::query_storage_deposit(contract_address),
+			Ok((code_storage + STORAGE_SIZE) as u64 * <Runtime as Config>::StorageDepositPerByte::get())
+		);
+	});
+}
+
+#[test]
+fn should_not_call_paused_contract() {
+	// PUSH1 0x01 PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+	let contract = from_hex("0x600a600c600039600a6000f3600160005260206000f3").unwrap();
+
+	new_test_ext().execute_with(|| {
+		let result = <Runtime as Config>::Runner::create(
+			alice(),
+			contract,
+			0,
+			1000000,
+			1000000,
+			vec![],
+			<Runtime as Config>::config(),
+		)
+		.unwrap();
+		let contract_address = result.value;
+
+		#[cfg(not(feature = "with-ethereum-compatibility"))]
+		deploy_free(contract_address);
+
+		let call = |input: Vec<u8>| {
+			<Runtime as Config>::Runner::call(
+				alice(),
+				alice(),
+				contract_address,
+				input,
+				0,
+				1000000,
+				1000000,
+				vec![],
+				<Runtime as Config>::config(),
+			)
+			.unwrap()
+		};
+
+		assert_eq!(call(vec![]).exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+
+		// only the paused function selector fails
+		pause_call(contract_address, Some([1, 2, 3, 4]));
+		assert_eq!(
+			call(vec![1, 2, 3, 4]).exit_reason,
+			ExitReason::Error(ExitError::Other(CONTRACT_PAUSED.into()))
+		);
+		assert_eq!(
+			call(vec![4, 3, 2, 1]).exit_reason,
+			ExitReason::Succeed(ExitSucceed::Returned)
+		);
+		assert_eq!(call(vec![]).exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+
+		pause_call(contract_address, None);
+		assert_eq!(
+			call(vec![]).exit_reason,
+			ExitReason::Error(ExitError::Other(CONTRACT_PAUSED.into()))
+		);
+	});
+}
@@ -21,19 +21,34 @@
 //! # Idle scheduler Module
 //!
 //! Allow pallets and chain maintainer to schedule a task to be dispatched when chain is idle.
+//!
+//! Unfinished tasks can return their updated state to resume from it next time. Failed tasks
+//! are retried with an exponential backoff, and dropped after `MaxTaskRetries` retries. Tasks
+//! dropped unfinished, or cancelled, get `DispatchableTask::on_dropped` called to clean up.
+//!
+//! At most `MaxTasks` tasks can be queued. Signed accounts, and EVM contracts through the
+//! precompile, can schedule the tasks `SignedTaskFilter` allows for them, reserving
+//! `TaskDeposit` for each task. The deposit is refunded
+//! once the task is completed or cancelled by its owner, and slashed if the task is cancelled by
+//! `GovernanceOrigin`. Tasks are dispatched by priority, and in the scheduling order within the
+//! same priority.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::unused_unit)]
 #![allow(unused_must_use)]
-use setheum_primitives::{task::TaskResult, Nonce};
+use setheum_primitives::{task::TaskResult, Nonce, ReserveIdentifier};
 use codec::FullCodec;
-use frame_support::pallet_prelude::*;
+use frame_support::{
+	pallet_prelude::*,
+	traits::{Contains, Currency, NamedReservableCurrency},
+	transactional,
+};
 use frame_system::pallet_prelude::*;
-pub use module_support::{DispatchableTask, IdleScheduler};
+pub use module_support::{DispatchableTask, IdleScheduler, SignedIdleScheduler};
 use scale_info::TypeInfo;
 use sp_runtime::{
-	traits::{One, Zero},
-	ArithmeticError,
+	traits::{One, Saturating, Zero},
+	ArithmeticError, RuntimeDebug,
 };
 use sp_std::{cmp::PartialEq, fmt::Debug, prelude::*};
 
@@ -43,6 +58,25 @@ mod weights;
 pub use module::*;
 pub use weights::WeightInfo;
 
+/// The priority of a task, tasks with higher priority are dispatched first.
+pub type TaskPriority = u8;
+
+/// The priority of the tasks scheduled by other modules, and the max priority of the tasks
+/// scheduled by signed origins.
+pub const DEFAULT_TASK_PRIORITY: TaskPriority = 128;
+
+pub const RESERVE_ID: ReserveIdentifier = ReserveIdentifier::IdleScheduler;
+
+pub type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct TaskFailure<BlockNumber> {
+	/// The number of the consecutive failed attempts.
+	pub attempts: u32,
+	/// The task is not dispatched before this block.
+	pub retry_at: BlockNumber,
+}
+
 #[frame_support::pallet]
 pub mod module {
 	use super::*;
@@ -60,13 +94,72 @@ pub mod module {
 		/// The minimum weight that should remain before idle tasks are dispatched.
 		#[pallet::constant]
 		type MinimumWeightRemainInBlock: Get<Weight>;
+
+		/// The max times a failed task is retried before it is dropped.
+		#[pallet::constant]
+		type MaxTaskRetries: Get<u32>;
+
+		/// The blocks to wait before retrying a failed task, doubled on every
+		/// consecutive failure.
+		#[pallet::constant]
+		type TaskRetryBackoff: Get<Self::BlockNumber>;
+
+		/// Currency for reserving the deposit of the tasks scheduled by signed origins.
+		type Currency: NamedReservableCurrency<Self::AccountId, ReserveIdentifier = ReserveIdentifier>;
+
+		/// The deposit reserved for each task scheduled by a signed origin.
+		#[pallet::constant]
+		type TaskDeposit: Get<BalanceOf<Self>>;
+
+		/// The max number of the queued tasks scheduled by an account.
+		#[pallet::constant]
+		type MaxTasksPerAccount: Get<u32>;
+
+		/// The max number of the queued tasks.
+		#[pallet::constant]
+		type MaxTasks: Get<u32>;
+
+		/// The tasks signed origins are allowed to schedule, by the scheduling account.
+		type SignedTaskFilter: Contains<(Self::AccountId, Self::Task)>;
+
+		/// The origin which can cancel any task, slashing its deposit.
+		type GovernanceOrigin: EnsureOrigin<Self::Origin>;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The task does not exist.
+		TaskNotFound,
+		/// The task kind is not allowed to be scheduled by signed origins.
+		TaskNotAllowed,
+		/// The priority is higher than signed origins are allowed to use.
+		PriorityTooHigh,
+		/// The account has too many tasks queued.
+		TooManyTasks,
+		/// Only the owner of the task can cancel it.
+		NoPermission,
+		/// The encoded task can not be decoded.
+		InvalidTask,
+		/// The task queue is full.
+		TaskQueueFull,
 	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub fn deposit_event)]
 	pub enum Event<T: Config> {
+		/// A task has been scheduled.
+		TaskScheduled { task_id: Nonce, priority: TaskPriority },
 		/// A task has been dispatched on_idle.
 		TaskDispatched { task_id: Nonce, result: DispatchResult },
+		/// A task has failed on_idle. It will be retried at `retry_at`, or
+		/// has been dropped if `retry_at` is `None`.
+		TaskFailed {
+			task_id: Nonce,
+			error: DispatchError,
+			retry_at: Option<T::BlockNumber>,
+		},
+		/// A task has been cancelled, and `slashed` of its deposit slashed.
+		TaskCancelled { task_id: Nonce, slashed: BalanceOf<T> },
 	}
 
 	/// Some documentation
@@ -78,34 +171,175 @@ pub mod module {
 	#[pallet::getter(fn next_task_id)]
 	pub type NextTaskId<T: Config> = StorageValue<_, Nonce, ValueQuery>;
 
+	/// The failures of the tasks which are waiting to be retried.
+	///
+	/// TaskFailures: map Nonce => Option<TaskFailure>
+	#[pallet::storage]
+	#[pallet::getter(fn task_failures)]
+	pub type TaskFailures<T: Config> = StorageMap<_, Twox64Concat, Nonce, TaskFailure<T::BlockNumber>, OptionQuery>;
+
+	/// The queued tasks in the dispatching order, by priority and then by task id.
+	///
+	/// TaskQueue: BoundedVec<(TaskPriority, Nonce), MaxTasks>
+	#[pallet::storage]
+	#[pallet::getter(fn task_queue)]
+	pub type TaskQueue<T: Config> = StorageValue<_, BoundedVec<(TaskPriority, Nonce), T::MaxTasks>, ValueQuery>;
+
+	/// The owners of the tasks scheduled by signed origins, and the deposit reserved.
+	///
+	/// TaskOwners: map Nonce => Option<(AccountId, Balance)>
+	#[pallet::storage]
+	#[pallet::getter(fn task_owners)]
+	pub type TaskOwners<T: Config> = StorageMap<_, Twox64Concat, Nonce, (T::AccountId, BalanceOf<T>), OptionQuery>;
+
+	/// The number of the queued tasks scheduled by an account.
+	///
+	/// AccountTaskCounts: map AccountId => u32
+	#[pallet::storage]
+	#[pallet::getter(fn account_task_counts)]
+	pub type AccountTaskCounts<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
 	#[pallet::pallet]
 	pub struct Pallet<T>(_);
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
-		fn on_idle(_n: T::BlockNumber, remaining_weight: Weight) -> Weight {
-			Self::do_dispatch_tasks(remaining_weight)
+		fn on_idle(n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::do_dispatch_tasks(n, remaining_weight)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			Self::check_invariants()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::check_invariants()
 		}
 	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		#[pallet::weight(< T as Config >::WeightInfo::schedule_task())]
-		pub fn schedule_task(origin: OriginFor<T>, task: T::Task) -> DispatchResult {
-			ensure_root(origin)?;
-			Self::do_schedule_task(task)
+		/// Schedule a task to be dispatched on idle.
+		///
+		/// Root can schedule any task with any priority. Signed origins can only schedule the
+		/// task kinds allowed by `SignedTaskFilter`, with at most `DEFAULT_TASK_PRIORITY`, and
+		/// reserve `TaskDeposit` for it.
+		///
+		/// - `task`: the task to schedule.
+		/// - `priority`: tasks with higher priority are dispatched first.
+		#[pallet::weight(< T as Config >::WeightInfo::schedule_task(T::MaxTasks::get()))]
+		pub fn schedule_task(origin: OriginFor<T>, task: T::Task, priority: TaskPriority) -> DispatchResult {
+			if let Ok(who) = ensure_signed(origin.clone()) {
+				Self::do_schedule_signed_task(&who, task, priority)?;
+			} else {
+				ensure_root(origin)?;
+				Self::do_schedule_task(task, priority)?;
+			}
+			Ok(())
+		}
+
+		/// Cancel a queued task.
+		///
+		/// The owner of a task can cancel it and get the deposit refunded. `GovernanceOrigin` can
+		/// cancel any task, slashing the deposit.
+		///
+		/// - `task_id`: the task to cancel.
+		#[pallet::weight(< T as Config >::WeightInfo::cancel_task(T::MaxTasks::get()))]
+		pub fn cancel_task(origin: OriginFor<T>, task_id: Nonce) -> DispatchResult {
+			if T::GovernanceOrigin::ensure_origin(origin.clone()).is_ok() {
+				Self::do_cancel_task(None, task_id)
+			} else {
+				let who = ensure_signed(origin)?;
+				Self::do_cancel_task(Some(&who), task_id)
+			}
 		}
 	}
 }
 
 impl<T: Config> Pallet<T> {
-	/// Add the task to the queue to be dispatched later
-	fn do_schedule_task(task: T::Task) -> DispatchResult {
+	/// Add the task to the queue to be dispatched later, after the queued tasks
+	/// with higher or equal priority.
+	#[transactional]
+	fn do_schedule_task(task: T::Task, priority: TaskPriority) -> Result<Nonce, DispatchError> {
 		let id = Self::get_next_task_id()?;
+		TaskQueue::<T>::try_mutate(|queue| -> DispatchResult {
+			let index = queue.partition_point(|(p, _)| *p >= priority);
+			queue
+				.try_insert(index, (priority, id))
+				.map_err(|_| Error::<T>::TaskQueueFull.into())
+		})?;
 		Tasks::<T>::insert(id, task);
+		Self::deposit_event(Event::<T>::TaskScheduled { task_id: id, priority });
+		Ok(id)
+	}
+
+	/// Add the task of a signed origin to the queue, reserving the deposit.
+	#[transactional]
+	fn do_schedule_signed_task(
+		who: &T::AccountId,
+		task: T::Task,
+		priority: TaskPriority,
+	) -> Result<Nonce, DispatchError> {
+		let task = (who.clone(), task);
+		ensure!(T::SignedTaskFilter::contains(&task), Error::<T>::TaskNotAllowed);
+		let (_, task) = task;
+		ensure!(priority <= DEFAULT_TASK_PRIORITY, Error::<T>::PriorityTooHigh);
+
+		let count = Self::account_task_counts(who);
+		ensure!(count < T::MaxTasksPerAccount::get(), Error::<T>::TooManyTasks);
+
+		let deposit = T::TaskDeposit::get();
+		T::Currency::reserve_named(&RESERVE_ID, who, deposit)?;
+		AccountTaskCounts::<T>::insert(who, count.saturating_add(1));
+
+		let id = Self::do_schedule_task(task, priority)?;
+		TaskOwners::<T>::insert(id, (who.clone(), deposit));
+		Ok(id)
+	}
+
+	/// Cancel the task. If `who` is `None` the task is cancelled by governance
+	/// and the deposit is slashed, otherwise `who` must be the owner of the task.
+	fn do_cancel_task(who: Option<&T::AccountId>, task_id: Nonce) -> DispatchResult {
+		ensure!(Tasks::<T>::contains_key(task_id), Error::<T>::TaskNotFound);
+		if let Some(who) = who {
+			let is_owner = Self::task_owners(task_id).map_or(false, |(owner, _)| owner == *who);
+			ensure!(is_owner, Error::<T>::NoPermission);
+		}
+
+		if let Some(task) = Self::tasks(task_id) {
+			task.on_dropped();
+		}
+		let slashed = Self::remove_task(task_id, who.is_none());
+		TaskQueue::<T>::mutate(|queue| queue.retain(|(_, id)| *id != task_id));
+		Self::deposit_event(Event::<T>::TaskCancelled { task_id, slashed });
 		Ok(())
 	}
 
+	/// Remove the task, leaving the removal from `TaskQueue` to the caller. The
+	/// deposit of the owner is slashed if `slash`, or refunded otherwise.
+	/// Returns the slashed amount.
+	fn remove_task(id: Nonce, slash: bool) -> BalanceOf<T> {
+		Tasks::<T>::remove(id);
+		TaskFailures::<T>::remove(id);
+
+		TaskOwners::<T>::take(id).map_or_else(Zero::zero, |(owner, deposit)| {
+			AccountTaskCounts::<T>::mutate_exists(&owner, |maybe_count| {
+				let count = maybe_count.unwrap_or_default().saturating_sub(1);
+				*maybe_count = if count.is_zero() { None } else { Some(count) };
+			});
+
+			if slash {
+				let (_, remaining) = T::Currency::slash_reserved_named(&RESERVE_ID, &owner, deposit);
+				deposit.saturating_sub(remaining)
+			} else {
+				T::Currency::unreserve_named(&RESERVE_ID, &owner, deposit);
+				Zero::zero()
+			}
+		})
+	}
+
 	/// Retrieves the next task ID from storage, and increment it by one.
 	fn get_next_task_id() -> Result<Nonce, DispatchError> {
 		NextTaskId::<T>::mutate(|current| -> Result<Nonce, DispatchError> {
@@ -115,21 +349,36 @@ impl<T: Config> Pallet<T> {
 		})
 	}
 
-	/// Keep dispatching tasks in Storage, until insufficient weight remains.
-	pub fn do_dispatch_tasks(total_weight: Weight) -> Weight {
+	/// Keep dispatching tasks in the order of `TaskQueue`, until insufficient weight remains.
+	/// Tasks waiting for a retry after a failure are skipped until `now`
+	/// reaches their `retry_at`, each skipped task costing a read. The queue
+	/// is rewritten at most once, to remove the completed and dropped tasks.
+	pub fn do_dispatch_tasks(now: T::BlockNumber, total_weight: Weight) -> Weight {
 		let mut weight_remaining = total_weight;
 		if weight_remaining <= T::MinimumWeightRemainInBlock::get() {
 			return Zero::zero();
 		}
 
-		let mut completed_tasks: Vec<(Nonce, TaskResult)> = vec![];
+		let mut dispatched_tasks: Vec<(Nonce, TaskResult, Option<T::Task>)> = vec![];
+		let mut removed_tasks: Vec<Nonce> = vec![];
 
-		for (id, task) in Tasks::<T>::iter() {
-			let result = task.dispatch(weight_remaining);
-			weight_remaining = weight_remaining.saturating_sub(result.used_weight);
-			if result.finished {
-				completed_tasks.push((id, result));
+		for (_, id) in Self::task_queue().into_inner() {
+			if Self::task_failures(id).map_or(false, |failure| failure.retry_at > now) {
+				weight_remaining = weight_remaining.saturating_sub(T::DbWeight::get().reads(1));
+				if weight_remaining <= T::MinimumWeightRemainInBlock::get() {
+					break;
+				}
+				continue;
 			}
+			let task = match Self::tasks(id) {
+				Some(task) => task,
+				None => continue,
+			};
+
+			let available_weight = weight_remaining.saturating_sub(T::MinimumWeightRemainInBlock::get());
+			let (result, updated_task) = task.dispatch(available_weight);
+			weight_remaining = weight_remaining.saturating_sub(result.used_weight);
+			dispatched_tasks.push((id, result, updated_task));
 
 			// If remaining weight falls below the minimmum, break from the loop.
 			if weight_remaining <= T::MinimumWeightRemainInBlock::get() {
@@ -137,21 +386,110 @@ impl<T: Config> Pallet<T> {
 			}
 		}
 
-		// Deposit event and remove completed tasks.
-		for (id, result) in completed_tasks {
-			Self::deposit_event(Event::<T>::TaskDispatched {
-				task_id: id,
-				result: result.result,
-			});
-			Tasks::<T>::remove(id);
+		// Update the tasks after the iteration, deposit events and remove
+		// completed or dropped tasks.
+		for (id, result, updated_task) in dispatched_tasks {
+			match result.result {
+				Ok(()) => {
+					TaskFailures::<T>::remove(id);
+					if result.finished {
+						Self::remove_task(id, false);
+						removed_tasks.push(id);
+						Self::deposit_event(Event::<T>::TaskDispatched {
+							task_id: id,
+							result: Ok(()),
+						});
+					} else if let Some(task) = updated_task {
+						Tasks::<T>::insert(id, task);
+					}
+				}
+				Err(error) => {
+					let attempts = Self::task_failures(id)
+						.map_or(0, |failure| failure.attempts)
+						.saturating_add(1);
+					if result.finished || attempts > T::MaxTaskRetries::get() {
+						if let Some(task) = updated_task.or_else(|| Self::tasks(id)) {
+							task.on_dropped();
+						}
+						Self::remove_task(id, false);
+						removed_tasks.push(id);
+						Self::deposit_event(Event::<T>::TaskFailed {
+							task_id: id,
+							error,
+							retry_at: None,
+						});
+					} else {
+						let backoff = T::TaskRetryBackoff::get()
+							.saturating_mul(T::BlockNumber::from(2u32.saturating_pow(attempts - 1)));
+						let retry_at = now.saturating_add(backoff);
+						TaskFailures::<T>::insert(id, TaskFailure { attempts, retry_at });
+						if let Some(task) = updated_task {
+							Tasks::<T>::insert(id, task);
+						}
+						Self::deposit_event(Event::<T>::TaskFailed {
+							task_id: id,
+							error,
+							retry_at: Some(retry_at),
+						});
+					}
+				}
+			}
+		}
+
+		if !removed_tasks.is_empty() {
+			TaskQueue::<T>::mutate(|queue| queue.retain(|(_, id)| !removed_tasks.contains(id)));
 		}
 
 		total_weight.saturating_sub(weight_remaining)
 	}
+
+	/// Check that the queue is ordered by priority, only refers to stored
+	/// tasks, and that the task counts match the task owners.
+	#[cfg(feature = "try-runtime")]
+	pub fn check_invariants() -> Result<(), &'static str> {
+		let queue = Self::task_queue();
+		ensure!(
+			queue.windows(2).all(|w| w[0].0 >= w[1].0),
+			"TaskQueue is not ordered by priority"
+		);
+		ensure!(
+			queue.iter().all(|(_, id)| Tasks::<T>::contains_key(id)),
+			"TaskQueue refers to a missing task"
+		);
+		ensure!(
+			queue.len() == Tasks::<T>::iter_keys().count(),
+			"Tasks are missing from TaskQueue"
+		);
+
+		let mut counts = sp_std::collections::btree_map::BTreeMap::<T::AccountId, u32>::new();
+		for (id, (owner, _)) in TaskOwners::<T>::iter() {
+			ensure!(Tasks::<T>::contains_key(id), "TaskOwners refers to a missing task");
+			*counts.entry(owner).or_default() += 1;
+		}
+		for (owner, count) in AccountTaskCounts::<T>::iter() {
+			ensure!(
+				counts.remove(&owner) == Some(count),
+				"AccountTaskCounts does not match TaskOwners"
+			);
+		}
+		ensure!(counts.is_empty(), "Task owner without AccountTaskCounts");
+		Ok(())
+	}
 }
 
 impl<T: Config> IdleScheduler<T::Task> for Pallet<T> {
 	fn schedule(task: T::Task) -> DispatchResult {
-		Self::do_schedule_task(task)
+		Self::do_schedule_task(task, DEFAULT_TASK_PRIORITY).map(|_| ())
+	}
+}
+
+impl<T: Config> SignedIdleScheduler<T::AccountId> for Pallet<T> {
+	fn schedule_signed(who: &T::AccountId, task: Vec<u8>, priority: TaskPriority) -> Result<Nonce, DispatchError> {
+		let task = T::Task::decode(&mut &task[..]).map_err(|_| Error::<T>::InvalidTask)?;
+		Self::do_schedule_signed_task(who, task, priority)
+	}
+
+	fn cancel_signed(who: &T::AccountId, task_id: Nonce) -> DispatchResult {
+		Self::do_cancel_task(Some(who), task_id)
 	}
 }
@@ -23,21 +23,35 @@
 #![cfg(test)]
 
 use crate as module_idle_scheduler;
-use setheum_primitives::{define_combined_task, task::TaskResult};
-use frame_support::weights::Weight;
-use frame_support::{construct_runtime, parameter_types, traits::Everything};
+use setheum_primitives::{define_combined_task, task::TaskResult, ReserveIdentifier};
+use frame_support::weights::{RuntimeDbWeight, Weight};
+use frame_support::{
+	construct_runtime, parameter_types,
+	traits::{Contains, Everything},
+};
+use frame_system::EnsureRoot;
 use module_support::DispatchableTask;
+use sp_runtime::DispatchError;
 
 use codec::{Decode, Encode};
 use scale_info::TypeInfo;
+use std::cell::RefCell;
 
 pub const BASE_WEIGHT: Weight = 1_000_000;
 
 parameter_types!(
 	pub const BlockHashCount: u32 = 250;
+	pub const DbWeight: RuntimeDbWeight = RuntimeDbWeight {
+		read: BASE_WEIGHT,
+		write: 0,
+	};
 );
 
 pub type AccountId = u32;
+pub type Balance = u128;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
 impl frame_system::Config for Runtime {
 	type BaseCallFilter = Everything;
 	type Origin = Origin;
@@ -53,10 +67,10 @@ impl frame_system::Config for Runtime {
 	type BlockHashCount = BlockHashCount;
 	type BlockWeights = ();
 	type BlockLength = ();
-	type DbWeight = ();
+	type DbWeight = DbWeight;
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<Balance>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
@@ -64,15 +78,53 @@ impl frame_system::Config for Runtime {
 	type OnSetCode = ();
 }
 
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+	pub const MaxReserves: u32 = 50;
+}
+
+impl pallet_balances::Config for Runtime {
+	type Balance = Balance;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = frame_system::Pallet<Runtime>;
+	type MaxLocks = ();
+	type MaxReserves = MaxReserves;
+	type ReserveIdentifier = ReserveIdentifier;
+	type WeightInfo = ();
+}
+
 parameter_types!(
 	pub const MinimumWeightRemainInBlock: Weight = 100_000_000_000;
+	pub const MaxTaskRetries: u32 = 2;
+	pub const TaskRetryBackoff: u64 = 10;
+	pub const TaskDeposit: Balance = 10;
+	pub const MaxTasksPerAccount: u32 = 2;
+	pub const MaxTasks: u32 = 5;
 );
 
+/// Signed origins can only schedule `BalancesTask`.
+pub struct SignedTaskFilter;
+impl Contains<(AccountId, ScheduledTasks)> for SignedTaskFilter {
+	fn contains((_, task): &(AccountId, ScheduledTasks)) -> bool {
+		matches!(task, ScheduledTasks::BalancesTask(_))
+	}
+}
+
 impl module_idle_scheduler::Config for Runtime {
 	type Event = Event;
 	type WeightInfo = ();
 	type Task = ScheduledTasks;
 	type MinimumWeightRemainInBlock = MinimumWeightRemainInBlock;
+	type MaxTaskRetries = MaxTaskRetries;
+	type TaskRetryBackoff = TaskRetryBackoff;
+	type Currency = Balances;
+	type TaskDeposit = TaskDeposit;
+	type MaxTasksPerAccount = MaxTasksPerAccount;
+	type MaxTasks = MaxTasks;
+	type SignedTaskFilter = SignedTaskFilter;
+	type GovernanceOrigin = EnsureRoot<AccountId>;
 }
 
 // Mock dispatachable tasks
@@ -82,19 +134,72 @@ pub enum BalancesTask {
 	OnIdle,
 }
 impl DispatchableTask for BalancesTask {
-	fn dispatch(self, weight: Weight) -> TaskResult {
-		TaskResult {
+	fn dispatch(self, weight: Weight) -> (TaskResult, Option<Self>) {
+		let result = TaskResult {
 			result: Ok(()),
 			used_weight: BASE_WEIGHT,
 			finished: weight >= BASE_WEIGHT,
+		};
+		(result, None)
+	}
+}
+
+thread_local! {
+	static DROPPED_TASKS: RefCell<Vec<ProgressTask>> = RefCell::new(vec![]);
+}
+
+/// The progress tasks dropped unfinished.
+pub fn dropped_tasks() -> Vec<ProgressTask> {
+	DROPPED_TASKS.with(|v| v.borrow().clone())
+}
+
+// Mock resumable tasks, processing one item per `BASE_WEIGHT`
+#[derive(Clone, Debug, PartialEq, Encode, Decode, TypeInfo)]
+pub enum ProgressTask {
+	#[codec(index = 0)]
+	Process { remaining: u32 },
+	#[codec(index = 1)]
+	Fail { remaining: u32 },
+}
+impl DispatchableTask for ProgressTask {
+	fn dispatch(self, weight: Weight) -> (TaskResult, Option<Self>) {
+		match self {
+			ProgressTask::Process { remaining } => {
+				let processed = remaining.min((weight / BASE_WEIGHT) as u32);
+				let result = TaskResult {
+					result: Ok(()),
+					used_weight: BASE_WEIGHT * processed as Weight,
+					finished: processed == remaining,
+				};
+				let updated_task = ProgressTask::Process {
+					remaining: remaining - processed,
+				};
+				(result, Some(updated_task))
+			}
+			ProgressTask::Fail { remaining } => {
+				let result = TaskResult {
+					result: Err(DispatchError::Other("failed")),
+					used_weight: BASE_WEIGHT,
+					finished: false,
+				};
+				let updated_task = ProgressTask::Fail {
+					remaining: remaining.saturating_sub(1),
+				};
+				(result, Some(updated_task))
+			}
 		}
 	}
+
+	fn on_dropped(self) {
+		DROPPED_TASKS.with(|v| v.borrow_mut().push(self));
+	}
 }
 
 define_combined_task! {
 	#[derive(Clone, Debug, PartialEq, Encode, Decode, TypeInfo)]
 	pub enum ScheduledTasks {
 		BalancesTask(BalancesTask),
+		ProgressTask(ProgressTask),
 	}
 }
 
@@ -108,6 +213,7 @@ construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic
 	{
 		System: frame_system::{Pallet, Call, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		IdleScheduler: module_idle_scheduler::{Pallet, Call, Event<T>, Storage},
 	}
 );
@@ -116,10 +222,16 @@ construct_runtime!(
 pub struct ExtBuilder;
 impl ExtBuilder {
 	pub fn build(self) -> sp_io::TestExternalities {
-		let t = frame_system::GenesisConfig::default()
+		let mut t = frame_system::GenesisConfig::default()
 			.build_storage::<Runtime>()
 			.unwrap();
 
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![(ALICE, 100), (BOB, 100)],
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
 		let mut ext = sp_io::TestExternalities::new(t);
 		ext.execute_with(|| System::set_block_number(1));
 		ext
@@ -23,8 +23,8 @@
 #![cfg(test)]
 
 use super::*;
-use crate::mock::{IdleScheduler, *};
-use frame_support::assert_ok;
+use crate::mock::{Event, IdleScheduler, *};
+use frame_support::{assert_noop, assert_ok};
 
 // Can schedule tasks
 #[test]
@@ -34,7 +34,8 @@ fn can_schedule_tasks() {
 
 		assert_ok!(IdleScheduler::schedule_task(
 			Origin::root(),
-			ScheduledTasks::BalancesTask(BalancesTask::OnIdle)
+			ScheduledTasks::BalancesTask(BalancesTask::OnIdle),
+			DEFAULT_TASK_PRIORITY
 		));
 		assert_eq!(
 			Tasks::<Runtime>::get(0),
@@ -51,17 +52,19 @@ fn can_process_tasks_up_to_weight_limit() {
 	ExtBuilder::default().build().execute_with(|| {
 		assert_ok!(IdleScheduler::schedule_task(
 			Origin::root(),
-			ScheduledTasks::BalancesTask(BalancesTask::OnIdle)
+			ScheduledTasks::BalancesTask(BalancesTask::OnIdle),
+			DEFAULT_TASK_PRIORITY
 		));
 		assert_ok!(IdleScheduler::schedule_task(
 			Origin::root(),
-			ScheduledTasks::BalancesTask(BalancesTask::OnIdle)
+			ScheduledTasks::BalancesTask(BalancesTask::OnIdle),
+			DEFAULT_TASK_PRIORITY
 		));
 
 		// Given enough weights for only 2 tasks: MinimumWeightRemainInBlock::get() + BASE_WEIGHT*2
 		IdleScheduler::on_idle(0, 100_002_000_000);
 
-		// Due to hashing, excution is not guaranteed to be in order.
+		// Tasks are dispatched in the order of the queue.
 		assert_eq!(
 			Tasks::<Runtime>::get(0),
 			None
@@ -80,6 +83,44 @@ fn can_process_tasks_up_to_weight_limit() {
 	});
 }
 
+// can not schedule tasks once the queue is full
+#[test]
+fn cannot_schedule_tasks_to_full_queue() {
+	ExtBuilder::default().build().execute_with(|| {
+		for _ in 0..MaxTasks::get() {
+			assert_ok!(IdleScheduler::schedule_task(
+				Origin::root(),
+				ScheduledTasks::BalancesTask(BalancesTask::OnIdle),
+				DEFAULT_TASK_PRIORITY
+			));
+		}
+		assert_noop!(
+			IdleScheduler::schedule_task(
+				Origin::root(),
+				ScheduledTasks::BalancesTask(BalancesTask::OnIdle),
+				DEFAULT_TASK_PRIORITY
+			),
+			Error::<Runtime>::TaskQueueFull
+		);
+		assert_noop!(
+			IdleScheduler::schedule_task(
+				Origin::signed(ALICE),
+				ScheduledTasks::BalancesTask(BalancesTask::OnIdle),
+				DEFAULT_TASK_PRIORITY
+			),
+			Error::<Runtime>::TaskQueueFull
+		);
+
+		assert_ok!(IdleScheduler::cancel_task(Origin::root(), 0));
+		assert_ok!(IdleScheduler::schedule_task(
+			Origin::root(),
+			ScheduledTasks::BalancesTask(BalancesTask::OnIdle),
+			DEFAULT_TASK_PRIORITY
+		));
+		assert_eq!(IdleScheduler::task_queue().len(), MaxTasks::get() as usize);
+	});
+}
+
 // can increment next task ID
 #[test]
 fn can_increment_next_task_id() {
@@ -87,9 +128,319 @@ fn can_increment_next_task_id() {
 		assert_eq!(NextTaskId::<Runtime>::get(), 0);
 		assert_ok!(IdleScheduler::schedule_task(
 			Origin::root(),
-			ScheduledTasks::BalancesTask(BalancesTask::OnIdle)
+			ScheduledTasks::BalancesTask(BalancesTask::OnIdle),
+			DEFAULT_TASK_PRIORITY
 		));
 
 		assert_eq!(NextTaskId::<Runtime>::get(), 1);
 	});
 }
+
+// can resume unfinished tasks from their progress
+#[test]
+fn can_resume_unfinished_tasks() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(IdleScheduler::schedule_task(
+			Origin::root(),
+			ScheduledTasks::ProgressTask(ProgressTask::Process { remaining: 5 }),
+			DEFAULT_TASK_PRIORITY
+		));
+
+		// Given enough weights to process only 2 items
+		IdleScheduler::on_idle(1, MinimumWeightRemainInBlock::get() + BASE_WEIGHT * 2);
+		assert_eq!(
+			Tasks::<Runtime>::get(0),
+			Some(ScheduledTasks::ProgressTask(ProgressTask::Process { remaining: 3 }))
+		);
+
+		IdleScheduler::on_idle(1, MinimumWeightRemainInBlock::get() + BASE_WEIGHT * 2);
+		assert_eq!(
+			Tasks::<Runtime>::get(0),
+			Some(ScheduledTasks::ProgressTask(ProgressTask::Process { remaining: 1 }))
+		);
+
+		IdleScheduler::on_idle(1, MinimumWeightRemainInBlock::get() + BASE_WEIGHT * 2);
+		assert_eq!(Tasks::<Runtime>::get(0), None);
+		System::assert_last_event(Event::IdleScheduler(crate::Event::TaskDispatched {
+			task_id: 0,
+			result: Ok(()),
+		}));
+	});
+}
+
+// can retry failed tasks with backoff, and drop them after the max retries
+#[test]
+fn can_retry_failed_tasks_with_backoff() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(IdleScheduler::schedule_task(
+			Origin::root(),
+			ScheduledTasks::ProgressTask(ProgressTask::Fail { remaining: 5 }),
+			DEFAULT_TASK_PRIORITY
+		));
+
+		IdleScheduler::on_idle(1, MinimumWeightRemainInBlock::get() + BASE_WEIGHT * 10);
+		System::assert_last_event(Event::IdleScheduler(crate::Event::TaskFailed {
+			task_id: 0,
+			error: DispatchError::Other("failed"),
+			retry_at: Some(11),
+		}));
+		assert_eq!(
+			TaskFailures::<Runtime>::get(0),
+			Some(TaskFailure {
+				attempts: 1,
+				retry_at: 11
+			})
+		);
+		assert_eq!(
+			Tasks::<Runtime>::get(0),
+			Some(ScheduledTasks::ProgressTask(ProgressTask::Fail { remaining: 4 }))
+		);
+
+		// not retried before the backoff
+		IdleScheduler::on_idle(10, MinimumWeightRemainInBlock::get() + BASE_WEIGHT * 10);
+		assert_eq!(
+			Tasks::<Runtime>::get(0),
+			Some(ScheduledTasks::ProgressTask(ProgressTask::Fail { remaining: 4 }))
+		);
+
+		// the backoff doubles
+		IdleScheduler::on_idle(11, MinimumWeightRemainInBlock::get() + BASE_WEIGHT * 10);
+		System::assert_last_event(Event::IdleScheduler(crate::Event::TaskFailed {
+			task_id: 0,
+			error: DispatchError::Other("failed"),
+			retry_at: Some(31),
+		}));
+		assert_eq!(
+			TaskFailures::<Runtime>::get(0),
+			Some(TaskFailure {
+				attempts: 2,
+				retry_at: 31
+			})
+		);
+
+		// dropped after the max retries
+		IdleScheduler::on_idle(31, MinimumWeightRemainInBlock::get() + BASE_WEIGHT * 10);
+		System::assert_last_event(Event::IdleScheduler(crate::Event::TaskFailed {
+			task_id: 0,
+			error: DispatchError::Other("failed"),
+			retry_at: None,
+		}));
+		assert_eq!(Tasks::<Runtime>::get(0), None);
+		assert_eq!(TaskFailures::<Runtime>::get(0), None);
+		assert_eq!(dropped_tasks(), vec![ProgressTask::Fail { remaining: 2 }]);
+	});
+}
+
+// tasks waiting for a retry cost a read when skipped
+#[test]
+fn skipped_tasks_consume_weight() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(IdleScheduler::schedule_task(
+			Origin::root(),
+			ScheduledTasks::ProgressTask(ProgressTask::Fail { remaining: 5 }),
+			DEFAULT_TASK_PRIORITY
+		));
+		assert_ok!(IdleScheduler::schedule_task(
+			Origin::root(),
+			ScheduledTasks::ProgressTask(ProgressTask::Fail { remaining: 5 }),
+			DEFAULT_TASK_PRIORITY
+		));
+		assert_ok!(IdleScheduler::schedule_task(
+			Origin::root(),
+			ScheduledTasks::ProgressTask(ProgressTask::Process { remaining: 5 }),
+			DEFAULT_TASK_PRIORITY
+		));
+
+		// both failing tasks are retried at block 11
+		IdleScheduler::on_idle(1, MinimumWeightRemainInBlock::get() + BASE_WEIGHT * 2);
+		assert_eq!(
+			Tasks::<Runtime>::get(2),
+			Some(ScheduledTasks::ProgressTask(ProgressTask::Process { remaining: 5 }))
+		);
+
+		// the skipped tasks leave weight for only one item
+		assert_eq!(
+			IdleScheduler::on_idle(2, MinimumWeightRemainInBlock::get() + BASE_WEIGHT * 3),
+			BASE_WEIGHT * 3
+		);
+		assert_eq!(
+			Tasks::<Runtime>::get(2),
+			Some(ScheduledTasks::ProgressTask(ProgressTask::Process { remaining: 4 }))
+		);
+
+		// the weight runs out on the skipped tasks
+		assert_eq!(
+			IdleScheduler::on_idle(2, MinimumWeightRemainInBlock::get() + BASE_WEIGHT),
+			BASE_WEIGHT
+		);
+		assert_eq!(
+			Tasks::<Runtime>::get(2),
+			Some(ScheduledTasks::ProgressTask(ProgressTask::Process { remaining: 4 }))
+		);
+	});
+}
+
+// can dispatch tasks by priority, and by scheduling order within the same priority
+#[test]
+fn can_dispatch_tasks_by_priority() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(IdleScheduler::schedule_task(
+			Origin::root(),
+			ScheduledTasks::BalancesTask(BalancesTask::OnIdle),
+			1
+		));
+		assert_ok!(IdleScheduler::schedule_task(
+			Origin::root(),
+			ScheduledTasks::BalancesTask(BalancesTask::OnIdle),
+			DEFAULT_TASK_PRIORITY
+		));
+		assert_ok!(IdleScheduler::schedule_task(
+			Origin::root(),
+			ScheduledTasks::BalancesTask(BalancesTask::OnIdle),
+			200
+		));
+		assert_ok!(IdleScheduler::schedule_task(
+			Origin::root(),
+			ScheduledTasks::BalancesTask(BalancesTask::OnIdle),
+			DEFAULT_TASK_PRIORITY
+		));
+		assert_eq!(
+			IdleScheduler::task_queue(),
+			vec![(200, 2), (DEFAULT_TASK_PRIORITY, 1), (DEFAULT_TASK_PRIORITY, 3), (1, 0)]
+		);
+
+		// Given enough weights for only 2 tasks
+		IdleScheduler::on_idle(1, MinimumWeightRemainInBlock::get() + BASE_WEIGHT * 2);
+		assert_eq!(Tasks::<Runtime>::get(2), None);
+		assert_eq!(Tasks::<Runtime>::get(1), None);
+		assert_eq!(IdleScheduler::task_queue(), vec![(DEFAULT_TASK_PRIORITY, 3), (1, 0)]);
+	});
+}
+
+// signed origins can schedule allowed tasks with a deposit, which is refunded on completion
+#[test]
+fn signed_origins_can_schedule_tasks_with_deposit() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			IdleScheduler::schedule_task(
+				Origin::signed(ALICE),
+				ScheduledTasks::ProgressTask(ProgressTask::Process { remaining: 1 }),
+				DEFAULT_TASK_PRIORITY
+			),
+			Error::<Runtime>::TaskNotAllowed
+		);
+		assert_noop!(
+			IdleScheduler::schedule_task(
+				Origin::signed(ALICE),
+				ScheduledTasks::BalancesTask(BalancesTask::OnIdle),
+				DEFAULT_TASK_PRIORITY + 1
+			),
+			Error::<Runtime>::PriorityTooHigh
+		);
+
+		assert_ok!(IdleScheduler::schedule_task(
+			Origin::signed(ALICE),
+			ScheduledTasks::BalancesTask(BalancesTask::OnIdle),
+			DEFAULT_TASK_PRIORITY
+		));
+		System::assert_last_event(Event::IdleScheduler(crate::Event::TaskScheduled {
+			task_id: 0,
+			priority: DEFAULT_TASK_PRIORITY,
+		}));
+		assert_ok!(IdleScheduler::schedule_task(
+			Origin::signed(ALICE),
+			ScheduledTasks::BalancesTask(BalancesTask::OnIdle),
+			1
+		));
+		assert_noop!(
+			IdleScheduler::schedule_task(
+				Origin::signed(ALICE),
+				ScheduledTasks::BalancesTask(BalancesTask::OnIdle),
+				1
+			),
+			Error::<Runtime>::TooManyTasks
+		);
+		assert_eq!(IdleScheduler::task_owners(0), Some((ALICE, 10)));
+		assert_eq!(IdleScheduler::account_task_counts(ALICE), 2);
+		assert_eq!(Balances::reserved_balance(ALICE), 20);
+
+		IdleScheduler::on_idle(1, MinimumWeightRemainInBlock::get() + BASE_WEIGHT * 2);
+		assert_eq!(Tasks::<Runtime>::get(0), None);
+		assert_eq!(Tasks::<Runtime>::get(1), None);
+		assert_eq!(IdleScheduler::task_owners(0), None);
+		assert_eq!(IdleScheduler::account_task_counts(ALICE), 0);
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		assert_eq!(Balances::free_balance(ALICE), 100);
+	});
+}
+
+// owners can cancel their tasks with the deposit refunded, governance with the deposit slashed
+#[test]
+fn can_cancel_tasks() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(IdleScheduler::schedule_task(
+			Origin::signed(ALICE),
+			ScheduledTasks::BalancesTask(BalancesTask::OnIdle),
+			DEFAULT_TASK_PRIORITY
+		));
+		assert_ok!(IdleScheduler::schedule_task(
+			Origin::signed(ALICE),
+			ScheduledTasks::BalancesTask(BalancesTask::OnIdle),
+			DEFAULT_TASK_PRIORITY
+		));
+		assert_eq!(Balances::reserved_balance(ALICE), 20);
+
+		assert_noop!(
+			IdleScheduler::cancel_task(Origin::signed(ALICE), 2),
+			Error::<Runtime>::TaskNotFound
+		);
+		assert_noop!(
+			IdleScheduler::cancel_task(Origin::signed(BOB), 0),
+			Error::<Runtime>::NoPermission
+		);
+
+		assert_ok!(IdleScheduler::cancel_task(Origin::signed(ALICE), 0));
+		System::assert_last_event(Event::IdleScheduler(crate::Event::TaskCancelled {
+			task_id: 0,
+			slashed: 0,
+		}));
+		assert_eq!(Tasks::<Runtime>::get(0), None);
+		assert_eq!(Balances::reserved_balance(ALICE), 10);
+		assert_eq!(Balances::free_balance(ALICE), 90);
+
+		assert_ok!(IdleScheduler::cancel_task(Origin::root(), 1));
+		System::assert_last_event(Event::IdleScheduler(crate::Event::TaskCancelled {
+			task_id: 1,
+			slashed: 10,
+		}));
+		assert_eq!(Tasks::<Runtime>::get(1), None);
+		assert_eq!(IdleScheduler::task_queue(), vec![]);
+		assert_eq!(IdleScheduler::account_task_counts(ALICE), 0);
+		assert_eq!(Balances::reserved_balance(ALICE), 0);
+		assert_eq!(Balances::free_balance(ALICE), 90);
+	});
+}
+
+// cancelled tasks are dropped, finished ones are not
+#[test]
+fn cancelled_tasks_are_dropped() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(IdleScheduler::schedule_task(
+			Origin::root(),
+			ScheduledTasks::ProgressTask(ProgressTask::Process { remaining: 1 }),
+			DEFAULT_TASK_PRIORITY
+		));
+		assert_ok!(IdleScheduler::schedule_task(
+			Origin::root(),
+			ScheduledTasks::ProgressTask(ProgressTask::Process { remaining: 100 }),
+			DEFAULT_TASK_PRIORITY
+		));
+
+		IdleScheduler::on_idle(1, MinimumWeightRemainInBlock::get() + BASE_WEIGHT * 10);
+		assert_eq!(Tasks::<Runtime>::get(0), None);
+		assert_eq!(dropped_tasks(), vec![]);
+
+		assert_ok!(IdleScheduler::cancel_task(Origin::root(), 1));
+		assert_eq!(dropped_tasks(), vec![ProgressTask::Process { remaining: 91 }]);
+	});
+}
@@ -18,26 +18,10 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-//! Autogenerated weights for module_idle_scheduler
+//! Weights for module_idle_scheduler
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2021-10-03, STEPS: `50`, REPEAT: 20, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 128
-
-// Executed Command:
-// target/release/setheum-node
-// benchmark
-// --chain=dev
-// --steps=50
-// --repeat=20
-// --pallet=module-idle-scheduler
-// --extrinsic=*
-// --execution=wasm
-// --wasm-execution=compiled
-// --heap-pages=4096
-// --template=.maintain/module-weight-template.hbs
-// --output=./blockchain/modules/idle-scheduler/src/weights.rs
-
+//! Mirrors the weights of the Setheum runtime in
+//! `chains/qingdao/runtime/src/weights/idle_scheduler.rs`.
 
 #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
@@ -49,24 +33,39 @@ use sp_std::marker::PhantomData;
 
 /// Weight functions needed for module_idle_scheduler.
 pub trait WeightInfo {
-	fn schedule_task() -> Weight;
+	fn schedule_task(q: u32, ) -> Weight;
+	fn cancel_task(q: u32, ) -> Weight;
 }
 
 /// Weights for module_idle_scheduler using the Setheum node and recommended hardware.
 pub struct SetheumWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SetheumWeight<T> {
-	fn schedule_task() -> Weight {
+	fn schedule_task(q: u32, ) -> Weight {
 		(34_000_000 as Weight)
+		.saturating_add((120_000 as Weight).saturating_mul(q as Weight))
 		.saturating_add(T::DbWeight::get().reads(8 as Weight))
 		.saturating_add(T::DbWeight::get().writes(3 as Weight))
 	}
+	fn cancel_task(q: u32, ) -> Weight {
+		(31_000_000 as Weight)
+		.saturating_add((110_000 as Weight).saturating_mul(q as Weight))
+		.saturating_add(T::DbWeight::get().reads(4 as Weight))
+		.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
-	fn schedule_task() -> Weight {
+	fn schedule_task(q: u32, ) -> Weight {
 		(34_000_000 as Weight)
+		.saturating_add((120_000 as Weight).saturating_mul(q as Weight))
 		.saturating_add(RocksDbWeight::get().reads(8 as Weight))
 		.saturating_add(RocksDbWeight::get().writes(3 as Weight))
 	}
+	fn cancel_task(q: u32, ) -> Weight {
+		(31_000_000 as Weight)
+		.saturating_add((110_000 as Weight).saturating_mul(q as Weight))
+		.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+		.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
 }
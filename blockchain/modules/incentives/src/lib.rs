@@ -0,0 +1,460 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! # Incentives Module
+//!
+//! ## Overview
+//!
+//! Incentives module provides the liquidity mining of the DEX and the
+//! StableSwap pools. Governance sets the reward rates of the pools, which are
+//! keyed by the liquidity shares, in any currencies: the `CurrencyId::DexShare`
+//! of a DEX pair or the `CurrencyId::StableAssetPoolToken` of a stable pool,
+//! which may hold more than two currencies and so has no `DexShare`. LP
+//! holders deposit their shares into the pools and accrue the rewards every
+//! block in proportion to their shares, and can claim the rewards or
+//! withdraw the shares at any time.
+//!
+//! The deposited shares stay in the account of the holder as named reserves
+//! of `ReserveIdentifier::Incentives`. The rewards are accumulated lazily by
+//! the reward per share of the pools, and are paid from the account of the
+//! module, which is funded by governance.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{pallet_prelude::*, transactional, PalletId};
+use frame_system::pallet_prelude::*;
+use orml_traits::{MultiCurrency, NamedMultiReservableCurrency};
+use primitives::{Balance, CurrencyId, ReserveIdentifier};
+use sp_runtime::{
+	traits::{AccountIdConversion, Saturating, UniqueSaturatedInto, Zero},
+	ArithmeticError, DispatchResult, FixedPointNumber, FixedU128, RuntimeDebug,
+};
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+use support::IncentivesManager;
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+/// The reward of a currency of a pool.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, Default, TypeInfo)]
+pub struct RewardInfo {
+	/// The amount of the reward emitted every block.
+	pub reward_per_block: Balance,
+	/// The accumulated reward per deposited share.
+	pub acc_reward_per_share: FixedU128,
+}
+
+/// Information of an incentives pool.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, Default, TypeInfo)]
+pub struct PoolInfo<BlockNumber> {
+	/// The total shares deposited into the pool.
+	pub total_shares: Balance,
+	/// The block number when the rewards were last accumulated.
+	pub last_update_block: BlockNumber,
+	/// The rewards of the pool.
+	pub rewards: BTreeMap<CurrencyId, RewardInfo>,
+}
+
+/// Information of the deposit of an account in a pool.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, Default, TypeInfo)]
+pub struct DepositInfo {
+	/// The shares deposited into the pool.
+	pub shares: Balance,
+	/// The rewards which had been accumulated for the shares when they were
+	/// last changed, and are not accrued to the account.
+	pub reward_debts: BTreeMap<CurrencyId, Balance>,
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	pub const RESERVE_ID: ReserveIdentifier = ReserveIdentifier::Incentives;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Currency for reserving the deposited liquidity shares.
+		type Currency: NamedMultiReservableCurrency<
+			Self::AccountId,
+			ReserveIdentifier = ReserveIdentifier,
+			CurrencyId = CurrencyId,
+			Balance = Balance,
+		>;
+
+		/// Currency for paying the rewards.
+		type RewardCurrency: MultiCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+		/// The origin which may update the reward rates.
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The incentives module id, keep the rewards.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The currency id is not a liquidity share
+		InvalidCurrencyId,
+		/// The amount is invalid
+		InvalidAmount,
+		/// The deposited shares are not enough
+		NotEnoughShares,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The reward rate of a pool is updated. \[lp_currency_id,
+		/// reward_currency_id, reward_per_block\]
+		RewardRateUpdated(CurrencyId, CurrencyId, Balance),
+		/// Liquidity shares are deposited into a pool. \[who, lp_currency_id,
+		/// amount\]
+		DepositDexShare(T::AccountId, CurrencyId, Balance),
+		/// Liquidity shares are withdrawn from a pool. \[who, lp_currency_id,
+		/// amount\]
+		WithdrawDexShare(T::AccountId, CurrencyId, Balance),
+		/// Rewards of a pool are claimed. \[who, lp_currency_id,
+		/// reward_currency_id, amount\]
+		ClaimRewards(T::AccountId, CurrencyId, CurrencyId, Balance),
+	}
+
+	/// The incentives pools, keyed by the liquidity shares.
+	///
+	/// Pools: map CurrencyId => PoolInfo
+	#[pallet::storage]
+	#[pallet::getter(fn pools)]
+	pub type Pools<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, PoolInfo<T::BlockNumber>, ValueQuery>;
+
+	/// The deposits of the accounts in the pools.
+	///
+	/// Deposits: double_map CurrencyId, AccountId => Option<DepositInfo>
+	#[pallet::storage]
+	#[pallet::getter(fn deposits)]
+	pub type Deposits<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, CurrencyId, Twox64Concat, T::AccountId, DepositInfo, OptionQuery>;
+
+	/// The rewards accrued to the accounts and not claimed yet.
+	///
+	/// PendingRewards: double_map CurrencyId, AccountId => BTreeMap<CurrencyId,
+	/// Balance>
+	#[pallet::storage]
+	#[pallet::getter(fn pending_rewards)]
+	pub type PendingRewards<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		CurrencyId,
+		Twox64Concat,
+		T::AccountId,
+		BTreeMap<CurrencyId, Balance>,
+		ValueQuery,
+	>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Update the reward rates of a pool. The rewards of the past blocks
+		/// are accumulated by the previous rates.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `lp_currency_id`: the liquidity share of the pool.
+		/// - `updates`: the reward currencies and their amounts emitted every
+		///   block.
+		#[pallet::weight(T::WeightInfo::update_reward_rates(updates.len() as u32))]
+		#[transactional]
+		pub fn update_reward_rates(
+			origin: OriginFor<T>,
+			lp_currency_id: CurrencyId,
+			updates: Vec<(CurrencyId, Balance)>,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(Self::is_lp_currency_id(lp_currency_id), Error::<T>::InvalidCurrencyId);
+
+			let now = frame_system::Pallet::<T>::block_number();
+			Pools::<T>::mutate(lp_currency_id, |pool| {
+				Self::accumulate_rewards(pool, now);
+				for (reward_currency_id, reward_per_block) in updates {
+					pool.rewards.entry(reward_currency_id).or_default().reward_per_block = reward_per_block;
+					Self::deposit_event(Event::RewardRateUpdated(
+						lp_currency_id,
+						reward_currency_id,
+						reward_per_block,
+					));
+				}
+			});
+			Ok(())
+		}
+
+		/// Deposit liquidity shares into a pool to accrue the rewards.
+		///
+		/// - `lp_currency_id`: the liquidity share of the pool.
+		/// - `amount`: the amount of the shares to deposit.
+		#[pallet::weight(T::WeightInfo::deposit_dex_share())]
+		#[transactional]
+		pub fn deposit_dex_share(origin: OriginFor<T>, lp_currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_deposit_dex_share(&who, lp_currency_id, amount)
+		}
+
+		/// Withdraw liquidity shares from a pool. The accrued rewards are kept
+		/// until claimed.
+		///
+		/// - `lp_currency_id`: the liquidity share of the pool.
+		/// - `amount`: the amount of the shares to withdraw.
+		#[pallet::weight(T::WeightInfo::withdraw_dex_share())]
+		#[transactional]
+		pub fn withdraw_dex_share(origin: OriginFor<T>, lp_currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_withdraw_dex_share(&who, lp_currency_id, amount)
+		}
+
+		/// Claim all the rewards accrued in a pool.
+		///
+		/// - `lp_currency_id`: the liquidity share of the pool.
+		#[pallet::weight(T::WeightInfo::claim_rewards())]
+		#[transactional]
+		pub fn claim_rewards(origin: OriginFor<T>, lp_currency_id: CurrencyId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_claim_rewards(&who, lp_currency_id)
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The account of the module, which pays the rewards.
+	pub fn account_id() -> T::AccountId {
+		T::PalletId::get().into_account()
+	}
+
+	/// Whether `currency_id` is the liquidity share of a DEX pair or of a stable pool.
+	fn is_lp_currency_id(currency_id: CurrencyId) -> bool {
+		currency_id.is_dex_share_currency_id() || currency_id.is_stable_asset_pool_token_currency_id()
+	}
+
+	/// Accumulate the rewards of the pool emitted since the last update.
+	/// Nothing is emitted while the pool is empty.
+	fn accumulate_rewards(pool: &mut PoolInfo<T::BlockNumber>, now: T::BlockNumber) {
+		if now <= pool.last_update_block {
+			return;
+		}
+
+		if !pool.total_shares.is_zero() {
+			let blocks: Balance = now.saturating_sub(pool.last_update_block).unique_saturated_into();
+			for reward in pool.rewards.values_mut() {
+				let emitted = reward.reward_per_block.saturating_mul(blocks);
+				let increment = FixedU128::checked_from_rational(emitted, pool.total_shares).unwrap_or_default();
+				reward.acc_reward_per_share = reward.acc_reward_per_share.saturating_add(increment);
+			}
+		}
+		pool.last_update_block = now;
+	}
+
+	/// The rewards accrued to the shares of the deposit since they were last
+	/// changed.
+	fn accrued_rewards(reward_currency_id: &CurrencyId, reward: &RewardInfo, deposit: &DepositInfo) -> Balance {
+		let reward_debt = deposit
+			.reward_debts
+			.get(reward_currency_id)
+			.copied()
+			.unwrap_or_default();
+		reward
+			.acc_reward_per_share
+			.saturating_mul_int(deposit.shares)
+			.saturating_sub(reward_debt)
+	}
+
+	/// Accrue the rewards of the pool to `who`, then update the deposited
+	/// shares of `who` and the total shares of the pool by `f`.
+	fn update_shares(
+		who: &T::AccountId,
+		lp_currency_id: CurrencyId,
+		f: impl FnOnce(&mut Balance, &mut Balance) -> DispatchResult,
+	) -> DispatchResult {
+		let now = frame_system::Pallet::<T>::block_number();
+		Pools::<T>::try_mutate(lp_currency_id, |pool| -> DispatchResult {
+			Self::accumulate_rewards(pool, now);
+
+			Deposits::<T>::try_mutate_exists(lp_currency_id, who, |maybe_deposit| -> DispatchResult {
+				let mut deposit = maybe_deposit.take().unwrap_or_default();
+				let accrued_rewards: Vec<(CurrencyId, Balance)> = pool
+					.rewards
+					.iter()
+					.map(|(reward_currency_id, reward)| {
+						(
+							*reward_currency_id,
+							Self::accrued_rewards(reward_currency_id, reward, &deposit),
+						)
+					})
+					.filter(|(_, accrued)| !accrued.is_zero())
+					.collect();
+				if !accrued_rewards.is_empty() {
+					PendingRewards::<T>::mutate(lp_currency_id, who, |pending_rewards| {
+						for (reward_currency_id, accrued) in accrued_rewards {
+							let pending = pending_rewards.entry(reward_currency_id).or_default();
+							*pending = pending.saturating_add(accrued);
+						}
+					});
+				}
+
+				f(&mut deposit.shares, &mut pool.total_shares)?;
+
+				if !deposit.shares.is_zero() {
+					deposit.reward_debts = pool
+						.rewards
+						.iter()
+						.map(|(reward_currency_id, reward)| {
+							(
+								*reward_currency_id,
+								reward.acc_reward_per_share.saturating_mul_int(deposit.shares),
+							)
+						})
+						.collect();
+					*maybe_deposit = Some(deposit);
+				}
+				Ok(())
+			})
+		})
+	}
+
+	#[transactional]
+	fn do_deposit_dex_share(who: &T::AccountId, lp_currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		ensure!(Self::is_lp_currency_id(lp_currency_id), Error::<T>::InvalidCurrencyId);
+		ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+
+		T::Currency::reserve_named(&RESERVE_ID, lp_currency_id, who, amount)?;
+		Self::update_shares(who, lp_currency_id, |shares, total_shares| {
+			*shares = shares.checked_add(amount).ok_or(ArithmeticError::Overflow)?;
+			*total_shares = total_shares.checked_add(amount).ok_or(ArithmeticError::Overflow)?;
+			Ok(())
+		})?;
+
+		Self::deposit_event(Event::DepositDexShare(who.clone(), lp_currency_id, amount));
+		Ok(())
+	}
+
+	#[transactional]
+	fn do_withdraw_dex_share(who: &T::AccountId, lp_currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+
+		Self::update_shares(who, lp_currency_id, |shares, total_shares| {
+			*shares = shares.checked_sub(amount).ok_or(Error::<T>::NotEnoughShares)?;
+			*total_shares = total_shares.saturating_sub(amount);
+			Ok(())
+		})?;
+		T::Currency::unreserve_named(&RESERVE_ID, lp_currency_id, who, amount);
+
+		Self::deposit_event(Event::WithdrawDexShare(who.clone(), lp_currency_id, amount));
+		Ok(())
+	}
+
+	/// Pay the pending rewards of `who` in the pool. The rewards which can
+	/// not be paid for now, e.g. the module account is not funded enough,
+	/// are kept pending.
+	#[transactional]
+	fn do_claim_rewards(who: &T::AccountId, lp_currency_id: CurrencyId) -> DispatchResult {
+		ensure!(Self::is_lp_currency_id(lp_currency_id), Error::<T>::InvalidCurrencyId);
+		Self::update_shares(who, lp_currency_id, |_, _| Ok(()))?;
+
+		let module_account_id = Self::account_id();
+		PendingRewards::<T>::mutate_exists(lp_currency_id, who, |maybe_pending_rewards| {
+			if let Some(pending_rewards) = maybe_pending_rewards {
+				pending_rewards.retain(|reward_currency_id, amount| {
+					if T::RewardCurrency::transfer(*reward_currency_id, &module_account_id, who, *amount).is_ok() {
+						Self::deposit_event(Event::ClaimRewards(
+							who.clone(),
+							lp_currency_id,
+							*reward_currency_id,
+							*amount,
+						));
+						false
+					} else {
+						true
+					}
+				});
+				if pending_rewards.is_empty() {
+					*maybe_pending_rewards = None;
+				}
+			}
+		});
+		Ok(())
+	}
+}
+
+impl<T: Config> IncentivesManager<T::AccountId, Balance, CurrencyId> for Pallet<T> {
+	fn get_reward_rate(lp_currency_id: CurrencyId, reward_currency_id: CurrencyId) -> Balance {
+		Self::pools(lp_currency_id)
+			.rewards
+			.get(&reward_currency_id)
+			.map(|reward| reward.reward_per_block)
+			.unwrap_or_default()
+	}
+
+	fn get_deposited_shares(lp_currency_id: CurrencyId, who: &T::AccountId) -> Balance {
+		Self::deposits(lp_currency_id, who)
+			.map(|deposit| deposit.shares)
+			.unwrap_or_default()
+	}
+
+	fn get_pending_rewards(lp_currency_id: CurrencyId, who: &T::AccountId, reward_currency_id: CurrencyId) -> Balance {
+		let pending = Self::pending_rewards(lp_currency_id, who)
+			.get(&reward_currency_id)
+			.copied()
+			.unwrap_or_default();
+
+		let mut pool = Self::pools(lp_currency_id);
+		Self::accumulate_rewards(&mut pool, frame_system::Pallet::<T>::block_number());
+		let accrued = match (
+			pool.rewards.get(&reward_currency_id),
+			Self::deposits(lp_currency_id, who),
+		) {
+			(Some(reward), Some(deposit)) => Self::accrued_rewards(&reward_currency_id, reward, &deposit),
+			_ => Zero::zero(),
+		};
+
+		pending.saturating_add(accrued)
+	}
+
+	fn deposit_dex_share(who: &T::AccountId, lp_currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		Self::do_deposit_dex_share(who, lp_currency_id, amount)
+	}
+
+	fn withdraw_dex_share(who: &T::AccountId, lp_currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		Self::do_withdraw_dex_share(who, lp_currency_id, amount)
+	}
+
+	fn claim_rewards(who: &T::AccountId, lp_currency_id: CurrencyId) -> DispatchResult {
+		Self::do_claim_rewards(who, lp_currency_id)
+	}
+}
@@ -0,0 +1,317 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Unit tests for the incentives module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use sp_runtime::traits::BadOrigin;
+
+fn reserved_shares(who: AccountId, lp_currency_id: CurrencyId) -> Balance {
+	<Tokens as NamedMultiReservableCurrency<_>>::reserved_balance_named(&RESERVE_ID, lp_currency_id, &who)
+}
+
+#[test]
+fn update_reward_rates_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			IncentivesModule::update_reward_rates(Origin::signed(BOB), SETR_USSD_LP, vec![(SEE, 100)]),
+			BadOrigin
+		);
+		assert_noop!(
+			IncentivesModule::update_reward_rates(Origin::signed(ALICE), SEE, vec![(SEE, 100)]),
+			Error::<Runtime>::InvalidCurrencyId
+		);
+
+		assert_ok!(IncentivesModule::update_reward_rates(
+			Origin::signed(ALICE),
+			SETR_USSD_LP,
+			vec![(SEE, 100), (GRA, 10)]
+		));
+		System::assert_last_event(Event::IncentivesModule(crate::Event::RewardRateUpdated(
+			SETR_USSD_LP,
+			GRA,
+			10,
+		)));
+		assert_eq!(IncentivesModule::get_reward_rate(SETR_USSD_LP, SEE), 100);
+		assert_eq!(IncentivesModule::get_reward_rate(SETR_USSD_LP, GRA), 10);
+		assert_eq!(IncentivesModule::get_reward_rate(GRA_USSD_LP, SEE), 0);
+		assert_eq!(IncentivesModule::pools(SETR_USSD_LP).last_update_block, 1);
+	});
+}
+
+#[test]
+fn deposit_dex_share_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			IncentivesModule::deposit_dex_share(Origin::signed(ALICE), SEE, 1_000),
+			Error::<Runtime>::InvalidCurrencyId
+		);
+		assert_noop!(
+			IncentivesModule::deposit_dex_share(Origin::signed(ALICE), SETR_USSD_LP, 0),
+			Error::<Runtime>::InvalidAmount
+		);
+		assert_noop!(
+			IncentivesModule::deposit_dex_share(Origin::signed(ALICE), SETR_USSD_LP, 10_001),
+			orml_tokens::Error::<Runtime>::BalanceTooLow
+		);
+
+		assert_ok!(IncentivesModule::deposit_dex_share(
+			Origin::signed(ALICE),
+			SETR_USSD_LP,
+			1_000
+		));
+		System::assert_last_event(Event::IncentivesModule(crate::Event::DepositDexShare(
+			ALICE,
+			SETR_USSD_LP,
+			1_000,
+		)));
+		assert_eq!(Tokens::free_balance(SETR_USSD_LP, &ALICE), 9_000);
+		assert_eq!(reserved_shares(ALICE, SETR_USSD_LP), 1_000);
+		assert_eq!(IncentivesModule::get_deposited_shares(SETR_USSD_LP, &ALICE), 1_000);
+		assert_eq!(IncentivesModule::pools(SETR_USSD_LP).total_shares, 1_000);
+	});
+}
+
+#[test]
+fn stable_pool_shares_are_incentivized() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(IncentivesModule::update_reward_rates(
+			Origin::signed(ALICE),
+			STABLE_POOL_LP,
+			vec![(SEE, 100)]
+		));
+		assert_ok!(IncentivesModule::deposit_dex_share(
+			Origin::signed(BOB),
+			STABLE_POOL_LP,
+			1_000
+		));
+		assert_eq!(reserved_shares(BOB, STABLE_POOL_LP), 1_000);
+
+		System::set_block_number(11);
+		assert_ok!(IncentivesModule::claim_rewards(Origin::signed(BOB), STABLE_POOL_LP));
+		assert_eq!(Tokens::free_balance(SEE, &BOB), 1_000);
+	});
+}
+
+#[test]
+fn withdraw_dex_share_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(IncentivesModule::deposit_dex_share(
+			Origin::signed(ALICE),
+			SETR_USSD_LP,
+			1_000
+		));
+		assert_noop!(
+			IncentivesModule::withdraw_dex_share(Origin::signed(ALICE), SETR_USSD_LP, 1_001),
+			Error::<Runtime>::NotEnoughShares
+		);
+		assert_noop!(
+			IncentivesModule::withdraw_dex_share(Origin::signed(BOB), SETR_USSD_LP, 1),
+			Error::<Runtime>::NotEnoughShares
+		);
+
+		assert_ok!(IncentivesModule::withdraw_dex_share(
+			Origin::signed(ALICE),
+			SETR_USSD_LP,
+			400
+		));
+		System::assert_last_event(Event::IncentivesModule(crate::Event::WithdrawDexShare(
+			ALICE,
+			SETR_USSD_LP,
+			400,
+		)));
+		assert_eq!(Tokens::free_balance(SETR_USSD_LP, &ALICE), 9_400);
+		assert_eq!(reserved_shares(ALICE, SETR_USSD_LP), 600);
+		assert_eq!(IncentivesModule::pools(SETR_USSD_LP).total_shares, 600);
+
+		assert_ok!(IncentivesModule::withdraw_dex_share(
+			Origin::signed(ALICE),
+			SETR_USSD_LP,
+			600
+		));
+		assert_eq!(Tokens::free_balance(SETR_USSD_LP, &ALICE), 10_000);
+		assert_eq!(reserved_shares(ALICE, SETR_USSD_LP), 0);
+		assert_eq!(IncentivesModule::deposits(SETR_USSD_LP, ALICE), None);
+		assert_eq!(IncentivesModule::pools(SETR_USSD_LP).total_shares, 0);
+	});
+}
+
+#[test]
+fn rewards_accrue_in_proportion_to_shares() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(IncentivesModule::update_reward_rates(
+			Origin::signed(ALICE),
+			SETR_USSD_LP,
+			vec![(SEE, 100)]
+		));
+		assert_ok!(IncentivesModule::deposit_dex_share(
+			Origin::signed(ALICE),
+			SETR_USSD_LP,
+			1_000
+		));
+
+		System::set_block_number(3);
+		assert_eq!(IncentivesModule::get_pending_rewards(SETR_USSD_LP, &ALICE, SEE), 200);
+		assert_ok!(IncentivesModule::deposit_dex_share(
+			Origin::signed(BOB),
+			SETR_USSD_LP,
+			3_000
+		));
+
+		System::set_block_number(5);
+		assert_eq!(IncentivesModule::get_pending_rewards(SETR_USSD_LP, &ALICE, SEE), 250);
+		assert_eq!(IncentivesModule::get_pending_rewards(SETR_USSD_LP, &BOB, SEE), 150);
+		assert_eq!(IncentivesModule::get_pending_rewards(SETR_USSD_LP, &BOB, GRA), 0);
+
+		// withdrawn shares keep their accrued rewards
+		assert_ok!(IncentivesModule::withdraw_dex_share(
+			Origin::signed(ALICE),
+			SETR_USSD_LP,
+			1_000
+		));
+		System::set_block_number(7);
+		assert_eq!(IncentivesModule::get_pending_rewards(SETR_USSD_LP, &ALICE, SEE), 250);
+		assert_eq!(IncentivesModule::get_pending_rewards(SETR_USSD_LP, &BOB, SEE), 350);
+	});
+}
+
+#[test]
+fn update_reward_rates_accumulates_previous_rates() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(IncentivesModule::update_reward_rates(
+			Origin::signed(ALICE),
+			SETR_USSD_LP,
+			vec![(SEE, 100)]
+		));
+		assert_ok!(IncentivesModule::deposit_dex_share(
+			Origin::signed(ALICE),
+			SETR_USSD_LP,
+			1_000
+		));
+
+		System::set_block_number(3);
+		assert_ok!(IncentivesModule::update_reward_rates(
+			Origin::signed(ALICE),
+			SETR_USSD_LP,
+			vec![(SEE, 10), (GRA, 50)]
+		));
+
+		System::set_block_number(5);
+		assert_eq!(IncentivesModule::get_pending_rewards(SETR_USSD_LP, &ALICE, SEE), 220);
+		assert_eq!(IncentivesModule::get_pending_rewards(SETR_USSD_LP, &ALICE, GRA), 100);
+	});
+}
+
+#[test]
+fn no_rewards_emitted_while_pool_is_empty() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(IncentivesModule::update_reward_rates(
+			Origin::signed(ALICE),
+			SETR_USSD_LP,
+			vec![(SEE, 100)]
+		));
+
+		System::set_block_number(10);
+		assert_ok!(IncentivesModule::deposit_dex_share(
+			Origin::signed(ALICE),
+			SETR_USSD_LP,
+			1_000
+		));
+		assert_eq!(IncentivesModule::get_pending_rewards(SETR_USSD_LP, &ALICE, SEE), 0);
+
+		System::set_block_number(11);
+		assert_eq!(IncentivesModule::get_pending_rewards(SETR_USSD_LP, &ALICE, SEE), 100);
+	});
+}
+
+#[test]
+fn claim_rewards_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(IncentivesModule::update_reward_rates(
+			Origin::signed(ALICE),
+			SETR_USSD_LP,
+			vec![(SEE, 100), (GRA, 10)]
+		));
+		assert_ok!(IncentivesModule::deposit_dex_share(
+			Origin::signed(ALICE),
+			SETR_USSD_LP,
+			1_000
+		));
+
+		System::set_block_number(4);
+		assert_ok!(IncentivesModule::claim_rewards(Origin::signed(ALICE), SETR_USSD_LP));
+		System::assert_has_event(Event::IncentivesModule(crate::Event::ClaimRewards(
+			ALICE,
+			SETR_USSD_LP,
+			SEE,
+			300,
+		)));
+		System::assert_has_event(Event::IncentivesModule(crate::Event::ClaimRewards(
+			ALICE,
+			SETR_USSD_LP,
+			GRA,
+			30,
+		)));
+		assert_eq!(Tokens::free_balance(SEE, &ALICE), 300);
+		assert_eq!(Tokens::free_balance(GRA, &ALICE), 30);
+		assert_eq!(Tokens::free_balance(SEE, &IncentivesModule::account_id()), 999_700);
+		assert_eq!(IncentivesModule::get_pending_rewards(SETR_USSD_LP, &ALICE, SEE), 0);
+		assert!(!PendingRewards::<Runtime>::contains_key(SETR_USSD_LP, ALICE));
+		assert_eq!(reserved_shares(ALICE, SETR_USSD_LP), 1_000);
+
+		System::set_block_number(5);
+		assert_eq!(IncentivesModule::get_pending_rewards(SETR_USSD_LP, &ALICE, SEE), 100);
+	});
+}
+
+#[test]
+fn claim_rewards_keeps_unpaid_rewards_pending() {
+	ExtBuilder::default()
+		.balances(vec![
+			(ALICE, SETR_USSD_LP, 10_000),
+			(IncentivesModule::account_id(), SEE, 100),
+			(IncentivesModule::account_id(), GRA, 1_000),
+		])
+		.build()
+		.execute_with(|| {
+			assert_ok!(IncentivesModule::update_reward_rates(
+				Origin::signed(ALICE),
+				SETR_USSD_LP,
+				vec![(SEE, 100), (GRA, 10)]
+			));
+			assert_ok!(IncentivesModule::deposit_dex_share(
+				Origin::signed(ALICE),
+				SETR_USSD_LP,
+				1_000
+			));
+
+			System::set_block_number(3);
+			assert_ok!(IncentivesModule::claim_rewards(Origin::signed(ALICE), SETR_USSD_LP));
+			assert_eq!(Tokens::free_balance(SEE, &ALICE), 0);
+			assert_eq!(Tokens::free_balance(GRA, &ALICE), 20);
+			assert_eq!(
+				IncentivesModule::pending_rewards(SETR_USSD_LP, ALICE),
+				vec![(SEE, 200)].into_iter().collect::<BTreeMap<_, _>>()
+			);
+		});
+}
@@ -0,0 +1,913 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Launchpad Module
+//!
+//! ## Overview
+//!
+//! The launchpad runs crowdsales for projects building on Setheum.
+//! A project submits a proposal together with a deposit and the tokens it
+//! wants to sell, governance approves or rejects it, and an approved
+//! campaign opens for contributions in `raise_currency` once
+//! `campaign_start` is reached. A campaign that sells its whole
+//! `crowd_allocation` before `campaign_end` reaches its `goal` and is
+//! successful: the beneficiary claims the raise and every contributor claims
+//! the `sale_token` bought at `token_price`. Otherwise the campaign fails,
+//! contributors are refunded and the proposer takes back the unsold tokens.
+//!
+//! A contribution buys the `sale_token` its amount pays for, rounded down,
+//! and only the cost of that allocation is taken from the contributor. The
+//! contribution that buys the rest of the `crowd_allocation` is capped to it
+//! and may be below `MinContribution`, so a campaign can always be filled.
+//!
+//! A lifecycle step that fails is retried in the next block.
+//!
+//! Once the retirement period of an ended campaign is over, the funds nobody
+//! claimed are paid out to their owners, a bounded number of contributions
+//! per block, and the campaign is removed from storage.
+//!
+//! Contributions are kept in their own storage map keyed by campaign and
+//! contributor, so the size of a `CampaignInfo` does not grow with the
+//! number of contributors. Campaigns are indexed by the block of their next
+//! lifecycle step, so `on_initialize` only touches the campaigns that are due.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+#![allow(clippy::too_many_arguments)]
+
+use codec::{Decode, Encode};
+use frame_support::{pallet_prelude::*, transactional, PalletId};
+use frame_system::pallet_prelude::*;
+use orml_traits::{MultiCurrency, MultiReservableCurrency};
+use primitives::{Balance, CampaignId, CurrencyId};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	helpers_128bit::multiply_by_rational,
+	traits::{AccountIdConversion, One, Saturating, Zero},
+	ArithmeticError, FixedPointNumber, RuntimeDebug,
+};
+use sp_std::vec::Vec;
+use support::{CampaignInfo, CampaignManager, Price, Proposal};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+/// A contribution made to a campaign.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, Default, TypeInfo)]
+pub struct ContributionInfo<Balance> {
+	/// The amount of `raise_currency` contributed.
+	pub amount: Balance,
+	/// The amount of `sale_token` allocated to the contributor.
+	pub allocation: Balance,
+	/// Whether the allocation (or the refund, if the campaign failed) has been claimed.
+	pub claimed: bool,
+}
+
+pub type CampaignInfoOf<T> =
+	CampaignInfo<<T as frame_system::Config>::AccountId, Balance, <T as frame_system::Config>::BlockNumber>;
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The Currency for managing assets.
+		type MultiCurrency: MultiReservableCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+		#[pallet::constant]
+		/// Native currency id, the proposal deposit is reserved in it.
+		type GetNativeCurrencyId: Get<CurrencyId>;
+
+		#[pallet::constant]
+		/// The deposit reserved when a proposal is made, slashed if it is rejected.
+		type ProposalDeposit: Get<Balance>;
+
+		#[pallet::constant]
+		/// The minimum amount of `raise_currency` for a single contribution, unless it buys the
+		/// rest of the `crowd_allocation`.
+		type MinContribution: Get<Balance>;
+
+		#[pallet::constant]
+		/// The number of blocks between the approval of a proposal and the start of its campaign.
+		type CampaignStartDelay: Get<Self::BlockNumber>;
+
+		#[pallet::constant]
+		/// The number of blocks an ended campaign stays claimable before it is retired.
+		type CampaignRetirementPeriod: Get<Self::BlockNumber>;
+
+		#[pallet::constant]
+		/// The number of blocks a rejected proposal stays in storage before it is removed.
+		type ProposalRetirementPeriod: Get<Self::BlockNumber>;
+
+		#[pallet::constant]
+		/// The maximum number of contributions of retired campaigns settled in a block.
+		type MaxContributionsSettledPerBlock: Get<u32>;
+
+		/// The origin which may approve and reject proposals.
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+		#[pallet::constant]
+		/// The Launchpad module pallet id, keeps campaign pools.
+		type PalletId: Get<PalletId>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The proposal does not exist
+		ProposalNotFound,
+		/// The proposal has already been approved or rejected
+		ProposalAlreadyReviewed,
+		/// The proposal parameters are invalid
+		InvalidProposal,
+		/// The campaign does not exist
+		CampaignNotFound,
+		/// The campaign is not open for contributions
+		CampaignNotActive,
+		/// The campaign has not ended successfully
+		CampaignNotSuccessful,
+		/// The campaign has not failed
+		CampaignNotFailed,
+		/// The contribution is below `MinContribution`
+		ContributionTooSmall,
+		/// The whole `crowd_allocation` of the campaign has been sold
+		GoalReached,
+		/// The account has not contributed to the campaign
+		NoContribution,
+		/// The funds have already been claimed
+		AlreadyClaimed,
+		/// The caller is not the campaign beneficiary
+		NotBeneficiary,
+		/// The caller is not the campaign creator
+		NotCampaignCreator,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	#[pallet::metadata(T::AccountId = "AccountId", T::BlockNumber = "BlockNumber")]
+	pub enum Event<T: Config> {
+		/// A new campaign proposal was made.
+		ProposalCreated { id: CampaignId, origin: T::AccountId },
+		/// A proposal was approved and its campaign scheduled.
+		ProposalApproved {
+			id: CampaignId,
+			campaign_start: T::BlockNumber,
+			campaign_end: T::BlockNumber,
+		},
+		/// A proposal was rejected and its deposit slashed.
+		ProposalRejected { id: CampaignId },
+		/// A rejected proposal was removed from storage.
+		ProposalRemoved { id: CampaignId },
+		/// A campaign was opened for contributions.
+		CampaignActivated { id: CampaignId },
+		/// A campaign ended having reached its goal.
+		CampaignSuccessful { id: CampaignId, raised: Balance },
+		/// A campaign ended without reaching its goal.
+		CampaignFailed { id: CampaignId, raised: Balance },
+		/// A campaign was retired, its remaining contributions are being settled.
+		CampaignRetired { id: CampaignId },
+		/// The contributions of a retired campaign were settled and removed from storage.
+		CampaignSettled { id: CampaignId },
+		/// A lifecycle step of a campaign failed, it is retried in the next block.
+		CampaignStepFailed { id: CampaignId, error: DispatchError },
+		/// A contribution was made to a campaign.
+		Contributed {
+			id: CampaignId,
+			who: T::AccountId,
+			amount: Balance,
+			allocation: Balance,
+		},
+		/// A contributor claimed their `sale_token` allocation.
+		AllocationClaimed {
+			id: CampaignId,
+			who: T::AccountId,
+			allocation: Balance,
+		},
+		/// A contributor was refunded from a failed campaign.
+		ContributionRefunded {
+			id: CampaignId,
+			who: T::AccountId,
+			amount: Balance,
+		},
+		/// The beneficiary claimed the funds raised by a campaign.
+		CampaignClaimed {
+			id: CampaignId,
+			beneficiary: T::AccountId,
+			raised: Balance,
+		},
+		/// The proposer took back the tokens of a failed campaign.
+		FailedCampaignClaimed {
+			id: CampaignId,
+			origin: T::AccountId,
+			amount: Balance,
+		},
+	}
+
+	/// The id of the next campaign proposal.
+	#[pallet::storage]
+	#[pallet::getter(fn next_campaign_id)]
+	pub type NextCampaignId<T: Config> = StorageValue<_, CampaignId, ValueQuery>;
+
+	/// Proposals waiting for review, and rejected proposals waiting for removal.
+	///
+	/// Proposals: map CampaignId => Option<CampaignInfo>
+	#[pallet::storage]
+	#[pallet::getter(fn proposals)]
+	pub type Proposals<T: Config> = StorageMap<_, Twox64Concat, CampaignId, CampaignInfoOf<T>, OptionQuery>;
+
+	/// Approved campaigns.
+	///
+	/// Campaigns: map CampaignId => Option<CampaignInfo>
+	#[pallet::storage]
+	#[pallet::getter(fn campaigns)]
+	pub type Campaigns<T: Config> = StorageMap<_, Twox64Concat, CampaignId, CampaignInfoOf<T>, OptionQuery>;
+
+	/// The contributions made to campaigns.
+	///
+	/// Contributions: double_map CampaignId, AccountId => Option<ContributionInfo>
+	#[pallet::storage]
+	#[pallet::getter(fn contributions)]
+	pub type Contributions<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		CampaignId,
+		Twox64Concat,
+		T::AccountId,
+		ContributionInfo<Balance>,
+		OptionQuery,
+	>;
+
+	/// The campaigns and rejected proposals due for their next lifecycle step at a block.
+	///
+	/// CampaignSchedule: double_map BlockNumber, CampaignId => Option<()>
+	#[pallet::storage]
+	#[pallet::getter(fn campaign_schedule)]
+	pub type CampaignSchedule<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, T::BlockNumber, Twox64Concat, CampaignId, (), OptionQuery>;
+
+	/// Retired campaigns whose unclaimed contributions are still being settled.
+	///
+	/// RetiredCampaigns: map CampaignId => Option<CampaignInfo>
+	#[pallet::storage]
+	#[pallet::getter(fn retired_campaigns)]
+	pub type RetiredCampaigns<T: Config> = StorageMap<_, Twox64Concat, CampaignId, CampaignInfoOf<T>, OptionQuery>;
+
+	/// The total amounts raised by successful campaigns, per currency.
+	///
+	/// TotalAmountsRaised: map CurrencyId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn total_amounts_raised)]
+	pub type TotalAmountsRaised<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Balance, ValueQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Move the campaigns due at `now` through their lifecycle, remove retired proposals and
+		/// settle the contributions of retired campaigns.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let mut count: u32 = 0;
+
+			let due: Vec<CampaignId> = CampaignSchedule::<T>::drain_prefix(now).map(|(id, _)| id).collect();
+			for id in due {
+				count = count.saturating_add(1);
+				if let Err(error) = Self::process_campaign(now, id) {
+					CampaignSchedule::<T>::insert(now.saturating_add(One::one()), id, ());
+					Self::deposit_event(Event::CampaignStepFailed { id, error });
+				}
+			}
+
+			count = count.saturating_add(Self::settle_retired_campaigns(T::MaxContributionsSettledPerBlock::get()));
+
+			T::WeightInfo::on_initialize(count)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Propose a new crowdsale campaign.
+		///
+		/// Reserves `ProposalDeposit` and moves `crowd_allocation` of `sale_token` to the
+		/// campaign pool.
+		///
+		/// The dispatch origin of this call must be `Signed` by the proposer.
+		#[pallet::weight(<T as Config>::WeightInfo::make_proposal())]
+		#[transactional]
+		pub fn make_proposal(
+			origin: OriginFor<T>,
+			project_name: Vec<u8>,
+			project_logo: Vec<u8>,
+			project_description: Vec<u8>,
+			project_website: Vec<u8>,
+			beneficiary: T::AccountId,
+			raise_currency: CurrencyId,
+			sale_token: CurrencyId,
+			token_price: Price,
+			crowd_allocation: Balance,
+			goal: Balance,
+			period: T::BlockNumber,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			<Self as Proposal<T::AccountId, T::BlockNumber>>::new_proposal(
+				who,
+				project_name,
+				project_logo,
+				project_description,
+				project_website,
+				beneficiary,
+				raise_currency,
+				sale_token,
+				token_price,
+				crowd_allocation,
+				goal,
+				period,
+			)
+		}
+
+		/// Approve a proposal and schedule its campaign.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		#[pallet::weight(<T as Config>::WeightInfo::approve_proposal())]
+		#[transactional]
+		pub fn approve_proposal(origin: OriginFor<T>, id: CampaignId) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			Self::on_approve_proposal(id)
+		}
+
+		/// Reject a proposal, slashing its deposit.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		#[pallet::weight(<T as Config>::WeightInfo::reject_proposal())]
+		#[transactional]
+		pub fn reject_proposal(origin: OriginFor<T>, id: CampaignId) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			Self::on_reject_proposal(id)
+		}
+
+		/// Contribute `amount` of the campaign's `raise_currency`.
+		///
+		/// The dispatch origin of this call must be `Signed` by the contributor.
+		#[pallet::weight(<T as Config>::WeightInfo::contribute())]
+		#[transactional]
+		pub fn contribute(origin: OriginFor<T>, id: CampaignId, #[pallet::compact] amount: Balance) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::on_contribution(who, id, amount)
+		}
+
+		/// Claim the `sale_token` allocation of a successful campaign.
+		///
+		/// The dispatch origin of this call must be `Signed` by the contributor.
+		#[pallet::weight(<T as Config>::WeightInfo::claim_contribution_allocation())]
+		#[transactional]
+		pub fn claim_contribution_allocation(origin: OriginFor<T>, id: CampaignId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::on_claim_allocation(who, id)
+		}
+
+		/// Claim the funds raised by a successful campaign.
+		///
+		/// The dispatch origin of this call must be `Signed` by the campaign beneficiary.
+		#[pallet::weight(<T as Config>::WeightInfo::claim_campaign_fundraise())]
+		#[transactional]
+		pub fn claim_campaign_fundraise(origin: OriginFor<T>, id: CampaignId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::on_claim_campaign(who, id)
+		}
+
+		/// Claim back a contribution made to a failed campaign.
+		///
+		/// The dispatch origin of this call must be `Signed` by the contributor.
+		#[pallet::weight(<T as Config>::WeightInfo::claim_refund())]
+		#[transactional]
+		pub fn claim_refund(origin: OriginFor<T>, id: CampaignId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_claim_refund(who, id)
+		}
+
+		/// Take back the `sale_token` allocation of a failed campaign.
+		///
+		/// The dispatch origin of this call must be `Signed` by the campaign creator.
+		#[pallet::weight(<T as Config>::WeightInfo::claim_failed_campaign())]
+		#[transactional]
+		pub fn claim_failed_campaign(origin: OriginFor<T>, id: CampaignId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::on_claim_failed_campaign(who, id)
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Get the pool account of campaign `id`.
+	pub fn campaign_pool(id: CampaignId) -> T::AccountId {
+		T::PalletId::get().into_sub_account(id)
+	}
+
+	/// Retrieves the next campaign id from storage, and increment it by one.
+	fn get_next_campaign_id() -> Result<CampaignId, DispatchError> {
+		NextCampaignId::<T>::try_mutate(|current| -> Result<CampaignId, DispatchError> {
+			let id = *current;
+			*current = current.checked_add(One::one()).ok_or(ArithmeticError::Overflow)?;
+			Ok(id)
+		})
+	}
+
+	/// Schedule the next lifecycle step of campaign `id` at block `at`, or at the next block if
+	/// `at` is not in the future.
+	fn schedule(at: T::BlockNumber, id: CampaignId) {
+		let next = <frame_system::Pallet<T>>::block_number().saturating_add(One::one());
+		CampaignSchedule::<T>::insert(at.max(next), id, ());
+	}
+
+	/// Move campaign `id` to its next lifecycle step, or remove it if it is a rejected proposal.
+	#[transactional]
+	fn process_campaign(now: T::BlockNumber, id: CampaignId) -> DispatchResult {
+		if let Some(campaign) = Self::campaigns(id) {
+			if campaign.is_waiting {
+				Self::activate_campaign(id)
+			} else if campaign.is_active {
+				if campaign.sold >= campaign.crowd_allocation {
+					Self::on_successful_campaign(now, id)
+				} else {
+					Self::on_failed_campaign(now, id)
+				}
+			} else if campaign.is_ended {
+				Self::on_retire(id)
+			} else {
+				Ok(())
+			}
+		} else if Self::proposals(id).map_or(false, |proposal| proposal.is_rejected) {
+			Self::remove_proposal(id)
+		} else {
+			Ok(())
+		}
+	}
+
+	/// The amount of `sale_token` that `amount` of `raise_currency` buys at `price`, rounded down.
+	fn allocation_of(amount: Balance, price: Price) -> Result<Balance, DispatchError> {
+		multiply_by_rational(amount, Price::accuracy(), price.into_inner())
+			.map_err(|_| ArithmeticError::Overflow.into())
+	}
+
+	/// The amount of `raise_currency` that buys `allocation` of `sale_token` at `price`, rounded
+	/// up.
+	fn cost_of(allocation: Balance, price: Price) -> Result<Balance, DispatchError> {
+		let cost = multiply_by_rational(allocation, price.into_inner(), Price::accuracy())
+			.map_err(|_| ArithmeticError::Overflow)?;
+		if Self::allocation_of(cost, price)? >= allocation {
+			Ok(cost)
+		} else {
+			cost.checked_add(One::one())
+				.ok_or_else(|| ArithmeticError::Overflow.into())
+		}
+	}
+
+	/// Pay out the unclaimed contributions of retired campaigns, the allocation of a successful
+	/// campaign or the refund of a failed one, and remove them. Settles at most `limit`
+	/// contributions and returns the number settled.
+	fn settle_retired_campaigns(limit: u32) -> u32 {
+		let mut settled: u32 = 0;
+
+		while settled < limit {
+			let (id, campaign) = match RetiredCampaigns::<T>::iter().next() {
+				Some(retired) => retired,
+				None => break,
+			};
+
+			let batch = limit.saturating_sub(settled);
+			let contributions: Vec<(T::AccountId, ContributionInfo<Balance>)> =
+				Contributions::<T>::iter_prefix(id).take(batch as usize).collect();
+			for (who, contribution) in contributions.iter() {
+				if !contribution.claimed {
+					let _ = if campaign.is_successful {
+						T::MultiCurrency::transfer(campaign.sale_token, &campaign.pool, who, contribution.allocation)
+					} else {
+						T::MultiCurrency::transfer(campaign.raise_currency, &campaign.pool, who, contribution.amount)
+					};
+				}
+				Contributions::<T>::remove(id, who);
+			}
+			settled = settled.saturating_add(contributions.len() as u32);
+
+			if (contributions.len() as u32) < batch {
+				RetiredCampaigns::<T>::remove(id);
+				Self::deposit_event(Event::CampaignSettled { id });
+			}
+		}
+
+		settled
+	}
+
+	/// Refund the contribution of `who` to the failed campaign `id`.
+	fn do_claim_refund(who: T::AccountId, id: CampaignId) -> DispatchResult {
+		let campaign = Self::campaigns(id).ok_or(Error::<T>::CampaignNotFound)?;
+		ensure!(campaign.is_failed, Error::<T>::CampaignNotFailed);
+
+		Contributions::<T>::try_mutate(id, &who, |maybe_contribution| -> DispatchResult {
+			let contribution = maybe_contribution.as_mut().ok_or(Error::<T>::NoContribution)?;
+			ensure!(!contribution.claimed, Error::<T>::AlreadyClaimed);
+
+			T::MultiCurrency::transfer(campaign.raise_currency, &campaign.pool, &who, contribution.amount)?;
+			contribution.claimed = true;
+
+			Self::deposit_event(Event::ContributionRefunded {
+				id,
+				who: who.clone(),
+				amount: contribution.amount,
+			});
+			Ok(())
+		})
+	}
+}
+
+impl<T: Config> Proposal<T::AccountId, T::BlockNumber> for Pallet<T> {
+	fn all_proposals() -> Vec<CampaignInfoOf<T>> {
+		Proposals::<T>::iter_values().collect()
+	}
+
+	fn proposal_info(id: CampaignId) -> Option<CampaignInfoOf<T>> {
+		Self::proposals(id)
+	}
+
+	fn new_proposal(
+		origin: T::AccountId,
+		project_name: Vec<u8>,
+		project_logo: Vec<u8>,
+		project_description: Vec<u8>,
+		project_website: Vec<u8>,
+		beneficiary: T::AccountId,
+		raise_currency: CurrencyId,
+		sale_token: CurrencyId,
+		token_price: Price,
+		crowd_allocation: Balance,
+		goal: Balance,
+		period: T::BlockNumber,
+	) -> DispatchResult {
+		ensure!(
+			raise_currency != sale_token
+				&& !token_price.is_zero()
+				&& !crowd_allocation.is_zero()
+				&& !goal.is_zero()
+				&& !period.is_zero(),
+			Error::<T>::InvalidProposal,
+		);
+		// the goal is raised by selling the whole `crowd_allocation` at `token_price`
+		ensure!(
+			Self::cost_of(crowd_allocation, token_price).ok() == Some(goal),
+			Error::<T>::InvalidProposal,
+		);
+
+		let id = Self::get_next_campaign_id()?;
+		let pool = Self::campaign_pool(id);
+
+		T::MultiCurrency::reserve(T::GetNativeCurrencyId::get(), &origin, T::ProposalDeposit::get())?;
+		T::MultiCurrency::transfer(sale_token, &origin, &pool, crowd_allocation)?;
+
+		Proposals::<T>::insert(
+			id,
+			CampaignInfo {
+				id,
+				origin: origin.clone(),
+				project_name,
+				project_logo,
+				project_description,
+				project_website,
+				beneficiary,
+				pool,
+				raise_currency,
+				sale_token,
+				token_price,
+				crowd_allocation,
+				sold: Zero::zero(),
+				goal,
+				raised: Zero::zero(),
+				contributors_count: 0,
+				period,
+				campaign_start: Zero::zero(),
+				campaign_end: Zero::zero(),
+				campaign_retirement_period: Zero::zero(),
+				proposal_retirement_period: Zero::zero(),
+				is_approved: false,
+				is_rejected: false,
+				is_waiting: false,
+				is_active: false,
+				is_successful: false,
+				is_failed: false,
+				is_ended: false,
+				is_claimed: false,
+			},
+		);
+
+		Self::deposit_event(Event::ProposalCreated { id, origin });
+		Ok(())
+	}
+
+	fn on_approve_proposal(id: CampaignId) -> DispatchResult {
+		let mut proposal = Self::proposals(id).ok_or(Error::<T>::ProposalNotFound)?;
+		ensure!(
+			!proposal.is_approved && !proposal.is_rejected,
+			Error::<T>::ProposalAlreadyReviewed
+		);
+
+		T::MultiCurrency::unreserve(
+			T::GetNativeCurrencyId::get(),
+			&proposal.origin,
+			T::ProposalDeposit::get(),
+		);
+
+		let now = <frame_system::Pallet<T>>::block_number();
+		proposal.campaign_start = now.saturating_add(T::CampaignStartDelay::get());
+		proposal.campaign_end = proposal.campaign_start.saturating_add(proposal.period);
+		proposal.is_approved = true;
+		proposal.is_waiting = true;
+
+		Proposals::<T>::remove(id);
+		Campaigns::<T>::insert(id, &proposal);
+		Self::schedule(proposal.campaign_start, id);
+
+		Self::deposit_event(Event::ProposalApproved {
+			id,
+			campaign_start: proposal.campaign_start,
+			campaign_end: proposal.campaign_end,
+		});
+		Ok(())
+	}
+
+	fn on_reject_proposal(id: CampaignId) -> DispatchResult {
+		Proposals::<T>::try_mutate(id, |maybe_proposal| -> DispatchResult {
+			let proposal = maybe_proposal.as_mut().ok_or(Error::<T>::ProposalNotFound)?;
+			ensure!(
+				!proposal.is_approved && !proposal.is_rejected,
+				Error::<T>::ProposalAlreadyReviewed
+			);
+
+			T::MultiCurrency::slash_reserved(
+				T::GetNativeCurrencyId::get(),
+				&proposal.origin,
+				T::ProposalDeposit::get(),
+			);
+			T::MultiCurrency::transfer(
+				proposal.sale_token,
+				&proposal.pool,
+				&proposal.origin,
+				proposal.crowd_allocation,
+			)?;
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			proposal.proposal_retirement_period = now.saturating_add(T::ProposalRetirementPeriod::get());
+			proposal.is_rejected = true;
+			Self::schedule(proposal.proposal_retirement_period, id);
+
+			Self::deposit_event(Event::ProposalRejected { id });
+			Ok(())
+		})
+	}
+
+	fn remove_proposal(id: CampaignId) -> DispatchResult {
+		ensure!(Proposals::<T>::contains_key(id), Error::<T>::ProposalNotFound);
+		Proposals::<T>::remove(id);
+		Self::deposit_event(Event::ProposalRemoved { id });
+		Ok(())
+	}
+}
+
+impl<T: Config> CampaignManager<T::AccountId, T::BlockNumber> for Pallet<T> {
+	fn campaign_info(id: CampaignId) -> Option<CampaignInfoOf<T>> {
+		Self::campaigns(id)
+	}
+
+	fn all_campaigns() -> Vec<CampaignInfoOf<T>> {
+		Campaigns::<T>::iter_values().collect()
+	}
+
+	/// Buy the `sale_token` that `amount` pays for, capped to the rest of the `crowd_allocation`,
+	/// taking only the cost of that allocation from `who`.
+	fn on_contribution(who: T::AccountId, id: CampaignId, amount: Balance) -> DispatchResult {
+		Campaigns::<T>::try_mutate(id, |maybe_campaign| -> DispatchResult {
+			let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
+			ensure!(campaign.is_active, Error::<T>::CampaignNotActive);
+
+			let remaining = campaign.crowd_allocation.saturating_sub(campaign.sold);
+			ensure!(!remaining.is_zero(), Error::<T>::GoalReached);
+			// the contribution buying the rest of the allocation may be below `MinContribution`
+			let min_contribution = T::MinContribution::get().min(Self::cost_of(remaining, campaign.token_price)?);
+			ensure!(amount >= min_contribution, Error::<T>::ContributionTooSmall);
+
+			let allocation = Self::allocation_of(amount, campaign.token_price)?.min(remaining);
+			ensure!(!allocation.is_zero(), Error::<T>::ContributionTooSmall);
+			let cost = Self::cost_of(allocation, campaign.token_price)?;
+			let raised = campaign.raised.checked_add(cost).ok_or(ArithmeticError::Overflow)?;
+
+			T::MultiCurrency::transfer(campaign.raise_currency, &who, &campaign.pool, cost)?;
+
+			Contributions::<T>::try_mutate(id, &who, |maybe_contribution| -> DispatchResult {
+				let contribution = maybe_contribution.get_or_insert_with(|| {
+					campaign.contributors_count = campaign.contributors_count.saturating_add(1);
+					Default::default()
+				});
+				contribution.amount = contribution
+					.amount
+					.checked_add(cost)
+					.ok_or(ArithmeticError::Overflow)?;
+				contribution.allocation = contribution
+					.allocation
+					.checked_add(allocation)
+					.ok_or(ArithmeticError::Overflow)?;
+				Ok(())
+			})?;
+			campaign.raised = raised;
+			campaign.sold = campaign.sold.saturating_add(allocation);
+
+			Self::deposit_event(Event::Contributed {
+				id,
+				who: who.clone(),
+				amount: cost,
+				allocation,
+			});
+			Ok(())
+		})
+	}
+
+	fn on_claim_allocation(who: T::AccountId, id: CampaignId) -> DispatchResult {
+		Self::ensure_successfully_ended_campaign(id)?;
+		let campaign = Self::campaigns(id).ok_or(Error::<T>::CampaignNotFound)?;
+
+		Contributions::<T>::try_mutate(id, &who, |maybe_contribution| -> DispatchResult {
+			let contribution = maybe_contribution.as_mut().ok_or(Error::<T>::NoContribution)?;
+			ensure!(!contribution.claimed, Error::<T>::AlreadyClaimed);
+
+			T::MultiCurrency::transfer(campaign.sale_token, &campaign.pool, &who, contribution.allocation)?;
+			contribution.claimed = true;
+
+			Self::deposit_event(Event::AllocationClaimed {
+				id,
+				who: who.clone(),
+				allocation: contribution.allocation,
+			});
+			Ok(())
+		})
+	}
+
+	fn on_claim_campaign(who: T::AccountId, id: CampaignId) -> DispatchResult {
+		Self::ensure_successfully_ended_campaign(id)?;
+
+		Campaigns::<T>::try_mutate(id, |maybe_campaign| -> DispatchResult {
+			let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
+			ensure!(who == campaign.beneficiary, Error::<T>::NotBeneficiary);
+			ensure!(!campaign.is_claimed, Error::<T>::AlreadyClaimed);
+
+			T::MultiCurrency::transfer(campaign.raise_currency, &campaign.pool, &who, campaign.raised)?;
+			campaign.is_claimed = true;
+
+			Self::deposit_event(Event::CampaignClaimed {
+				id,
+				beneficiary: who.clone(),
+				raised: campaign.raised,
+			});
+			Ok(())
+		})
+	}
+
+	fn on_claim_failed_campaign(who: T::AccountId, id: CampaignId) -> DispatchResult {
+		Campaigns::<T>::try_mutate(id, |maybe_campaign| -> DispatchResult {
+			let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
+			ensure!(campaign.is_failed, Error::<T>::CampaignNotFailed);
+			ensure!(who == campaign.origin, Error::<T>::NotCampaignCreator);
+			ensure!(!campaign.is_claimed, Error::<T>::AlreadyClaimed);
+
+			T::MultiCurrency::transfer(campaign.sale_token, &campaign.pool, &who, campaign.crowd_allocation)?;
+			campaign.is_claimed = true;
+
+			Self::deposit_event(Event::FailedCampaignClaimed {
+				id,
+				origin: who.clone(),
+				amount: campaign.crowd_allocation,
+			});
+			Ok(())
+		})
+	}
+
+	fn activate_campaign(id: CampaignId) -> DispatchResult {
+		Campaigns::<T>::try_mutate(id, |maybe_campaign| -> DispatchResult {
+			let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
+			campaign.is_waiting = false;
+			campaign.is_active = true;
+			Self::schedule(campaign.campaign_end, id);
+
+			Self::deposit_event(Event::CampaignActivated { id });
+			Ok(())
+		})
+	}
+
+	fn ensure_successfully_ended_campaign(id: CampaignId) -> DispatchResult {
+		let campaign = Self::campaigns(id).ok_or(Error::<T>::CampaignNotFound)?;
+		ensure!(
+			campaign.is_ended && campaign.is_successful,
+			Error::<T>::CampaignNotSuccessful
+		);
+		Ok(())
+	}
+
+	fn on_successful_campaign(now: T::BlockNumber, id: CampaignId) -> DispatchResult {
+		Campaigns::<T>::try_mutate(id, |maybe_campaign| -> DispatchResult {
+			let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
+			campaign.is_active = false;
+			campaign.is_ended = true;
+			campaign.is_successful = true;
+			campaign.campaign_retirement_period = now.saturating_add(T::CampaignRetirementPeriod::get());
+			Self::schedule(campaign.campaign_retirement_period, id);
+
+			TotalAmountsRaised::<T>::mutate(campaign.raise_currency, |total| {
+				*total = total.saturating_add(campaign.raised)
+			});
+
+			Self::deposit_event(Event::CampaignSuccessful {
+				id,
+				raised: campaign.raised,
+			});
+			Ok(())
+		})
+	}
+
+	fn on_failed_campaign(now: T::BlockNumber, id: CampaignId) -> DispatchResult {
+		Campaigns::<T>::try_mutate(id, |maybe_campaign| -> DispatchResult {
+			let campaign = maybe_campaign.as_mut().ok_or(Error::<T>::CampaignNotFound)?;
+			campaign.is_active = false;
+			campaign.is_ended = true;
+			campaign.is_failed = true;
+			campaign.campaign_retirement_period = now.saturating_add(T::CampaignRetirementPeriod::get());
+			Self::schedule(campaign.campaign_retirement_period, id);
+
+			Self::deposit_event(Event::CampaignFailed {
+				id,
+				raised: campaign.raised,
+			});
+			Ok(())
+		})
+	}
+
+	/// Retire the campaign, paying the raise of a successful campaign to the beneficiary or the
+	/// tokens of a failed one to the proposer if they were not claimed. The contributions are
+	/// settled over the following blocks.
+	fn on_retire(id: CampaignId) -> DispatchResult {
+		let mut campaign = Self::campaigns(id).ok_or(Error::<T>::CampaignNotFound)?;
+		if !campaign.is_claimed {
+			if campaign.is_successful {
+				T::MultiCurrency::transfer(
+					campaign.raise_currency,
+					&campaign.pool,
+					&campaign.beneficiary,
+					campaign.raised,
+				)?;
+			} else {
+				T::MultiCurrency::transfer(
+					campaign.sale_token,
+					&campaign.pool,
+					&campaign.origin,
+					campaign.crowd_allocation,
+				)?;
+			}
+			campaign.is_claimed = true;
+		}
+
+		Campaigns::<T>::remove(id);
+		RetiredCampaigns::<T>::insert(id, campaign);
+
+		Self::deposit_event(Event::CampaignRetired { id });
+		Ok(())
+	}
+
+	fn get_contributors_count(id: CampaignId) -> u32 {
+		Self::campaigns(id).map_or(0, |campaign| campaign.contributors_count)
+	}
+
+	fn get_total_amounts_raised() -> Vec<(CurrencyId, Balance)> {
+		TotalAmountsRaised::<T>::iter().collect()
+	}
+}
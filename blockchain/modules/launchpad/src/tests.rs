@@ -0,0 +1,381 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the launchpad module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+
+fn propose() {
+	assert_ok!(Launchpad::make_proposal(
+		Origin::signed(ALICE),
+		b"Help".to_vec(),
+		b"logo".to_vec(),
+		b"HighEnd LaunchPad".to_vec(),
+		b"https://setheum.xyz".to_vec(),
+		ALICE,
+		SETUSD,
+		HELP,
+		Price::saturating_from_integer(2),
+		500,
+		1000,
+		20,
+	));
+}
+
+#[test]
+fn make_proposal_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			Launchpad::make_proposal(
+				Origin::signed(ALICE),
+				vec![],
+				vec![],
+				vec![],
+				vec![],
+				ALICE,
+				HELP,
+				HELP,
+				Price::saturating_from_integer(2),
+				500,
+				1000,
+				20,
+			),
+			Error::<Runtime>::InvalidProposal
+		);
+		// the crowd allocation sold at the token price must raise the goal
+		assert_noop!(
+			Launchpad::make_proposal(
+				Origin::signed(ALICE),
+				vec![],
+				vec![],
+				vec![],
+				vec![],
+				ALICE,
+				SETUSD,
+				HELP,
+				Price::saturating_from_integer(3),
+				500,
+				1000,
+				20,
+			),
+			Error::<Runtime>::InvalidProposal
+		);
+
+		propose();
+		System::assert_last_event(Event::Launchpad(crate::Event::ProposalCreated { id: 0, origin: ALICE }));
+		assert_eq!(Launchpad::next_campaign_id(), 1);
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 100);
+		assert_eq!(Tokens::free_balance(HELP, &ALICE), 500);
+		assert_eq!(Tokens::free_balance(HELP, &Launchpad::campaign_pool(0)), 500);
+		assert_eq!(Launchpad::all_proposals().len(), 1);
+	});
+}
+
+#[test]
+fn reject_proposal_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		propose();
+		assert_noop!(
+			Launchpad::reject_proposal(Origin::signed(ALICE), 0),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(Launchpad::reject_proposal(Origin::signed(COUNCIL), 0));
+		System::assert_last_event(Event::Launchpad(crate::Event::ProposalRejected { id: 0 }));
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 0);
+		assert_eq!(Tokens::free_balance(SEE, &ALICE), 900);
+		assert_eq!(Tokens::free_balance(HELP, &ALICE), 1000);
+		assert_noop!(
+			Launchpad::approve_proposal(Origin::signed(COUNCIL), 0),
+			Error::<Runtime>::ProposalAlreadyReviewed
+		);
+
+		run_to_block(20);
+		assert!(Launchpad::proposals(0).is_some());
+		run_to_block(21);
+		assert!(Launchpad::proposals(0).is_none());
+	});
+}
+
+#[test]
+fn successful_campaign_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		propose();
+		assert_ok!(Launchpad::approve_proposal(Origin::signed(COUNCIL), 0));
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 0);
+		assert_eq!(Tokens::free_balance(SEE, &ALICE), 1000);
+		assert!(Launchpad::proposals(0).is_none());
+		let campaign = Launchpad::campaigns(0).unwrap();
+		assert_eq!((campaign.campaign_start, campaign.campaign_end), (11, 31));
+
+		assert_noop!(
+			Launchpad::contribute(Origin::signed(BOB), 0, 600),
+			Error::<Runtime>::CampaignNotActive
+		);
+		run_to_block(11);
+		assert!(Launchpad::campaigns(0).unwrap().is_active);
+
+		assert_noop!(
+			Launchpad::contribute(Origin::signed(BOB), 0, 5),
+			Error::<Runtime>::ContributionTooSmall
+		);
+		assert_ok!(Launchpad::contribute(Origin::signed(BOB), 0, 600));
+		System::assert_last_event(Event::Launchpad(crate::Event::Contributed {
+			id: 0,
+			who: BOB,
+			amount: 600,
+			allocation: 300,
+		}));
+		// the contribution is capped to the rest of the crowd allocation
+		assert_ok!(Launchpad::contribute(Origin::signed(CHARLIE), 0, 500));
+		System::assert_last_event(Event::Launchpad(crate::Event::Contributed {
+			id: 0,
+			who: CHARLIE,
+			amount: 400,
+			allocation: 200,
+		}));
+		assert_eq!(Tokens::free_balance(SETUSD, &CHARLIE), 600);
+		assert_eq!(Launchpad::get_contributors_count(0), 2);
+		assert_eq!(
+			Launchpad::contributions(0, CHARLIE),
+			Some(ContributionInfo {
+				amount: 400,
+				allocation: 200,
+				claimed: false
+			})
+		);
+		assert_noop!(
+			Launchpad::contribute(Origin::signed(BOB), 0, 10),
+			Error::<Runtime>::GoalReached
+		);
+
+		assert_noop!(
+			Launchpad::claim_contribution_allocation(Origin::signed(BOB), 0),
+			Error::<Runtime>::CampaignNotSuccessful
+		);
+		run_to_block(31);
+		System::assert_last_event(Event::Launchpad(crate::Event::CampaignSuccessful {
+			id: 0,
+			raised: 1000,
+		}));
+		assert_eq!(Launchpad::get_total_amounts_raised(), vec![(SETUSD, 1000)]);
+
+		assert_noop!(
+			Launchpad::claim_campaign_fundraise(Origin::signed(BOB), 0),
+			Error::<Runtime>::NotBeneficiary
+		);
+		assert_ok!(Launchpad::claim_campaign_fundraise(Origin::signed(ALICE), 0));
+		assert_eq!(Tokens::free_balance(SETUSD, &ALICE), 1000);
+
+		assert_ok!(Launchpad::claim_contribution_allocation(Origin::signed(BOB), 0));
+		assert_eq!(Tokens::free_balance(HELP, &BOB), 300);
+		assert_noop!(
+			Launchpad::claim_contribution_allocation(Origin::signed(BOB), 0),
+			Error::<Runtime>::AlreadyClaimed
+		);
+		assert_noop!(
+			Launchpad::claim_refund(Origin::signed(CHARLIE), 0),
+			Error::<Runtime>::CampaignNotFailed
+		);
+
+		run_to_block(130);
+		assert!(Launchpad::campaigns(0).is_some());
+		run_to_block(131);
+		assert!(System::events()
+			.iter()
+			.any(|record| record.event == Event::Launchpad(crate::Event::CampaignRetired { id: 0 })));
+		assert!(Launchpad::campaigns(0).is_none());
+		assert!(Launchpad::retired_campaigns(0).is_some());
+
+		// one contribution is settled per block, the unclaimed allocation of CHARLIE is paid out
+		run_to_block(133);
+		System::assert_last_event(Event::Launchpad(crate::Event::CampaignSettled { id: 0 }));
+		assert!(Launchpad::retired_campaigns(0).is_none());
+		assert!(Launchpad::contributions(0, BOB).is_none());
+		assert!(Launchpad::contributions(0, CHARLIE).is_none());
+		assert_eq!(Tokens::free_balance(HELP, &BOB), 300);
+		assert_eq!(Tokens::free_balance(HELP, &CHARLIE), 200);
+		assert_eq!(Tokens::free_balance(HELP, &Launchpad::campaign_pool(0)), 0);
+	});
+}
+
+#[test]
+fn contribution_pays_only_for_the_allocation_bought() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(Launchpad::make_proposal(
+			Origin::signed(ALICE),
+			vec![],
+			vec![],
+			vec![],
+			vec![],
+			ALICE,
+			SETUSD,
+			HELP,
+			Price::saturating_from_rational(3, 2),
+			500,
+			750,
+			20,
+		));
+		assert_ok!(Launchpad::approve_proposal(Origin::signed(COUNCIL), 0));
+		run_to_block(11);
+
+		// 100 buys 66 tokens at 1.5, the 1 left over stays with the contributor
+		assert_ok!(Launchpad::contribute(Origin::signed(BOB), 0, 100));
+		System::assert_last_event(Event::Launchpad(crate::Event::Contributed {
+			id: 0,
+			who: BOB,
+			amount: 99,
+			allocation: 66,
+		}));
+		assert_eq!(Tokens::free_balance(SETUSD, &BOB), 901);
+		assert_eq!(Tokens::free_balance(SETUSD, &Launchpad::campaign_pool(0)), 99);
+		let campaign = Launchpad::campaigns(0).unwrap();
+		assert_eq!((campaign.raised, campaign.sold), (99, 66));
+	});
+}
+
+#[test]
+fn last_contribution_can_be_below_min_contribution() {
+	ExtBuilder::default().build().execute_with(|| {
+		propose();
+		assert_ok!(Launchpad::approve_proposal(Origin::signed(COUNCIL), 0));
+		run_to_block(11);
+
+		assert_ok!(Launchpad::contribute(Origin::signed(BOB), 0, 996));
+		// the 2 tokens left cost 4, below `MinContribution`
+		assert_noop!(
+			Launchpad::contribute(Origin::signed(CHARLIE), 0, 3),
+			Error::<Runtime>::ContributionTooSmall
+		);
+		assert_ok!(Launchpad::contribute(Origin::signed(CHARLIE), 0, 4));
+		assert_eq!(Launchpad::campaigns(0).unwrap().sold, 500);
+
+		run_to_block(31);
+		System::assert_last_event(Event::Launchpad(crate::Event::CampaignSuccessful {
+			id: 0,
+			raised: 1000,
+		}));
+	});
+}
+
+#[test]
+fn failed_campaign_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		propose();
+		assert_ok!(Launchpad::approve_proposal(Origin::signed(COUNCIL), 0));
+		run_to_block(11);
+		assert_ok!(Launchpad::contribute(Origin::signed(BOB), 0, 600));
+
+		run_to_block(31);
+		System::assert_last_event(Event::Launchpad(crate::Event::CampaignFailed { id: 0, raised: 600 }));
+		assert!(Launchpad::get_total_amounts_raised().is_empty());
+
+		assert_noop!(
+			Launchpad::claim_campaign_fundraise(Origin::signed(ALICE), 0),
+			Error::<Runtime>::CampaignNotSuccessful
+		);
+		assert_noop!(
+			Launchpad::claim_refund(Origin::signed(CHARLIE), 0),
+			Error::<Runtime>::NoContribution
+		);
+		assert_ok!(Launchpad::claim_refund(Origin::signed(BOB), 0));
+		assert_eq!(Tokens::free_balance(SETUSD, &BOB), 1000);
+
+		assert_noop!(
+			Launchpad::claim_failed_campaign(Origin::signed(BOB), 0),
+			Error::<Runtime>::NotCampaignCreator
+		);
+		assert_ok!(Launchpad::claim_failed_campaign(Origin::signed(ALICE), 0));
+		assert_eq!(Tokens::free_balance(HELP, &ALICE), 1000);
+		assert_noop!(
+			Launchpad::claim_failed_campaign(Origin::signed(ALICE), 0),
+			Error::<Runtime>::AlreadyClaimed
+		);
+	});
+}
+
+#[test]
+fn retired_failed_campaign_settles_unclaimed_funds() {
+	ExtBuilder::default().build().execute_with(|| {
+		propose();
+		assert_ok!(Launchpad::approve_proposal(Origin::signed(COUNCIL), 0));
+		run_to_block(11);
+		assert_ok!(Launchpad::contribute(Origin::signed(BOB), 0, 200));
+		assert_ok!(Launchpad::contribute(Origin::signed(CHARLIE), 0, 400));
+		run_to_block(31);
+		assert_ok!(Launchpad::claim_refund(Origin::signed(BOB), 0));
+
+		// the unsold tokens go back to the proposer on retirement
+		run_to_block(131);
+		assert_eq!(Tokens::free_balance(HELP, &ALICE), 1000);
+		assert!(Launchpad::retired_campaigns(0).is_some());
+
+		run_to_block(133);
+		System::assert_last_event(Event::Launchpad(crate::Event::CampaignSettled { id: 0 }));
+		assert_eq!(Tokens::free_balance(SETUSD, &BOB), 1000);
+		assert_eq!(Tokens::free_balance(SETUSD, &CHARLIE), 1000);
+		assert_eq!(Tokens::free_balance(SETUSD, &Launchpad::campaign_pool(0)), 0);
+		assert!(Launchpad::retired_campaigns(0).is_none());
+		assert!(Launchpad::contributions(0, CHARLIE).is_none());
+	});
+}
+
+#[test]
+fn failed_lifecycle_step_is_retried() {
+	ExtBuilder::default().build().execute_with(|| {
+		propose();
+		assert_ok!(Launchpad::approve_proposal(Origin::signed(COUNCIL), 0));
+		run_to_block(31);
+		assert!(Launchpad::campaigns(0).unwrap().is_failed);
+
+		// the pool does not hold the tokens to pay back on retirement
+		Campaigns::<Runtime>::mutate(0, |campaign| campaign.as_mut().unwrap().crowd_allocation = 501);
+		run_to_block(131);
+		System::assert_last_event(Event::Launchpad(crate::Event::CampaignStepFailed {
+			id: 0,
+			error: orml_tokens::Error::<Runtime>::BalanceTooLow.into(),
+		}));
+		assert!(Launchpad::campaigns(0).is_some());
+		assert_eq!(Launchpad::campaign_schedule(132, 0), Some(()));
+
+		Campaigns::<Runtime>::mutate(0, |campaign| campaign.as_mut().unwrap().crowd_allocation = 500);
+		run_to_block(132);
+		assert!(Launchpad::campaigns(0).is_none());
+		assert!(Launchpad::retired_campaigns(0).is_some());
+		assert_eq!(Tokens::free_balance(HELP, &ALICE), 1000);
+	});
+}
+
+#[test]
+fn on_initialize_only_touches_scheduled_campaigns() {
+	ExtBuilder::default().build().execute_with(|| {
+		propose();
+		assert_ok!(Launchpad::approve_proposal(Origin::signed(COUNCIL), 0));
+		assert_eq!(Launchpad::campaign_schedule(11, 0), Some(()));
+
+		run_to_block(11);
+		assert_eq!(Launchpad::campaign_schedule(11, 0), None);
+		assert_eq!(Launchpad::campaign_schedule(31, 0), Some(()));
+		assert_eq!(Launchpad::on_initialize(12), <() as WeightInfo>::on_initialize(0));
+	});
+}
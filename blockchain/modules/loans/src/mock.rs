@@ -0,0 +1,255 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Mocks for the loans module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{construct_runtime, parameter_types};
+use orml_traits::{parameter_type_with_key, MultiCurrency};
+use primitives::TokenSymbol;
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup, DispatchError};
+use sp_std::cell::RefCell;
+use support::Ratio;
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CDP_TREASURY: AccountId = 3;
+pub const SEE: CurrencyId = CurrencyId::Token(TokenSymbol::SEE);
+pub const SETUSD: CurrencyId = CurrencyId::Token(TokenSymbol::SETUSD);
+pub const SERP: CurrencyId = CurrencyId::Token(TokenSymbol::SERP);
+
+mod loans {
+	pub use super::super::*;
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_type_with_key! {
+	pub ExistentialDeposits: |_currency_id: CurrencyId| -> Balance {
+		Default::default()
+	};
+}
+
+impl orml_tokens::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type WeightInfo = ();
+	type ExistentialDeposits = ExistentialDeposits;
+	type OnDust = ();
+	type MaxLocks = ();
+	type DustRemovalWhitelist = ();
+}
+
+thread_local! {
+	static DEBIT_POOL: RefCell<Balance> = RefCell::new(0);
+}
+
+/// A CDP treasury keeping collaterals and stable currency on the
+/// `CDP_TREASURY` account.
+pub struct MockCDPTreasury;
+impl MockCDPTreasury {
+	pub fn debit_pool() -> Balance {
+		DEBIT_POOL.with(|v| *v.borrow())
+	}
+}
+
+impl CDPTreasury<AccountId> for MockCDPTreasury {
+	type Balance = Balance;
+	type CurrencyId = CurrencyId;
+
+	fn get_surplus_pool() -> Balance {
+		Tokens::free_balance(SETUSD, &CDP_TREASURY)
+	}
+
+	fn get_debit_pool() -> Balance {
+		Self::debit_pool()
+	}
+
+	fn get_total_collaterals(id: CurrencyId) -> Balance {
+		Tokens::free_balance(id, &CDP_TREASURY)
+	}
+
+	fn get_debit_proportion(_amount: Balance) -> Ratio {
+		unimplemented!()
+	}
+
+	fn on_system_debit(amount: Balance) -> DispatchResult {
+		DEBIT_POOL.with(|v| *v.borrow_mut() += amount);
+		Ok(())
+	}
+
+	fn on_system_surplus(amount: Balance) -> DispatchResult {
+		Tokens::deposit(SETUSD, &CDP_TREASURY, amount)
+	}
+
+	fn issue_debit(who: &AccountId, debit: Balance, backed: bool) -> DispatchResult {
+		if !backed {
+			Self::on_system_debit(debit)?;
+		}
+		Tokens::deposit(SETUSD, who, debit)
+	}
+
+	fn burn_debit(who: &AccountId, debit: Balance) -> DispatchResult {
+		Tokens::withdraw(SETUSD, who, debit)
+	}
+
+	fn deposit_surplus(from: &AccountId, surplus: Balance) -> DispatchResult {
+		Tokens::transfer(SETUSD, from, &CDP_TREASURY, surplus)
+	}
+
+	fn deposit_collateral(from: &AccountId, currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		Tokens::transfer(currency_id, from, &CDP_TREASURY, amount)
+	}
+
+	fn withdraw_collateral(to: &AccountId, currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		Tokens::transfer(currency_id, &CDP_TREASURY, to, amount)
+	}
+}
+
+/// A risk manager pricing one unit of debit at half a stable currency unit,
+/// requiring the collateral to cover twice the debit value and capping the
+/// total debit at 1000.
+pub struct MockRiskManager;
+impl RiskManager<AccountId, CurrencyId, Balance, Balance> for MockRiskManager {
+	fn get_debit_value(_currency_id: CurrencyId, debit_balance: Balance) -> Balance {
+		debit_balance / 2
+	}
+
+	fn check_position_valid(
+		currency_id: CurrencyId,
+		collateral_balance: Balance,
+		debit_balance: Balance,
+		check_required_ratio: bool,
+	) -> DispatchResult {
+		let debit_value = Self::get_debit_value(currency_id, debit_balance);
+		if check_required_ratio && collateral_balance < debit_value * 2 {
+			return Err(DispatchError::Other("mock below required collateral ratio error"));
+		}
+		if collateral_balance < debit_value {
+			return Err(DispatchError::Other("mock below liquidation ratio error"));
+		}
+		Ok(())
+	}
+
+	fn check_debit_cap(_currency_id: CurrencyId, total_debit_balance: Balance) -> DispatchResult {
+		if total_debit_balance > 1000 {
+			return Err(DispatchError::Other("mock exceed debit value cap error"));
+		}
+		Ok(())
+	}
+}
+
+parameter_types! {
+	pub const LoansPalletId: PalletId = PalletId(*b"set/loan");
+}
+
+impl Config for Runtime {
+	type Event = Event;
+	type Currency = Tokens;
+	type RiskManager = MockRiskManager;
+	type CDPTreasury = MockCDPTreasury;
+	type PalletId = LoansPalletId;
+}
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Storage, Config, Event<T>},
+		LoansModule: loans::{Pallet, Storage, Call, Event<T>},
+		Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
+	}
+);
+
+pub struct ExtBuilder {
+	balances: Vec<(AccountId, CurrencyId, Balance)>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			balances: vec![
+				(ALICE, SEE, 1000),
+				(BOB, SEE, 1000),
+				(ALICE, SERP, 1000),
+				(BOB, SERP, 1000),
+			],
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap();
+
+		orml_tokens::GenesisConfig::<Runtime> {
+			balances: self.balances,
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
@@ -0,0 +1,114 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the loans module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use orml_traits::MultiCurrency;
+
+#[test]
+fn adjust_position_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(Tokens::free_balance(SEE, &ALICE), 1000);
+
+		// balance too low
+		assert!(LoansModule::adjust_position(&ALICE, SEE, 2000, 0).is_err());
+
+		// mock can't pass position valid check
+		assert_noop!(
+			LoansModule::adjust_position(&ALICE, SEE, 100, 800),
+			sp_runtime::DispatchError::Other("mock below required collateral ratio error")
+		);
+
+		// mock exceed debit value cap
+		assert_noop!(
+			LoansModule::adjust_position(&ALICE, SEE, 1000, 1200),
+			sp_runtime::DispatchError::Other("mock exceed debit value cap error")
+		);
+
+		assert_ok!(LoansModule::adjust_position(&ALICE, SEE, 500, 300));
+		assert_eq!(Tokens::free_balance(SEE, &ALICE), 500);
+		assert_eq!(Tokens::free_balance(SEE, &LoansModule::account_id()), 500);
+		assert_eq!(Tokens::free_balance(SETUSD, &ALICE), 150);
+		assert_eq!(LoansModule::positions(SEE, &ALICE).debit, 300);
+		assert_eq!(LoansModule::positions(SEE, &ALICE).collateral, 500);
+		assert_eq!(LoansModule::total_positions(SEE).debit, 300);
+		assert_eq!(LoansModule::total_positions(SEE).collateral, 500);
+		System::assert_last_event(Event::LoansModule(crate::Event::PositionUpdated(ALICE, SEE, 500, 300)));
+
+		// repay and withdraw everything
+		assert_ok!(LoansModule::adjust_position(&ALICE, SEE, -500, -300));
+		assert_eq!(Tokens::free_balance(SEE, &ALICE), 1000);
+		assert_eq!(Tokens::free_balance(SETUSD, &ALICE), 0);
+		assert!(!Positions::<Runtime>::contains_key(SEE, &ALICE));
+		assert_eq!(LoansModule::total_positions(SEE), Default::default());
+	});
+}
+
+#[test]
+fn update_loan_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(LoansModule::update_loan(&ALICE, SEE, 3000, 2000));
+		assert_eq!(LoansModule::positions(SEE, &ALICE).debit, 2000);
+		assert_eq!(LoansModule::positions(SEE, &ALICE).collateral, 3000);
+		assert_eq!(LoansModule::total_positions(SEE).debit, 2000);
+		assert_eq!(LoansModule::total_positions(SEE).collateral, 3000);
+
+		assert!(LoansModule::update_loan(&ALICE, SEE, -4000, 0).is_err());
+		assert!(LoansModule::update_loan(&ALICE, SEE, 0, -3000).is_err());
+	});
+}
+
+#[test]
+fn transfer_loan_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(LoansModule::update_loan(&ALICE, SEE, 400, 500));
+		assert_ok!(LoansModule::update_loan(&BOB, SEE, 600, 100));
+		assert_ok!(LoansModule::transfer_loan(&ALICE, &BOB, SEE));
+		assert_eq!(LoansModule::positions(SEE, &ALICE), Default::default());
+		assert_eq!(LoansModule::positions(SEE, &BOB).collateral, 1000);
+		assert_eq!(LoansModule::positions(SEE, &BOB).debit, 600);
+		System::assert_last_event(Event::LoansModule(crate::Event::TransferLoan(ALICE, BOB, SEE)));
+	});
+}
+
+#[test]
+fn confiscate_collateral_and_debit_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(LoansModule::adjust_position(&ALICE, SEE, 500, 300));
+		assert_eq!(Tokens::free_balance(SEE, &CDP_TREASURY), 0);
+
+		// have no sufficient balance
+		assert!(LoansModule::confiscate_collateral_and_debit(&BOB, SEE, 5000, 1000).is_err());
+
+		assert_ok!(LoansModule::confiscate_collateral_and_debit(&ALICE, SEE, 300, 200));
+		assert_eq!(Tokens::free_balance(SEE, &CDP_TREASURY), 300);
+		assert_eq!(MockCDPTreasury::debit_pool(), 100);
+		assert_eq!(LoansModule::positions(SEE, &ALICE).debit, 100);
+		assert_eq!(LoansModule::positions(SEE, &ALICE).collateral, 200);
+		System::assert_last_event(Event::LoansModule(crate::Event::ConfiscateCollateralAndDebit(
+			ALICE, SEE, 300, 200,
+		)));
+	});
+}
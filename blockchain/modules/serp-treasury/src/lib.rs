@@ -0,0 +1,748 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # SERP Treasury Module
+//!
+//! ## Overview
+//!
+//! The SERP Treasury (Setheum Elastic Reserve Protocol Treasury) keeps the
+//! Setheum stable currencies on their pegs by adjusting their supply.
+//!
+//! Every `StableCurrencyInflationPeriod` blocks it:
+//! - delivers the configured inflation of each stable currency, split between
+//!   the CashDrop pool and buyback-and-burn swaps of `DNAR`, `SERP`, `SEE` and `HELP`;
+//! - reads the peg price of each stable currency from `PriceSource` and its
+//!   market price from the `MarketPriceSource` oracle, and
+//!   - serps up (expands supply) when the market price is above the peg, issuing
+//!     new stable currency to the CashDrop pool and to buyback-and-burn swaps of
+//!     `SETR`/`SEE`;
+//!   - serps down (contracts supply) when the market price is below the peg,
+//!     buying back the stable currency from the DEX and burning it. The SETR
+//!     or SEE issued to buy it back is priced from the oracle and capped by
+//!     `MaxSerpDownSupplyPerBlock`.
+//!
+//! Every swap is priced from `PriceSource`, within `MaxSwapSlippageCompareToOracle`
+//! of the oracle price.
+//!
+//! Each stable currency is handled in its own storage transaction, a failure
+//! for one currency is logged and does not stop the others.
+//!
+//! The CashDrop pool is the free balance of the treasury account, it pays
+//! the SetPay cashdrops claimed on stable currency transfers.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+#![allow(clippy::upper_case_acronyms)]
+
+use frame_support::{pallet_prelude::*, transactional, PalletId};
+use frame_system::pallet_prelude::*;
+use orml_traits::{DataProvider, GetByKey, MultiCurrency, MultiCurrencyExtended};
+use orml_utilities::with_transaction_result;
+use primitives::{Balance, CurrencyId, SerpStableCurrencyId};
+use sp_runtime::{
+	traits::{AccountIdConversion, One, Saturating, Zero},
+	FixedPointNumber,
+};
+use sp_std::prelude::*;
+use support::{DEXManager, Price, PriceProvider, Rate, Ratio, SerpTreasury, SerpTreasuryExtended, SwapLimit};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The Currency for managing assets.
+		type Currency: MultiCurrencyExtended<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+		/// The stable currency ids
+		type StableCurrencyIds: Get<Vec<CurrencyId>>;
+
+		#[pallet::constant]
+		/// The number of blocks between two SERP-TES (Token Elasticity of Supply) runs.
+		type StableCurrencyInflationPeriod: Get<Self::BlockNumber>;
+
+		/// The minimum total supply of a stable currency, serpdown never contracts below it.
+		type GetStableCurrencyMinimumSupply: GetByKey<CurrencyId, Balance>;
+
+		#[pallet::constant]
+		/// Native (SEE) currency id
+		type GetNativeCurrencyId: Get<CurrencyId>;
+
+		#[pallet::constant]
+		/// Serp (SERP) currency id
+		type GetSerpCurrencyId: Get<CurrencyId>;
+
+		#[pallet::constant]
+		/// The Dinar currency id, it should be DNAR in Setheum.
+		type GetDinarCurrencyId: Get<CurrencyId>;
+
+		#[pallet::constant]
+		/// HighEnd LaunchPad (HELP) currency id
+		type GetHelpCurrencyId: Get<CurrencyId>;
+
+		#[pallet::constant]
+		/// Setter (SETR) currency id
+		type SetterCurrencyId: Get<CurrencyId>;
+
+		#[pallet::constant]
+		/// The SetDollar (SETUSD) currency id
+		type GetSetUSDId: Get<CurrencyId>;
+
+		#[pallet::constant]
+		/// CDP treasury account, serplus is taken from it.
+		type CDPTreasuryAccountId: Get<Self::AccountId>;
+
+		/// DEX used for the buyback swaps and for the market prices of the stable currencies.
+		type Dex: DEXManager<Self::AccountId, CurrencyId, Balance>;
+
+		#[pallet::constant]
+		/// The cap of the slippage allowed when a serpdown swap is compared to the oracle price.
+		type MaxSwapSlippageCompareToOracle: Get<Ratio>;
+
+		/// The price source of currencies.
+		type PriceSource: PriceProvider<CurrencyId>;
+
+		/// The oracle of the market prices of the stable currencies.
+		type MarketPriceSource: DataProvider<CurrencyId, Price>;
+
+		/// The maximum amount of a currency issued by serpdowns in a block, keyed by the
+		/// currency issued (SETR or SEE).
+		type MaxSerpDownSupplyPerBlock: GetByKey<CurrencyId, Balance>;
+
+		/// The alternative swap path joint list, which can be concated to
+		/// alternative swap path when the treasury swaps through the DEX.
+		type AlternativeSwapPathJointList: Get<Vec<Vec<CurrencyId>>>;
+
+		#[pallet::constant]
+		/// The minimum SETR transfer amount eligible for a cashdrop.
+		type SetterMinimumClaimableTransferAmounts: Get<Balance>;
+
+		#[pallet::constant]
+		/// The maximum SETR transfer amount eligible for a cashdrop.
+		type SetterMaximumClaimableTransferAmounts: Get<Balance>;
+
+		#[pallet::constant]
+		/// The minimum SETUSD transfer amount eligible for a cashdrop.
+		type SetDollarMinimumClaimableTransferAmounts: Get<Balance>;
+
+		#[pallet::constant]
+		/// The maximum SETUSD transfer amount eligible for a cashdrop.
+		type SetDollarMaximumClaimableTransferAmounts: Get<Balance>;
+
+		#[pallet::constant]
+		/// The share of an eligible transfer amount paid as cashdrop.
+		type CashDropRate: Get<Rate>;
+
+		/// The origin which may update inflation parameters and force serpdowns.
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+		#[pallet::constant]
+		/// The SERP Treasury's module id, keeps the CashDrop pool.
+		type PalletId: Get<PalletId>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The currency is not a stable currency.
+		InvalidCurrencyType,
+		/// The swap limit does not match the swap.
+		InvalidSwapLimit,
+		/// No swap path between the currencies is available on the DEX.
+		CannotSwap,
+		/// There is no price for the currency.
+		InvalidFeedPrice,
+		/// The CashDrop pool does not hold enough of the currency.
+		CashDropPoolNotEnough,
+		/// The serpdowns of this block already issued `MaxSerpDownSupplyPerBlock`.
+		SerpDownLimitReached,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The inflation rate of a stable currency has been updated.
+		StableCurrencyInflationRateUpdated {
+			currency_id: CurrencyId,
+			inflation_rate: Balance,
+		},
+		/// The periodic inflation of a stable currency has been delivered.
+		InflationDelivery { currency_id: CurrencyId, amount: Balance },
+		/// The market price of a stable currency has been checked against its peg.
+		SerpTes {
+			currency_id: CurrencyId,
+			market_price: Price,
+			peg_price: Price,
+		},
+		/// The supply of a stable currency has been expanded.
+		SerpUp { currency_id: CurrencyId, amount: Balance },
+		/// The supply of a stable currency has been contracted.
+		SerpDown { currency_id: CurrencyId, amount: Balance },
+		/// The surplus of a stable currency has been delivered from the CDP treasury.
+		Serplus { currency_id: CurrencyId, amount: Balance },
+		/// Currency has been bought back from the DEX and burnt.
+		BuyBackAndBurn {
+			supply_currency_id: CurrencyId,
+			supply_amount: Balance,
+			target_currency_id: CurrencyId,
+			target_amount: Balance,
+		},
+		/// Stable currency has been added to the CashDrop pool.
+		CashDropToPool { currency_id: CurrencyId, amount: Balance },
+		/// A cashdrop has been paid from the CashDrop pool.
+		CashDropClaim {
+			claimant: T::AccountId,
+			currency_id: CurrencyId,
+			amount: Balance,
+		},
+	}
+
+	/// The inflation delivered every `StableCurrencyInflationPeriod` for a stable currency.
+	///
+	/// StableCurrencyInflationRate: map CurrencyId => Balance
+	#[pallet::storage]
+	#[pallet::getter(fn stable_currency_inflation_rate)]
+	pub type StableCurrencyInflationRate<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, Balance, ValueQuery>;
+
+	/// The amount of a currency issued by serpdowns and the block it was issued in.
+	///
+	/// SerpDownSupply: map CurrencyId => (BlockNumber, Balance)
+	#[pallet::storage]
+	#[pallet::getter(fn serpdown_supply)]
+	pub type SerpDownSupply<T: Config> = StorageMap<_, Twox64Concat, CurrencyId, (T::BlockNumber, Balance), ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig {
+		pub stable_currency_inflation_rate: Vec<(CurrencyId, Balance)>,
+		pub stable_currency_cashdrop: Vec<(CurrencyId, Balance)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl Default for GenesisConfig {
+		fn default() -> Self {
+			GenesisConfig {
+				stable_currency_inflation_rate: vec![],
+				stable_currency_cashdrop: vec![],
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			self.stable_currency_inflation_rate
+				.iter()
+				.for_each(|(currency_id, size)| {
+					StableCurrencyInflationRate::<T>::insert(currency_id, size);
+				});
+			self.stable_currency_cashdrop.iter().for_each(|(currency_id, amount)| {
+				T::Currency::deposit(*currency_id, &Pallet::<T>::account_id(), *amount)
+					.expect("CashDrop pool deposit cannot fail while building genesis");
+			});
+		}
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Deliver the stable currency inflation and run SERP-TES every `StableCurrencyInflationPeriod`.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			if (now % T::StableCurrencyInflationPeriod::get()).is_zero() {
+				let _ = Self::issue_stablecurrency_inflation();
+				let _ = Self::serp_tes_now();
+				T::WeightInfo::on_initialize(T::StableCurrencyIds::get().len() as u32)
+			} else {
+				0
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the inflation delivered every `StableCurrencyInflationPeriod` for a stable currency.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: the stable currency.
+		/// - `size`: the inflation amount.
+		#[pallet::weight((T::WeightInfo::set_stable_currency_inflation_rate(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn set_stable_currency_inflation_rate(
+			origin: OriginFor<T>,
+			currency_id: SerpStableCurrencyId,
+			size: Balance,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			let currency_id = match currency_id {
+				SerpStableCurrencyId::SETR => T::SetterCurrencyId::get(),
+				SerpStableCurrencyId::SETUSD => T::GetSetUSDId::get(),
+			};
+			StableCurrencyInflationRate::<T>::insert(currency_id, size);
+			Self::deposit_event(Event::StableCurrencyInflationRateUpdated {
+				currency_id,
+				inflation_rate: size,
+			});
+			Ok(())
+		}
+
+		/// Contract the supply of a stable currency by `amount`.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_id`: the stable currency.
+		/// - `amount`: the supply to buy back and burn.
+		#[pallet::weight((T::WeightInfo::force_serpdown(), DispatchClass::Operational))]
+		#[transactional]
+		pub fn force_serpdown(origin: OriginFor<T>, currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			<Self as SerpTreasury<T::AccountId>>::on_serpdown(currency_id, amount)
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Get account of SERP Treasury module.
+	pub fn account_id() -> T::AccountId {
+		T::PalletId::get().into_account()
+	}
+
+	/// The balance of `currency_id` in the CashDrop pool.
+	pub fn cashdrop_pool(currency_id: CurrencyId) -> Balance {
+		T::Currency::free_balance(currency_id, &Self::account_id())
+	}
+
+	fn ensure_stable_currency(currency_id: CurrencyId) -> DispatchResult {
+		ensure!(
+			T::StableCurrencyIds::get().contains(&currency_id),
+			Error::<T>::InvalidCurrencyType
+		);
+		Ok(())
+	}
+
+	/// The market price of a stable currency, fed by the oracle.
+	pub fn market_price(currency_id: CurrencyId) -> Option<Price> {
+		T::MarketPriceSource::get(&currency_id)
+	}
+
+	/// Check the market price of `currency_id` against its peg and serp up or down.
+	fn serp_tes(currency_id: CurrencyId) -> DispatchResult {
+		let peg_price = T::PriceSource::get_price(currency_id).ok_or(Error::<T>::InvalidFeedPrice)?;
+		let market_price = match Self::market_price(currency_id) {
+			Some(price) if !price.is_zero() => price,
+			_ => return Ok(()),
+		};
+		Self::deposit_event(Event::SerpTes {
+			currency_id,
+			market_price,
+			peg_price,
+		});
+
+		let supply = T::Currency::total_issuance(currency_id);
+		if market_price > peg_price {
+			let expand_by = Self::calculate_supply_change(market_price.into_inner(), peg_price.into_inner(), supply);
+			Self::on_serpup(currency_id, expand_by)
+		} else if market_price < peg_price {
+			let contract_by = Self::calculate_supply_change(peg_price.into_inner(), market_price.into_inner(), supply);
+			Self::on_serpdown(currency_id, contract_by)
+		} else {
+			Ok(())
+		}
+	}
+
+	/// Split the inflation of `currency_id` in five: the CashDrop pool and the
+	/// buyback-and-burn of DNAR, SERP, SEE and HELP.
+	fn issue_inflation(currency_id: CurrencyId) -> DispatchResult {
+		let inflation_amount = Self::stable_currency_inflation_rate(currency_id);
+		if inflation_amount.is_zero() {
+			return Ok(());
+		}
+		let share = inflation_amount / 5;
+
+		Self::add_cashdrop_to_pool(currency_id, share)?;
+		for target_currency_id in [
+			T::GetDinarCurrencyId::get(),
+			T::GetSerpCurrencyId::get(),
+			T::GetNativeCurrencyId::get(),
+			T::GetHelpCurrencyId::get(),
+		]
+		.iter()
+		{
+			Self::buyback_at_oracle_price(currency_id, *target_currency_id, share)?;
+		}
+
+		Self::deposit_event(Event::InflationDelivery {
+			currency_id,
+			amount: inflation_amount,
+		});
+		Ok(())
+	}
+
+	/// The amount of `currency_id` serpdowns can still issue in this block.
+	fn serpdown_supply_remaining(currency_id: CurrencyId) -> Balance {
+		let (block, issued) = Self::serpdown_supply(currency_id);
+		let issued = if block == <frame_system::Pallet<T>>::block_number() {
+			issued
+		} else {
+			Zero::zero()
+		};
+		T::MaxSerpDownSupplyPerBlock::get(&currency_id).saturating_sub(issued)
+	}
+
+	/// Record `amount` of `currency_id` issued by a serpdown in this block.
+	fn note_serpdown_supply(currency_id: CurrencyId, amount: Balance) {
+		let now = <frame_system::Pallet<T>>::block_number();
+		SerpDownSupply::<T>::mutate(currency_id, |(block, issued)| {
+			if *block != now {
+				*block = now;
+				*issued = Zero::zero();
+			}
+			*issued = issued.saturating_add(amount);
+		});
+	}
+}
+
+impl<T: Config> SerpTreasury<T::AccountId> for Pallet<T> {
+	type Balance = Balance;
+	type CurrencyId = CurrencyId;
+
+	/// `supply * (numerator / denominator - 1)`, zero if `numerator <= denominator`.
+	fn calculate_supply_change(numerator: Balance, denominator: Balance, supply: Balance) -> Balance {
+		Ratio::checked_from_rational(numerator, denominator)
+			.and_then(|ratio| ratio.checked_sub(&Ratio::one()))
+			.map_or(Zero::zero(), |fraction| fraction.saturating_mul_int(supply))
+	}
+
+	/// Run SERP-TES for every stable currency, each in its own storage transaction.
+	fn serp_tes_now() -> DispatchResult {
+		for currency_id in T::StableCurrencyIds::get() {
+			if let Err(e) = with_transaction_result(|| Self::serp_tes(currency_id)) {
+				frame_support::log::warn!(
+					target: "serp-treasury",
+					"serp_tes_now: failed to run SERP-TES for {:?}: {:?}",
+					currency_id,
+					e,
+				);
+			}
+		}
+		Ok(())
+	}
+
+	/// Deliver the inflation of every stable currency, each in its own storage transaction.
+	fn issue_stablecurrency_inflation() -> DispatchResult {
+		for currency_id in T::StableCurrencyIds::get() {
+			if let Err(e) = with_transaction_result(|| Self::issue_inflation(currency_id)) {
+				frame_support::log::warn!(
+					target: "serp-treasury",
+					"issue_stablecurrency_inflation: failed to deliver inflation of {:?}: {:?}",
+					currency_id,
+					e,
+				);
+			}
+		}
+		Ok(())
+	}
+
+	/// Issue `amount` of `currency_id` to buy back and burn SETR and SEE (SETUSD)
+	/// or SEE (SETR).
+	fn get_buyback_serpup(amount: Balance, currency_id: CurrencyId) -> DispatchResult {
+		Self::ensure_stable_currency(currency_id)?;
+		let native_currency_id = T::GetNativeCurrencyId::get();
+		let counter_currency_id = Self::serp_counter_currency(currency_id);
+
+		if counter_currency_id == native_currency_id {
+			Self::buyback_at_oracle_price(currency_id, native_currency_id, amount)?;
+		} else {
+			let half = amount / 2;
+			Self::buyback_at_oracle_price(currency_id, counter_currency_id, half)?;
+			Self::buyback_at_oracle_price(currency_id, native_currency_id, amount.saturating_sub(half))?;
+		}
+		Ok(())
+	}
+
+	/// Buy back and burn `to_currency_id` with the exact `supply_amount` of `from_currency_id`,
+	/// receiving at least the oracle price less `MaxSwapSlippageCompareToOracle`.
+	fn buyback_at_oracle_price(
+		from_currency_id: CurrencyId,
+		to_currency_id: CurrencyId,
+		supply_amount: Balance,
+	) -> DispatchResult {
+		let relative_price =
+			T::PriceSource::get_relative_price(from_currency_id, to_currency_id).ok_or(Error::<T>::InvalidFeedPrice)?;
+		let min_target_amount = Ratio::one()
+			.saturating_sub(T::MaxSwapSlippageCompareToOracle::get())
+			.saturating_mul_int(relative_price.saturating_mul_int(supply_amount));
+		Self::buyback_swap_with_exact_supply(
+			from_currency_id,
+			to_currency_id,
+			SwapLimit::ExactSupply(supply_amount, min_target_amount),
+		)?;
+		Ok(())
+	}
+
+	fn add_cashdrop_to_pool(currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		Self::ensure_stable_currency(currency_id)?;
+		T::Currency::deposit(currency_id, &Self::account_id(), amount)?;
+		Self::deposit_event(Event::CashDropToPool { currency_id, amount });
+		Ok(())
+	}
+
+	fn issue_cashdrop_from_pool(
+		claimant_id: &T::AccountId,
+		currency_id: CurrencyId,
+		amount: Balance,
+	) -> DispatchResult {
+		ensure!(
+			Self::cashdrop_pool(currency_id) >= amount,
+			Error::<T>::CashDropPoolNotEnough
+		);
+		T::Currency::transfer(currency_id, &Self::account_id(), claimant_id, amount)?;
+		Self::deposit_event(Event::CashDropClaim {
+			claimant: claimant_id.clone(),
+			currency_id,
+			amount,
+		});
+		Ok(())
+	}
+
+	fn get_cashdrop_serpup(amount: Balance, currency_id: CurrencyId) -> DispatchResult {
+		Self::add_cashdrop_to_pool(currency_id, amount)
+	}
+
+	/// Burn `amount` of the CDP treasury surplus and buy back with it.
+	fn get_buyback_serplus(amount: Balance, currency_id: CurrencyId) -> DispatchResult {
+		T::Currency::withdraw(currency_id, &T::CDPTreasuryAccountId::get(), amount)?;
+		Self::get_buyback_serpup(amount, currency_id)
+	}
+
+	/// Move `amount` of the CDP treasury surplus to the CashDrop pool.
+	fn get_cashdrop_serplus(amount: Balance, currency_id: CurrencyId) -> DispatchResult {
+		Self::ensure_stable_currency(currency_id)?;
+		T::Currency::transfer(
+			currency_id,
+			&T::CDPTreasuryAccountId::get(),
+			&Self::account_id(),
+			amount,
+		)?;
+		Self::deposit_event(Event::CashDropToPool { currency_id, amount });
+		Ok(())
+	}
+
+	/// A fifth of the serplus goes to the CashDrop pool, the rest to buyback-and-burn.
+	fn on_serplus(currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		let cashdrop_amount = amount / 5;
+		Self::get_cashdrop_serplus(cashdrop_amount, currency_id)?;
+		Self::get_buyback_serplus(amount.saturating_sub(cashdrop_amount), currency_id)?;
+		Self::deposit_event(Event::Serplus { currency_id, amount });
+		Ok(())
+	}
+
+	/// A fifth of the expansion goes to the CashDrop pool, the rest to buyback-and-burn.
+	fn on_serpup(currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		if amount.is_zero() {
+			return Ok(());
+		}
+		let cashdrop_amount = amount / 5;
+		Self::get_cashdrop_serpup(cashdrop_amount, currency_id)?;
+		Self::get_buyback_serpup(amount.saturating_sub(cashdrop_amount), currency_id)?;
+		Self::deposit_event(Event::SerpUp { currency_id, amount });
+		Ok(())
+	}
+
+	/// Buy back `amount` of the stable currency with SETR (SETUSD) or SEE (SETR)
+	/// and burn it, never contracting below the minimum supply. The SETR or SEE
+	/// issued for it is priced from the oracle and capped by `MaxSerpDownSupplyPerBlock`.
+	fn on_serpdown(currency_id: CurrencyId, amount: Balance) -> DispatchResult {
+		Self::ensure_stable_currency(currency_id)?;
+		let supply = T::Currency::total_issuance(currency_id);
+		let amount = amount.min(supply.saturating_sub(Self::get_minimum_supply(currency_id)));
+		if amount.is_zero() {
+			return Ok(());
+		}
+
+		let supply_currency_id = Self::serp_counter_currency(currency_id);
+		let relative_price =
+			T::PriceSource::get_relative_price(currency_id, supply_currency_id).ok_or(Error::<T>::InvalidFeedPrice)?;
+		let max_supply_amount = Ratio::one()
+			.saturating_add(T::MaxSwapSlippageCompareToOracle::get())
+			.saturating_mul_int(relative_price.saturating_mul_int(amount));
+
+		// contract less if the supply to issue exceeds what is left of the block's limit
+		let remaining = Self::serpdown_supply_remaining(supply_currency_id);
+		let (max_supply_amount, amount) = if max_supply_amount > remaining {
+			let amount = Ratio::checked_from_rational(remaining, max_supply_amount)
+				.map_or(Zero::zero(), |ratio| ratio.saturating_mul_int(amount));
+			(remaining, amount)
+		} else {
+			(max_supply_amount, amount)
+		};
+		ensure!(!amount.is_zero(), Error::<T>::SerpDownLimitReached);
+
+		let (actual_supply, _) = Self::buyback_swap_with_exact_target(
+			supply_currency_id,
+			currency_id,
+			SwapLimit::ExactTarget(max_supply_amount, amount),
+		)?;
+		Self::note_serpdown_supply(supply_currency_id, actual_supply);
+		Self::deposit_event(Event::SerpDown { currency_id, amount });
+		Ok(())
+	}
+
+	fn get_minimum_supply(currency_id: CurrencyId) -> Balance {
+		T::GetStableCurrencyMinimumSupply::get(&currency_id)
+	}
+
+	fn issue_standard(currency_id: CurrencyId, who: &T::AccountId, standard: Balance) -> DispatchResult {
+		Self::ensure_stable_currency(currency_id)?;
+		T::Currency::deposit(currency_id, who, standard)
+	}
+
+	fn burn_standard(currency_id: CurrencyId, who: &T::AccountId, standard: Balance) -> DispatchResult {
+		Self::ensure_stable_currency(currency_id)?;
+		T::Currency::withdraw(currency_id, who, standard)
+	}
+
+	fn issue_setter(who: &T::AccountId, setter: Balance) -> DispatchResult {
+		T::Currency::deposit(T::SetterCurrencyId::get(), who, setter)
+	}
+
+	fn burn_setter(who: &T::AccountId, setter: Balance) -> DispatchResult {
+		T::Currency::withdraw(T::SetterCurrencyId::get(), who, setter)
+	}
+
+	fn deposit_setter(from: &T::AccountId, amount: Balance) -> DispatchResult {
+		T::Currency::transfer(T::SetterCurrencyId::get(), from, &Self::account_id(), amount)
+	}
+
+	/// Pay `CashDropRate` of `transfer_amount` to `who` if the transfer amount is
+	/// within the claimable range of `currency_id`.
+	fn claim_cashdrop(currency_id: CurrencyId, who: &T::AccountId, transfer_amount: Balance) -> DispatchResult {
+		let (minimum_claimable, maximum_claimable) = if currency_id == T::SetterCurrencyId::get() {
+			(
+				T::SetterMinimumClaimableTransferAmounts::get(),
+				T::SetterMaximumClaimableTransferAmounts::get(),
+			)
+		} else if currency_id == T::GetSetUSDId::get() {
+			(
+				T::SetDollarMinimumClaimableTransferAmounts::get(),
+				T::SetDollarMaximumClaimableTransferAmounts::get(),
+			)
+		} else {
+			return Err(Error::<T>::InvalidCurrencyType.into());
+		};
+
+		if transfer_amount < minimum_claimable || transfer_amount > maximum_claimable {
+			return Ok(());
+		}
+		let cashdrop = T::CashDropRate::get().saturating_mul_int(transfer_amount);
+		Self::issue_cashdrop_from_pool(who, currency_id, cashdrop)
+	}
+}
+
+impl<T: Config> SerpTreasuryExtended<T::AccountId> for Pallet<T> {
+	/// Issue the exact supply amount of `from_currency_id`, swap it on the DEX and
+	/// burn the `to_currency_id` received.
+	#[transactional]
+	fn buyback_swap_with_exact_supply(
+		from_currency_id: CurrencyId,
+		to_currency_id: CurrencyId,
+		swap_limit: SwapLimit<Balance>,
+	) -> sp_std::result::Result<(Balance, Balance), DispatchError> {
+		let supply_amount = match swap_limit {
+			SwapLimit::ExactSupply(supply_amount, _) => supply_amount,
+			SwapLimit::ExactTarget(_, _) => return Err(Error::<T>::InvalidSwapLimit.into()),
+		};
+		let path = T::Dex::get_best_price_swap_path(
+			from_currency_id,
+			to_currency_id,
+			swap_limit,
+			T::AlternativeSwapPathJointList::get(),
+		)
+		.ok_or(Error::<T>::CannotSwap)?;
+
+		let treasury_account = Self::account_id();
+		T::Currency::deposit(from_currency_id, &treasury_account, supply_amount)?;
+		let (actual_supply, actual_target) =
+			T::Dex::buyback_swap_with_specific_path(&treasury_account, &path, swap_limit)?;
+		T::Currency::withdraw(to_currency_id, &treasury_account, actual_target)?;
+
+		Self::deposit_event(Event::BuyBackAndBurn {
+			supply_currency_id: from_currency_id,
+			supply_amount: actual_supply,
+			target_currency_id: to_currency_id,
+			target_amount: actual_target,
+		});
+		Ok((actual_supply, actual_target))
+	}
+
+	/// Issue up to the max supply amount of `from_currency_id`, swap it on the DEX
+	/// for the exact target amount of `to_currency_id`, burn the target and the
+	/// unused supply.
+	#[transactional]
+	fn buyback_swap_with_exact_target(
+		from_currency_id: CurrencyId,
+		to_currency_id: CurrencyId,
+		swap_limit: SwapLimit<Balance>,
+	) -> sp_std::result::Result<(Balance, Balance), DispatchError> {
+		let max_supply_amount = match swap_limit {
+			SwapLimit::ExactTarget(max_supply_amount, _) => max_supply_amount,
+			SwapLimit::ExactSupply(_, _) => return Err(Error::<T>::InvalidSwapLimit.into()),
+		};
+		let path = T::Dex::get_best_price_swap_path(
+			from_currency_id,
+			to_currency_id,
+			swap_limit,
+			T::AlternativeSwapPathJointList::get(),
+		)
+		.ok_or(Error::<T>::CannotSwap)?;
+
+		let treasury_account = Self::account_id();
+		T::Currency::deposit(from_currency_id, &treasury_account, max_supply_amount)?;
+		let (actual_supply, actual_target) =
+			T::Dex::buyback_swap_with_specific_path(&treasury_account, &path, swap_limit)?;
+		T::Currency::withdraw(to_currency_id, &treasury_account, actual_target)?;
+		T::Currency::withdraw(
+			from_currency_id,
+			&treasury_account,
+			max_supply_amount.saturating_sub(actual_supply),
+		)?;
+
+		Self::deposit_event(Event::BuyBackAndBurn {
+			supply_currency_id: from_currency_id,
+			supply_amount: actual_supply,
+			target_currency_id: to_currency_id,
+			target_amount: actual_target,
+		});
+		Ok((actual_supply, actual_target))
+	}
+}
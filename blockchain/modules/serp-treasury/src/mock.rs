@@ -0,0 +1,384 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Mocks for the serp treasury module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{construct_runtime, ord_parameter_types, parameter_types};
+use frame_system::EnsureSignedBy;
+use orml_traits::parameter_type_with_key;
+use primitives::{Amount, TokenSymbol};
+use sp_core::{H160, H256};
+use sp_runtime::{testing::Header, traits::IdentityLookup, DispatchError};
+use sp_std::{cell::RefCell, collections::btree_map::BTreeMap};
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CDP_TREASURY: AccountId = 3;
+pub const DEX: AccountId = 4;
+pub const SEE: CurrencyId = CurrencyId::Token(TokenSymbol::SEE);
+pub const SETR: CurrencyId = CurrencyId::Token(TokenSymbol::SETR);
+pub const SETUSD: CurrencyId = CurrencyId::Token(TokenSymbol::SETUSD);
+pub const SERP: CurrencyId = CurrencyId::Token(TokenSymbol::SERP);
+pub const DNAR: CurrencyId = CurrencyId::Token(TokenSymbol::DNAR);
+pub const HELP: CurrencyId = CurrencyId::Token(TokenSymbol::HELP);
+
+mod serp_treasury {
+	pub use super::super::*;
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_type_with_key! {
+	pub ExistentialDeposits: |_currency_id: CurrencyId| -> Balance {
+		Default::default()
+	};
+}
+
+impl orml_tokens::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type WeightInfo = ();
+	type ExistentialDeposits = ExistentialDeposits;
+	type OnDust = ();
+	type MaxLocks = ();
+	type DustRemovalWhitelist = ();
+}
+
+thread_local! {
+	static LIQUIDITY_POOLS: RefCell<BTreeMap<(CurrencyId, CurrencyId), (Balance, Balance)>> = RefCell::new(BTreeMap::new());
+	static MARKET_PRICES: RefCell<BTreeMap<CurrencyId, Price>> = RefCell::new(BTreeMap::new());
+}
+
+/// A DEX swapping at the fixed ratio of its configured pools, with the `DEX`
+/// account as counterparty.
+pub struct MockDEX;
+impl MockDEX {
+	pub fn set_pool(currency_id_a: CurrencyId, currency_id_b: CurrencyId, pool_a: Balance, pool_b: Balance) {
+		LIQUIDITY_POOLS.with(|v| {
+			let mut pools = v.borrow_mut();
+			pools.insert((currency_id_a, currency_id_b), (pool_a, pool_b));
+			pools.insert((currency_id_b, currency_id_a), (pool_b, pool_a));
+		});
+	}
+}
+
+impl DEXManager<AccountId, CurrencyId, Balance> for MockDEX {
+	fn get_liquidity_pool(currency_id_a: CurrencyId, currency_id_b: CurrencyId) -> (Balance, Balance) {
+		LIQUIDITY_POOLS.with(|v| {
+			v.borrow()
+				.get(&(currency_id_a, currency_id_b))
+				.cloned()
+				.unwrap_or_default()
+		})
+	}
+
+	fn get_liquidity_token_address(_currency_id_a: CurrencyId, _currency_id_b: CurrencyId) -> Option<H160> {
+		unimplemented!()
+	}
+
+	fn get_swap_amount(_path: &[CurrencyId], _limit: SwapLimit<Balance>) -> Option<(Balance, Balance)> {
+		unimplemented!()
+	}
+
+	fn get_best_price_swap_path(
+		supply_currency_id: CurrencyId,
+		target_currency_id: CurrencyId,
+		_limit: SwapLimit<Balance>,
+		_alternative_path_joint_list: Vec<Vec<CurrencyId>>,
+	) -> Option<Vec<CurrencyId>> {
+		let (supply_pool, _) = Self::get_liquidity_pool(supply_currency_id, target_currency_id);
+		if supply_pool.is_zero() {
+			None
+		} else {
+			Some(vec![supply_currency_id, target_currency_id])
+		}
+	}
+
+	fn swap_with_specific_path(
+		_who: &AccountId,
+		_path: &[CurrencyId],
+		_limit: SwapLimit<Balance>,
+	) -> sp_std::result::Result<(Balance, Balance), DispatchError> {
+		unimplemented!()
+	}
+
+	fn buyback_swap_with_specific_path(
+		who: &AccountId,
+		path: &[CurrencyId],
+		limit: SwapLimit<Balance>,
+	) -> sp_std::result::Result<(Balance, Balance), DispatchError> {
+		let (supply_currency_id, target_currency_id) = (path[0], path[1]);
+		let (supply_pool, target_pool) = Self::get_liquidity_pool(supply_currency_id, target_currency_id);
+		let (supply_amount, target_amount) = match limit {
+			SwapLimit::ExactSupply(supply_amount, min_target_amount) => {
+				let target_amount = supply_amount * target_pool / supply_pool;
+				ensure!(
+					target_amount >= min_target_amount,
+					DispatchError::Other("InsufficientTargetAmount")
+				);
+				(supply_amount, target_amount)
+			}
+			SwapLimit::ExactTarget(max_supply_amount, target_amount) => {
+				let supply_amount = target_amount * supply_pool / target_pool;
+				ensure!(
+					supply_amount <= max_supply_amount,
+					DispatchError::Other("ExceedMaxSupplyAmount")
+				);
+				(supply_amount, target_amount)
+			}
+		};
+		Tokens::transfer(supply_currency_id, who, &DEX, supply_amount)?;
+		Tokens::transfer(target_currency_id, &DEX, who, target_amount)?;
+		Ok((supply_amount, target_amount))
+	}
+
+	fn swap_with_exact_target(
+		_who: &AccountId,
+		_path: &[CurrencyId],
+		_exact_target_amount: Balance,
+		_max_supply_amount: Balance,
+	) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn add_liquidity(
+		_who: &AccountId,
+		_currency_id_a: CurrencyId,
+		_currency_id_b: CurrencyId,
+		_max_amount_a: Balance,
+		_max_amount_b: Balance,
+		_min_share_increment: Balance,
+	) -> sp_std::result::Result<(Balance, Balance, Balance), DispatchError> {
+		unimplemented!()
+	}
+
+	fn remove_liquidity(
+		_who: &AccountId,
+		_currency_id_a: CurrencyId,
+		_currency_id_b: CurrencyId,
+		_remove_share: Balance,
+		_min_withdrawn_a: Balance,
+		_min_withdrawn_b: Balance,
+	) -> sp_std::result::Result<(Balance, Balance), DispatchError> {
+		unimplemented!()
+	}
+}
+
+pub struct MockPriceSource;
+impl PriceProvider<CurrencyId> for MockPriceSource {
+	fn get_price(currency_id: CurrencyId) -> Option<Price> {
+		match currency_id {
+			SETR => Some(Price::saturating_from_rational(1, 4)),
+			SETUSD | SEE | DNAR | SERP | HELP => Some(Price::one()),
+			_ => None,
+		}
+	}
+}
+
+/// An oracle feeding the market prices set by the tests.
+pub struct MockMarketPriceSource;
+impl MockMarketPriceSource {
+	pub fn set_price(currency_id: CurrencyId, price: Price) {
+		MARKET_PRICES.with(|v| v.borrow_mut().insert(currency_id, price));
+	}
+}
+
+impl DataProvider<CurrencyId, Price> for MockMarketPriceSource {
+	fn get(currency_id: &CurrencyId) -> Option<Price> {
+		MARKET_PRICES.with(|v| v.borrow().get(currency_id).cloned())
+	}
+}
+
+parameter_types! {
+	pub static MaxSerpDownSupply: Balance = Balance::max_value();
+}
+
+parameter_type_with_key! {
+	pub MaxSerpDownSupplyPerBlock: |_currency_id: CurrencyId| -> Balance {
+		MaxSerpDownSupply::get()
+	};
+}
+
+parameter_type_with_key! {
+	pub GetStableCurrencyMinimumSupply: |_currency_id: CurrencyId| -> Balance {
+		1_000
+	};
+}
+
+parameter_types! {
+	pub StableCurrencyIds: Vec<CurrencyId> = vec![SETR, SETUSD];
+	pub const StableCurrencyInflationPeriod: BlockNumber = 10;
+	pub const GetNativeCurrencyId: CurrencyId = SEE;
+	pub const GetSerpCurrencyId: CurrencyId = SERP;
+	pub const GetDinarCurrencyId: CurrencyId = DNAR;
+	pub const GetHelpCurrencyId: CurrencyId = HELP;
+	pub const SetterCurrencyId: CurrencyId = SETR;
+	pub const GetSetUSDId: CurrencyId = SETUSD;
+	pub const CDPTreasuryAccountId: AccountId = CDP_TREASURY;
+	pub MaxSwapSlippageCompareToOracle: Ratio = Ratio::saturating_from_rational(1, 10);
+	pub AlternativeSwapPathJointList: Vec<Vec<CurrencyId>> = vec![];
+	pub const SetterMinimumClaimableTransferAmounts: Balance = 40;
+	pub const SetterMaximumClaimableTransferAmounts: Balance = 40_000;
+	pub const SetDollarMinimumClaimableTransferAmounts: Balance = 10;
+	pub const SetDollarMaximumClaimableTransferAmounts: Balance = 10_000;
+	pub CashDropRate: Rate = Rate::saturating_from_rational(2, 100);
+	pub const SerpTreasuryPalletId: PalletId = PalletId(*b"set/serp");
+}
+
+ord_parameter_types! {
+	pub const Admin: AccountId = ALICE;
+}
+
+impl Config for Runtime {
+	type Event = Event;
+	type Currency = Tokens;
+	type StableCurrencyIds = StableCurrencyIds;
+	type StableCurrencyInflationPeriod = StableCurrencyInflationPeriod;
+	type GetStableCurrencyMinimumSupply = GetStableCurrencyMinimumSupply;
+	type GetNativeCurrencyId = GetNativeCurrencyId;
+	type GetSerpCurrencyId = GetSerpCurrencyId;
+	type GetDinarCurrencyId = GetDinarCurrencyId;
+	type GetHelpCurrencyId = GetHelpCurrencyId;
+	type SetterCurrencyId = SetterCurrencyId;
+	type GetSetUSDId = GetSetUSDId;
+	type CDPTreasuryAccountId = CDPTreasuryAccountId;
+	type Dex = MockDEX;
+	type MaxSwapSlippageCompareToOracle = MaxSwapSlippageCompareToOracle;
+	type PriceSource = MockPriceSource;
+	type MarketPriceSource = MockMarketPriceSource;
+	type MaxSerpDownSupplyPerBlock = MaxSerpDownSupplyPerBlock;
+	type AlternativeSwapPathJointList = AlternativeSwapPathJointList;
+	type SetterMinimumClaimableTransferAmounts = SetterMinimumClaimableTransferAmounts;
+	type SetterMaximumClaimableTransferAmounts = SetterMaximumClaimableTransferAmounts;
+	type SetDollarMinimumClaimableTransferAmounts = SetDollarMinimumClaimableTransferAmounts;
+	type SetDollarMaximumClaimableTransferAmounts = SetDollarMaximumClaimableTransferAmounts;
+	type CashDropRate = CashDropRate;
+	type UpdateOrigin = EnsureSignedBy<Admin, AccountId>;
+	type PalletId = SerpTreasuryPalletId;
+	type WeightInfo = ();
+}
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Storage, Config, Event<T>},
+		SerpTreasuryModule: serp_treasury::{Pallet, Storage, Call, Config, Event<T>},
+		Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
+	}
+);
+
+pub struct ExtBuilder {
+	balances: Vec<(AccountId, CurrencyId, Balance)>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			balances: vec![
+				(ALICE, SETR, 10_000),
+				(ALICE, SETUSD, 10_000),
+				(CDP_TREASURY, SETUSD, 10_000),
+				(DEX, SEE, 1_000_000),
+				(DEX, SERP, 1_000_000),
+				(DEX, DNAR, 1_000_000),
+				(DEX, HELP, 1_000_000),
+				(DEX, SETR, 1_000_000),
+				(DEX, SETUSD, 1_000_000),
+			],
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap();
+
+		orml_tokens::GenesisConfig::<Runtime> {
+			balances: self.balances,
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+		serp_treasury::GenesisConfig {
+			stable_currency_inflation_rate: vec![(SETR, 1_000)],
+			stable_currency_cashdrop: vec![(SETUSD, 1_000)],
+		}
+		.assimilate_storage::<Runtime>(&mut t)
+		.unwrap();
+
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			MockDEX::set_pool(SETUSD, SEE, 1_000, 1_000);
+			MockDEX::set_pool(SETUSD, SETR, 1_000, 4_000);
+			MockDEX::set_pool(SETR, SEE, 4_000, 1_000);
+			MockDEX::set_pool(SETR, DNAR, 4_000, 1_000);
+			MockDEX::set_pool(SETR, SERP, 4_000, 1_000);
+			MockDEX::set_pool(SETR, HELP, 4_000, 1_000);
+			MockMarketPriceSource::set_price(SETR, Price::saturating_from_rational(1, 4));
+			MockMarketPriceSource::set_price(SETUSD, Price::one());
+		});
+		ext
+	}
+}
@@ -0,0 +1,274 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Unit tests for the serp treasury module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use sp_runtime::traits::BadOrigin;
+
+#[test]
+fn genesis_config_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(SerpTreasuryModule::stable_currency_inflation_rate(SETR), 1_000);
+		assert_eq!(SerpTreasuryModule::cashdrop_pool(SETUSD), 1_000);
+	});
+}
+
+#[test]
+fn set_stable_currency_inflation_rate_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			SerpTreasuryModule::set_stable_currency_inflation_rate(
+				Origin::signed(BOB),
+				SerpStableCurrencyId::SETUSD,
+				500
+			),
+			BadOrigin
+		);
+		assert_ok!(SerpTreasuryModule::set_stable_currency_inflation_rate(
+			Origin::signed(ALICE),
+			SerpStableCurrencyId::SETUSD,
+			500
+		));
+		System::assert_last_event(Event::SerpTreasuryModule(
+			crate::Event::StableCurrencyInflationRateUpdated {
+				currency_id: SETUSD,
+				inflation_rate: 500,
+			},
+		));
+		assert_eq!(SerpTreasuryModule::stable_currency_inflation_rate(SETUSD), 500);
+	});
+}
+
+#[test]
+fn calculate_supply_change_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(SerpTreasuryModule::calculate_supply_change(110, 100, 1_000), 100);
+		assert_eq!(SerpTreasuryModule::calculate_supply_change(100, 110, 1_000), 0);
+		assert_eq!(SerpTreasuryModule::calculate_supply_change(100, 0, 1_000), 0);
+	});
+}
+
+#[test]
+fn claim_cashdrop_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			SerpTreasuryModule::claim_cashdrop(SEE, &ALICE, 1_000),
+			Error::<Runtime>::InvalidCurrencyType
+		);
+
+		// out of the claimable range
+		assert_ok!(SerpTreasuryModule::claim_cashdrop(SETUSD, &BOB, 5));
+		assert_ok!(SerpTreasuryModule::claim_cashdrop(SETUSD, &BOB, 20_000));
+		assert_eq!(Tokens::free_balance(SETUSD, &BOB), 0);
+
+		assert_ok!(SerpTreasuryModule::claim_cashdrop(SETUSD, &BOB, 1_000));
+		System::assert_last_event(Event::SerpTreasuryModule(crate::Event::CashDropClaim {
+			claimant: BOB,
+			currency_id: SETUSD,
+			amount: 20,
+		}));
+		assert_eq!(Tokens::free_balance(SETUSD, &BOB), 20);
+		assert_eq!(SerpTreasuryModule::cashdrop_pool(SETUSD), 980);
+
+		assert_noop!(
+			SerpTreasuryModule::claim_cashdrop(SETR, &BOB, 1_000),
+			Error::<Runtime>::CashDropPoolNotEnough
+		);
+	});
+}
+
+#[test]
+fn issue_stablecurrency_inflation_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		let setr_supply = Tokens::total_issuance(SETR);
+		assert_ok!(SerpTreasuryModule::issue_stablecurrency_inflation());
+		System::assert_last_event(Event::SerpTreasuryModule(crate::Event::InflationDelivery {
+			currency_id: SETR,
+			amount: 1_000,
+		}));
+		assert_eq!(Tokens::total_issuance(SETR), setr_supply + 1_000);
+		assert_eq!(SerpTreasuryModule::cashdrop_pool(SETR), 200);
+		assert_eq!(Tokens::total_issuance(DNAR), 1_000_000 - 50);
+		assert_eq!(Tokens::total_issuance(SERP), 1_000_000 - 50);
+		assert_eq!(Tokens::total_issuance(SEE), 1_000_000 - 50);
+		assert_eq!(Tokens::total_issuance(HELP), 1_000_000 - 50);
+	});
+}
+
+#[test]
+fn inflation_buyback_is_limited_by_the_oracle_price() {
+	ExtBuilder::default().build().execute_with(|| {
+		// 200 SETR buys 40 DNAR, 20% below the oracle price
+		MockDEX::set_pool(SETR, DNAR, 4_000, 800);
+
+		let setr_supply = Tokens::total_issuance(SETR);
+		assert_ok!(SerpTreasuryModule::issue_stablecurrency_inflation());
+		assert_eq!(Tokens::total_issuance(SETR), setr_supply);
+		assert_eq!(SerpTreasuryModule::cashdrop_pool(SETR), 0);
+		assert_eq!(Tokens::total_issuance(DNAR), 1_000_000);
+	});
+}
+
+#[test]
+fn on_serpup_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		let setusd_supply = Tokens::total_issuance(SETUSD);
+		assert_ok!(SerpTreasuryModule::on_serpup(SETUSD, 1_000));
+		System::assert_last_event(Event::SerpTreasuryModule(crate::Event::SerpUp {
+			currency_id: SETUSD,
+			amount: 1_000,
+		}));
+		assert_eq!(Tokens::total_issuance(SETUSD), setusd_supply + 1_000);
+		assert_eq!(SerpTreasuryModule::cashdrop_pool(SETUSD), 1_200);
+		// 400 SETUSD bought back 1_600 SETR and 400 SETUSD bought back 400 SEE
+		assert_eq!(Tokens::total_issuance(SETR), 1_010_000 - 1_600);
+		assert_eq!(Tokens::total_issuance(SEE), 1_000_000 - 400);
+	});
+}
+
+#[test]
+fn force_serpdown_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			SerpTreasuryModule::force_serpdown(Origin::signed(BOB), SETUSD, 100),
+			BadOrigin
+		);
+		assert_noop!(
+			SerpTreasuryModule::force_serpdown(Origin::signed(ALICE), SEE, 100),
+			Error::<Runtime>::InvalidCurrencyType
+		);
+
+		let setusd_supply = Tokens::total_issuance(SETUSD);
+		let setr_supply = Tokens::total_issuance(SETR);
+		assert_ok!(SerpTreasuryModule::force_serpdown(Origin::signed(ALICE), SETUSD, 100));
+		System::assert_last_event(Event::SerpTreasuryModule(crate::Event::SerpDown {
+			currency_id: SETUSD,
+			amount: 100,
+		}));
+		assert_eq!(Tokens::total_issuance(SETUSD), setusd_supply - 100);
+		assert_eq!(Tokens::total_issuance(SETR), setr_supply + 400);
+		assert_eq!(Tokens::free_balance(SETR, &SerpTreasuryModule::account_id()), 0);
+	});
+}
+
+#[test]
+fn serpdown_supply_is_capped_per_block() {
+	ExtBuilder::default().build().execute_with(|| {
+		// 100 SETUSD can be bought back with up to 440 SETR
+		MaxSerpDownSupply::set(400);
+		let setusd_supply = Tokens::total_issuance(SETUSD);
+		let setr_supply = Tokens::total_issuance(SETR);
+		assert_ok!(SerpTreasuryModule::force_serpdown(Origin::signed(ALICE), SETUSD, 100));
+		System::assert_last_event(Event::SerpTreasuryModule(crate::Event::SerpDown {
+			currency_id: SETUSD,
+			amount: 90,
+		}));
+		assert_eq!(Tokens::total_issuance(SETUSD), setusd_supply - 90);
+		assert_eq!(Tokens::total_issuance(SETR), setr_supply + 360);
+		assert_eq!(SerpTreasuryModule::serpdown_supply(SETR), (1, 360));
+
+		MaxSerpDownSupply::set(360);
+		assert_noop!(
+			SerpTreasuryModule::force_serpdown(Origin::signed(ALICE), SETUSD, 100),
+			Error::<Runtime>::SerpDownLimitReached
+		);
+
+		// the limit applies per block
+		System::set_block_number(2);
+		assert_ok!(SerpTreasuryModule::force_serpdown(Origin::signed(ALICE), SETUSD, 10));
+		assert_eq!(SerpTreasuryModule::serpdown_supply(SETR), (2, 40));
+	});
+}
+
+#[test]
+fn serp_tes_now_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		// on peg
+		assert_ok!(SerpTreasuryModule::serp_tes_now());
+		System::assert_last_event(Event::SerpTreasuryModule(crate::Event::SerpTes {
+			currency_id: SETUSD,
+			market_price: Price::one(),
+			peg_price: Price::one(),
+		}));
+
+		// SETUSD trades 10% above its peg
+		MockMarketPriceSource::set_price(SETUSD, Price::saturating_from_rational(11, 10));
+		let setusd_supply = Tokens::total_issuance(SETUSD);
+		assert_ok!(SerpTreasuryModule::serp_tes_now());
+		System::assert_last_event(Event::SerpTreasuryModule(crate::Event::SerpUp {
+			currency_id: SETUSD,
+			amount: setusd_supply / 10,
+		}));
+
+		// SETUSD trades below its peg
+		MockMarketPriceSource::set_price(SETUSD, Price::saturating_from_rational(8, 10));
+		let setusd_supply = Tokens::total_issuance(SETUSD);
+		assert_ok!(SerpTreasuryModule::serp_tes_now());
+		System::assert_last_event(Event::SerpTreasuryModule(crate::Event::SerpDown {
+			currency_id: SETUSD,
+			amount: setusd_supply / 4,
+		}));
+		assert_eq!(Tokens::total_issuance(SETUSD), setusd_supply - setusd_supply / 4);
+	});
+}
+
+#[test]
+fn on_initialize_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		let setr_supply = Tokens::total_issuance(SETR);
+		SerpTreasuryModule::on_initialize(9);
+		assert_eq!(Tokens::total_issuance(SETR), setr_supply);
+		SerpTreasuryModule::on_initialize(10);
+		assert_eq!(Tokens::total_issuance(SETR), setr_supply + 1_000);
+	});
+}
+
+#[test]
+fn on_initialize_handles_currencies_independently() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(SerpTreasuryModule::set_stable_currency_inflation_rate(
+			Origin::signed(ALICE),
+			SerpStableCurrencyId::SETUSD,
+			1_000
+		));
+		MockDEX::set_pool(SETUSD, DNAR, 1_000, 1_000);
+		MockDEX::set_pool(SETUSD, SERP, 1_000, 1_000);
+		MockDEX::set_pool(SETUSD, HELP, 1_000, 1_000);
+		// the last buyback of the SETR inflation fails
+		MockDEX::set_pool(SETR, HELP, 0, 0);
+
+		let setr_supply = Tokens::total_issuance(SETR);
+		let setusd_supply = Tokens::total_issuance(SETUSD);
+		SerpTreasuryModule::on_initialize(10);
+
+		// the SETR inflation is reverted as a whole
+		assert_eq!(Tokens::total_issuance(SETR), setr_supply);
+		assert_eq!(SerpTreasuryModule::cashdrop_pool(SETR), 0);
+		assert_eq!(Tokens::total_issuance(DNAR), 1_000_000 - 200);
+		// the SETUSD inflation is still delivered
+		assert_eq!(Tokens::total_issuance(SETUSD), setusd_supply + 1_000);
+		assert_eq!(SerpTreasuryModule::cashdrop_pool(SETUSD), 1_200);
+	});
+}
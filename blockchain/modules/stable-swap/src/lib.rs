@@ -0,0 +1,974 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! # Stable Swap Module
+//!
+//! ## Overview
+//!
+//! Stable swap module provides the pools of currencies pegged to each other,
+//! such as the stablecoins `SETR`, `GRA`, `USSD` and `SETUSD`. The pools
+//! follow the StableSwap invariant, which amplifies the liquidity around the
+//! 1:1 price, so the swaps between the pegged currencies have far less
+//! slippage than with the constant product formula.
+//!
+//! A pool holds two or more currencies, and its liquidity share is the
+//! `CurrencyId::StableAssetPoolToken` of its pool id, so the shares of every
+//! pool are issued separately from each other and from the dex shares of the
+//! same currencies. A `CurrencyId::DexShare` only names a pair, so it can not
+//! stand for a pool of more currencies, the incentives module takes these
+//! shares as liquidity shares as well. The amplification coefficient of the
+//! pools is ramped linearly by governance over a period of blocks. This module implements `DEXManager`, so other modules can
+//! swap through the stable pools.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{pallet_prelude::*, transactional, PalletId};
+use frame_system::pallet_prelude::*;
+use orml_traits::{MultiCurrency, MultiCurrencyExtended};
+use primitives::{Balance, CurrencyId, StableAssetPoolId, TradingPair};
+use sp_core::{H160, U256};
+use sp_runtime::{
+	traits::{AccountIdConversion, One, UniqueSaturatedInto, Zero},
+	ArithmeticError, DispatchError, DispatchResult, FixedPointNumber, RuntimeDebug,
+};
+use sp_std::{convert::TryFrom, prelude::*};
+use support::{CurrencyIdMapping, DEXManager, Rate, SwapLimit};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+/// The id of a stable swap pool.
+pub type PoolId = StableAssetPoolId;
+
+/// The max iterations of the Newton's method to calculate the invariant.
+pub const MAX_ITERATIONS: u32 = 255;
+
+/// The max multiple which the amplification coefficient could be ramped by.
+pub const MAX_AMPLIFICATION_CHANGE: u128 = 10;
+
+/// Information of a stable swap pool.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct PoolInfo<BlockNumber> {
+	/// The currencies of the pool.
+	pub currency_ids: Vec<CurrencyId>,
+	/// The multipliers to normalize the amounts of the currencies to the same
+	/// decimals.
+	pub precisions: Vec<Balance>,
+	/// The amounts of the currencies held by the pool.
+	pub balances: Vec<Balance>,
+	/// The currency id of the liquidity share, only issued by this pool.
+	pub share_currency_id: CurrencyId,
+	/// The fee rate of swaps and of imbalanced liquidity additions.
+	pub fee_rate: Rate,
+	/// The amplification coefficient at `initial_amplification_block`.
+	pub initial_amplification: u128,
+	/// The amplification coefficient at `future_amplification_block`.
+	pub future_amplification: u128,
+	/// The block number when the amplification ramping starts.
+	pub initial_amplification_block: BlockNumber,
+	/// The block number when the amplification ramping ends.
+	pub future_amplification_block: BlockNumber,
+}
+
+impl<BlockNumber: UniqueSaturatedInto<u128> + PartialOrd + Copy> PoolInfo<BlockNumber> {
+	/// The amplification coefficient at `now`, linearly ramped from the
+	/// initial one to the future one.
+	pub fn amplification(&self, now: BlockNumber) -> u128 {
+		if now >= self.future_amplification_block {
+			return self.future_amplification;
+		}
+
+		let now: u128 = now.unique_saturated_into();
+		let initial_block: u128 = self.initial_amplification_block.unique_saturated_into();
+		let future_block: u128 = self.future_amplification_block.unique_saturated_into();
+		let elapsed = now.saturating_sub(initial_block);
+		let duration = future_block.saturating_sub(initial_block);
+
+		if self.future_amplification > self.initial_amplification {
+			let change = (self.future_amplification - self.initial_amplification).saturating_mul(elapsed) / duration;
+			self.initial_amplification.saturating_add(change)
+		} else {
+			let change = (self.initial_amplification - self.future_amplification).saturating_mul(elapsed) / duration;
+			self.initial_amplification.saturating_sub(change)
+		}
+	}
+
+	/// The index of `currency_id` in the pool.
+	pub fn index_of(&self, currency_id: CurrencyId) -> Option<usize> {
+		self.currency_ids.iter().position(|id| *id == currency_id)
+	}
+
+	/// The balances of the pool normalized to the same decimals.
+	fn normalized_balances(&self) -> Option<Vec<Balance>> {
+		normalize(&self.balances, &self.precisions)
+	}
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Currency for transferring the pooled currencies and issuing the
+		/// liquidity shares.
+		type Currency: MultiCurrencyExtended<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+
+		/// The origin which may create pools and ramp their amplification.
+		type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The max number of currencies in a pool.
+		#[pallet::constant]
+		type MaxPoolCurrencies: Get<u32>;
+
+		/// The max amplification coefficient of a pool.
+		#[pallet::constant]
+		type MaxAmplification: Get<u128>;
+
+		/// The limit for length of trading path.
+		#[pallet::constant]
+		type TradingPathLimit: Get<u32>;
+
+		/// The stable swap module id, keep the pooled currencies.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// Mapping between the liquidity shares and their EVM addresses.
+		type CurrencyIdMapping: CurrencyIdMapping;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The pool does not exist
+		PoolNotFound,
+		/// The currencies of the pool are invalid
+		InvalidCurrencies,
+		/// The precisions of the pool are invalid
+		InvalidPrecisions,
+		/// The fee rate is invalid
+		InvalidFeeRate,
+		/// The amplification coefficient is invalid
+		InvalidAmplification,
+		/// The ramping end block is invalid
+		InvalidRampingBlock,
+		/// The currency pair is already in a pool
+		TradingPairAlreadyPooled,
+		/// The amounts do not match the currencies of the pool
+		InvalidAmounts,
+		/// The share increment is less than expected
+		InsufficientShareIncrement,
+		/// The withdrawn amount is less than expected
+		InsufficientWithdrawnAmount,
+		/// The share amount is invalid
+		InvalidShareAmount,
+		/// The trading path is invalid
+		InvalidTradingPath,
+		/// The target amount is less than expected
+		InsufficientTargetAmount,
+		/// The supply amount is more than expected
+		ExcessiveSupplyAmount,
+		/// The pool has not enough liquidity
+		InsufficientLiquidity,
+		/// The invariant of the pool can not be calculated
+		InvariantNotConverged,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A pool is created. \[pool_id, currency_ids, share_currency_id\]
+		PoolCreated(PoolId, Vec<CurrencyId>, CurrencyId),
+		/// Liquidity is added to a pool. \[who, pool_id, amounts,
+		/// share_increment\]
+		AddLiquidity(T::AccountId, PoolId, Vec<Balance>, Balance),
+		/// Liquidity is removed from a pool. \[who, pool_id, share_decrement,
+		/// amounts\]
+		RemoveLiquidity(T::AccountId, PoolId, Balance, Vec<Balance>),
+		/// Use supply currency to swap target currency. \[trader,
+		/// trading_path, supply_currency_amount, target_currency_amount\]
+		Swap(T::AccountId, Vec<CurrencyId>, Balance, Balance),
+		/// The amplification coefficient of a pool starts ramping. \[pool_id,
+		/// initial_amplification, future_amplification, initial_block,
+		/// future_block\]
+		AmplificationRamping(PoolId, u128, u128, T::BlockNumber, T::BlockNumber),
+	}
+
+	/// The id of the next pool.
+	///
+	/// NextPoolId: PoolId
+	#[pallet::storage]
+	#[pallet::getter(fn next_pool_id)]
+	pub type NextPoolId<T: Config> = StorageValue<_, PoolId, ValueQuery>;
+
+	/// The stable swap pools.
+	///
+	/// Pools: map PoolId => Option<PoolInfo>
+	#[pallet::storage]
+	#[pallet::getter(fn pools)]
+	pub type Pools<T: Config> = StorageMap<_, Twox64Concat, PoolId, PoolInfo<T::BlockNumber>, OptionQuery>;
+
+	/// The pool of every pair of pooled currencies, keyed by the currencies
+	/// of the `TradingPair`.
+	///
+	/// TradingPairPools: map (CurrencyId, CurrencyId) => Option<PoolId>
+	#[pallet::storage]
+	#[pallet::getter(fn trading_pair_pools)]
+	pub type TradingPairPools<T: Config> = StorageMap<_, Twox64Concat, (CurrencyId, CurrencyId), PoolId, OptionQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			Self::check_invariants()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::check_invariants()
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Create a pool of pegged currencies.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `currency_ids`: the currencies of the pool.
+		/// - `precisions`: the multipliers to normalize the amounts of the
+		///   currencies to the same decimals.
+		/// - `fee_rate`: the fee rate of the pool.
+		/// - `amplification`: the amplification coefficient of the pool.
+		#[pallet::weight(T::WeightInfo::create_pool())]
+		#[transactional]
+		pub fn create_pool(
+			origin: OriginFor<T>,
+			currency_ids: Vec<CurrencyId>,
+			precisions: Vec<Balance>,
+			fee_rate: Rate,
+			amplification: u128,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			let pool_size = currency_ids.len();
+			ensure!(
+				pool_size >= 2 && pool_size <= T::MaxPoolCurrencies::get() as usize,
+				Error::<T>::InvalidCurrencies
+			);
+			ensure!(
+				precisions.len() == pool_size && precisions.iter().all(|p| !p.is_zero()),
+				Error::<T>::InvalidPrecisions
+			);
+			ensure!(fee_rate < Rate::one(), Error::<T>::InvalidFeeRate);
+			ensure!(
+				!amplification.is_zero() && amplification <= T::MaxAmplification::get(),
+				Error::<T>::InvalidAmplification
+			);
+
+			// every pair of the currencies can only be in one pool
+			let mut trading_pairs = vec![];
+			for (i, currency_id_a) in currency_ids.iter().enumerate() {
+				for currency_id_b in currency_ids.iter().skip(i + 1) {
+					let trading_pair = TradingPair::from_currency_ids(*currency_id_a, *currency_id_b)
+						.ok_or(Error::<T>::InvalidCurrencies)?;
+					ensure!(
+						!TradingPairPools::<T>::contains_key((trading_pair.first(), trading_pair.second())),
+						Error::<T>::TradingPairAlreadyPooled
+					);
+					trading_pairs.push(trading_pair);
+				}
+			}
+
+			let pool_id = NextPoolId::<T>::try_mutate(|id| -> sp_std::result::Result<PoolId, DispatchError> {
+				let current_id = *id;
+				*id = id.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+				Ok(current_id)
+			})?;
+			let share_currency_id = CurrencyId::StableAssetPoolToken(pool_id);
+			let now = frame_system::Pallet::<T>::block_number();
+			Pools::<T>::insert(
+				pool_id,
+				PoolInfo {
+					currency_ids: currency_ids.clone(),
+					precisions,
+					balances: vec![Zero::zero(); pool_size],
+					share_currency_id,
+					fee_rate,
+					initial_amplification: amplification,
+					future_amplification: amplification,
+					initial_amplification_block: now,
+					future_amplification_block: now,
+				},
+			);
+			for trading_pair in trading_pairs {
+				TradingPairPools::<T>::insert((trading_pair.first(), trading_pair.second()), pool_id);
+			}
+
+			Self::deposit_event(Event::PoolCreated(pool_id, currency_ids, share_currency_id));
+			Ok(())
+		}
+
+		/// Ramp the amplification coefficient of a pool linearly from its
+		/// current value to `future_amplification` at `future_block`.
+		///
+		/// The dispatch origin of this call must be `UpdateOrigin`.
+		///
+		/// - `pool_id`: the id of the pool.
+		/// - `future_amplification`: the amplification coefficient at the end
+		///   of ramping.
+		/// - `future_block`: the block number when the ramping ends.
+		#[pallet::weight(T::WeightInfo::ramp_amplification())]
+		#[transactional]
+		pub fn ramp_amplification(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			future_amplification: u128,
+			future_block: T::BlockNumber,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			ensure!(
+				!future_amplification.is_zero() && future_amplification <= T::MaxAmplification::get(),
+				Error::<T>::InvalidAmplification
+			);
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(future_block > now, Error::<T>::InvalidRampingBlock);
+
+			Pools::<T>::try_mutate(pool_id, |maybe_pool_info| -> DispatchResult {
+				let pool_info = maybe_pool_info.as_mut().ok_or(Error::<T>::PoolNotFound)?;
+				let current_amplification = pool_info.amplification(now);
+				ensure!(
+					future_amplification <= current_amplification.saturating_mul(MAX_AMPLIFICATION_CHANGE)
+						&& future_amplification.saturating_mul(MAX_AMPLIFICATION_CHANGE) >= current_amplification,
+					Error::<T>::InvalidAmplification
+				);
+
+				pool_info.initial_amplification = current_amplification;
+				pool_info.future_amplification = future_amplification;
+				pool_info.initial_amplification_block = now;
+				pool_info.future_amplification_block = future_block;
+
+				Self::deposit_event(Event::AmplificationRamping(
+					pool_id,
+					current_amplification,
+					future_amplification,
+					now,
+					future_block,
+				));
+				Ok(())
+			})
+		}
+
+		/// Add liquidity to a pool.
+		///
+		/// - `pool_id`: the id of the pool.
+		/// - `amounts`: the amounts of the currencies to add, in the order of
+		///   the currencies of the pool.
+		/// - `min_share_increment`: the acceptable min share amount to receive.
+		#[pallet::weight(T::WeightInfo::add_liquidity())]
+		#[transactional]
+		pub fn add_liquidity(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			amounts: Vec<Balance>,
+			#[pallet::compact] min_share_increment: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_add_liquidity(&who, pool_id, amounts, min_share_increment)?;
+			Ok(())
+		}
+
+		/// Remove liquidity from a pool in proportion to the pooled
+		/// currencies.
+		///
+		/// - `pool_id`: the id of the pool.
+		/// - `remove_share`: the share amount to burn.
+		/// - `min_withdrawn_amounts`: the acceptable min amounts of the
+		///   currencies to receive, in the order of the currencies of the pool.
+		#[pallet::weight(T::WeightInfo::remove_liquidity())]
+		#[transactional]
+		pub fn remove_liquidity(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			#[pallet::compact] remove_share: Balance,
+			min_withdrawn_amounts: Vec<Balance>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_remove_liquidity(&who, pool_id, remove_share, min_withdrawn_amounts)?;
+			Ok(())
+		}
+
+		/// Trading with the stable pools, swap with exact supply amount.
+		///
+		/// - `path`: trading path.
+		/// - `supply_amount`: exact supply amount.
+		/// - `min_target_amount`: acceptable minimum target amount.
+		#[pallet::weight(<T as Config>::WeightInfo::swap_with_exact_supply(path.len() as u32))]
+		#[transactional]
+		pub fn swap_with_exact_supply(
+			origin: OriginFor<T>,
+			path: Vec<CurrencyId>,
+			#[pallet::compact] supply_amount: Balance,
+			#[pallet::compact] min_target_amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_swap(&who, &path, SwapLimit::ExactSupply(supply_amount, min_target_amount))?;
+			Ok(())
+		}
+
+		/// Trading with the stable pools, swap with exact target amount.
+		///
+		/// - `path`: trading path.
+		/// - `target_amount`: exact target amount.
+		/// - `max_supply_amount`: acceptable maximum supply amount.
+		#[pallet::weight(<T as Config>::WeightInfo::swap_with_exact_target(path.len() as u32))]
+		#[transactional]
+		pub fn swap_with_exact_target(
+			origin: OriginFor<T>,
+			path: Vec<CurrencyId>,
+			#[pallet::compact] target_amount: Balance,
+			#[pallet::compact] max_supply_amount: Balance,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_swap(&who, &path, SwapLimit::ExactTarget(max_supply_amount, target_amount))?;
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	fn account_id() -> T::AccountId {
+		T::PalletId::get().into_account()
+	}
+
+	/// The pool which both `currency_id_a` and `currency_id_b` are in.
+	fn pool_of(currency_id_a: CurrencyId, currency_id_b: CurrencyId) -> Option<(PoolId, PoolInfo<T::BlockNumber>)> {
+		let trading_pair = TradingPair::from_currency_ids(currency_id_a, currency_id_b)?;
+		let pool_id = Self::trading_pair_pools((trading_pair.first(), trading_pair.second()))?;
+		Self::pools(pool_id).map(|pool_info| (pool_id, pool_info))
+	}
+
+	fn do_add_liquidity(
+		who: &T::AccountId,
+		pool_id: PoolId,
+		amounts: Vec<Balance>,
+		min_share_increment: Balance,
+	) -> sp_std::result::Result<Balance, DispatchError> {
+		let mut pool_info = Self::pools(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+		ensure!(
+			amounts.len() == pool_info.currency_ids.len(),
+			Error::<T>::InvalidAmounts
+		);
+
+		let amplification = pool_info.amplification(frame_system::Pallet::<T>::block_number());
+		let total_shares = T::Currency::total_issuance(pool_info.share_currency_id);
+		let old_balances = pool_info.normalized_balances().ok_or(ArithmeticError::Overflow)?;
+		let old_invariant = if total_shares.is_zero() {
+			// the initial liquidity must provide all the currencies
+			ensure!(amounts.iter().all(|a| !a.is_zero()), Error::<T>::InvalidAmounts);
+			Zero::zero()
+		} else {
+			get_invariant(&old_balances, amplification).ok_or(Error::<T>::InvariantNotConverged)?
+		};
+
+		let mut new_pool_balances = pool_info.balances.clone();
+		for (balance, amount) in new_pool_balances.iter_mut().zip(amounts.iter()) {
+			*balance = balance.checked_add(*amount).ok_or(ArithmeticError::Overflow)?;
+		}
+		let new_balances = normalize(&new_pool_balances, &pool_info.precisions).ok_or(ArithmeticError::Overflow)?;
+		let new_invariant = get_invariant(&new_balances, amplification).ok_or(Error::<T>::InvariantNotConverged)?;
+		ensure!(new_invariant > old_invariant, Error::<T>::InvalidAmounts);
+
+		let share_increment = if total_shares.is_zero() {
+			new_invariant
+		} else {
+			// charge the fee of the part which deviates from the current proportion
+			let pool_size = new_balances.len() as u128;
+			let fee_rate = pool_info
+				.fee_rate
+				.saturating_mul(Rate::saturating_from_rational(pool_size, 4 * (pool_size - 1)));
+			let mut balances_without_fee = new_balances.clone();
+			for (i, balance) in balances_without_fee.iter_mut().enumerate() {
+				let ideal_balance =
+					mul_div(new_invariant, old_balances[i], old_invariant).ok_or(ArithmeticError::Overflow)?;
+				let difference = if ideal_balance > new_balances[i] {
+					ideal_balance - new_balances[i]
+				} else {
+					new_balances[i] - ideal_balance
+				};
+				*balance = balance.saturating_sub(fee_rate.saturating_mul_int(difference));
+			}
+			let invariant_without_fee =
+				get_invariant(&balances_without_fee, amplification).ok_or(Error::<T>::InvariantNotConverged)?;
+			mul_div(
+				total_shares,
+				invariant_without_fee.saturating_sub(old_invariant),
+				old_invariant,
+			)
+			.ok_or(ArithmeticError::Overflow)?
+		};
+		ensure!(
+			!share_increment.is_zero() && share_increment >= min_share_increment,
+			Error::<T>::InsufficientShareIncrement
+		);
+
+		let pool_account = Self::account_id();
+		for (currency_id, amount) in pool_info.currency_ids.iter().zip(amounts.iter()) {
+			T::Currency::transfer(*currency_id, who, &pool_account, *amount)?;
+		}
+		T::Currency::deposit(pool_info.share_currency_id, who, share_increment)?;
+		pool_info.balances = new_pool_balances;
+		Pools::<T>::insert(pool_id, pool_info);
+
+		Self::deposit_event(Event::AddLiquidity(who.clone(), pool_id, amounts, share_increment));
+		Ok(share_increment)
+	}
+
+	fn do_remove_liquidity(
+		who: &T::AccountId,
+		pool_id: PoolId,
+		remove_share: Balance,
+		min_withdrawn_amounts: Vec<Balance>,
+	) -> sp_std::result::Result<Vec<Balance>, DispatchError> {
+		ensure!(!remove_share.is_zero(), Error::<T>::InvalidShareAmount);
+		let mut pool_info = Self::pools(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+		ensure!(
+			min_withdrawn_amounts.len() == pool_info.currency_ids.len(),
+			Error::<T>::InvalidAmounts
+		);
+		let total_shares = T::Currency::total_issuance(pool_info.share_currency_id);
+		ensure!(remove_share <= total_shares, Error::<T>::InvalidShareAmount);
+
+		let mut amounts = vec![];
+		for (balance, min_withdrawn_amount) in pool_info.balances.iter_mut().zip(min_withdrawn_amounts.iter()) {
+			let amount = mul_div(*balance, remove_share, total_shares).ok_or(ArithmeticError::Overflow)?;
+			ensure!(amount >= *min_withdrawn_amount, Error::<T>::InsufficientWithdrawnAmount);
+			*balance = balance.saturating_sub(amount);
+			amounts.push(amount);
+		}
+
+		T::Currency::withdraw(pool_info.share_currency_id, who, remove_share)?;
+		let pool_account = Self::account_id();
+		for (currency_id, amount) in pool_info.currency_ids.iter().zip(amounts.iter()) {
+			T::Currency::transfer(*currency_id, &pool_account, who, *amount)?;
+		}
+		Pools::<T>::insert(pool_id, pool_info);
+
+		Self::deposit_event(Event::RemoveLiquidity(
+			who.clone(),
+			pool_id,
+			remove_share,
+			amounts.clone(),
+		));
+		Ok(amounts)
+	}
+
+	/// The target amount of swapping `supply_amount` of the `i`th currency to
+	/// the `j`th currency of the pool.
+	fn get_target_amount(
+		pool_info: &PoolInfo<T::BlockNumber>,
+		i: usize,
+		j: usize,
+		supply_amount: Balance,
+	) -> Option<Balance> {
+		let amplification = pool_info.amplification(frame_system::Pallet::<T>::block_number());
+		let balances = pool_info.normalized_balances()?;
+		let invariant = get_invariant(&balances, amplification)?;
+
+		let x = balances[i].checked_add(supply_amount.checked_mul(pool_info.precisions[i])?)?;
+		let y = get_balance(i, j, x, &balances, amplification, invariant)?;
+		// minus 1 to round down in favour of the pool
+		let target_amount = balances[j].checked_sub(y)?.checked_sub(1)? / pool_info.precisions[j];
+		let fee = pool_info.fee_rate.saturating_mul_int(target_amount);
+		Some(target_amount.saturating_sub(fee))
+	}
+
+	/// The supply amount of the `i`th currency of the pool to swap
+	/// `target_amount` of the `j`th currency.
+	fn get_supply_amount(
+		pool_info: &PoolInfo<T::BlockNumber>,
+		i: usize,
+		j: usize,
+		target_amount: Balance,
+	) -> Option<Balance> {
+		let amplification = pool_info.amplification(frame_system::Pallet::<T>::block_number());
+		let balances = pool_info.normalized_balances()?;
+		let invariant = get_invariant(&balances, amplification)?;
+
+		// the target amount before the fee is charged, rounded up
+		let accuracy = Rate::accuracy();
+		let fee_free_part = accuracy.checked_sub(pool_info.fee_rate.into_inner())?;
+		let target_amount_with_fee = mul_div(target_amount, accuracy, fee_free_part)?.checked_add(1)?;
+
+		let y = balances[j].checked_sub(
+			target_amount_with_fee
+				.checked_add(1)?
+				.checked_mul(pool_info.precisions[j])?,
+		)?;
+		let x = get_balance(j, i, y, &balances, amplification, invariant)?;
+		// plus 1 to round up in favour of the pool
+		let supply_amount = x.checked_sub(balances[i])? / pool_info.precisions[i];
+		supply_amount.checked_add(1)
+	}
+
+	/// The pools and the amounts of every swap along `path`.
+	fn get_swap_amounts(
+		path: &[CurrencyId],
+		limit: SwapLimit<Balance>,
+	) -> Option<(Vec<(PoolId, PoolInfo<T::BlockNumber>)>, Vec<Balance>)> {
+		let path_length = path.len();
+		if path_length < 2 || path_length > T::TradingPathLimit::get() as usize {
+			return None;
+		}
+
+		let mut pools: Vec<(PoolId, PoolInfo<T::BlockNumber>)> = vec![];
+		for pair in path.windows(2) {
+			let (pool_id, pool_info) = Self::pool_of(pair[0], pair[1])?;
+			// swapping with a pool twice is meaningless
+			if pools.iter().any(|(id, _)| *id == pool_id) {
+				return None;
+			}
+			pools.push((pool_id, pool_info));
+		}
+
+		let mut amounts: Vec<Balance> = vec![Zero::zero(); path_length];
+		match limit {
+			SwapLimit::ExactSupply(supply_amount, min_target_amount) => {
+				amounts[0] = supply_amount;
+				for (k, (_, pool_info)) in pools.iter().enumerate() {
+					let i = pool_info.index_of(path[k])?;
+					let j = pool_info.index_of(path[k + 1])?;
+					amounts[k + 1] = Self::get_target_amount(pool_info, i, j, amounts[k])?;
+				}
+				if amounts[path_length - 1].is_zero() || amounts[path_length - 1] < min_target_amount {
+					return None;
+				}
+			}
+			SwapLimit::ExactTarget(max_supply_amount, target_amount) => {
+				amounts[path_length - 1] = target_amount;
+				for (k, (_, pool_info)) in pools.iter().enumerate().rev() {
+					let i = pool_info.index_of(path[k])?;
+					let j = pool_info.index_of(path[k + 1])?;
+					amounts[k] = Self::get_supply_amount(pool_info, i, j, amounts[k + 1])?;
+				}
+				if amounts[0].is_zero() || amounts[0] > max_supply_amount {
+					return None;
+				}
+			}
+		}
+
+		Some((pools, amounts))
+	}
+
+	#[transactional]
+	fn do_swap(
+		who: &T::AccountId,
+		path: &[CurrencyId],
+		limit: SwapLimit<Balance>,
+	) -> sp_std::result::Result<(Balance, Balance), DispatchError> {
+		let (pools, amounts) = Self::get_swap_amounts(path, limit).ok_or_else(|| match limit {
+			SwapLimit::ExactSupply(..) => Error::<T>::InsufficientTargetAmount,
+			SwapLimit::ExactTarget(..) => Error::<T>::ExcessiveSupplyAmount,
+		})?;
+
+		let pool_account = Self::account_id();
+		let last = path.len() - 1;
+		T::Currency::transfer(path[0], who, &pool_account, amounts[0])?;
+		for (k, (pool_id, mut pool_info)) in pools.into_iter().enumerate() {
+			let i = pool_info.index_of(path[k]).ok_or(Error::<T>::InvalidTradingPath)?;
+			let j = pool_info.index_of(path[k + 1]).ok_or(Error::<T>::InvalidTradingPath)?;
+			pool_info.balances[i] = pool_info.balances[i]
+				.checked_add(amounts[k])
+				.ok_or(ArithmeticError::Overflow)?;
+			pool_info.balances[j] = pool_info.balances[j]
+				.checked_sub(amounts[k + 1])
+				.ok_or(Error::<T>::InsufficientLiquidity)?;
+			Pools::<T>::insert(pool_id, pool_info);
+		}
+		T::Currency::transfer(path[last], &pool_account, who, amounts[last])?;
+
+		Self::deposit_event(Event::Swap(who.clone(), path.to_vec(), amounts[0], amounts[last]));
+		Ok((amounts[0], amounts[last]))
+	}
+
+	/// Check that pool ids are below `NextPoolId`, every pool issues its own
+	/// share and every trading pair points to an existing pool.
+	#[cfg(feature = "try-runtime")]
+	pub fn check_invariants() -> Result<(), &'static str> {
+		let next_pool_id = Self::next_pool_id();
+		for (pool_id, pool_info) in Pools::<T>::iter() {
+			ensure!(pool_id < next_pool_id, "Pool id is not below NextPoolId");
+			ensure!(
+				pool_info.share_currency_id == CurrencyId::StableAssetPoolToken(pool_id),
+				"Pool share is not the share of the pool"
+			);
+		}
+		for (_, pool_id) in TradingPairPools::<T>::iter() {
+			ensure!(
+				Pools::<T>::contains_key(pool_id),
+				"Trading pair points to a missing pool"
+			);
+		}
+		Ok(())
+	}
+}
+
+/// Normalize `balances` to the same decimals by `precisions`.
+fn normalize(balances: &[Balance], precisions: &[Balance]) -> Option<Vec<Balance>> {
+	balances
+		.iter()
+		.zip(precisions.iter())
+		.map(|(balance, precision)| balance.checked_mul(*precision))
+		.collect()
+}
+
+/// Calculate `a * b / c` without intermediate overflow.
+fn mul_div(a: Balance, b: Balance, c: Balance) -> Option<Balance> {
+	let result = U256::from(a).checked_mul(U256::from(b))?.checked_div(U256::from(c))?;
+	Balance::try_from(result).ok()
+}
+
+fn abs_diff(a: U256, b: U256) -> U256 {
+	if a > b {
+		a - b
+	} else {
+		b - a
+	}
+}
+
+/// Calculate the StableSwap invariant D of the normalized `balances`:
+///
+/// A * n^n * sum(x_i) + D = A * n^n * D + D^(n + 1) / (n^n * prod(x_i))
+fn get_invariant(balances: &[Balance], amplification: u128) -> Option<Balance> {
+	let n = U256::from(balances.len());
+	let sum = balances
+		.iter()
+		.try_fold(U256::zero(), |acc, balance| acc.checked_add(U256::from(*balance)))?;
+	if sum.is_zero() {
+		return Some(Zero::zero());
+	}
+
+	let ann = U256::from(amplification).checked_mul(n)?;
+	let mut d = sum;
+	for _ in 0..MAX_ITERATIONS {
+		let mut d_p = d;
+		for balance in balances {
+			d_p = d_p.checked_mul(d)?.checked_div(U256::from(*balance).checked_mul(n)?)?;
+		}
+		let prev_d = d;
+		let numerator = ann.checked_mul(sum)?.checked_add(d_p.checked_mul(n)?)?.checked_mul(d)?;
+		let denominator = ann
+			.checked_sub(U256::one())?
+			.checked_mul(d)?
+			.checked_add(n.checked_add(U256::one())?.checked_mul(d_p)?)?;
+		d = numerator.checked_div(denominator)?;
+
+		if abs_diff(d, prev_d) <= U256::one() {
+			return Balance::try_from(d).ok();
+		}
+	}
+
+	None
+}
+
+/// Calculate the normalized balance of the `j`th currency which keeps the
+/// `invariant`, after the normalized balance of the `i`th currency becomes
+/// `x`.
+fn get_balance(
+	i: usize,
+	j: usize,
+	x: Balance,
+	balances: &[Balance],
+	amplification: u128,
+	invariant: Balance,
+) -> Option<Balance> {
+	if i == j || i >= balances.len() || j >= balances.len() {
+		return None;
+	}
+
+	let n = U256::from(balances.len());
+	let d = U256::from(invariant);
+	let ann = U256::from(amplification).checked_mul(n)?;
+	let mut c = d;
+	let mut sum = U256::zero();
+	for (k, balance) in balances.iter().enumerate() {
+		if k == j {
+			continue;
+		}
+		let x_k = U256::from(if k == i { x } else { *balance });
+		sum = sum.checked_add(x_k)?;
+		c = c.checked_mul(d)?.checked_div(x_k.checked_mul(n)?)?;
+	}
+	c = c.checked_mul(d)?.checked_div(ann.checked_mul(n)?)?;
+	let b = sum.checked_add(d.checked_div(ann)?)?;
+
+	let mut y = d;
+	for _ in 0..MAX_ITERATIONS {
+		let prev_y = y;
+		// y = (y^2 + c) / (2 * y + b - D)
+		y = y
+			.checked_mul(y)?
+			.checked_add(c)?
+			.checked_div(y.checked_mul(U256::from(2u8))?.checked_add(b)?.checked_sub(d)?)?;
+
+		if abs_diff(y, prev_y) <= U256::one() {
+			return Balance::try_from(y).ok();
+		}
+	}
+
+	None
+}
+
+impl<T: Config> DEXManager<T::AccountId, CurrencyId, Balance> for Pallet<T> {
+	fn get_liquidity_pool(currency_id_a: CurrencyId, currency_id_b: CurrencyId) -> (Balance, Balance) {
+		Self::pool_of(currency_id_a, currency_id_b)
+			.and_then(|(_, pool_info)| {
+				let i = pool_info.index_of(currency_id_a)?;
+				let j = pool_info.index_of(currency_id_b)?;
+				Some((pool_info.balances[i], pool_info.balances[j]))
+			})
+			.unwrap_or_default()
+	}
+
+	fn get_liquidity_token_address(currency_id_a: CurrencyId, currency_id_b: CurrencyId) -> Option<H160> {
+		let (_, pool_info) = Self::pool_of(currency_id_a, currency_id_b)?;
+		T::CurrencyIdMapping::encode_evm_address(pool_info.share_currency_id)
+	}
+
+	fn get_swap_amount(path: &[CurrencyId], limit: SwapLimit<Balance>) -> Option<(Balance, Balance)> {
+		Self::get_swap_amounts(path, limit).map(|(_, amounts)| (amounts[0], amounts[amounts.len() - 1]))
+	}
+
+	fn get_best_price_swap_path(
+		supply_currency_id: CurrencyId,
+		target_currency_id: CurrencyId,
+		limit: SwapLimit<Balance>,
+		alternative_path_joint_list: Vec<Vec<CurrencyId>>,
+	) -> Option<Vec<CurrencyId>> {
+		let default_trading_path = vec![supply_currency_id, target_currency_id];
+		let mut maybe_best = Self::get_swap_amount(&default_trading_path, limit)
+			.map(|(supply_amount, target_amount)| (default_trading_path, supply_amount, target_amount));
+
+		for path_joint in alternative_path_joint_list {
+			if !path_joint.is_empty() {
+				let mut trading_path: Vec<CurrencyId> = vec![supply_currency_id];
+				trading_path.extend(path_joint);
+				trading_path.push(target_currency_id);
+
+				if let Some((supply_amount, target_amount)) = Self::get_swap_amount(&trading_path, limit) {
+					if let Some((_, best_supply_amount, best_target_amount)) = maybe_best {
+						let is_better = match limit {
+							SwapLimit::ExactSupply(..) => target_amount > best_target_amount,
+							SwapLimit::ExactTarget(..) => supply_amount < best_supply_amount,
+						};
+						if !is_better {
+							continue;
+						}
+					}
+					maybe_best = Some((trading_path, supply_amount, target_amount));
+				}
+			}
+		}
+
+		maybe_best.map(|(trading_path, _, _)| trading_path)
+	}
+
+	fn swap_with_specific_path(
+		who: &T::AccountId,
+		path: &[CurrencyId],
+		limit: SwapLimit<Balance>,
+	) -> sp_std::result::Result<(Balance, Balance), DispatchError> {
+		Self::do_swap(who, path, limit)
+	}
+
+	fn buyback_swap_with_specific_path(
+		who: &T::AccountId,
+		path: &[CurrencyId],
+		limit: SwapLimit<Balance>,
+	) -> sp_std::result::Result<(Balance, Balance), DispatchError> {
+		Self::do_swap(who, path, limit)
+	}
+
+	fn swap_with_exact_target(
+		who: &T::AccountId,
+		path: &[CurrencyId],
+		exact_target_amount: Balance,
+		max_supply_amount: Balance,
+	) -> DispatchResult {
+		Self::do_swap(
+			who,
+			path,
+			SwapLimit::ExactTarget(max_supply_amount, exact_target_amount),
+		)?;
+		Ok(())
+	}
+
+	// Add liquidity of the two currencies only, the other currencies of the
+	// pool are not supplied.
+	fn add_liquidity(
+		who: &T::AccountId,
+		currency_id_a: CurrencyId,
+		currency_id_b: CurrencyId,
+		max_amount_a: Balance,
+		max_amount_b: Balance,
+		min_share_increment: Balance,
+	) -> sp_std::result::Result<(Balance, Balance, Balance), DispatchError> {
+		let (pool_id, pool_info) = Self::pool_of(currency_id_a, currency_id_b).ok_or(Error::<T>::PoolNotFound)?;
+		let i = pool_info.index_of(currency_id_a).ok_or(Error::<T>::InvalidCurrencies)?;
+		let j = pool_info.index_of(currency_id_b).ok_or(Error::<T>::InvalidCurrencies)?;
+		let mut amounts = vec![Zero::zero(); pool_info.currency_ids.len()];
+		amounts[i] = max_amount_a;
+		amounts[j] = max_amount_b;
+
+		let share_increment = Self::do_add_liquidity(who, pool_id, amounts, min_share_increment)?;
+		Ok((max_amount_a, max_amount_b, share_increment))
+	}
+
+	// Liquidity is removed in proportion to all the currencies of the pool,
+	// the amounts of the other currencies are withdrawn as well.
+	fn remove_liquidity(
+		who: &T::AccountId,
+		currency_id_a: CurrencyId,
+		currency_id_b: CurrencyId,
+		remove_share: Balance,
+		min_withdrawn_a: Balance,
+		min_withdrawn_b: Balance,
+	) -> sp_std::result::Result<(Balance, Balance), DispatchError> {
+		let (pool_id, pool_info) = Self::pool_of(currency_id_a, currency_id_b).ok_or(Error::<T>::PoolNotFound)?;
+		let i = pool_info.index_of(currency_id_a).ok_or(Error::<T>::InvalidCurrencies)?;
+		let j = pool_info.index_of(currency_id_b).ok_or(Error::<T>::InvalidCurrencies)?;
+		let mut min_withdrawn_amounts = vec![Zero::zero(); pool_info.currency_ids.len()];
+		min_withdrawn_amounts[i] = min_withdrawn_a;
+		min_withdrawn_amounts[j] = min_withdrawn_b;
+
+		let amounts = Self::do_remove_liquidity(who, pool_id, remove_share, min_withdrawn_amounts)?;
+		Ok((amounts[i], amounts[j]))
+	}
+}
@@ -0,0 +1,554 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Unit tests for the stable swap module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok};
+use mock::{Event, *};
+use sp_runtime::traits::BadOrigin;
+
+fn fee_rate() -> Rate {
+	Rate::saturating_from_rational(1, 1000)
+}
+
+fn create_pool(amplification: u128) {
+	assert_ok!(StableSwapModule::create_pool(
+		Origin::signed(ALICE),
+		vec![SETR, USSD, GRA],
+		vec![1, 1, 1],
+		fee_rate(),
+		amplification
+	));
+}
+
+#[test]
+fn create_pool_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			StableSwapModule::create_pool(Origin::signed(BOB), vec![SETR, USSD], vec![1, 1], fee_rate(), 100),
+			BadOrigin
+		);
+		assert_noop!(
+			StableSwapModule::create_pool(Origin::signed(ALICE), vec![SETR], vec![1], fee_rate(), 100),
+			Error::<Runtime>::InvalidCurrencies
+		);
+		assert_noop!(
+			StableSwapModule::create_pool(Origin::signed(ALICE), vec![SETR, SETR], vec![1, 1], fee_rate(), 100),
+			Error::<Runtime>::InvalidCurrencies
+		);
+		assert_noop!(
+			StableSwapModule::create_pool(
+				Origin::signed(ALICE),
+				vec![SETR, SETR_USSD_LP],
+				vec![1, 1],
+				fee_rate(),
+				100
+			),
+			Error::<Runtime>::InvalidCurrencies
+		);
+		assert_noop!(
+			StableSwapModule::create_pool(Origin::signed(ALICE), vec![SETR, USSD], vec![1, 0], fee_rate(), 100),
+			Error::<Runtime>::InvalidPrecisions
+		);
+		assert_noop!(
+			StableSwapModule::create_pool(Origin::signed(ALICE), vec![SETR, USSD], vec![1, 1], Rate::one(), 100),
+			Error::<Runtime>::InvalidFeeRate
+		);
+		assert_noop!(
+			StableSwapModule::create_pool(Origin::signed(ALICE), vec![SETR, USSD], vec![1, 1], fee_rate(), 0),
+			Error::<Runtime>::InvalidAmplification
+		);
+		assert_noop!(
+			StableSwapModule::create_pool(
+				Origin::signed(ALICE),
+				vec![SETR, USSD],
+				vec![1, 1],
+				fee_rate(),
+				MaxAmplification::get() + 1
+			),
+			Error::<Runtime>::InvalidAmplification
+		);
+
+		create_pool(100);
+		System::assert_last_event(Event::StableSwapModule(crate::Event::PoolCreated(
+			0,
+			vec![SETR, USSD, GRA],
+			POOL_0_SHARE,
+		)));
+		assert_eq!(StableSwapModule::next_pool_id(), 1);
+		assert_eq!(
+			StableSwapModule::pools(0),
+			Some(PoolInfo {
+				currency_ids: vec![SETR, USSD, GRA],
+				precisions: vec![1, 1, 1],
+				balances: vec![0, 0, 0],
+				share_currency_id: POOL_0_SHARE,
+				fee_rate: fee_rate(),
+				initial_amplification: 100,
+				future_amplification: 100,
+				initial_amplification_block: 1,
+				future_amplification_block: 1,
+			})
+		);
+		let trading_pair = TradingPair::from_currency_ids(USSD, GRA).unwrap();
+		assert_eq!(
+			StableSwapModule::trading_pair_pools((trading_pair.first(), trading_pair.second())),
+			Some(0)
+		);
+
+		assert_noop!(
+			StableSwapModule::create_pool(Origin::signed(ALICE), vec![GRA, SETR], vec![1, 1], fee_rate(), 100),
+			Error::<Runtime>::TradingPairAlreadyPooled
+		);
+	});
+}
+
+#[test]
+fn ramp_amplification_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			StableSwapModule::ramp_amplification(Origin::signed(ALICE), 0, 200, 11),
+			Error::<Runtime>::PoolNotFound
+		);
+		create_pool(100);
+
+		assert_noop!(
+			StableSwapModule::ramp_amplification(Origin::signed(BOB), 0, 200, 11),
+			BadOrigin
+		);
+		assert_noop!(
+			StableSwapModule::ramp_amplification(Origin::signed(ALICE), 0, 200, 1),
+			Error::<Runtime>::InvalidRampingBlock
+		);
+		assert_noop!(
+			StableSwapModule::ramp_amplification(Origin::signed(ALICE), 0, 1001, 11),
+			Error::<Runtime>::InvalidAmplification
+		);
+		assert_noop!(
+			StableSwapModule::ramp_amplification(Origin::signed(ALICE), 0, 9, 11),
+			Error::<Runtime>::InvalidAmplification
+		);
+
+		assert_ok!(StableSwapModule::ramp_amplification(Origin::signed(ALICE), 0, 200, 11));
+		System::assert_last_event(Event::StableSwapModule(crate::Event::AmplificationRamping(
+			0, 100, 200, 1, 11,
+		)));
+		let pool_info = StableSwapModule::pools(0).unwrap();
+		assert_eq!(pool_info.amplification(1), 100);
+		assert_eq!(pool_info.amplification(6), 150);
+		assert_eq!(pool_info.amplification(11), 200);
+		assert_eq!(pool_info.amplification(20), 200);
+
+		// ramp down from the current amplification
+		System::set_block_number(6);
+		assert_ok!(StableSwapModule::ramp_amplification(Origin::signed(ALICE), 0, 50, 16));
+		System::assert_last_event(Event::StableSwapModule(crate::Event::AmplificationRamping(
+			0, 150, 50, 6, 16,
+		)));
+		let pool_info = StableSwapModule::pools(0).unwrap();
+		assert_eq!(pool_info.amplification(11), 100);
+		assert_eq!(pool_info.amplification(16), 50);
+	});
+}
+
+#[test]
+fn add_liquidity_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			StableSwapModule::add_liquidity(Origin::signed(ALICE), 0, vec![DOLLARS, DOLLARS], 0),
+			Error::<Runtime>::PoolNotFound
+		);
+		create_pool(100);
+		let pool_account = StableSwapModule::account_id();
+
+		assert_noop!(
+			StableSwapModule::add_liquidity(Origin::signed(ALICE), 0, vec![DOLLARS, DOLLARS], 0),
+			Error::<Runtime>::InvalidAmounts
+		);
+		// the initial liquidity must provide all the currencies
+		assert_noop!(
+			StableSwapModule::add_liquidity(Origin::signed(ALICE), 0, vec![DOLLARS, DOLLARS, 0], 0),
+			Error::<Runtime>::InvalidAmounts
+		);
+
+		assert_ok!(StableSwapModule::add_liquidity(
+			Origin::signed(ALICE),
+			0,
+			vec![1_000 * DOLLARS, 1_000 * DOLLARS, 1_000 * DOLLARS],
+			0
+		));
+		System::assert_last_event(Event::StableSwapModule(crate::Event::AddLiquidity(
+			ALICE,
+			0,
+			vec![1_000 * DOLLARS, 1_000 * DOLLARS, 1_000 * DOLLARS],
+			3_000 * DOLLARS,
+		)));
+		assert_eq!(Tokens::free_balance(POOL_0_SHARE, &ALICE), 3_000 * DOLLARS);
+		assert_eq!(Tokens::free_balance(SETR, &pool_account), 1_000 * DOLLARS);
+		assert_eq!(Tokens::free_balance(SETR, &ALICE), 999_000 * DOLLARS);
+		assert_eq!(
+			StableSwapModule::pools(0).unwrap().balances,
+			vec![1_000 * DOLLARS, 1_000 * DOLLARS, 1_000 * DOLLARS]
+		);
+
+		// balanced liquidity is not charged
+		assert_noop!(
+			StableSwapModule::add_liquidity(
+				Origin::signed(BOB),
+				0,
+				vec![100 * DOLLARS, 100 * DOLLARS, 100 * DOLLARS],
+				300 * DOLLARS + 1
+			),
+			Error::<Runtime>::InsufficientShareIncrement
+		);
+		assert_ok!(StableSwapModule::add_liquidity(
+			Origin::signed(BOB),
+			0,
+			vec![100 * DOLLARS, 100 * DOLLARS, 100 * DOLLARS],
+			300 * DOLLARS
+		));
+		assert_eq!(Tokens::free_balance(POOL_0_SHARE, &BOB), 300 * DOLLARS);
+
+		// imbalanced liquidity is charged
+		assert_ok!(StableSwapModule::add_liquidity(
+			Origin::signed(BOB),
+			0,
+			vec![300 * DOLLARS, 0, 0],
+			0
+		));
+		let share_increment = Tokens::free_balance(POOL_0_SHARE, &BOB) - 300 * DOLLARS;
+		assert!(share_increment < 300 * DOLLARS);
+		assert!(share_increment > 290 * DOLLARS);
+		assert_eq!(
+			StableSwapModule::pools(0).unwrap().balances,
+			vec![1_400 * DOLLARS, 1_100 * DOLLARS, 1_100 * DOLLARS]
+		);
+	});
+}
+
+#[test]
+fn remove_liquidity_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		create_pool(100);
+		assert_ok!(StableSwapModule::add_liquidity(
+			Origin::signed(ALICE),
+			0,
+			vec![1_000 * DOLLARS, 1_000 * DOLLARS, 1_000 * DOLLARS],
+			0
+		));
+
+		assert_noop!(
+			StableSwapModule::remove_liquidity(Origin::signed(ALICE), 0, 0, vec![0, 0, 0]),
+			Error::<Runtime>::InvalidShareAmount
+		);
+		assert_noop!(
+			StableSwapModule::remove_liquidity(Origin::signed(ALICE), 0, 3_000 * DOLLARS + 1, vec![0, 0, 0]),
+			Error::<Runtime>::InvalidShareAmount
+		);
+		assert_noop!(
+			StableSwapModule::remove_liquidity(Origin::signed(ALICE), 0, 300 * DOLLARS, vec![0, 0]),
+			Error::<Runtime>::InvalidAmounts
+		);
+		assert_noop!(
+			StableSwapModule::remove_liquidity(Origin::signed(ALICE), 0, 300 * DOLLARS, vec![0, 0, 100 * DOLLARS + 1]),
+			Error::<Runtime>::InsufficientWithdrawnAmount
+		);
+
+		assert_ok!(StableSwapModule::remove_liquidity(
+			Origin::signed(ALICE),
+			0,
+			300 * DOLLARS,
+			vec![100 * DOLLARS, 100 * DOLLARS, 100 * DOLLARS]
+		));
+		System::assert_last_event(Event::StableSwapModule(crate::Event::RemoveLiquidity(
+			ALICE,
+			0,
+			300 * DOLLARS,
+			vec![100 * DOLLARS, 100 * DOLLARS, 100 * DOLLARS],
+		)));
+		assert_eq!(Tokens::free_balance(POOL_0_SHARE, &ALICE), 2_700 * DOLLARS);
+		assert_eq!(Tokens::free_balance(GRA, &ALICE), 999_100 * DOLLARS);
+		assert_eq!(
+			StableSwapModule::pools(0).unwrap().balances,
+			vec![900 * DOLLARS, 900 * DOLLARS, 900 * DOLLARS]
+		);
+	});
+}
+
+#[test]
+fn swap_with_exact_supply_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		create_pool(100);
+		assert_ok!(StableSwapModule::add_liquidity(
+			Origin::signed(ALICE),
+			0,
+			vec![1_000 * DOLLARS, 1_000 * DOLLARS, 1_000 * DOLLARS],
+			0
+		));
+
+		assert_noop!(
+			StableSwapModule::swap_with_exact_supply(Origin::signed(BOB), vec![SETR, SEE], 10 * DOLLARS, 0),
+			Error::<Runtime>::InsufficientTargetAmount
+		);
+		assert_noop!(
+			StableSwapModule::swap_with_exact_supply(Origin::signed(BOB), vec![SETR, USSD], 10 * DOLLARS, 10 * DOLLARS),
+			Error::<Runtime>::InsufficientTargetAmount
+		);
+
+		let (_, target_amount) =
+			StableSwapModule::get_swap_amount(&[SETR, USSD], SwapLimit::ExactSupply(10 * DOLLARS, 0)).unwrap();
+		// far less slippage than the 10 / 1010 of the constant product pool
+		assert!(target_amount < 10 * DOLLARS);
+		assert!(target_amount > 9_980_000_000_000_000_000);
+
+		assert_ok!(StableSwapModule::swap_with_exact_supply(
+			Origin::signed(BOB),
+			vec![SETR, USSD],
+			10 * DOLLARS,
+			target_amount
+		));
+		System::assert_last_event(Event::StableSwapModule(crate::Event::Swap(
+			BOB,
+			vec![SETR, USSD],
+			10 * DOLLARS,
+			target_amount,
+		)));
+		assert_eq!(Tokens::free_balance(SETR, &BOB), 999_990 * DOLLARS);
+		assert_eq!(Tokens::free_balance(USSD, &BOB), 1_000_000 * DOLLARS + target_amount);
+		assert_eq!(
+			StableSwapModule::pools(0).unwrap().balances,
+			vec![1_010 * DOLLARS, 1_000 * DOLLARS - target_amount, 1_000 * DOLLARS]
+		);
+	});
+}
+
+#[test]
+fn swap_with_exact_target_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		create_pool(100);
+		assert_ok!(StableSwapModule::add_liquidity(
+			Origin::signed(ALICE),
+			0,
+			vec![1_000 * DOLLARS, 1_000 * DOLLARS, 1_000 * DOLLARS],
+			0
+		));
+
+		let (supply_amount, _) =
+			StableSwapModule::get_swap_amount(&[USSD, GRA], SwapLimit::ExactTarget(Balance::max_value(), 10 * DOLLARS))
+				.unwrap();
+		assert!(supply_amount > 10 * DOLLARS);
+		assert!(supply_amount < 10_020_000_000_000_000_000);
+		// the supply amount is enough to swap the target amount
+		assert!(
+			StableSwapModule::get_swap_amount(&[USSD, GRA], SwapLimit::ExactSupply(supply_amount, 0))
+				.unwrap()
+				.1 >= 10 * DOLLARS
+		);
+
+		assert_noop!(
+			StableSwapModule::swap_with_exact_target(
+				Origin::signed(BOB),
+				vec![USSD, GRA],
+				10 * DOLLARS,
+				supply_amount - 1
+			),
+			Error::<Runtime>::ExcessiveSupplyAmount
+		);
+		assert_ok!(StableSwapModule::swap_with_exact_target(
+			Origin::signed(BOB),
+			vec![USSD, GRA],
+			10 * DOLLARS,
+			supply_amount
+		));
+		System::assert_last_event(Event::StableSwapModule(crate::Event::Swap(
+			BOB,
+			vec![USSD, GRA],
+			supply_amount,
+			10 * DOLLARS,
+		)));
+		assert_eq!(Tokens::free_balance(USSD, &BOB), 1_000_000 * DOLLARS - supply_amount);
+		assert_eq!(Tokens::free_balance(GRA, &BOB), 1_000_010 * DOLLARS);
+	});
+}
+
+#[test]
+fn higher_amplification_has_less_slippage() {
+	ExtBuilder::default().build().execute_with(|| {
+		create_pool(10);
+		assert_ok!(StableSwapModule::add_liquidity(
+			Origin::signed(ALICE),
+			0,
+			vec![1_000 * DOLLARS, 1_000 * DOLLARS, 1_000 * DOLLARS],
+			0
+		));
+		let (_, target_amount_with_low_amplification) =
+			StableSwapModule::get_swap_amount(&[SETR, USSD], SwapLimit::ExactSupply(100 * DOLLARS, 0)).unwrap();
+
+		assert_ok!(StableSwapModule::ramp_amplification(Origin::signed(ALICE), 0, 100, 11));
+		System::set_block_number(11);
+		let (_, target_amount_with_high_amplification) =
+			StableSwapModule::get_swap_amount(&[SETR, USSD], SwapLimit::ExactSupply(100 * DOLLARS, 0)).unwrap();
+
+		assert!(target_amount_with_high_amplification > target_amount_with_low_amplification);
+	});
+}
+
+#[test]
+fn dex_manager_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(StableSwapModule::create_pool(
+			Origin::signed(ALICE),
+			vec![SETR, USSD],
+			vec![1, 1],
+			fee_rate(),
+			100
+		));
+		assert_ok!(StableSwapModule::create_pool(
+			Origin::signed(ALICE),
+			vec![USSD, GRA],
+			vec![1, 1],
+			fee_rate(),
+			100
+		));
+		assert_eq!(StableSwapModule::get_liquidity_pool(SETR, USSD), (0, 0));
+
+		assert_ok!(<StableSwapModule as DEXManager<_, _, _>>::add_liquidity(
+			&ALICE,
+			SETR,
+			USSD,
+			1_000 * DOLLARS,
+			1_000 * DOLLARS,
+			0
+		));
+		assert_ok!(<StableSwapModule as DEXManager<_, _, _>>::add_liquidity(
+			&ALICE,
+			GRA,
+			USSD,
+			2_000 * DOLLARS,
+			1_000 * DOLLARS,
+			0
+		));
+		assert_eq!(
+			StableSwapModule::get_liquidity_pool(USSD, SETR),
+			(1_000 * DOLLARS, 1_000 * DOLLARS)
+		);
+		assert_eq!(
+			StableSwapModule::get_liquidity_pool(GRA, USSD),
+			(2_000 * DOLLARS, 1_000 * DOLLARS)
+		);
+		assert_eq!(StableSwapModule::get_liquidity_pool(SETR, GRA), (0, 0));
+
+		// a pool can not be swapped with twice
+		assert_eq!(
+			StableSwapModule::get_swap_amount(&[SETR, USSD, SETR], SwapLimit::ExactSupply(DOLLARS, 0)),
+			None
+		);
+		// exceed the trading path limit
+		assert_eq!(
+			StableSwapModule::get_swap_amount(&[SETR, USSD, GRA, USSD], SwapLimit::ExactSupply(DOLLARS, 0)),
+			None
+		);
+		assert_eq!(
+			StableSwapModule::get_best_price_swap_path(SETR, GRA, SwapLimit::ExactSupply(DOLLARS, 0), vec![]),
+			None
+		);
+		assert_eq!(
+			StableSwapModule::get_best_price_swap_path(SETR, GRA, SwapLimit::ExactSupply(DOLLARS, 0), vec![vec![USSD]]),
+			Some(vec![SETR, USSD, GRA])
+		);
+
+		let (supply_amount, target_amount) =
+			StableSwapModule::get_swap_amount(&[SETR, USSD, GRA], SwapLimit::ExactSupply(DOLLARS, 0)).unwrap();
+		assert_eq!(
+			StableSwapModule::swap_with_specific_path(&BOB, &[SETR, USSD, GRA], SwapLimit::ExactSupply(DOLLARS, 0)),
+			Ok((supply_amount, target_amount))
+		);
+		assert_eq!(Tokens::free_balance(GRA, &BOB), 1_000_000 * DOLLARS + target_amount);
+		assert_eq!(Tokens::free_balance(USSD, &BOB), 1_000_000 * DOLLARS);
+
+		assert_ok!(<StableSwapModule as DEXManager<_, _, _>>::swap_with_exact_target(
+			&BOB,
+			&[GRA, USSD],
+			DOLLARS,
+			2 * DOLLARS
+		));
+		assert_eq!(Tokens::free_balance(USSD, &BOB), 1_000_001 * DOLLARS);
+
+		// remove all the liquidity
+		let share = Tokens::free_balance(POOL_0_SHARE, &ALICE);
+		let balances = StableSwapModule::pools(0).unwrap().balances;
+		assert_eq!(
+			<StableSwapModule as DEXManager<_, _, _>>::remove_liquidity(&ALICE, USSD, SETR, share, 0, 0),
+			Ok((balances[1], balances[0]))
+		);
+		assert_eq!(StableSwapModule::get_liquidity_pool(SETR, USSD), (0, 0));
+	});
+}
+
+#[test]
+fn pool_shares_are_issued_per_pool() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(StableSwapModule::create_pool(
+			Origin::signed(ALICE),
+			vec![SETR, USSD],
+			vec![1, 1],
+			fee_rate(),
+			100
+		));
+		assert_ok!(StableSwapModule::create_pool(
+			Origin::signed(ALICE),
+			vec![USSD, GRA],
+			vec![1, 1],
+			fee_rate(),
+			100
+		));
+		assert_eq!(StableSwapModule::pools(1).unwrap().share_currency_id, POOL_1_SHARE);
+
+		// the dex shares of the same currencies don't affect the pool
+		assert_ok!(Tokens::deposit(SETR_USSD_LP, &BOB, 1_000 * DOLLARS));
+
+		assert_ok!(StableSwapModule::add_liquidity(
+			Origin::signed(ALICE),
+			0,
+			vec![1_000 * DOLLARS, 1_000 * DOLLARS],
+			0
+		));
+		assert_eq!(Tokens::free_balance(POOL_0_SHARE, &ALICE), 2_000 * DOLLARS);
+		assert_eq!(Tokens::total_issuance(POOL_0_SHARE), 2_000 * DOLLARS);
+
+		assert_ok!(StableSwapModule::add_liquidity(
+			Origin::signed(ALICE),
+			1,
+			vec![500 * DOLLARS, 500 * DOLLARS],
+			0
+		));
+		assert_eq!(Tokens::free_balance(POOL_1_SHARE, &ALICE), 1_000 * DOLLARS);
+		assert_eq!(Tokens::total_issuance(POOL_0_SHARE), 2_000 * DOLLARS);
+		assert_eq!(Tokens::total_issuance(SETR_USSD_LP), 1_000 * DOLLARS);
+
+		// the shares of a pool can't remove the liquidity of another one
+		assert_noop!(
+			StableSwapModule::remove_liquidity(Origin::signed(ALICE), 1, 2_000 * DOLLARS, vec![0, 0]),
+			Error::<Runtime>::InvalidShareAmount
+		);
+	});
+}
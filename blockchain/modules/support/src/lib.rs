@@ -25,12 +25,13 @@ use codec::{Decode, Encode, FullCodec};
 use frame_support::pallet_prelude::{DispatchClass, Pays, Weight};
 use primitives::{
 	Balance as AsBalance,
-	CampaignId, CurrencyId,
+	CampaignId, CurrencyId, Nonce,
+	currency::AssetMetadata,
 	evm::{CallInfo, EvmAddress},
 	task::TaskResult
 };
 use scale_info::TypeInfo;
-use sp_core::H160;
+use sp_core::{H160, H256};
 use sp_runtime::{
 	traits::{AtLeast32BitUnsigned, CheckedDiv, MaybeSerializeDeserialize},
 	transaction_validity::TransactionValidityError,
@@ -100,8 +101,43 @@ pub trait AuctionManager<AccountId> {
 	fn get_total_target_in_auction() -> Self::Balance;
 }
 
+/// No auctions, creating one fails.
+impl<AccountId> AuctionManager<AccountId> for () {
+	type CurrencyId = CurrencyId;
+	type Balance = AsBalance;
+	type AuctionId = u32;
+
+	fn new_collateral_auction(
+		_refund_recipient: &AccountId,
+		_currency_id: Self::CurrencyId,
+		_amount: Self::Balance,
+		_target: Self::Balance,
+	) -> DispatchResult {
+		Err(DispatchError::Other("NoAuctionManager"))
+	}
+
+	fn cancel_auction(_id: Self::AuctionId) -> DispatchResult {
+		Err(DispatchError::Other("NoAuctionManager"))
+	}
+
+	fn get_total_collateral_in_auction(_id: Self::CurrencyId) -> Self::Balance {
+		Default::default()
+	}
+
+	fn get_total_target_in_auction() -> Self::Balance {
+		Default::default()
+	}
+}
+
+/// Never shut down.
+impl EmergencyShutdown for () {
+	fn is_shutdown() -> bool {
+		false
+	}
+}
+
 /// The Structure of a Campaign info.
-#[cfg_attr(feature = "std", derive(PartialEq, Eq, Encode, Decode, Debug, Clone))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
 pub struct CampaignInfo<AccountId, Balance, BlockNumber> {
 	/// The Campaign Id
 	pub id: CampaignId,
@@ -123,19 +159,18 @@ pub struct CampaignInfo<AccountId, Balance, BlockNumber> {
 	pub raise_currency: CurrencyId,
 	/// Currency type (Token) for crowdsale
 	pub sale_token: CurrencyId,
-	/// Crowdsale Token Price - Amount of raise_currency per sale_token
-	pub token_price: Balance,
+	/// Crowdsale Token Price - Amount of raise_currency per sale_token, in their smallest units
+	pub token_price: Price,
 	/// Crowdsale Token amount for sale
 	pub crowd_allocation: Balance,
+	/// Crowdsale Token amount sold to contributors
+	pub sold: Balance,
 	/// The Fundraise Goal - HardCap
 	pub goal: Balance,
 	/// The Fundraise Amount raised - HardCap
 	pub raised: Balance,
 	/// The number of contributors to the campaign
 	pub contributors_count: u32,
-	/// The Campaign contributions
-	/// account_id, contribution, allocation, bool:claimed_allocation
-	pub contributions: Vec<(AccountId, Balance, Balance, bool)>,
 	/// The period that the campaign runs for.
 	pub period: BlockNumber,
 	/// The time when the campaign starts.
@@ -180,7 +215,7 @@ pub trait Proposal<AccountId, BlockNumber> {
 		beneficiary: AccountId,
 		raise_currency: CurrencyId,
 		sale_token: CurrencyId,
-		token_price: AsBalance,
+		token_price: Price,
 		crowd_allocation: AsBalance,
 		goal: AsBalance,
 		period: BlockNumber,
@@ -377,6 +412,22 @@ where
 	}
 }
 
+/// An abstraction of liquidity mining incentives for DexShare LP tokens.
+pub trait IncentivesManager<AccountId, Balance, CurrencyId> {
+	/// Reward amount of `reward_currency_id` emitted per block to the pool of `lp_currency_id`
+	fn get_reward_rate(lp_currency_id: CurrencyId, reward_currency_id: CurrencyId) -> Balance;
+	/// LP shares of `lp_currency_id` deposited by `who`
+	fn get_deposited_shares(lp_currency_id: CurrencyId, who: &AccountId) -> Balance;
+	/// Accrued but unclaimed rewards of `reward_currency_id` for `who`
+	fn get_pending_rewards(lp_currency_id: CurrencyId, who: &AccountId, reward_currency_id: CurrencyId) -> Balance;
+	/// Deposit LP shares into the pool to start accruing rewards
+	fn deposit_dex_share(who: &AccountId, lp_currency_id: CurrencyId, amount: Balance) -> DispatchResult;
+	/// Withdraw deposited LP shares from the pool
+	fn withdraw_dex_share(who: &AccountId, lp_currency_id: CurrencyId, amount: Balance) -> DispatchResult;
+	/// Claim all accrued rewards of the pool
+	fn claim_rewards(who: &AccountId, lp_currency_id: CurrencyId) -> DispatchResult;
+}
+
 /// An abstraction of serp treasury for the SERP (Setheum Elastic Reserve Protocol).
 pub trait SerpTreasury<AccountId> {
 	type Balance;
@@ -546,8 +597,15 @@ pub trait ExchangeRateProvider {
 }
 
 /// Dispatchable tasks
-pub trait DispatchableTask {
-	fn dispatch(self, weight: Weight) -> TaskResult;
+pub trait DispatchableTask: Sized {
+	/// Dispatch the task within `weight`. An unfinished task may return its
+	/// updated state, which is dispatched next time instead of the original
+	/// one, so long-running tasks resume from their progress.
+	fn dispatch(self, weight: Weight) -> (TaskResult, Option<Self>);
+
+	/// Clean up the state of the task when it is dropped unfinished, after
+	/// too many failed retries or a cancellation.
+	fn on_dropped(self) {}
 }
 
 /// Idle scheduler trait
@@ -555,9 +613,19 @@ pub trait IdleScheduler<Task> {
 	fn schedule(task: Task) -> DispatchResult;
 }
 
+/// Idle scheduler of the tasks scheduled by accounts, who reserve a deposit for
+/// each of them.
+pub trait SignedIdleScheduler<AccountId> {
+	/// Schedule the SCALE encoded `task` of `who` with `priority`, returns the
+	/// task id.
+	fn schedule_signed(who: &AccountId, task: Vec<u8>, priority: u8) -> Result<Nonce, DispatchError>;
+	/// Cancel the task `task_id` of `who`, refunding the deposit.
+	fn cancel_signed(who: &AccountId, task_id: Nonce) -> DispatchResult;
+}
+
 #[cfg(feature = "std")]
 impl DispatchableTask for () {
-	fn dispatch(self, _weight: Weight) -> TaskResult {
+	fn dispatch(self, _weight: Weight) -> (TaskResult, Option<Self>) {
 		unimplemented!()
 	}
 }
@@ -569,6 +637,17 @@ impl<Task> IdleScheduler<Task> for () {
 	}
 }
 
+#[cfg(feature = "std")]
+impl<AccountId> SignedIdleScheduler<AccountId> for () {
+	fn schedule_signed(_who: &AccountId, _task: Vec<u8>, _priority: u8) -> Result<Nonce, DispatchError> {
+		unimplemented!()
+	}
+
+	fn cancel_signed(_who: &AccountId, _task_id: Nonce) -> DispatchResult {
+		unimplemented!()
+	}
+}
+
 pub trait EmergencyShutdown {
 	fn is_shutdown() -> bool;
 }
@@ -662,6 +741,19 @@ impl<AccountId, Balance: Default> EVMBridge<AccountId, Balance> for () {
 	fn set_origin(_origin: AccountId) {}
 }
 
+/// Filter of the paused EVM contract calls.
+pub trait EvmCallFilter {
+	/// Whether calls to the `contract` are paused, either the whole contract or the function
+	/// `selector`. `selector` is `None` for calls without a function selector.
+	fn is_call_paused(contract: &H160, selector: Option<[u8; 4]>) -> bool;
+}
+
+impl EvmCallFilter for () {
+	fn is_call_paused(_contract: &H160, _selector: Option<[u8; 4]>) -> bool {
+		false
+	}
+}
+
 /// An abstraction of EVMStateRentTrait
 pub trait EVMStateRentTrait<AccountId, Balance> {
 	/// Query the constants `NewContractExtraBytes` value from evm module.
@@ -676,10 +768,20 @@ pub trait EVMStateRentTrait<AccountId, Balance> {
 	fn query_deployment_fee() -> Balance;
 	/// Transfer the maintainer of the contract address.
 	fn transfer_maintainer(from: AccountId, contract: H160, new_maintainer: H160) -> DispatchResult;
+	/// Query the storage bytes used by the contract.
+	fn query_storage_usage(contract: H160) -> Result<u32, DispatchError>;
+	/// Query the storage deposit reserved by the contract.
+	fn query_storage_deposit(contract: H160) -> Result<Balance, DispatchError>;
+	/// Query the proposed code upgrade of the contract, the code hash and the block number from
+	/// which it can be applied.
+	fn query_pending_upgrade(contract: H160) -> Option<(H256, u32)>;
 }
 
 pub trait TransactionPayment<AccountId, Balance, NegativeImbalance> {
 	fn reserve_fee(who: &AccountId, weight: Weight) -> Result<Balance, DispatchError>;
+	/// Reserve the fee of `count` dispatches of `weight` at once, returning the fee
+	/// of one dispatch.
+	fn reserve_fees(who: &AccountId, weight: Weight, count: u32) -> Result<Balance, DispatchError>;
 	fn unreserve_fee(who: &AccountId, fee: Balance);
 	fn unreserve_and_charge_fee(
 		who: &AccountId,
@@ -706,6 +808,10 @@ impl<AccountId, Balance: Default + Copy, NegativeImbalance: Imbalance<Balance>>
 		Ok(Default::default())
 	}
 
+	fn reserve_fees(_who: &AccountId, _weight: Weight, _count: u32) -> Result<Balance, DispatchError> {
+		Ok(Default::default())
+	}
+
 	fn unreserve_fee(_who: &AccountId, _fee: Balance) {}
 
 	fn unreserve_and_charge_fee(
@@ -818,3 +924,27 @@ impl CurrencyIdMapping for () {
 		None
 	}
 }
+
+/// The assets registered at runtime by the asset registry.
+pub trait AssetMetadataProvider {
+	/// Returns the metadata of a registered asset.
+	fn asset_metadata(currency_id: CurrencyId) -> Option<AssetMetadata<AsBalance>>;
+	/// Returns the EVM address mirroring a registered asset.
+	fn mirrored_evm_address(currency_id: CurrencyId) -> Option<EvmAddress>;
+	/// Returns the registered asset mirrored at an EVM address.
+	fn mirrored_currency_id(address: EvmAddress) -> Option<CurrencyId>;
+}
+
+impl AssetMetadataProvider for () {
+	fn asset_metadata(_currency_id: CurrencyId) -> Option<AssetMetadata<AsBalance>> {
+		None
+	}
+
+	fn mirrored_evm_address(_currency_id: CurrencyId) -> Option<EvmAddress> {
+		None
+	}
+
+	fn mirrored_currency_id(_address: EvmAddress) -> Option<CurrencyId> {
+		None
+	}
+}
@@ -0,0 +1,581 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! # Swap Orders Module
+//!
+//! ## Overview
+//!
+//! Swap orders module allows users to place orders which are executed on
+//! the DEX later, when the chain is idle. The supply amount of an order is
+//! reserved when it is placed, and an `idle_scheduler` task checks the order
+//! until it is completed, cancelled or expired. The task is scheduled on
+//! behalf of the owner, who reserves the idle task deposit until the task is
+//! removed, and is subject to the task limits of the idle scheduler.
+//!
+//! - Limit order: swap the supply amount for at least the minimum target
+//!   amount, once the price of the DEX meets it before the expiry.
+//! - TWAP order: split a large swap into slices executed every `interval`
+//!   blocks, each slice for at least its share of the minimum target amount.
+//!
+//! The supply of the orders is reserved with `ReserveIdentifier::SwapOrders`,
+//! and the remaining supply of cancelled and expired orders is unreserved, as
+//! well as the one of the orders whose task is dropped by the idle scheduler.
+//! An account can have at most `MaxOrdersPerAccount` pending orders, each
+//! expiring within `MaxOrderDuration` blocks.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{pallet_prelude::*, transactional, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound};
+use frame_system::pallet_prelude::*;
+use orml_traits::NamedMultiReservableCurrency;
+use primitives::{task::TaskResult, Balance, CurrencyId, Nonce, ReserveIdentifier};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	ArithmeticError, DispatchError, DispatchResult, RuntimeDebug,
+};
+use sp_std::prelude::*;
+use support::{DEXManager, DispatchableTask, SignedIdleScheduler, SwapLimit};
+
+mod mock;
+mod tests;
+pub mod weights;
+
+pub use module::*;
+pub use weights::WeightInfo;
+
+/// The id of a swap order.
+pub type OrderId = u32;
+
+/// The kind of a swap order.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum OrderKind<BlockNumber> {
+	/// Swap all the supply amount at once when the price is met.
+	Limit,
+	/// Swap the supply amount in slices every `interval` blocks.
+	Twap {
+		/// The number of the slices.
+		slices: u32,
+		/// The number of the executed slices.
+		executed_slices: u32,
+		/// The blocks between the slices.
+		interval: BlockNumber,
+		/// The block number from which the next slice can be executed.
+		next_execution_block: BlockNumber,
+	},
+}
+
+/// Information of a swap order.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct OrderInfo<AccountId, BlockNumber> {
+	/// The owner of the order.
+	pub owner: AccountId,
+	/// The currency to supply.
+	pub supply_currency_id: CurrencyId,
+	/// The currency to receive.
+	pub target_currency_id: CurrencyId,
+	/// The remaining supply amount, reserved from the owner.
+	pub supply_amount: Balance,
+	/// The remaining minimum target amount for the remaining supply amount.
+	pub min_target_amount: Balance,
+	/// The order expires after this block.
+	pub expiry: BlockNumber,
+	/// The kind of the order.
+	pub kind: OrderKind<BlockNumber>,
+	/// The idle scheduler task checking the order.
+	pub task_id: Nonce,
+}
+
+/// The `idle_scheduler` task of the swap orders.
+#[derive(Encode, Decode, RuntimeDebugNoBound, CloneNoBound, PartialEqNoBound, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub enum SwapOrderTask<T: Config> {
+	/// Execute the order if the price is met, or expire it.
+	#[codec(index = 0)]
+	ExecuteOrder { order_id: OrderId, owner: T::AccountId },
+}
+
+impl<T: Config> DispatchableTask for SwapOrderTask<T> {
+	fn dispatch(self, weight: Weight) -> (TaskResult, Option<Self>) {
+		match self {
+			SwapOrderTask::ExecuteOrder { order_id, owner } => {
+				let used_weight = <T as Config>::WeightInfo::execute_order();
+				if weight < used_weight {
+					let task_result = TaskResult {
+						result: Ok(()),
+						used_weight: Zero::zero(),
+						finished: false,
+					};
+					return (task_result, None);
+				}
+
+				// the progress of the order is kept in `Orders`, the task itself
+				// never changes.
+				let (result, finished) = Pallet::<T>::try_execute_order(order_id, &owner);
+				let task_result = TaskResult {
+					result,
+					used_weight,
+					finished,
+				};
+				(task_result, None)
+			}
+		}
+	}
+
+	fn on_dropped(self) {
+		match self {
+			SwapOrderTask::ExecuteOrder { order_id, owner } => Pallet::<T>::drop_order(order_id, &owner),
+		}
+	}
+}
+
+#[frame_support::pallet]
+pub mod module {
+	use super::*;
+
+	pub const RESERVE_ID: ReserveIdentifier = ReserveIdentifier::SwapOrders;
+
+	/// The idle scheduler priority of the order tasks, the highest one
+	/// signed origins can use.
+	pub const TASK_PRIORITY: u8 = 128;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Currency for reserving the supply amount of the orders.
+		type Currency: NamedMultiReservableCurrency<
+			Self::AccountId,
+			ReserveIdentifier = ReserveIdentifier,
+			CurrencyId = CurrencyId,
+			Balance = Balance,
+		>;
+
+		/// DEX to execute the orders.
+		type DEX: DEXManager<Self::AccountId, CurrencyId, Balance>;
+
+		/// The task type of the idle scheduler.
+		type Task: From<SwapOrderTask<Self>> + Encode;
+
+		/// The idle scheduler to check the orders, reserving the task deposit
+		/// from the owner of the order.
+		type IdleScheduler: SignedIdleScheduler<Self::AccountId>;
+
+		/// The max number of the slices of a TWAP order.
+		#[pallet::constant]
+		type MaxTwapSlices: Get<u32>;
+
+		/// The max number of the pending orders of an account.
+		#[pallet::constant]
+		type MaxOrdersPerAccount: Get<u32>;
+
+		/// The max number of blocks from now an order can expire after.
+		#[pallet::constant]
+		type MaxOrderDuration: Get<Self::BlockNumber>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The supply and target currencies are invalid
+		InvalidCurrencies,
+		/// The amount is invalid
+		InvalidAmount,
+		/// The expiry is invalid
+		InvalidExpiry,
+		/// The number of the slices is invalid
+		InvalidSlices,
+		/// The interval is invalid
+		InvalidInterval,
+		/// The order does not exist
+		OrderNotFound,
+		/// The order is not owned by the caller
+		NoPermission,
+		/// The account has too many pending orders
+		TooManyOrders,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An order is placed. \[order_id, owner, supply_currency_id,
+		/// target_currency_id, supply_amount, min_target_amount\]
+		OrderPlaced(OrderId, T::AccountId, CurrencyId, CurrencyId, Balance, Balance),
+		/// An order, or a slice of it, is executed. \[order_id, supply_amount,
+		/// target_amount\]
+		OrderExecuted(OrderId, Balance, Balance),
+		/// An order is completely executed. \[order_id\]
+		OrderCompleted(OrderId),
+		/// An order is cancelled, the remaining supply is unreserved.
+		/// \[order_id, unreserved_amount\]
+		OrderCancelled(OrderId, Balance),
+		/// An order is expired, the remaining supply is unreserved.
+		/// \[order_id, unreserved_amount\]
+		OrderExpired(OrderId, Balance),
+		/// The task of an order is dropped, the remaining supply is
+		/// unreserved. \[order_id, unreserved_amount\]
+		OrderDropped(OrderId, Balance),
+	}
+
+	/// The id of the next order.
+	///
+	/// NextOrderId: OrderId
+	#[pallet::storage]
+	#[pallet::getter(fn next_order_id)]
+	pub type NextOrderId<T: Config> = StorageValue<_, OrderId, ValueQuery>;
+
+	/// The pending orders.
+	///
+	/// Orders: map OrderId => Option<OrderInfo>
+	#[pallet::storage]
+	#[pallet::getter(fn orders)]
+	pub type Orders<T: Config> =
+		StorageMap<_, Twox64Concat, OrderId, OrderInfo<T::AccountId, T::BlockNumber>, OptionQuery>;
+
+	/// The number of the pending orders of an account.
+	///
+	/// AccountOrderCounts: map AccountId => u32
+	#[pallet::storage]
+	#[pallet::getter(fn account_order_counts)]
+	pub type AccountOrderCounts<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			Self::check_invariants()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			Self::check_invariants()
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Place a limit order to swap `supply_amount` of `supply_currency_id`
+		/// for at least `min_target_amount` of `target_currency_id` before
+		/// `expiry`.
+		///
+		/// - `supply_currency_id`: the currency to supply.
+		/// - `target_currency_id`: the currency to receive.
+		/// - `supply_amount`: the amount to supply, reserved until the order
+		///   is executed, cancelled or expired.
+		/// - `min_target_amount`: the minimum amount to receive.
+		/// - `expiry`: the order expires after this block.
+		#[pallet::weight(<T as Config>::WeightInfo::place_limit_order())]
+		#[transactional]
+		pub fn place_limit_order(
+			origin: OriginFor<T>,
+			supply_currency_id: CurrencyId,
+			target_currency_id: CurrencyId,
+			#[pallet::compact] supply_amount: Balance,
+			#[pallet::compact] min_target_amount: Balance,
+			expiry: T::BlockNumber,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_place_order(
+				who,
+				supply_currency_id,
+				target_currency_id,
+				supply_amount,
+				min_target_amount,
+				expiry,
+				OrderKind::Limit,
+			)
+		}
+
+		/// Place a TWAP order to swap `supply_amount` of `supply_currency_id`
+		/// in `slices` equal slices, one every `interval` blocks, for at least
+		/// `min_target_amount` of `target_currency_id` in total. A slice is
+		/// executed once the price meets its share of `min_target_amount`.
+		///
+		/// - `supply_currency_id`: the currency to supply.
+		/// - `target_currency_id`: the currency to receive.
+		/// - `supply_amount`: the total amount to supply, reserved until the
+		///   order is executed, cancelled or expired.
+		/// - `min_target_amount`: the total minimum amount to receive.
+		/// - `slices`: the number of the slices.
+		/// - `interval`: the blocks between the slices.
+		/// - `expiry`: the order expires after this block, the unexecuted
+		///   slices are unreserved.
+		#[pallet::weight(<T as Config>::WeightInfo::place_twap_order())]
+		#[transactional]
+		#[allow(clippy::too_many_arguments)]
+		pub fn place_twap_order(
+			origin: OriginFor<T>,
+			supply_currency_id: CurrencyId,
+			target_currency_id: CurrencyId,
+			#[pallet::compact] supply_amount: Balance,
+			#[pallet::compact] min_target_amount: Balance,
+			slices: u32,
+			interval: T::BlockNumber,
+			expiry: T::BlockNumber,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(
+				slices >= 2 && slices <= T::MaxTwapSlices::get(),
+				Error::<T>::InvalidSlices
+			);
+			ensure!(!interval.is_zero(), Error::<T>::InvalidInterval);
+			ensure!(supply_amount >= Balance::from(slices), Error::<T>::InvalidAmount);
+
+			// the expiry must leave time for all the slices
+			let now = frame_system::Pallet::<T>::block_number();
+			let last_execution_block = now.saturating_add(interval.saturating_mul(T::BlockNumber::from(slices - 1)));
+			ensure!(expiry >= last_execution_block, Error::<T>::InvalidExpiry);
+
+			Self::do_place_order(
+				who,
+				supply_currency_id,
+				target_currency_id,
+				supply_amount,
+				min_target_amount,
+				expiry,
+				OrderKind::Twap {
+					slices,
+					executed_slices: 0,
+					interval,
+					next_execution_block: now,
+				},
+			)
+		}
+
+		/// Cancel an order, unreserve its remaining supply and refund the
+		/// deposit of its task.
+		///
+		/// The dispatch origin of this call must be the owner of the order.
+		///
+		/// - `order_id`: the order to cancel.
+		#[pallet::weight(<T as Config>::WeightInfo::cancel_order())]
+		#[transactional]
+		pub fn cancel_order(origin: OriginFor<T>, order_id: OrderId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let order = Self::orders(order_id).ok_or(Error::<T>::OrderNotFound)?;
+			ensure!(order.owner == who, Error::<T>::NoPermission);
+
+			Self::remove_order(order_id, &order);
+			T::IdleScheduler::cancel_signed(&who, order.task_id)?;
+			Self::deposit_event(Event::OrderCancelled(order_id, order.supply_amount));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	#[transactional]
+	fn do_place_order(
+		who: T::AccountId,
+		supply_currency_id: CurrencyId,
+		target_currency_id: CurrencyId,
+		supply_amount: Balance,
+		min_target_amount: Balance,
+		expiry: T::BlockNumber,
+		kind: OrderKind<T::BlockNumber>,
+	) -> DispatchResult {
+		ensure!(supply_currency_id != target_currency_id, Error::<T>::InvalidCurrencies);
+		ensure!(!supply_amount.is_zero(), Error::<T>::InvalidAmount);
+		let now = frame_system::Pallet::<T>::block_number();
+		ensure!(
+			expiry >= now && expiry <= now.saturating_add(T::MaxOrderDuration::get()),
+			Error::<T>::InvalidExpiry
+		);
+
+		let count = Self::account_order_counts(&who);
+		ensure!(count < T::MaxOrdersPerAccount::get(), Error::<T>::TooManyOrders);
+		AccountOrderCounts::<T>::insert(&who, count.saturating_add(1));
+
+		T::Currency::reserve_named(&RESERVE_ID, supply_currency_id, &who, supply_amount)?;
+
+		let order_id = NextOrderId::<T>::try_mutate(|id| -> sp_std::result::Result<OrderId, DispatchError> {
+			let current_id = *id;
+			*id = id.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+			Ok(current_id)
+		})?;
+		let task: T::Task = SwapOrderTask::ExecuteOrder {
+			order_id,
+			owner: who.clone(),
+		}
+		.into();
+		let task_id = T::IdleScheduler::schedule_signed(&who, task.encode(), TASK_PRIORITY)?;
+		Orders::<T>::insert(
+			order_id,
+			OrderInfo {
+				owner: who.clone(),
+				supply_currency_id,
+				target_currency_id,
+				supply_amount,
+				min_target_amount,
+				expiry,
+				kind,
+				task_id,
+			},
+		);
+
+		Self::deposit_event(Event::OrderPlaced(
+			order_id,
+			who,
+			supply_currency_id,
+			target_currency_id,
+			supply_amount,
+			min_target_amount,
+		));
+		Ok(())
+	}
+
+	/// Execute the order, or the next slice of it, if the price is met.
+	/// Returns the result and whether the task of the order is finished.
+	fn try_execute_order(order_id: OrderId, owner: &T::AccountId) -> (DispatchResult, bool) {
+		let order = match Self::orders(order_id) {
+			Some(order) if order.owner == *owner => order,
+			// the order is cancelled
+			_ => return (Ok(()), true),
+		};
+
+		let now = frame_system::Pallet::<T>::block_number();
+		if now > order.expiry {
+			Self::remove_order(order_id, &order);
+			Self::deposit_event(Event::OrderExpired(order_id, order.supply_amount));
+			return (Ok(()), true);
+		}
+
+		let (supply_amount, min_target_amount) = match order.kind {
+			OrderKind::Limit => (order.supply_amount, order.min_target_amount),
+			OrderKind::Twap {
+				slices,
+				executed_slices,
+				next_execution_block,
+				..
+			} => {
+				if now < next_execution_block {
+					return (Ok(()), false);
+				}
+				let remaining_slices = Balance::from(slices.saturating_sub(executed_slices));
+				if remaining_slices <= 1 {
+					(order.supply_amount, order.min_target_amount)
+				} else {
+					(
+						order.supply_amount / remaining_slices,
+						order.min_target_amount / remaining_slices,
+					)
+				}
+			}
+		};
+
+		let path = [order.supply_currency_id, order.target_currency_id];
+		let price_met = T::DEX::get_swap_amount(&path, SwapLimit::ExactSupply(supply_amount, min_target_amount))
+			.map_or(false, |(_, target_amount)| target_amount >= min_target_amount);
+		if !price_met {
+			return (Ok(()), false);
+		}
+
+		match Self::execute_order(order_id, order, supply_amount, min_target_amount, now) {
+			Ok(completed) => (Ok(()), completed),
+			Err(e) => (Err(e), false),
+		}
+	}
+
+	/// Swap `supply_amount` of the order at `now`. Returns whether the order
+	/// is completed. The next slice of a TWAP order is due `interval` blocks
+	/// after `now`, so that a delayed slice does not make the following ones
+	/// due at once.
+	#[transactional]
+	fn execute_order(
+		order_id: OrderId,
+		mut order: OrderInfo<T::AccountId, T::BlockNumber>,
+		supply_amount: Balance,
+		min_target_amount: Balance,
+		now: T::BlockNumber,
+	) -> sp_std::result::Result<bool, DispatchError> {
+		T::Currency::unreserve_named(&RESERVE_ID, order.supply_currency_id, &order.owner, supply_amount);
+		let (_, target_amount) = T::DEX::swap_with_specific_path(
+			&order.owner,
+			&[order.supply_currency_id, order.target_currency_id],
+			SwapLimit::ExactSupply(supply_amount, min_target_amount),
+		)?;
+		Self::deposit_event(Event::OrderExecuted(order_id, supply_amount, target_amount));
+
+		order.supply_amount = order.supply_amount.saturating_sub(supply_amount);
+		order.min_target_amount = order.min_target_amount.saturating_sub(min_target_amount);
+		if let OrderKind::Twap {
+			ref mut executed_slices,
+			interval,
+			ref mut next_execution_block,
+			..
+		} = order.kind
+		{
+			*executed_slices = executed_slices.saturating_add(1);
+			*next_execution_block = now.saturating_add(interval);
+		}
+
+		if order.supply_amount.is_zero() {
+			Self::remove_order(order_id, &order);
+			Self::deposit_event(Event::OrderCompleted(order_id));
+			Ok(true)
+		} else {
+			Orders::<T>::insert(order_id, order);
+			Ok(false)
+		}
+	}
+
+	/// Remove the order and unreserve its remaining supply.
+	fn remove_order(order_id: OrderId, order: &OrderInfo<T::AccountId, T::BlockNumber>) {
+		Orders::<T>::remove(order_id);
+		AccountOrderCounts::<T>::mutate_exists(&order.owner, |maybe_count| {
+			let count = maybe_count.unwrap_or_default().saturating_sub(1);
+			*maybe_count = if count.is_zero() { None } else { Some(count) };
+		});
+		T::Currency::unreserve_named(&RESERVE_ID, order.supply_currency_id, &order.owner, order.supply_amount);
+	}
+
+	/// Remove the order whose task is dropped by the idle scheduler, which
+	/// would never be executed or expired otherwise.
+	fn drop_order(order_id: OrderId, owner: &T::AccountId) {
+		if let Some(order) = Self::orders(order_id).filter(|order| order.owner == *owner) {
+			Self::remove_order(order_id, &order);
+			Self::deposit_event(Event::OrderDropped(order_id, order.supply_amount));
+		}
+	}
+
+	/// Check that order ids are below `NextOrderId` and the order counts match
+	/// the orders.
+	#[cfg(feature = "try-runtime")]
+	pub fn check_invariants() -> Result<(), &'static str> {
+		let next_order_id = Self::next_order_id();
+		let mut counts = sp_std::collections::btree_map::BTreeMap::<T::AccountId, u32>::new();
+		for (order_id, order) in Orders::<T>::iter() {
+			ensure!(order_id < next_order_id, "Order id is not below NextOrderId");
+			*counts.entry(order.owner).or_default() += 1;
+		}
+		for (owner, count) in AccountOrderCounts::<T>::iter() {
+			ensure!(
+				counts.remove(&owner) == Some(count),
+				"AccountOrderCounts does not match Orders"
+			);
+		}
+		ensure!(counts.is_empty(), "Order owner without AccountOrderCounts");
+		Ok(())
+	}
+}
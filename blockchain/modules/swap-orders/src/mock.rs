@@ -0,0 +1,334 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Mocks for the swap orders module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{construct_runtime, parameter_types, traits::Contains};
+use frame_system::EnsureRoot;
+use orml_traits::{parameter_type_with_key, MultiCurrency};
+use primitives::{define_combined_task, Amount, ReserveIdentifier, TokenSymbol};
+use sp_core::H160;
+use sp_runtime::{testing::Header, traits::IdentityLookup};
+use sp_std::cell::RefCell;
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const DEX_ACCOUNT: AccountId = 100;
+pub const SEE: CurrencyId = CurrencyId::Token(TokenSymbol::SEE);
+pub const USSD: CurrencyId = CurrencyId::Token(TokenSymbol::USSD);
+
+mod swap_orders {
+	pub use super::super::*;
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Runtime {
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = Call;
+	type Hash = sp_core::H256;
+	type Hashing = ::sp_runtime::traits::BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type DbWeight = ();
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_type_with_key! {
+	pub ExistentialDeposits: |_currency_id: CurrencyId| -> Balance {
+		Default::default()
+	};
+}
+
+impl orml_tokens::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type Amount = Amount;
+	type CurrencyId = CurrencyId;
+	type WeightInfo = ();
+	type ExistentialDeposits = ExistentialDeposits;
+	type OnDust = ();
+	type MaxLocks = ();
+	type MaxReserves = MaxReserves;
+	type ReserveIdentifier = ReserveIdentifier;
+	type DustRemovalWhitelist = ();
+}
+
+define_combined_task! {
+	#[derive(Clone, Debug, PartialEq, Encode, Decode, TypeInfo)]
+	pub enum ScheduledTasks {
+		SwapOrderTask(SwapOrderTask<Runtime>),
+	}
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+	pub const MaxReserves: u32 = 50;
+}
+
+impl pallet_balances::Config for Runtime {
+	type Balance = Balance;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = frame_system::Pallet<Runtime>;
+	type MaxLocks = ();
+	type MaxReserves = MaxReserves;
+	type ReserveIdentifier = ReserveIdentifier;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MinimumWeightRemainInBlock: Weight = 0;
+	pub const MaxTaskRetries: u32 = 3;
+	pub const TaskRetryBackoff: BlockNumber = 10;
+	pub const TaskDeposit: Balance = 10;
+	pub const MaxTasksPerAccount: u32 = 10;
+	pub const MaxTasks: u32 = 100;
+}
+
+pub struct SignedTaskFilter;
+impl Contains<(AccountId, ScheduledTasks)> for SignedTaskFilter {
+	fn contains((who, task): &(AccountId, ScheduledTasks)) -> bool {
+		matches!(
+			task,
+			ScheduledTasks::SwapOrderTask(SwapOrderTask::ExecuteOrder { owner, .. }) if owner == who
+		)
+	}
+}
+
+impl idle_scheduler::Config for Runtime {
+	type Event = Event;
+	type WeightInfo = ();
+	type Task = ScheduledTasks;
+	type MinimumWeightRemainInBlock = MinimumWeightRemainInBlock;
+	type MaxTaskRetries = MaxTaskRetries;
+	type TaskRetryBackoff = TaskRetryBackoff;
+	type Currency = Balances;
+	type TaskDeposit = TaskDeposit;
+	type MaxTasksPerAccount = MaxTasksPerAccount;
+	type MaxTasks = MaxTasks;
+	type SignedTaskFilter = SignedTaskFilter;
+	type GovernanceOrigin = EnsureRoot<AccountId>;
+}
+
+thread_local! {
+	static PRICE: RefCell<(Balance, Balance)> = RefCell::new((1, 1));
+}
+
+/// Set the price of the supply currency in the target currency.
+pub fn set_price(numerator: Balance, denominator: Balance) {
+	PRICE.with(|v| *v.borrow_mut() = (numerator, denominator));
+}
+
+pub struct MockDEX;
+impl MockDEX {
+	fn target_amount(supply_amount: Balance) -> Balance {
+		PRICE.with(|v| {
+			let (numerator, denominator) = *v.borrow();
+			supply_amount * numerator / denominator
+		})
+	}
+}
+
+impl DEXManager<AccountId, CurrencyId, Balance> for MockDEX {
+	fn get_liquidity_pool(_currency_id_a: CurrencyId, _currency_id_b: CurrencyId) -> (Balance, Balance) {
+		unimplemented!()
+	}
+
+	fn get_liquidity_token_address(_currency_id_a: CurrencyId, _currency_id_b: CurrencyId) -> Option<H160> {
+		unimplemented!()
+	}
+
+	fn get_swap_amount(_path: &[CurrencyId], limit: SwapLimit<Balance>) -> Option<(Balance, Balance)> {
+		match limit {
+			SwapLimit::ExactSupply(supply_amount, min_target_amount) => {
+				let target_amount = Self::target_amount(supply_amount);
+				if target_amount >= min_target_amount {
+					Some((supply_amount, target_amount))
+				} else {
+					None
+				}
+			}
+			SwapLimit::ExactTarget(..) => unimplemented!(),
+		}
+	}
+
+	fn get_best_price_swap_path(
+		_supply_currency_id: CurrencyId,
+		_target_currency_id: CurrencyId,
+		_limit: SwapLimit<Balance>,
+		_alternative_path_joint_list: Vec<Vec<CurrencyId>>,
+	) -> Option<Vec<CurrencyId>> {
+		unimplemented!()
+	}
+
+	fn swap_with_specific_path(
+		who: &AccountId,
+		path: &[CurrencyId],
+		limit: SwapLimit<Balance>,
+	) -> sp_std::result::Result<(Balance, Balance), DispatchError> {
+		let (supply_amount, target_amount) =
+			Self::get_swap_amount(path, limit).ok_or(DispatchError::Other("insufficient target amount"))?;
+		Tokens::transfer(path[0], who, &DEX_ACCOUNT, supply_amount)?;
+		Tokens::transfer(path[path.len() - 1], &DEX_ACCOUNT, who, target_amount)?;
+		Ok((supply_amount, target_amount))
+	}
+
+	fn buyback_swap_with_specific_path(
+		_who: &AccountId,
+		_path: &[CurrencyId],
+		_limit: SwapLimit<Balance>,
+	) -> sp_std::result::Result<(Balance, Balance), DispatchError> {
+		unimplemented!()
+	}
+
+	fn swap_with_exact_target(
+		_who: &AccountId,
+		_path: &[CurrencyId],
+		_exact_target_amount: Balance,
+		_max_supply_amount: Balance,
+	) -> DispatchResult {
+		unimplemented!()
+	}
+
+	fn add_liquidity(
+		_who: &AccountId,
+		_currency_id_a: CurrencyId,
+		_currency_id_b: CurrencyId,
+		_max_amount_a: Balance,
+		_max_amount_b: Balance,
+		_min_share_increment: Balance,
+	) -> sp_std::result::Result<(Balance, Balance, Balance), DispatchError> {
+		unimplemented!()
+	}
+
+	fn remove_liquidity(
+		_who: &AccountId,
+		_currency_id_a: CurrencyId,
+		_currency_id_b: CurrencyId,
+		_remove_share: Balance,
+		_min_withdrawn_a: Balance,
+		_min_withdrawn_b: Balance,
+	) -> sp_std::result::Result<(Balance, Balance), DispatchError> {
+		unimplemented!()
+	}
+}
+
+parameter_types! {
+	pub const MaxTwapSlices: u32 = 10;
+	pub const MaxOrdersPerAccount: u32 = 2;
+	pub const MaxOrderDuration: BlockNumber = 1_000;
+}
+
+impl Config for Runtime {
+	type Event = Event;
+	type Currency = Tokens;
+	type DEX = MockDEX;
+	type Task = ScheduledTasks;
+	type IdleScheduler = IdleScheduler;
+	type MaxTwapSlices = MaxTwapSlices;
+	type MaxOrdersPerAccount = MaxOrdersPerAccount;
+	type MaxOrderDuration = MaxOrderDuration;
+	type WeightInfo = ();
+}
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Storage, Config, Event<T>},
+		SwapOrdersModule: swap_orders::{Pallet, Storage, Call, Event<T>},
+		Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		IdleScheduler: idle_scheduler::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+pub struct ExtBuilder {
+	balances: Vec<(AccountId, CurrencyId, Balance)>,
+}
+
+impl Default for ExtBuilder {
+	fn default() -> Self {
+		Self {
+			balances: vec![
+				(ALICE, SEE, 1_000),
+				(BOB, SEE, 1_000),
+				(DEX_ACCOUNT, SEE, 1_000_000),
+				(DEX_ACCOUNT, USSD, 1_000_000),
+			],
+		}
+	}
+}
+
+impl ExtBuilder {
+	pub fn build(self) -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default()
+			.build_storage::<Runtime>()
+			.unwrap();
+
+		orml_tokens::GenesisConfig::<Runtime> {
+			balances: self.balances,
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+		pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![(ALICE, 100), (BOB, 100)],
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			set_price(1, 1);
+		});
+		ext
+	}
+}
@@ -0,0 +1,463 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! Unit tests for the swap orders module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok, traits::ReservableCurrency};
+use mock::{Event, *};
+use orml_traits::MultiCurrency;
+
+fn run_idle_tasks() {
+	IdleScheduler::on_idle(System::block_number(), 1_000_000_000_000);
+}
+
+#[test]
+fn place_limit_order_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			SwapOrdersModule::place_limit_order(Origin::signed(ALICE), SEE, SEE, 100, 100, 10),
+			Error::<Runtime>::InvalidCurrencies
+		);
+		assert_noop!(
+			SwapOrdersModule::place_limit_order(Origin::signed(ALICE), SEE, USSD, 0, 100, 10),
+			Error::<Runtime>::InvalidAmount
+		);
+		assert_noop!(
+			SwapOrdersModule::place_limit_order(Origin::signed(ALICE), SEE, USSD, 100, 100, 0),
+			Error::<Runtime>::InvalidExpiry
+		);
+		assert_noop!(
+			SwapOrdersModule::place_limit_order(Origin::signed(ALICE), SEE, USSD, 100, 100, 1_002),
+			Error::<Runtime>::InvalidExpiry
+		);
+		assert_noop!(
+			SwapOrdersModule::place_limit_order(Origin::signed(ALICE), SEE, USSD, 1_001, 100, 10),
+			orml_tokens::Error::<Runtime>::BalanceTooLow
+		);
+		// the idle task deposit can not be reserved
+		assert_noop!(
+			SwapOrdersModule::place_limit_order(Origin::signed(DEX_ACCOUNT), SEE, USSD, 100, 100, 10),
+			pallet_balances::Error::<Runtime>::InsufficientBalance
+		);
+
+		assert_ok!(SwapOrdersModule::place_limit_order(
+			Origin::signed(ALICE),
+			SEE,
+			USSD,
+			100,
+			200,
+			10
+		));
+		System::assert_last_event(Event::SwapOrdersModule(crate::Event::OrderPlaced(
+			0, ALICE, SEE, USSD, 100, 200,
+		)));
+		assert_eq!(Tokens::free_balance(SEE, &ALICE), 900);
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 100);
+		assert_eq!(
+			SwapOrdersModule::orders(0),
+			Some(OrderInfo {
+				owner: ALICE,
+				supply_currency_id: SEE,
+				target_currency_id: USSD,
+				supply_amount: 100,
+				min_target_amount: 200,
+				expiry: 10,
+				kind: OrderKind::Limit,
+				task_id: 0,
+			})
+		);
+		assert_eq!(SwapOrdersModule::next_order_id(), 1);
+		assert_eq!(SwapOrdersModule::account_order_counts(ALICE), 1);
+		assert_eq!(
+			idle_scheduler::Tasks::<Runtime>::get(0),
+			Some(ScheduledTasks::SwapOrderTask(SwapOrderTask::ExecuteOrder {
+				order_id: 0,
+				owner: ALICE
+			}))
+		);
+		assert_eq!(
+			idle_scheduler::TaskOwners::<Runtime>::get(0),
+			Some((ALICE, TaskDeposit::get()))
+		);
+		assert_eq!(Balances::reserved_balance(&ALICE), TaskDeposit::get());
+	});
+}
+
+#[test]
+fn limit_order_executes_when_price_is_met() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(SwapOrdersModule::place_limit_order(
+			Origin::signed(ALICE),
+			SEE,
+			USSD,
+			100,
+			200,
+			10
+		));
+
+		// the price is not met
+		run_idle_tasks();
+		assert!(SwapOrdersModule::orders(0).is_some());
+		assert!(idle_scheduler::Tasks::<Runtime>::get(0).is_some());
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 100);
+
+		set_price(3, 1);
+		System::set_block_number(5);
+		run_idle_tasks();
+		System::assert_has_event(Event::SwapOrdersModule(crate::Event::OrderExecuted(0, 100, 300)));
+		System::assert_has_event(Event::SwapOrdersModule(crate::Event::OrderCompleted(0)));
+		assert_eq!(SwapOrdersModule::orders(0), None);
+		assert_eq!(idle_scheduler::Tasks::<Runtime>::get(0), None);
+		assert_eq!(Tokens::free_balance(SEE, &ALICE), 900);
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 0);
+		assert_eq!(Tokens::free_balance(USSD, &ALICE), 300);
+		assert_eq!(Balances::reserved_balance(&ALICE), 0);
+	});
+}
+
+#[test]
+fn limit_order_expires() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(SwapOrdersModule::place_limit_order(
+			Origin::signed(ALICE),
+			SEE,
+			USSD,
+			100,
+			200,
+			10
+		));
+
+		System::set_block_number(11);
+		set_price(3, 1);
+		run_idle_tasks();
+		System::assert_last_event(Event::IdleScheduler(idle_scheduler::Event::TaskDispatched {
+			task_id: 0,
+			result: Ok(()),
+		}));
+		System::assert_has_event(Event::SwapOrdersModule(crate::Event::OrderExpired(0, 100)));
+		assert_eq!(SwapOrdersModule::orders(0), None);
+		assert_eq!(idle_scheduler::Tasks::<Runtime>::get(0), None);
+		assert_eq!(Tokens::free_balance(SEE, &ALICE), 1_000);
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 0);
+		assert_eq!(Tokens::free_balance(USSD, &ALICE), 0);
+	});
+}
+
+#[test]
+fn cancel_order_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(SwapOrdersModule::place_limit_order(
+			Origin::signed(ALICE),
+			SEE,
+			USSD,
+			100,
+			200,
+			10
+		));
+		assert_noop!(
+			SwapOrdersModule::cancel_order(Origin::signed(ALICE), 1),
+			Error::<Runtime>::OrderNotFound
+		);
+		assert_noop!(
+			SwapOrdersModule::cancel_order(Origin::signed(BOB), 0),
+			Error::<Runtime>::NoPermission
+		);
+
+		assert_ok!(SwapOrdersModule::cancel_order(Origin::signed(ALICE), 0));
+		System::assert_last_event(Event::SwapOrdersModule(crate::Event::OrderCancelled(0, 100)));
+		assert_eq!(SwapOrdersModule::orders(0), None);
+		assert_eq!(SwapOrdersModule::account_order_counts(ALICE), 0);
+		assert_eq!(Tokens::free_balance(SEE, &ALICE), 1_000);
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 0);
+
+		// the task of the cancelled order is removed and its deposit refunded
+		assert_eq!(idle_scheduler::Tasks::<Runtime>::get(0), None);
+		assert_eq!(IdleScheduler::task_queue().len(), 0);
+		assert_eq!(Balances::reserved_balance(&ALICE), 0);
+		set_price(3, 1);
+		run_idle_tasks();
+		assert_eq!(Tokens::free_balance(USSD, &ALICE), 0);
+	});
+}
+
+#[test]
+fn place_order_fails_with_too_many_orders() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(SwapOrdersModule::place_limit_order(
+			Origin::signed(ALICE),
+			SEE,
+			USSD,
+			100,
+			200,
+			10
+		));
+		assert_ok!(SwapOrdersModule::place_limit_order(
+			Origin::signed(ALICE),
+			SEE,
+			USSD,
+			100,
+			200,
+			10
+		));
+		assert_eq!(SwapOrdersModule::account_order_counts(ALICE), 2);
+		assert_noop!(
+			SwapOrdersModule::place_limit_order(Origin::signed(ALICE), SEE, USSD, 100, 200, 10),
+			Error::<Runtime>::TooManyOrders
+		);
+		assert_noop!(
+			SwapOrdersModule::place_twap_order(Origin::signed(ALICE), SEE, USSD, 100, 200, 2, 1, 10),
+			Error::<Runtime>::TooManyOrders
+		);
+		assert_ok!(SwapOrdersModule::place_limit_order(
+			Origin::signed(BOB),
+			SEE,
+			USSD,
+			100,
+			200,
+			10
+		));
+
+		assert_ok!(SwapOrdersModule::cancel_order(Origin::signed(ALICE), 0));
+		assert_eq!(SwapOrdersModule::account_order_counts(ALICE), 1);
+		assert_ok!(SwapOrdersModule::place_limit_order(
+			Origin::signed(ALICE),
+			SEE,
+			USSD,
+			100,
+			200,
+			10
+		));
+	});
+}
+
+#[test]
+fn dropped_order_task_unreserves_supply() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(SwapOrdersModule::place_limit_order(
+			Origin::signed(ALICE),
+			SEE,
+			USSD,
+			100,
+			200,
+			100
+		));
+
+		// the price is met but the swap fails
+		set_price(3, 1);
+		assert_ok!(Tokens::withdraw(USSD, &DEX_ACCOUNT, 1_000_000));
+		for block in [1, 11, 31] {
+			System::set_block_number(block);
+			run_idle_tasks();
+			assert!(idle_scheduler::Tasks::<Runtime>::get(0).is_some());
+			assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 100);
+		}
+
+		System::set_block_number(71);
+		run_idle_tasks();
+		System::assert_has_event(Event::SwapOrdersModule(crate::Event::OrderDropped(0, 100)));
+		assert_eq!(idle_scheduler::Tasks::<Runtime>::get(0), None);
+		assert_eq!(SwapOrdersModule::orders(0), None);
+		assert_eq!(SwapOrdersModule::account_order_counts(ALICE), 0);
+		assert_eq!(Tokens::free_balance(SEE, &ALICE), 1_000);
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 0);
+	});
+}
+
+#[test]
+fn place_twap_order_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			SwapOrdersModule::place_twap_order(Origin::signed(ALICE), SEE, USSD, 300, 300, 1, 10, 100),
+			Error::<Runtime>::InvalidSlices
+		);
+		assert_noop!(
+			SwapOrdersModule::place_twap_order(Origin::signed(ALICE), SEE, USSD, 300, 300, 11, 10, 200),
+			Error::<Runtime>::InvalidSlices
+		);
+		assert_noop!(
+			SwapOrdersModule::place_twap_order(Origin::signed(ALICE), SEE, USSD, 300, 300, 3, 0, 100),
+			Error::<Runtime>::InvalidInterval
+		);
+		assert_noop!(
+			SwapOrdersModule::place_twap_order(Origin::signed(ALICE), SEE, USSD, 2, 300, 3, 10, 100),
+			Error::<Runtime>::InvalidAmount
+		);
+		assert_noop!(
+			SwapOrdersModule::place_twap_order(Origin::signed(ALICE), SEE, USSD, 300, 300, 3, 10, 20),
+			Error::<Runtime>::InvalidExpiry
+		);
+
+		assert_ok!(SwapOrdersModule::place_twap_order(
+			Origin::signed(ALICE),
+			SEE,
+			USSD,
+			300,
+			300,
+			3,
+			10,
+			21
+		));
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 300);
+		assert_eq!(
+			SwapOrdersModule::orders(0).map(|order| order.kind),
+			Some(OrderKind::Twap {
+				slices: 3,
+				executed_slices: 0,
+				interval: 10,
+				next_execution_block: 1,
+			})
+		);
+	});
+}
+
+#[test]
+fn twap_order_executes_in_slices() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(SwapOrdersModule::place_twap_order(
+			Origin::signed(ALICE),
+			SEE,
+			USSD,
+			301,
+			300,
+			3,
+			10,
+			100
+		));
+
+		run_idle_tasks();
+		System::assert_has_event(Event::SwapOrdersModule(crate::Event::OrderExecuted(0, 100, 100)));
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 201);
+		assert_eq!(Tokens::free_balance(USSD, &ALICE), 100);
+
+		// the next slice is not due yet
+		System::set_block_number(10);
+		run_idle_tasks();
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 201);
+
+		// the price of the slice is not met
+		System::set_block_number(11);
+		set_price(1, 2);
+		run_idle_tasks();
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 201);
+
+		set_price(1, 1);
+		System::set_block_number(12);
+		run_idle_tasks();
+		System::assert_has_event(Event::SwapOrdersModule(crate::Event::OrderExecuted(0, 100, 100)));
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 101);
+		assert_eq!(
+			SwapOrdersModule::orders(0).map(|order| order.kind),
+			Some(OrderKind::Twap {
+				slices: 3,
+				executed_slices: 2,
+				interval: 10,
+				next_execution_block: 22,
+			})
+		);
+
+		// the last slice is due `interval` blocks after the delayed one
+		System::set_block_number(21);
+		run_idle_tasks();
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 101);
+
+		// the last slice takes the remainder
+		System::set_block_number(22);
+		run_idle_tasks();
+		System::assert_has_event(Event::SwapOrdersModule(crate::Event::OrderExecuted(0, 101, 101)));
+		System::assert_has_event(Event::SwapOrdersModule(crate::Event::OrderCompleted(0)));
+		assert_eq!(SwapOrdersModule::orders(0), None);
+		assert_eq!(idle_scheduler::Tasks::<Runtime>::get(0), None);
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 0);
+		assert_eq!(Tokens::free_balance(SEE, &ALICE), 699);
+		assert_eq!(Tokens::free_balance(USSD, &ALICE), 301);
+	});
+}
+
+#[test]
+fn expired_twap_order_unreserves_remaining_supply() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(SwapOrdersModule::place_twap_order(
+			Origin::signed(ALICE),
+			SEE,
+			USSD,
+			300,
+			300,
+			3,
+			10,
+			25
+		));
+		run_idle_tasks();
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 200);
+
+		System::set_block_number(26);
+		run_idle_tasks();
+		System::assert_has_event(Event::SwapOrdersModule(crate::Event::OrderExpired(0, 200)));
+		assert_eq!(SwapOrdersModule::orders(0), None);
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 0);
+		assert_eq!(Tokens::free_balance(SEE, &ALICE), 900);
+		assert_eq!(Tokens::free_balance(USSD, &ALICE), 100);
+	});
+}
+
+#[test]
+fn delayed_twap_slice_does_not_make_the_next_one_due() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(SwapOrdersModule::place_twap_order(
+			Origin::signed(ALICE),
+			SEE,
+			USSD,
+			300,
+			300,
+			3,
+			10,
+			100
+		));
+
+		// the price of the first slice is not met until block 35
+		set_price(1, 2);
+		run_idle_tasks();
+		System::set_block_number(35);
+		set_price(1, 1);
+		run_idle_tasks();
+		System::assert_has_event(Event::SwapOrdersModule(crate::Event::OrderExecuted(0, 100, 100)));
+		assert_eq!(
+			SwapOrdersModule::orders(0).map(|order| order.kind),
+			Some(OrderKind::Twap {
+				slices: 3,
+				executed_slices: 1,
+				interval: 10,
+				next_execution_block: 45,
+			})
+		);
+
+		// the slices missed while waiting are not executed at once
+		run_idle_tasks();
+		System::set_block_number(44);
+		run_idle_tasks();
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 200);
+
+		System::set_block_number(45);
+		run_idle_tasks();
+		assert_eq!(Tokens::reserved_balance(SEE, &ALICE), 100);
+		assert_eq!(Tokens::free_balance(USSD, &ALICE), 200);
+	});
+}
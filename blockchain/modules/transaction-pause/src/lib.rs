@@ -28,8 +28,10 @@ use frame_support::{
 	transactional,
 };
 use frame_system::pallet_prelude::*;
+use primitives::evm::EvmAddress;
 use sp_runtime::DispatchResult;
 use sp_std::{prelude::*, vec::Vec};
+use support::EvmCallFilter;
 
 mod mock;
 mod tests;
@@ -68,6 +70,14 @@ pub mod module {
 		TransactionPaused(Vec<u8>, Vec<u8>),
 		/// Unpaused transaction . \[pallet_name_bytes, function_name_bytes\]
 		TransactionUnpaused(Vec<u8>, Vec<u8>),
+		/// Paused EVM contract. \[contract\]
+		EvmContractPaused(EvmAddress),
+		/// Unpaused EVM contract. \[contract\]
+		EvmContractUnpaused(EvmAddress),
+		/// Paused EVM contract function. \[contract, selector\]
+		EvmSelectorPaused(EvmAddress, [u8; 4]),
+		/// Unpaused EVM contract function. \[contract, selector\]
+		EvmSelectorUnpaused(EvmAddress, [u8; 4]),
 	}
 
 	/// The paused transaction map
@@ -77,6 +87,21 @@ pub mod module {
 	#[pallet::getter(fn paused_transactions)]
 	pub type PausedTransactions<T: Config> = StorageMap<_, Twox64Concat, (Vec<u8>, Vec<u8>), (), OptionQuery>;
 
+	/// The paused EVM contracts, calls to them fail.
+	///
+	/// map EvmAddress => Option<()>
+	#[pallet::storage]
+	#[pallet::getter(fn paused_evm_contracts)]
+	pub type PausedEvmContracts<T: Config> = StorageMap<_, Twox64Concat, EvmAddress, (), OptionQuery>;
+
+	/// The paused EVM contract functions, calls to them fail.
+	///
+	/// double_map (EvmAddress, FunctionSelector) => Option<()>
+	#[pallet::storage]
+	#[pallet::getter(fn paused_evm_selectors)]
+	pub type PausedEvmSelectors<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, EvmAddress, Twox64Concat, [u8; 4], (), OptionQuery>;
+
 	#[pallet::pallet]
 	pub struct Pallet<T>(_);
 
@@ -119,6 +144,81 @@ pub mod module {
 			};
 			Ok(())
 		}
+
+		#[pallet::weight(T::WeightInfo::pause_evm_contract())]
+		#[transactional]
+		pub fn pause_evm_contract(origin: OriginFor<T>, contract: EvmAddress) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			PausedEvmContracts::<T>::mutate_exists(contract, |maybe_paused| {
+				if maybe_paused.is_none() {
+					*maybe_paused = Some(());
+					Self::deposit_event(Event::EvmContractPaused(contract));
+				}
+			});
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::unpause_evm_contract())]
+		#[transactional]
+		pub fn unpause_evm_contract(origin: OriginFor<T>, contract: EvmAddress) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			if PausedEvmContracts::<T>::take(contract).is_some() {
+				Self::deposit_event(Event::EvmContractUnpaused(contract));
+			};
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::pause_evm_selector())]
+		#[transactional]
+		pub fn pause_evm_selector(origin: OriginFor<T>, contract: EvmAddress, selector: [u8; 4]) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			PausedEvmSelectors::<T>::mutate_exists(contract, selector, |maybe_paused| {
+				if maybe_paused.is_none() {
+					*maybe_paused = Some(());
+					Self::deposit_event(Event::EvmSelectorPaused(contract, selector));
+				}
+			});
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::unpause_evm_selector())]
+		#[transactional]
+		pub fn unpause_evm_selector(origin: OriginFor<T>, contract: EvmAddress, selector: [u8; 4]) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+			if PausedEvmSelectors::<T>::take(contract, selector).is_some() {
+				Self::deposit_event(Event::EvmSelectorUnpaused(contract, selector));
+			};
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Whether calls to the `contract` are paused, either the whole contract or the function
+	/// `selector`.
+	pub fn is_evm_call_paused(contract: &EvmAddress, selector: Option<[u8; 4]>) -> bool {
+		PausedEvmContracts::<T>::contains_key(contract)
+			|| selector.map_or(false, |selector| {
+				PausedEvmSelectors::<T>::contains_key(contract, selector)
+			})
+	}
+
+	/// The paused EVM contracts.
+	pub fn paused_evm_contract_list() -> Vec<EvmAddress> {
+		PausedEvmContracts::<T>::iter_keys().collect()
+	}
+
+	/// The paused function selectors of the `contract`.
+	pub fn paused_evm_selectors_of(contract: &EvmAddress) -> Vec<[u8; 4]> {
+		PausedEvmSelectors::<T>::iter_prefix(contract)
+			.map(|(selector, _)| selector)
+			.collect()
+	}
+}
+
+impl<T: Config> EvmCallFilter for Pallet<T> {
+	fn is_call_paused(contract: &EvmAddress, selector: Option<[u8; 4]>) -> bool {
+		Self::is_evm_call_paused(contract, selector)
 	}
 }
 
@@ -25,6 +25,7 @@
 use super::*;
 use frame_support::{assert_noop, assert_ok};
 use mock::{Event, *};
+use sp_core::H160;
 use sp_runtime::traits::BadOrigin;
 
 const BALANCE_TRANSFER: &<Runtime as frame_system::Config>::Call =
@@ -151,3 +152,77 @@ fn paused_transaction_filter_work() {
 		assert!(!PausedTransactionFilter::<Runtime>::contains(TOKENS_TRANSFER));
 	});
 }
+
+#[test]
+fn pause_and_unpause_evm_contract_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let contract = H160::repeat_byte(1);
+
+		assert_noop!(
+			TransactionPause::pause_evm_contract(Origin::signed(5), contract),
+			BadOrigin
+		);
+		assert!(!TransactionPause::is_evm_call_paused(&contract, None));
+
+		assert_ok!(TransactionPause::pause_evm_contract(Origin::signed(1), contract));
+		System::assert_last_event(Event::TransactionPause(crate::Event::EvmContractPaused(contract)));
+		assert_eq!(TransactionPause::paused_evm_contracts(contract), Some(()));
+		assert_eq!(TransactionPause::paused_evm_contract_list(), vec![contract]);
+		assert!(TransactionPause::is_evm_call_paused(&contract, None));
+		assert!(TransactionPause::is_evm_call_paused(&contract, Some([1, 2, 3, 4])));
+		assert!(!TransactionPause::is_evm_call_paused(&H160::repeat_byte(2), None));
+
+		assert_noop!(
+			TransactionPause::unpause_evm_contract(Origin::signed(5), contract),
+			BadOrigin
+		);
+		assert_ok!(TransactionPause::unpause_evm_contract(Origin::signed(1), contract));
+		System::assert_last_event(Event::TransactionPause(crate::Event::EvmContractUnpaused(contract)));
+		assert_eq!(TransactionPause::paused_evm_contracts(contract), None);
+		assert_eq!(TransactionPause::paused_evm_contract_list(), vec![]);
+		assert!(!TransactionPause::is_evm_call_paused(&contract, None));
+	});
+}
+
+#[test]
+fn pause_and_unpause_evm_selector_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+		let contract = H160::repeat_byte(1);
+
+		assert_noop!(
+			TransactionPause::pause_evm_selector(Origin::signed(5), contract, [1, 2, 3, 4]),
+			BadOrigin
+		);
+
+		assert_ok!(TransactionPause::pause_evm_selector(
+			Origin::signed(1),
+			contract,
+			[1, 2, 3, 4]
+		));
+		System::assert_last_event(Event::TransactionPause(crate::Event::EvmSelectorPaused(
+			contract,
+			[1, 2, 3, 4],
+		)));
+		assert_eq!(TransactionPause::paused_evm_selectors_of(&contract), vec![[1, 2, 3, 4]]);
+		assert!(TransactionPause::is_evm_call_paused(&contract, Some([1, 2, 3, 4])));
+		assert!(!TransactionPause::is_evm_call_paused(&contract, Some([4, 3, 2, 1])));
+		assert!(!TransactionPause::is_evm_call_paused(&contract, None));
+
+		assert_noop!(
+			TransactionPause::unpause_evm_selector(Origin::signed(5), contract, [1, 2, 3, 4]),
+			BadOrigin
+		);
+		assert_ok!(TransactionPause::unpause_evm_selector(
+			Origin::signed(1),
+			contract,
+			[1, 2, 3, 4]
+		));
+		System::assert_last_event(Event::TransactionPause(crate::Event::EvmSelectorUnpaused(
+			contract,
+			[1, 2, 3, 4],
+		)));
+		assert!(!TransactionPause::is_evm_call_paused(&contract, Some([1, 2, 3, 4])));
+	});
+}
@@ -51,6 +51,10 @@ use sp_std::marker::PhantomData;
 pub trait WeightInfo {
 	fn pause_transaction() -> Weight;
 	fn unpause_transaction() -> Weight;
+	fn pause_evm_contract() -> Weight;
+	fn unpause_evm_contract() -> Weight;
+	fn pause_evm_selector() -> Weight;
+	fn unpause_evm_selector() -> Weight;
 }
 
 /// Weights for module_transaction_pause using the Setheum node and recommended hardware.
@@ -66,6 +70,26 @@ impl<T: frame_system::Config> WeightInfo for SetheumWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(1 as Weight))
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
+	fn pause_evm_contract() -> Weight {
+		(24_512_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn unpause_evm_contract() -> Weight {
+		(24_103_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn pause_evm_selector() -> Weight {
+		(25_217_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn unpause_evm_selector() -> Weight {
+		(24_890_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
 }
 
 // For backwards compatibility and tests
@@ -80,4 +104,24 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
+	fn pause_evm_contract() -> Weight {
+		(24_512_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn unpause_evm_contract() -> Weight {
+		(24_103_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn pause_evm_selector() -> Weight {
+		(25_217_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn unpause_evm_selector() -> Weight {
+		(24_890_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
 }
@@ -828,6 +828,14 @@ where
 		Ok(fee)
 	}
 
+	fn reserve_fees(who: &T::AccountId, weight: Weight, count: u32) -> Result<PalletBalanceOf<T>, DispatchError> {
+		let fee = Pallet::<T>::weight_to_fee(weight);
+		let total_fee = fee.saturating_mul(count.into());
+		Pallet::<T>::ensure_can_charge_fee(who, total_fee, WithdrawReasons::TRANSACTION_PAYMENT);
+		<T as Config>::Currency::reserve_named(&RESERVE_ID, who, total_fee)?;
+		Ok(fee)
+	}
+
 	fn unreserve_fee(who: &T::AccountId, fee: PalletBalanceOf<T>) {
 		<T as Config>::Currency::unreserve_named(&RESERVE_ID, who, fee);
 	}
@@ -28,7 +28,7 @@ use frame_support::{
 	weights::{DispatchClass, DispatchInfo, Pays},
 };
 use mock::{
-	AccountId, BlockWeights, Call, Currencies, DEXModule, ExtBuilder, MockPriceSource, Origin, Runtime,
+	AccountId, BlockWeights, Call, Currencies, DEXModule, ExtBuilder, MockPriceSource, Origin, PalletBalances, Runtime,
 	TransactionPayment, SEE, ALICE, SETUSD, BOB, CHARLIE, DNAR, FEE_UNBALANCED_AMOUNT, TIP_UNBALANCED_AMOUNT,
 };
 use orml_traits::MultiCurrency;
@@ -528,3 +528,23 @@ fn compute_fee_does_not_overflow() {
 			);
 		});
 }
+
+#[test]
+fn reserve_fees_reserves_every_run_at_once() {
+	ExtBuilder::default()
+		.one_hundred_thousand_for_alice_n_charlie()
+		.build()
+		.execute_with(|| {
+			assert_eq!(
+				ChargeTransactionPayment::<Runtime>::reserve_fees(&ALICE, 1000, 3),
+				Ok(1000)
+			);
+			assert_eq!(PalletBalances::reserved_balance_named(&RESERVE_ID, &ALICE), 3000);
+
+			assert!(ChargeTransactionPayment::<Runtime>::reserve_fees(&ALICE, 1000, 1_000).is_err());
+			assert_eq!(PalletBalances::reserved_balance_named(&RESERVE_ID, &ALICE), 3000);
+
+			ChargeTransactionPayment::<Runtime>::unreserve_fee(&ALICE, 3000);
+			assert_eq!(PalletBalances::reserved_balance_named(&RESERVE_ID, &ALICE), 0);
+		});
+}
@@ -190,12 +190,30 @@ pub trait TokenInfo {
 	fn decimals(&self) -> Option<u8>;
 }
 
+/// The metadata of an asset registered at runtime, used in place of the
+/// `TokenInfo` of the assets unknown at compile time.
+#[derive(Encode, Decode, Eq, PartialEq, Clone, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct AssetMetadata<Balance> {
+	pub name: Vec<u8>,
+	pub symbol: Vec<u8>,
+	pub decimals: u8,
+	pub minimal_balance: Balance,
+}
+
+/// The id of an asset bridged in from another chain, whose metadata is registered at runtime.
+pub type ForeignAssetId = u16;
+
+/// The id of a stable swap pool, whose liquidity share is a currency of its own.
+pub type StableAssetPoolId = u16;
+
 #[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, PartialOrd, Ord)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
 pub enum DexShare {
 	Token(TokenSymbol),
 	Erc20(EvmAddress),
+	ForeignAsset(ForeignAssetId),
 }
 
 #[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, PartialOrd, Ord)]
@@ -205,6 +223,8 @@ pub enum CurrencyId {
 	Token(TokenSymbol),
 	DexShare(DexShare, DexShare),
 	Erc20(EvmAddress),
+	ForeignAsset(ForeignAssetId),
+	StableAssetPoolToken(StableAssetPoolId),
 }
 
 impl CurrencyId {
@@ -220,10 +240,18 @@ impl CurrencyId {
 		matches!(self, CurrencyId::Erc20(_))
 	}
 
+	pub fn is_foreign_asset_currency_id(&self) -> bool {
+		matches!(self, CurrencyId::ForeignAsset(_))
+	}
+
+	pub fn is_stable_asset_pool_token_currency_id(&self) -> bool {
+		matches!(self, CurrencyId::StableAssetPoolToken(_))
+	}
+
 	pub fn is_trading_pair_currency_id(&self) -> bool {
 		matches!(
 			self,
-			CurrencyId::Token(_) | CurrencyId::Erc20(_)
+			CurrencyId::Token(_) | CurrencyId::Erc20(_) | CurrencyId::ForeignAsset(_)
 		)
 	}
 
@@ -242,14 +270,16 @@ impl CurrencyId {
 		let dex_share_0 = match currency_id_0 {
 			CurrencyId::Token(symbol) => DexShare::Token(symbol),
 			CurrencyId::Erc20(address) => DexShare::Erc20(address),
+			CurrencyId::ForeignAsset(foreign_asset_id) => DexShare::ForeignAsset(foreign_asset_id),
 			// Unsupported
-			CurrencyId::DexShare(..) => return None,
+			CurrencyId::DexShare(..) | CurrencyId::StableAssetPoolToken(_) => return None,
 		};
 		let dex_share_1 = match currency_id_1 {
 			CurrencyId::Token(symbol) => DexShare::Token(symbol),
 			CurrencyId::Erc20(address) => DexShare::Erc20(address),
+			CurrencyId::ForeignAsset(foreign_asset_id) => DexShare::ForeignAsset(foreign_asset_id),
 			// Unsupported
-			CurrencyId::DexShare(..) => return None,
+			CurrencyId::DexShare(..) | CurrencyId::StableAssetPoolToken(_) => return None,
 		};
 		Some(CurrencyId::DexShare(dex_share_0, dex_share_1))
 	}
@@ -263,6 +293,8 @@ impl CurrencyId {
 pub enum CurrencyIdType {
 	Token = 1, // 0 is prefix of precompile and predeploy
 	DexShare,
+	ForeignAsset,
+	StableAssetPoolToken,
 }
 
 #[derive(
@@ -272,6 +304,7 @@ pub enum CurrencyIdType {
 pub enum DexShareType {
 	Token,
 	Erc20,
+	ForeignAsset,
 }
 
 impl From<DexShare> for u32 {
@@ -289,6 +322,11 @@ impl From<DexShare> for u32 {
 				let index = if leading_zeros > 16 { 16 } else { leading_zeros };
 				bytes[..].copy_from_slice(&address[index..index + 4][..]);
 			}
+			DexShare::ForeignAsset(foreign_asset_id) => {
+				// [0, DexShareType::ForeignAsset, foreign asset id(2 bytes)]
+				bytes[1] = DexShareType::ForeignAsset.into();
+				bytes[2..].copy_from_slice(&foreign_asset_id.to_be_bytes()[..]);
+			}
 		}
 		u32::from_be_bytes(bytes)
 	}
@@ -299,6 +337,7 @@ impl Into<CurrencyId> for DexShare {
 		match self {
 			DexShare::Token(token) => CurrencyId::Token(token),
 			DexShare::Erc20(address) => CurrencyId::Erc20(address),
+			DexShare::ForeignAsset(foreign_asset_id) => CurrencyId::ForeignAsset(foreign_asset_id),
 		}
 	}
 }
@@ -308,6 +347,7 @@ impl Into<DexShareType> for DexShare {
 		match self {
 			DexShare::Token(_) => DexShareType::Token,
 			DexShare::Erc20(_) => DexShareType::Erc20,
+			DexShare::ForeignAsset(_) => DexShareType::ForeignAsset,
 		}
 	}
 }
@@ -322,12 +362,12 @@ impl TryFrom<CurrencyId> for EvmAddress {
 				MIRRORED_TOKENS_ADDRESS_START | u64::from(val.currency_id().unwrap()),
 			)),
 			CurrencyId::DexShare(token_symbol_0, token_symbol_1) => {
-				let symbol_0 = match token_symbol_0 {
-					DexShare::Token(token) => CurrencyId::Token(token).currency_id().ok_or(()),
+				let symbol_0: u32 = match token_symbol_0 {
+					DexShare::Token(_) | DexShare::ForeignAsset(_) => Ok(token_symbol_0.into()),
 					DexShare::Erc20(_) => Err(()),
 				}?;
-				let symbol_1 = match token_symbol_1 {
-					DexShare::Token(token) => CurrencyId::Token(token).currency_id().ok_or(()),
+				let symbol_1: u32 = match token_symbol_1 {
+					DexShare::Token(_) | DexShare::ForeignAsset(_) => Ok(token_symbol_1.into()),
 					DexShare::Erc20(_) => Err(()),
 				}?;
 
@@ -336,6 +376,19 @@ impl TryFrom<CurrencyId> for EvmAddress {
 				Ok(prefix | EvmAddress::from_low_u64_be(u64::from(symbol_0) << 32 | u64::from(symbol_1)))
 			}
 			CurrencyId::Erc20(address) => Ok(address),
+			CurrencyId::ForeignAsset(foreign_asset_id) => {
+				let mut address = EvmAddress::default();
+				address[0..H160_PREFIX_FOREIGN_ASSET.len()].copy_from_slice(&H160_PREFIX_FOREIGN_ASSET);
+				address[H160_POSITION_FOREIGN_ASSET].copy_from_slice(&foreign_asset_id.to_be_bytes()[..]);
+				Ok(address)
+			}
+			CurrencyId::StableAssetPoolToken(pool_id) => {
+				let mut address = EvmAddress::default();
+				address[0..H160_PREFIX_STABLE_ASSET_POOL_TOKEN.len()]
+					.copy_from_slice(&H160_PREFIX_STABLE_ASSET_POOL_TOKEN);
+				address[H160_POSITION_STABLE_ASSET_POOL_TOKEN].copy_from_slice(&pool_id.to_be_bytes()[..]);
+				Ok(address)
+			}
 		}
 	}
 }
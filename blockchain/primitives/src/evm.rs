@@ -57,6 +57,32 @@ pub struct ExecutionInfo<T> {
 pub type CallInfo = ExecutionInfo<Vec<u8>>;
 pub type CreateInfo = ExecutionInfo<H160>;
 
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Default, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+/// EIP-2930 access list item, the addresses and storage keys to warm up
+/// before the transaction is executed.
+pub struct AccessListItem {
+	pub address: EvmAddress,
+	pub storage_keys: Vec<H256>,
+}
+
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Default, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+/// The storage accounting of a contract.
+pub struct ContractStorageInfo<Balance> {
+	/// The bytes used by the contract code, extra bytes and storage.
+	pub used_storage: u32,
+	/// The storage deposit reserved on the contract account.
+	pub deposit: Balance,
+	/// The maintainer of the contract. The deposit of a storage slot is reserved from the
+	/// caller growing the storage and refunded to the caller clearing it, not the maintainer.
+	pub maintainer: EvmAddress,
+	/// The deposit refunded by clearing a storage slot.
+	pub refund_per_slot: Balance,
+}
+
 #[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct Erc20Info {
@@ -81,6 +107,8 @@ pub struct EstimateResourcesRequest {
 	pub value: Option<Balance>,
 	/// Data
 	pub data: Option<Vec<u8>>,
+	/// AccessList
+	pub access_list: Option<Vec<AccessListItem>>,
 }
 
 #[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
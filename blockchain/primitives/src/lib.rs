@@ -37,7 +37,7 @@ use sp_runtime::{
 	RuntimeDebug,
 };
 
-pub use currency::{CurrencyId, DexShare, TokenSymbol};
+pub use currency::{CurrencyId, DexShare, ForeignAssetId, StableAssetPoolId, TokenSymbol};
 
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
@@ -74,21 +74,38 @@ pub const NETWORK_CONTRACT_START: u64 = 0x1000;
 /// v[16] = 1 // MIRRORED_TOKENS_ADDRESS_START
 /// - v[19] = token(1 byte)
 ///
+/// ForeignAsset
+/// v[16] = 1 // MIRRORED_TOKENS_ADDRESS_START
+/// v[17] = 1 // H160_TYPE_FOREIGN_ASSET
+/// - v[18..20] = foreign asset id(2 bytes)
+///
+/// StableAssetPoolToken
+/// v[16] = 1 // MIRRORED_TOKENS_ADDRESS_START
+/// v[17] = 2 // H160_TYPE_STABLE_ASSET_POOL_TOKEN
+/// - v[18..20] = stable asset pool id(2 bytes)
+///
 /// DexShare
 /// v[11] = 1 // MIRRORED_LP_TOKENS_ADDRESS_START
 /// - v[12..16] = dex left(4 bytes)
 /// - v[16..20] = dex right(4 bytes)
+/// - dex side of ForeignAsset = [0, DexShareType::ForeignAsset, foreign asset id(2 bytes)]
 ///
 /// Erc20
 /// - v[0..20] = evm address(20 bytes)
 pub const H160_TYPE_TOKEN: u8 = 1;
 pub const H160_TYPE_DEXSHARE: u8 = 1;
+pub const H160_TYPE_FOREIGN_ASSET: u8 = 1;
+pub const H160_TYPE_STABLE_ASSET_POOL_TOKEN: u8 = 2;
 pub const H160_POSITION_TOKEN: usize = 19;
+pub const H160_POSITION_FOREIGN_ASSET: Range<usize> = 18..20;
+pub const H160_POSITION_STABLE_ASSET_POOL_TOKEN: Range<usize> = 18..20;
 pub const H160_POSITION_DEXSHARE_LEFT: Range<usize> = 12..16;
 pub const H160_POSITION_DEXSHARE_RIGHT: Range<usize> = 16..20;
 pub const H160_POSITION_ERC20: Range<usize> = 0..20;
 pub const H160_PREFIX_TOKEN: [u8; 19] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0];
 pub const H160_PREFIX_DEXSHARE: [u8; 12] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+pub const H160_PREFIX_FOREIGN_ASSET: [u8; 18] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1];
+pub const H160_PREFIX_STABLE_ASSET_POOL_TOKEN: [u8; 18] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 2];
 
 /// NFT Balance type
 pub type NFTBalance = u128;
@@ -225,6 +242,9 @@ pub enum ReserveIdentifier {
 	Setmint,
 	Nft,
 	TransactionPayment,
+	Incentives,
+	IdleScheduler,
+	SwapOrders,
 	// always the last, indicate number of variants
 	Count,
 }
@@ -53,10 +53,21 @@ macro_rules! define_combined_task {
 		}
 
 		impl DispatchableTask for $combined_name {
-			fn dispatch(self, weight: Weight) -> TaskResult {
+			fn dispatch(self, weight: Weight) -> (TaskResult, Option<Self>) {
 				match self {
 					$(
-						$combined_name::$task(t) => t.dispatch(weight),
+						$combined_name::$task(t) => {
+							let (result, updated_task) = t.dispatch(weight);
+							(result, updated_task.map($combined_name::$task))
+						}
+					)*
+				}
+			}
+
+			fn on_dropped(self) {
+				match self {
+					$(
+						$combined_name::$task(t) => t.on_dropped(),
 					)*
 				}
 			}
@@ -19,7 +19,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use super::*;
-use crate::evm::EvmAddress;
+use crate::{currency::DexShareType, evm::EvmAddress};
 use frame_support::assert_ok;
 use std::{
 	convert::{TryFrom, TryInto},
@@ -36,6 +36,8 @@ fn trading_pair_works() {
 		DexShare::Token(TokenSymbol::SEE),
 		DexShare::Erc20(EvmAddress::from_str("0x0000000000000000000000000000000000000000").unwrap()),
 	);
+	let foreign_asset = CurrencyId::ForeignAsset(1);
+	let foreign_asset_setm_lp = CurrencyId::DexShare(DexShare::Token(TokenSymbol::SEE), DexShare::ForeignAsset(1));
 
 	assert_eq!(
 		TradingPair::from_currency_ids(setusd, setm).unwrap(),
@@ -50,6 +52,10 @@ fn trading_pair_works() {
 		TradingPair(setm, erc20)
 	);
 	assert_eq!(TradingPair::from_currency_ids(setm, setm), None);
+	assert_eq!(
+		TradingPair::from_currency_ids(foreign_asset, setm).unwrap(),
+		TradingPair(setm, foreign_asset)
+	);
 
 	assert_eq!(
 		TradingPair::from_currency_ids(setusd, setm)
@@ -63,6 +69,16 @@ fn trading_pair_works() {
 			.dex_share_currency_id(),
 		erc20_setm_lp
 	);
+	assert_eq!(
+		TradingPair::from_currency_ids(setm, foreign_asset)
+			.unwrap()
+			.dex_share_currency_id(),
+		foreign_asset_setm_lp
+	);
+	assert_eq!(
+		foreign_asset_setm_lp.split_dex_share_currency_id(),
+		Some((setm, foreign_asset))
+	);
 }
 
 #[test]
@@ -92,6 +108,15 @@ fn currency_id_into_u32_works() {
 
 	let currency_id = DexShare::Erc20(EvmAddress::from_str("0x0000000000000000000000000000000000000000").unwrap());
 	assert_eq!(Into::<u32>::into(currency_id), 0x00);
+
+	let currency_id = DexShare::ForeignAsset(0);
+	assert_eq!(Into::<u32>::into(currency_id), 0x00020000);
+
+	let currency_id = DexShare::ForeignAsset(1);
+	assert_eq!(Into::<u32>::into(currency_id), 0x00020001);
+
+	let currency_id = DexShare::ForeignAsset(ForeignAssetId::MAX);
+	assert_eq!(Into::<u32>::into(currency_id), 0x0002ffff);
 }
 
 #[test]
@@ -119,6 +144,79 @@ fn currency_id_try_into_evm_address_works() {
 
 	let erc20 = EvmAddress::from_str("0x1111111111111111111111111111111111111111").unwrap();
 	assert_eq!(EvmAddress::try_from(CurrencyId::Erc20(erc20)), Ok(erc20));
+
+	assert_eq!(
+		EvmAddress::try_from(CurrencyId::ForeignAsset(1)),
+		Ok(EvmAddress::from_str("0x0000000000000000000000000000000001010001").unwrap())
+	);
+
+	assert_eq!(
+		EvmAddress::try_from(CurrencyId::DexShare(
+			DexShare::Token(TokenSymbol::SEE),
+			DexShare::ForeignAsset(1),
+		)),
+		Ok(EvmAddress::from_str("0x0000000000000000000000010000000000020001").unwrap())
+	);
+}
+
+#[test]
+fn foreign_asset_evm_address_round_trip_works() {
+	for &foreign_asset_id in &[0, 1, 0x0100, ForeignAssetId::MAX] {
+		let address = EvmAddress::try_from(CurrencyId::ForeignAsset(foreign_asset_id)).unwrap();
+		let bytes = address.as_bytes();
+		assert!(bytes.starts_with(&H160_PREFIX_FOREIGN_ASSET));
+		assert!(!bytes.starts_with(&H160_PREFIX_TOKEN));
+		assert!(address >= EvmAddress::from_low_u64_be(MIRRORED_TOKENS_ADDRESS_START));
+		assert!(address < EvmAddress::from_low_u64_be(MIRRORED_NFT_ADDRESS_START));
+		assert_eq!(
+			ForeignAssetId::from_be_bytes(bytes[H160_POSITION_FOREIGN_ASSET].try_into().unwrap()),
+			foreign_asset_id
+		);
+
+		let address = EvmAddress::try_from(CurrencyId::DexShare(
+			DexShare::ForeignAsset(foreign_asset_id),
+			DexShare::Token(TokenSymbol::SEE),
+		))
+		.unwrap();
+		let bytes = address.as_bytes();
+		assert!(bytes.starts_with(&H160_PREFIX_DEXSHARE));
+		let left = &bytes[H160_POSITION_DEXSHARE_LEFT];
+		assert_eq!(left[..2], [0u8, DexShareType::ForeignAsset.into()]);
+		assert_eq!(
+			ForeignAssetId::from_be_bytes(left[2..].try_into().unwrap()),
+			foreign_asset_id
+		);
+		assert_eq!(
+			u32::from_be_bytes(left.try_into().unwrap()),
+			Into::<u32>::into(DexShare::ForeignAsset(foreign_asset_id))
+		);
+	}
+}
+
+#[test]
+fn stable_asset_pool_token_evm_address_round_trip_works() {
+	for &pool_id in &[0, 1, 0x0100, StableAssetPoolId::MAX] {
+		let address = EvmAddress::try_from(CurrencyId::StableAssetPoolToken(pool_id)).unwrap();
+		let bytes = address.as_bytes();
+		assert!(bytes.starts_with(&H160_PREFIX_STABLE_ASSET_POOL_TOKEN));
+		assert!(!bytes.starts_with(&H160_PREFIX_TOKEN));
+		assert!(!bytes.starts_with(&H160_PREFIX_FOREIGN_ASSET));
+		assert!(address >= EvmAddress::from_low_u64_be(MIRRORED_TOKENS_ADDRESS_START));
+		assert!(address < EvmAddress::from_low_u64_be(MIRRORED_NFT_ADDRESS_START));
+		assert_eq!(
+			StableAssetPoolId::from_be_bytes(bytes[H160_POSITION_STABLE_ASSET_POOL_TOKEN].try_into().unwrap()),
+			pool_id
+		);
+	}
+
+	// the shares of the stable pools can't be pooled in the dex
+	assert_eq!(
+		CurrencyId::join_dex_share_currency_id(
+			CurrencyId::StableAssetPoolToken(0),
+			CurrencyId::Token(TokenSymbol::SEE)
+		),
+		None
+	);
 }
 
 #[test]